@@ -75,6 +75,7 @@ async fn live_example() {
         port: 16001,
         max_messages: 100,
         log_level: LogLevel::Info,
+        ..Default::default()
     };
 
     let driver = match FanucDriver::connect(cfg).await {
@@ -56,6 +56,7 @@ async fn main() -> Result<(), FrcError> {
         port: 16001,
         max_messages: 30,
         log_level: fanuc_rmi::drivers::LogLevel::Info,
+        ..Default::default()
     };
 
     println!("Connecting to robot at {}:{}...", driver_settings.addr, driver_settings.port);
@@ -78,12 +79,12 @@ async fn main() -> Result<(), FrcError> {
                 println!("✓ Initialize successful");
             } else {
                 eprintln!("✗ Initialize failed with error: {}", response.error_id);
-                return Err(FrcError::FailedToSend(format!("Initialize failed: {}", response.error_id)));
+                return Err(FrcError::FailedToSend(format!("Initialize failed: {}", response.error_id), None));
             }
         }
         Err(e) => {
             eprintln!("✗ Initialize error: {}", e);
-            return Err(FrcError::FailedToSend(e));
+            return Err(FrcError::FailedToSend(e, None));
         }
     }
 
@@ -150,6 +150,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         port: 16001,
         max_messages: 30,
         log_level: fanuc_rmi::drivers::LogLevel::Info,
+        ..Default::default()
     };
 
     let driver = FanucDriver::connect(driver_settings.clone()).await
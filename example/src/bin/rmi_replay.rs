@@ -0,0 +1,54 @@
+// Replay a recorded driver session as a fake controller
+// Record with: FanucDriverConfig { record: Some("session.jsonl".into()), .. }
+// Run with: cargo run -p example --bin rmi_replay -- session.jsonl [port]
+// Then point the client at 127.0.0.1:<port> (default 16001). The replay fails
+// as soon as the client sends a line that differs from the recording.
+
+use fanuc_rmi::drivers::{load_recording, replay_session};
+use tokio::net::TcpListener;
+
+#[tokio::main]
+async fn main() {
+    let mut args = std::env::args().skip(1);
+    let Some(path) = args.next() else {
+        eprintln!("usage: rmi_replay <recording.jsonl> [port]");
+        std::process::exit(2);
+    };
+    let port: u16 = match args.next().map(|p| p.parse()) {
+        None => 16001,
+        Some(Ok(port)) => port,
+        Some(Err(e)) => {
+            eprintln!("invalid port: {}", e);
+            std::process::exit(2);
+        }
+    };
+
+    let recording = match load_recording(&path) {
+        Ok(recording) => recording,
+        Err(e) => {
+            eprintln!("failed to load {}: {}", path, e);
+            std::process::exit(2);
+        }
+    };
+
+    let connect_listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .expect("bind connect port");
+    let data_listener = TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .expect("bind data port");
+    println!(
+        "Replaying {} lines from {} on 127.0.0.1:{}",
+        recording.len(),
+        path,
+        port
+    );
+
+    match replay_session(connect_listener, data_listener, &recording).await {
+        Ok(matched) => println!("✓ Replay matched ({} client lines)", matched),
+        Err(e) => {
+            eprintln!("✗ {}", e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use std::time::Duration;
 use tokio::time::sleep;
 use fanuc_rmi::{
-    drivers::{FanucDriver, FanucDriverConfig, LogLevel}, instructions::FrcLinearRelative, packets::*, Configuration, FrcError, Position, SpeedType, TermType
+    drivers::{FanucDriver, FanucDriverConfig, LogLevel}, Configuration, FrcError, Position, TermType
 };
 
 #[tokio::main]
@@ -12,6 +12,7 @@ async fn main() -> Result<(), FrcError > {
         port: 16001,
         max_messages: 30,
         log_level: LogLevel::Info,
+        ..Default::default()
     };
 
     let driver = FanucDriver::connect(driver_settings.clone()).await.unwrap();
@@ -24,12 +25,12 @@ async fn main() -> Result<(), FrcError > {
                 println!("✓ Initialize successful");
             } else {
                 eprintln!("✗ Initialize failed with error: {}", response.error_id);
-                return Err(FrcError::FailedToSend(format!("Initialize failed: {}", response.error_id)));
+                return Err(FrcError::FailedToSend(format!("Initialize failed: {}", response.error_id), None));
             }
         }
         Err(e) => {
             eprintln!("✗ Initialize error: {}", e);
-            return Err(FrcError::FailedToSend(e));
+            return Err(FrcError::FailedToSend(e, None));
         }
     }
 
@@ -37,45 +38,12 @@ async fn main() -> Result<(), FrcError > {
 
 
 
-    match driver.send_packet(
-        SendPacket::Instruction(Instruction::FrcLinearRelative(
-            FrcLinearRelative::new(
-                0,
-                Configuration {
-                    u_tool_number: 1,
-                    u_frame_number: 1,
-                    front: 1,
-                    up: 1,
-                    left: 0,
-                    flip: 0,
-                    turn4: 0,
-                    turn5: 0,
-                    turn6: 0,
-                },
-                Position {
-                    x: 10.0,
-                    y: 0.0,
-                    z: 0.0,
-                    w: 0.0,
-                    p: 0.0,
-                    r: 0.0,
-                    ext1: 0.0,
-                    ext2: 0.0,
-                    ext3: 0.0,
-                },
-                SpeedType::MMSec,
-                30.0,
-                TermType::FINE,
-                1,
-            )
-        )),
-        PacketPriority::Standard,
-    ) {
-        Ok(_id) => {
-            // driver.wait_on_command_completion(_id).await
-        },
-        Err(e) => return Err(FrcError::FailedToSend(e)),
-    };
+    let delta = Position { x: 10.0, ..Default::default() };
+    let configuration = Configuration { left: 0, ..Default::default() };
+    match driver.move_relative(delta, configuration, 30.0, TermType::FINE)?.await {
+        Ok(sequence_id) => println!("✓ Move {} complete", sequence_id),
+        Err(e) => eprintln!("✗ Move failed: {}", e),
+    }
 
 
     // Abort and disconnect with response handling
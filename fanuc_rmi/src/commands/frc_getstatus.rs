@@ -26,4 +26,40 @@ pub struct FrcGetStatusResponse {
     // Not in B-84184EN_02 docs, but Robot CRX-30iA returns it. 
     #[serde(rename = "Override", default)]
     pub override_value: u32,
-}
\ No newline at end of file
+    /// Motion instructions queued or executing and not yet completed, out of
+    /// the simulator's instruction ring buffer (200 by default). Only the
+    /// simulator reports it; `None` from a real controller.
+    #[serde(rename = "BufferOccupancy", default, skip_serializing_if = "Option::is_none")]
+    pub buffer_occupancy: Option<u32>,
+}
+/// The state fields of an `FRC_GetStatus` response, with flags decoded.
+///
+/// Leaves out `ErrorID` and `NextSequenceID`: the latter advances with every
+/// instruction, so it would make every status look like a change.
+#[cfg_attr(feature = "DTO", crate::mirror_dto)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RobotStatus {
+    pub servo_ready: bool,
+    pub tp_mode: bool,
+    pub rmi_motion_status: i8,
+    pub program_status: i8,
+    pub single_step_mode: bool,
+    pub number_utool: i8,
+    pub number_uframe: i8,
+    pub override_value: u32,
+}
+
+impl From<&FrcGetStatusResponse> for RobotStatus {
+    fn from(response: &FrcGetStatusResponse) -> Self {
+        Self {
+            servo_ready: response.servo_ready != 0,
+            tp_mode: response.tp_mode != 0,
+            rmi_motion_status: response.rmi_motion_status,
+            program_status: response.program_status,
+            single_step_mode: response.single_step_mode != 0,
+            number_utool: response.number_utool,
+            number_uframe: response.number_uframe,
+            override_value: response.override_value,
+        }
+    }
+}
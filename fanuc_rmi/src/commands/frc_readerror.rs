@@ -29,7 +29,7 @@ impl Default for FrcReadError {
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct FrcReadErrorResponse {
     #[serde(rename = "ErrorID")]
-    pub error_id: u16,
+    pub error_id: u32,
     #[serde(rename = "Count", default)]
     pub count: u8,
     #[serde(rename = "ErrorData", default)]
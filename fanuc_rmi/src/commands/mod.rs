@@ -24,6 +24,7 @@ mod frc_writepositionregister;
 mod frc_reset;
 mod frc_readtcpspeed;
 mod frc_unknown;
+mod sim_mode;
 
 pub use frc_initialize::*;
 pub use frc_readerror::*;
@@ -51,6 +52,7 @@ pub use frc_writepositionregister::*;
 pub use frc_reset::*;
 pub use frc_readtcpspeed::*;
 pub use frc_unknown::*;
+pub use sim_mode::*;
 
 #[cfg(feature = "DTO")]
 pub mod dto {
@@ -61,6 +63,7 @@ pub mod dto {
     pub use super::frc_continue::FrcContinueResponseDto as FrcContinueResponse;
     pub use super::frc_setuframeutool::FrcSetUFrameUToolDto as FrcSetUFrameUTool;
     pub use super::frc_getstatus::FrcGetStatusResponseDto as FrcGetStatusResponse;
+    pub use super::frc_getstatus::RobotStatusDto as RobotStatus;
     pub use super::frc_readuframedata::FrcReadUFrameDataDto as FrcReadUFrameData;
     pub use super::frc_writeuframedata::FrcWriteUFrameDataDto as FrcWriteUFrameData;
     pub use super::frc_readutooldata::FrcReadUToolDataDto as FrcReadUToolData;
@@ -99,6 +102,9 @@ pub mod dto {
     pub use super::frc_readpositionregister::FrcReadPositionRegisterResponseDto as FrcReadPositionRegisterResponse;
     pub use super::frc_writepositionregister::FrcWritePositionRegisterResponseDto as FrcWritePositionRegisterResponse;
     pub use super::frc_unknown::FrcUnknownResponseDto as FrcUnknownResponse;
+    pub use super::sim_mode::SimulatorModeDto as SimulatorMode;
+    pub use super::sim_mode::SimModeDto as SimMode;
+    pub use super::sim_mode::SimModeResponseDto as SimModeResponse;
 }
 
 
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// How the simulator times motion instructions.
+#[cfg_attr(feature = "DTO", crate::mirror_dto)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulatorMode {
+    /// Positions jump to the target and the response is sent at once.
+    Immediate,
+    /// Motions take as long as their distance and speed dictate.
+    Realtime,
+}
+
+/// Query or switch the simulator's mode (`SIM_Mode`).
+///
+/// Simulator-only: a real controller answers with `Unknown`. Leaving `mode`
+/// empty just reads the current mode. A switch applies to motions that
+/// start after it; one already executing keeps its timing.
+#[cfg_attr(feature = "DTO", crate::mirror_dto)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SimMode {
+    #[serde(rename = "Mode", default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<SimulatorMode>,
+}
+
+impl SimMode {
+    #[allow(unused)]
+    pub fn new(mode: Option<SimulatorMode>) -> Self {
+        Self { mode }
+    }
+}
+
+#[cfg_attr(feature = "DTO", crate::mirror_dto)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SimModeResponse {
+    #[serde(rename = "ErrorID")]
+    pub error_id: u32,
+    /// The mode in effect after the command.
+    #[serde(rename = "Mode")]
+    pub mode: SimulatorMode,
+}
@@ -1,30 +1,113 @@
 use serde::{Deserialize, Serialize};
 use tokio::{
-    io::{split, AsyncReadExt, AsyncWriteExt, ReadHalf, WriteHalf},
-    net::TcpStream,
-    sync::{broadcast, mpsc, Mutex},
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{tcp::{OwnedReadHalf, OwnedWriteHalf}, TcpStream},
+    sync::{broadcast, mpsc, oneshot, watch, Mutex},
     time::sleep,
 };
 
-use tracing::{debug, error, info};
+use tracing::{Instrument, Span};
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::Duration;
 use std::time::Instant;
 
+/// Emit a `tracing` event if the driver's [`LogLevel`] admits `level`.
+///
+/// `log_event!(log_level, Warn, "...")` takes the same field and format
+/// arguments as [`tracing::event!`]. Nothing is emitted without the
+/// `logging` feature.
+///
+/// [`LogLevel`]: crate::drivers::LogLevel
+macro_rules! log_event {
+    (@emit $log_level:expr, $ours:ident, $theirs:ident, $($arg:tt)+) => {
+        if cfg!(feature = "logging") && $log_level >= $crate::drivers::LogLevel::$ours {
+            tracing::event!(tracing::Level::$theirs, $($arg)+);
+        }
+    };
+    ($log_level:expr, Error, $($arg:tt)+) => { log_event!(@emit $log_level, Error, ERROR, $($arg)+) };
+    ($log_level:expr, Warn, $($arg:tt)+) => { log_event!(@emit $log_level, Warn, WARN, $($arg)+) };
+    ($log_level:expr, Info, $($arg:tt)+) => { log_event!(@emit $log_level, Info, INFO, $($arg)+) };
+    ($log_level:expr, Debug, $($arg:tt)+) => { log_event!(@emit $log_level, Debug, DEBUG, $($arg)+) };
+    ($log_level:expr, Trace, $($arg:tt)+) => { log_event!(@emit $log_level, Trace, TRACE, $($arg)+) };
+}
+
 // Global request ID counter
 static REQUEST_COUNTER: AtomicU64 = AtomicU64::new(1);
 
+/// Caller waiting on a command's response, if any.
+type CommandWaiter = Option<oneshot::Sender<CommandResponse>>;
+
+/// Commands written to the controller and not yet answered.
+///
+/// Entries are registered while holding `fanuc_write`, so ticket order is
+/// wire order. The controller answers commands of one type in order, so a
+/// response completes the oldest entry for its name. An `Unknown` response
+/// names no command and completes the oldest entry overall. A `None` waiter
+/// marks a command sent without anyone awaiting the answer. Each entry holds
+/// the command's span (see [`FanucDriver::packet_span`]), which closes when
+/// the entry is completed or removed.
+#[derive(Debug, Default)]
+struct PendingCommands {
+    next_ticket: u64,
+    by_name: HashMap<&'static str, VecDeque<(u64, CommandWaiter, Span)>>,
+}
+
+impl PendingCommands {
+    fn register(&mut self, name: &'static str, waiter: CommandWaiter, span: Span) -> u64 {
+        let ticket = self.next_ticket;
+        self.next_ticket += 1;
+        self.by_name.entry(name).or_default().push_back((ticket, waiter, span));
+        ticket
+    }
+
+    /// Forget an entry whose command failed to send or was given up on.
+    fn remove(&mut self, name: &'static str, ticket: u64) {
+        if let Some(queue) = self.by_name.get_mut(name) {
+            queue.retain(|(t, _, _)| *t != ticket);
+            if queue.is_empty() {
+                self.by_name.remove(name);
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.by_name.values().map(VecDeque::len).sum()
+    }
+
+    /// Take the entry `response` answers, returning its waiter if any.
+    fn complete(&mut self, response: &CommandResponse) -> Option<oneshot::Sender<CommandResponse>> {
+        let name = match response {
+            CommandResponse::Unknown(_) => self
+                .by_name
+                .iter()
+                .filter_map(|(name, queue)| queue.front().map(|(ticket, _, _)| (*ticket, *name)))
+                .min()
+                .map(|(_, name)| name)?,
+            _ => response.name(),
+        };
+        self.by_name.get_mut(name)?.pop_front()?.1
+    }
+}
+
 // Prefer importing from the module rather than re-exporting from here
 // Prefer downstream crates to reference modules directly (crate::commands, crate::instructions, crate::dto)
 use crate::commands::*;
 use crate::packets::*;
-use crate::FrcError;
+use crate::{FanucErrorCode, FrameData, FrcError};
 
+use super::ConnectionHealth;
 use super::DriverState;
 use super::FanucDriverConfig;
+use super::LogLevel;
+use super::StartupReport;
+use super::recording::{Direction, SessionRecorder};
+use super::stats::{DriverCounters, DriverStats};
+use super::TrajectoryBuffer;
+use crate::instructions::FrcJointMotionJRep;
+use crate::{Position, SpeedType, TermType};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct DriverPacket {
@@ -44,6 +127,8 @@ impl DriverPacket {
 pub struct ProtocolError {
     pub error_type: String,
     pub message: String,
+    /// Non-zero `ErrorID` found in the failing packet, if any.
+    pub error_id: Option<u32>,
     pub raw_data: Option<String>,
 }
 
@@ -60,9 +145,15 @@ pub struct FanucDriver {
     /// sequence IDs and sent to the controller. This allows correlating send_packet()
     /// calls (via request_id) with actual sequence IDs.
     pub sent_instruction_tx: tokio::sync::broadcast::Sender<SentInstructionInfo>,
+    /// Broadcast channel for robot status changes
+    ///
+    /// Carries the new [`RobotStatus`] whenever a successful `FRC_GetStatus`
+    /// response differs from the previous one. The first response only sets
+    /// the baseline; read it with [`FanucDriver::robot_status`].
+    pub status_tx: tokio::sync::broadcast::Sender<RobotStatus>,
     next_available_sequence_number: Arc<std::sync::Mutex<u32>>, // could prop be taken out and just a varible in the send_queue function
-    fanuc_write: Arc<Mutex<WriteHalf<TcpStream>>>,
-    fanuc_read: Arc<Mutex<ReadHalf<TcpStream>>>,
+    fanuc_write: Arc<Mutex<OwnedWriteHalf>>,
+    fanuc_read: Arc<Mutex<OwnedReadHalf>>,
     queue_tx: mpsc::Sender<DriverPacket>,
     pub connected: Arc<Mutex<bool>>,
     completed_packet_channel: Arc<Mutex<broadcast::Receiver<CompletedPacketReturnInfo>>>,
@@ -70,6 +161,74 @@ pub struct FanucDriver {
     /// When program_pause is called, in-flight instructions are stored here.
     /// When program_resume is called, instructions are read from here for replay.
     program_pause_instructions: Arc<std::sync::Mutex<Vec<Instruction>>>,
+    /// Connection health published by the heartbeat task (and the reader on socket close).
+    health_tx: Arc<watch::Sender<ConnectionHealth>>,
+    /// Raw session recorder, present when `config.record` is set.
+    recorder: Option<Arc<SessionRecorder>>,
+    /// Reported positions, present when `config.trajectory_capacity` is set.
+    trajectory: Option<Arc<std::sync::Mutex<TrajectoryBuffer>>>,
+    /// Outstanding commands, matched to their responses in `process_line`.
+    pending_commands: Arc<std::sync::Mutex<PendingCommands>>,
+    /// Most recent status reported by `FRC_GetStatus`.
+    last_status: Arc<std::sync::Mutex<Option<RobotStatus>>>,
+    /// Set by a successful `FRC_Initialize` response, cleared by `FRC_Abort`.
+    rmi_initialized: Arc<AtomicBool>,
+    /// Instructions queued in the driver or sent and not yet answered.
+    outstanding_instructions: Arc<watch::Sender<usize>>,
+    /// Packets written and read on the data port, for [`FanucDriver::stats`].
+    counters: Arc<DriverCounters>,
+    /// RMI version the controller reported in the `FRC_Connect` handshake.
+    controller_version: RmiVersion,
+}
+
+/// Record a command about to be written. Call while holding `fanuc_write`.
+fn register_command(
+    pending_commands: &std::sync::Mutex<PendingCommands>,
+    name: &'static str,
+    waiter: CommandWaiter,
+    span: Span,
+) -> Option<u64> {
+    pending_commands.lock().ok().map(|mut pending| pending.register(name, waiter, span))
+}
+
+/// Undo [`register_command`].
+fn unregister_command(pending_commands: &std::sync::Mutex<PendingCommands>, name: &'static str, ticket: Option<u64>) {
+    if let (Ok(mut pending), Some(ticket)) = (pending_commands.lock(), ticket) {
+        pending.remove(name, ticket);
+    }
+}
+
+/// Removes a command's [`PendingCommands`] entry when dropped, so a caller
+/// that stops waiting, whether on timeout or because its future was dropped,
+/// leaves no registration behind. A no-op once the response has taken the
+/// entry.
+struct PendingCommandGuard<'a> {
+    pending_commands: &'a std::sync::Mutex<PendingCommands>,
+    name: &'static str,
+    ticket: Option<u64>,
+}
+
+impl Drop for PendingCommandGuard<'_> {
+    fn drop(&mut self) {
+        unregister_command(self.pending_commands, self.name, self.ticket);
+    }
+}
+
+/// The error for a command the controller answered with a non-zero `ErrorID`.
+fn rejected(response: &CommandResponse) -> FrcError {
+    let error_id = match response {
+        CommandResponse::FrcReadUFrameData(resp) => resp.error_id,
+        CommandResponse::FrcReadUToolData(resp) => resp.error_id,
+        CommandResponse::FrcReadAIN(resp) => resp.error_id,
+        CommandResponse::FrcReadGIN(resp) => resp.error_id,
+        CommandResponse::FrcWriteAOUT(resp) => resp.error_id,
+        CommandResponse::FrcWriteGOUT(resp) => resp.error_id,
+        CommandResponse::SimMode(resp) => resp.error_id,
+        _ => 0,
+    };
+    FrcError::FanucErrorCode(
+        FanucErrorCode::try_from(error_id).unwrap_or(FanucErrorCode::UnrecognizedFrcError),
+    )
 }
 
 impl FanucDriver {
@@ -97,7 +256,8 @@ impl FanucDriver {
     /// # Errors
     ///
     /// This function will return an error if:
-    /// - The connection to the initial address fails after the specified number of retries.
+    /// - The connection to the initial address fails after the specified number of retries
+    ///   (`FrcError::Initialization`, whose `source()` is the last `std::io::Error`).
     /// - The connection packet cannot be serialized.
     /// - The connection packet cannot be sent.
     /// - No response is received from the controller.
@@ -122,49 +282,63 @@ impl FanucDriver {
     /// }
     /// ```
     pub async fn connect(config: FanucDriverConfig) -> Result<FanucDriver, FrcError> {
-        info!("Connecting fanuc");
+        log_event!(config.log_level, Info, "Connecting to {}:{}", config.addr, config.port);
+        let recorder = match &config.record {
+            Some(path) => Some(Arc::new(SessionRecorder::create(path).map_err(|e| {
+                let message = format!("Could not create recording {}: {}", path.display(), e);
+                FrcError::Initialization(message, Some(e.into()))
+            })?)),
+            None => None,
+        };
         let init_addr = format!("{}:{}", config.addr, config.port);
-        let mut stream = connect_with_retries(&init_addr, 3).await?;
+        let mut stream = connect_with_retries(&init_addr, 3, config.log_level, config.tcp_nodelay).await?;
 
         let packet = Communication::FrcConnect {};
-        let serialized_packet = serde_json::to_string(&packet).map_err(|_| {
+        let serialized_packet = serde_json::to_string(&packet).map_err(|e| {
             FrcError::Serialization(
                 "Communication: Connect packet didn't serialize correctly".to_string(),
+                Some(e.into()),
             )
         })? + "\r\n";
 
         stream
             .write_all(serialized_packet.as_bytes())
             .await
-            .map_err(|e| FrcError::FailedToSend(e.to_string()))?;
+            .map_err(|e| FrcError::FailedToSend(e.to_string(), Some(e.into())))?;
 
         let mut buffer = vec![0; 2048];
         let n = stream
             .read(&mut buffer)
             .await
-            .map_err(|e| FrcError::FailedToReceive(e.to_string()))?;
+            .map_err(|e| FrcError::FailedToReceive(e.to_string(), Some(e.into())))?;
 
         if n == 0 {
             return Err(FrcError::Disconnected());
         }
 
         let response = String::from_utf8_lossy(&buffer[..n]);
-        info!("Sent: {}Received: {}", &serialized_packet, &response);
+        log_event!(config.log_level, Trace, "Sent: {}", serialized_packet.trim_end());
+        log_event!(config.log_level, Trace, "Received: {}", response.trim_end());
+        if let Some(recorder) = &recorder {
+            recorder.record(Direction::Sent, &serialized_packet);
+            recorder.record(Direction::Received, &response);
+        }
 
         let res: CommunicationResponse = serde_json::from_str(&response)
-            .map_err(|e| FrcError::Serialization(format!("Could not parse response: {}", e)))?;
+            .map_err(|e| FrcError::Serialization(format!("Could not parse response: {}", e), Some(e.into())))?;
 
-        let new_port = if let CommunicationResponse::FrcConnect(res) = res {
-            res.port_number
+        let (new_port, controller_version) = if let CommunicationResponse::FrcConnect(res) = res {
+            (res.port_number, res.version())
         } else {
             return Err(FrcError::UnrecognizedPacket);
         };
+        log_event!(config.log_level, Info, "Controller reports RMI v{}", controller_version);
 
         drop(stream);
         let init_addr = format!("{}:{}", config.addr, new_port);
-        let stream = connect_with_retries(&init_addr, 3).await?;
+        let stream = connect_with_retries(&init_addr, 3, config.log_level, config.tcp_nodelay).await?;
 
-        let (read_half, write_half) = split(stream);
+        let (read_half, write_half) = stream.into_split();
         let read_half = Arc::new(Mutex::new(read_half));
         let write_half = Arc::new(Mutex::new(write_half));
         let (message_channel, _rx) = broadcast::channel(100);
@@ -183,12 +357,22 @@ impl FanucDriver {
         // Error channel for protocol errors
         let (error_tx, _) = broadcast::channel(100);
 
+        let (health_tx, _) = watch::channel(ConnectionHealth::Healthy);
+        let (outstanding_instructions, _) = watch::channel(0);
+
+        let (status_tx, _) = broadcast::channel(100);
+
+        let trajectory = config
+            .trajectory_capacity
+            .map(|capacity| Arc::new(std::sync::Mutex::new(TrajectoryBuffer::new(capacity))));
+
         let driver = Self {
             config,
             log_channel: message_channel,
             response_tx,
             error_tx,
             sent_instruction_tx,
+            status_tx,
             next_available_sequence_number,
             fanuc_write: write_half,
             fanuc_read: read_half,
@@ -196,6 +380,15 @@ impl FanucDriver {
             connected,
             completed_packet_channel,
             program_pause_instructions: Arc::new(std::sync::Mutex::new(Vec::new())),
+            health_tx: Arc::new(health_tx),
+            recorder,
+            trajectory,
+            pending_commands: Arc::new(std::sync::Mutex::new(PendingCommands::default())),
+            last_status: Arc::new(std::sync::Mutex::new(None)),
+            rmi_initialized: Arc::new(AtomicBool::new(false)),
+            outstanding_instructions: Arc::new(outstanding_instructions),
+            counters: Arc::new(DriverCounters::default()),
+            controller_version,
         };
 
         let driver_clone1 = driver.clone();
@@ -206,57 +399,80 @@ impl FanucDriver {
                 .send_queue_to_controller(queue_rx, return_info)
                 .await
             {
-                error!("send_queue failed: {}", e);
+                log_event!(driver_clone1.config.log_level, Error, "send_queue failed: {}", e);
             }
         });
 
         tokio::spawn(async move {
             if let Err(e) = driver_clone2.read_responses(completed_packet_tx).await {
-                error!("read_queue_responses failed: {}", e);
+                log_event!(driver_clone2.config.log_level, Error, "read_queue_responses failed: {}", e);
             }
         });
 
+        if let Some(interval_ms) = driver.config.heartbeat_interval_ms {
+            let driver_clone3 = driver.clone();
+            let max_missed = driver.config.heartbeat_max_missed;
+            tokio::spawn(async move {
+                driver_clone3
+                    .run_heartbeat(Duration::from_millis(interval_ms), max_missed)
+                    .await;
+            });
+        }
+
         Ok(driver)
     }
 
-    /// Log an error message (always shown if logging feature enabled)
+    /// Log an error message (emitted at every log_level)
     async fn log_error<T: Into<String>>(&self, message: T) {
-        let message = format!("[ERROR] {}", message.into());
-        let _ = self.log_channel.send(message.clone());
-        #[cfg(feature = "logging")]
-        if self.config.log_level >= crate::drivers::driver_config::LogLevel::Error {
-            eprintln!("{}", message);
-        }
+        let message = message.into();
+        log_event!(self.config.log_level, Error, "{}", message);
+        let _ = self.log_channel.send(format!("[ERROR] {}", message));
     }
 
-    /// Log a warning message (shown if log_level >= Warn)
+    /// Log a warning message (emitted if log_level >= Warn)
     async fn log_warn<T: Into<String>>(&self, message: T) {
-        let message = format!("[WARN] {}", message.into());
-        let _ = self.log_channel.send(message.clone());
-        #[cfg(feature = "logging")]
-        if self.config.log_level >= crate::drivers::driver_config::LogLevel::Warn {
-            println!("{}", message);
-        }
+        let message = message.into();
+        log_event!(self.config.log_level, Warn, "{}", message);
+        let _ = self.log_channel.send(format!("[WARN] {}", message));
     }
 
-    /// Log an info message (shown if log_level >= Info, which is default)
+    /// Log a lifecycle message (emitted if log_level >= Info, which is default)
     async fn log_info<T: Into<String>>(&self, message: T) {
-        let message = format!("[INFO] {}", message.into());
-        let _ = self.log_channel.send(message.clone());
-        #[cfg(feature = "logging")]
-        if self.config.log_level >= crate::drivers::driver_config::LogLevel::Info {
-            println!("{}", message);
-        }
+        let message = message.into();
+        log_event!(self.config.log_level, Info, "{}", message);
+        let _ = self.log_channel.send(format!("[INFO] {}", message));
     }
 
-    /// Log a debug message (only shown if log_level == Debug)
+    /// Log a per-packet message (emitted if log_level >= Debug)
     async fn log_debug<T: Into<String>>(&self, message: T) {
-        let message = format!("[DEBUG] {}", message.into());
-        let _ = self.log_channel.send(message.clone());
+        let message = message.into();
+        log_event!(self.config.log_level, Debug, "{}", message);
+        let _ = self.log_channel.send(format!("[DEBUG] {}", message));
+    }
+
+    /// Log a raw packet payload (emitted only if log_level == Trace)
+    async fn log_trace<T: Into<String>>(&self, message: T) {
+        let message = message.into();
+        log_event!(self.config.log_level, Trace, "{}", message);
+        let _ = self.log_channel.send(format!("[TRACE] {}", message));
+    }
+
+    /// Span covering one packet from send until its response arrives, so
+    /// events logged meanwhile carry the packet's name and sequence id.
+    ///
+    /// Spans are only opened with the `logging` feature and a `log_level` of
+    /// `Debug` or `Trace`; otherwise this returns [`Span::none`], which allocates nothing.
+    fn packet_span(&self, name: &'static str, sequence_id: Option<u32>) -> Span {
         #[cfg(feature = "logging")]
-        if self.config.log_level >= crate::drivers::driver_config::LogLevel::Debug {
-            println!("{}", message);
+        if self.config.log_level >= LogLevel::Debug {
+            let span = tracing::debug_span!("rmi_packet", command = name, sequence_id = tracing::field::Empty);
+            if let Some(sequence_id) = sequence_id {
+                span.record("sequence_id", sequence_id);
+            }
+            return span;
         }
+        let _ = (name, sequence_id);
+        Span::none()
     }
 
     /// Send an abort command to the FANUC controller
@@ -329,6 +545,20 @@ impl FanucDriver {
         Ok(())
     }
 
+    /// Drop the instructions queued in the driver that have not been sent
+    /// to the controller yet.
+    ///
+    /// Instructions already in flight are left to resolve on the controller,
+    /// as errors after an abort. Flushed instructions are never assigned a
+    /// sequence ID, so waiting on their request IDs does not complete. Pair
+    /// with [`abort`](Self::abort) so queued motions don't drain afterwards.
+    pub fn flush_pending(&self) -> Result<(), String> {
+        let packet = SendPacket::DriverCommand(DriverCommand::Flush);
+        // Queued behind the instructions sent before it, so all of those are dropped
+        self.send_packet(packet, PacketPriority::Standard)?;
+        Ok(())
+    }
+
     /// Send a reset command to the FANUC controller
     ///
     /// Returns the request ID for tracking this request.
@@ -851,6 +1081,607 @@ impl FanucDriver {
         .map_err(|_| "Timeout waiting for get status response".to_string())?
     }
 
+    /// Number of motion instructions the controller holds but has not
+    /// completed, read from `FRC_GetStatus`.
+    ///
+    /// Use it to pace sends against the controller's instruction buffer.
+    /// Returns `Ok(None)` when the controller does not report its buffer
+    /// (only the simulator does).
+    pub async fn buffer_occupancy(&self) -> Result<Option<u32>, String> {
+        Ok(self.get_status().await?.buffer_occupancy)
+    }
+
+    /// Send a command and wait for the controller's answer to it.
+    ///
+    /// The command is written straight to the controller (commands never
+    /// queue behind instructions) and the matching `CommandResponse` is
+    /// returned. Responses carry no request ID, so they are matched by
+    /// command name in send order; overlapping calls, including ones for the
+    /// same command, each get their own response.
+    ///
+    /// A response with a non-zero `ErrorID` is still returned as `Ok`; check
+    /// the payload's `error_id`. A `CommandResponse::Unknown`, which the
+    /// controller sends for a command it rejects outright, is returned as
+    /// `FrcError::UnsupportedCommand`.
+    ///
+    /// Cancel-safe: dropping the returned future, e.g. from `select!` or
+    /// because the client it serves went away, removes the command from the
+    /// driver's pending set just as a timeout does.
+    ///
+    /// # Errors
+    /// * `FrcError::FailedToSend` - the command could not be written
+    /// * `FrcError::Initialization` - an `FRC_Initialize` group mask names a
+    ///   group outside [`FanucDriverConfig::group_count`]
+    /// * `FrcError::UnsupportedCommand` - the controller does not recognize
+    ///   the command, or could not parse it
+    /// * `FrcError::FailedToReceive` - no response within the command's
+    ///   timeout (see [`FanucDriverConfig::timeouts`])
+    /// * `FrcError::Disconnected` - the connection closed before the response
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use fanuc_rmi::drivers::FanucDriver;
+    /// # use fanuc_rmi::commands::FrcGetUFrameUTool;
+    /// # use fanuc_rmi::packets::{Command, CommandResponse};
+    /// # async fn example(driver: &FanucDriver) -> Result<(), fanuc_rmi::FrcError> {
+    /// if let CommandResponse::FrcGetStatus(status) = driver.command(Command::FrcGetStatus).await? {
+    ///     println!("Servo ready: {}", status.servo_ready);
+    /// }
+    /// let frames = driver.command(FrcGetUFrameUTool::new(None)).await?;
+    /// println!("{:?}", frames);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn command<C: Into<Command>>(&self, cmd: C) -> Result<CommandResponse, FrcError> {
+        let cmd = cmd.into();
+        let timeout = self.config.command_timeout(cmd.kind());
+        self.command_with_timeout(cmd, timeout).await
+    }
+
+    /// Reject a command the controller is known to refuse with this
+    /// configuration, before it is sent.
+    fn check_command(&self, cmd: &Command) -> Result<(), String> {
+        match cmd {
+            Command::FrcInitialize(init) => self.config.check_group_mask(init.group_mask),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reject an instruction using a feature the controller's RMI version
+    /// lacks, before it is queued.
+    fn check_instruction(&self, instruction: &Instruction) -> Result<(), String> {
+        if instruction.no_blend() && self.controller_version < RmiVersion::NO_BLEND {
+            return Err(format!(
+                "{} with NoBlend requires RMI v{} or later, the controller reports v{}",
+                instruction.name(),
+                RmiVersion::NO_BLEND,
+                self.controller_version
+            ));
+        }
+        Ok(())
+    }
+
+    /// RMI version the controller reported when the driver connected.
+    pub fn controller_version(&self) -> RmiVersion {
+        self.controller_version
+    }
+
+    /// [`command`](Self::command) with a custom response timeout.
+    pub async fn command_with_timeout<C: Into<Command>>(
+        &self,
+        cmd: C,
+        timeout: Duration,
+    ) -> Result<CommandResponse, FrcError> {
+        if !*self.connected.lock().await {
+            return Err(FrcError::Disconnected());
+        }
+        let cmd = cmd.into();
+        self.check_command(&cmd).map_err(|message| FrcError::Initialization(message, None))?;
+        let name = cmd.name();
+        let packet = SendPacket::Command(cmd);
+        self.log_debug(format!("📤 Sending command: {:?}", packet)).await;
+
+        let serialized_packet = serde_json::to_string(&packet)
+            .map_err(|e| FrcError::Serialization(e.to_string(), Some(e.into())))?
+            + "\r\n";
+
+        let span = self.packet_span(name, None);
+        let (response_tx, response_rx) = oneshot::channel();
+        // Held until this future completes or is dropped
+        let _guard = {
+            let mut stream = self.fanuc_write.lock().await;
+            let guard = PendingCommandGuard {
+                pending_commands: &self.pending_commands,
+                name,
+                ticket: register_command(&self.pending_commands, name, Some(response_tx), span.clone()),
+            };
+            if let Err(e) = stream.write_all(serialized_packet.as_bytes()).await {
+                let err = FrcError::FailedToSend(e.to_string(), Some(e.into()));
+                self.log_error(err.to_string()).await;
+                return Err(err);
+            }
+            self.log_trace(format!("Sent: {}", serialized_packet.trim_end())).await;
+            self.counters.sent();
+            if let Some(recorder) = &self.recorder {
+                recorder.record(Direction::Sent, &serialized_packet);
+            }
+            guard
+        };
+
+        match tokio::time::timeout(timeout, response_rx).instrument(span.clone()).await {
+            Ok(Ok(CommandResponse::Unknown(resp))) => {
+                let code = FanucErrorCode::try_from(resp.error_id).unwrap_or(FanucErrorCode::UnrecognizedFrcError);
+                let err = FrcError::UnsupportedCommand(name.to_string(), code);
+                span.in_scope(|| log_event!(self.config.log_level, Warn, "{}", err));
+                Err(err)
+            }
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => Err(FrcError::Disconnected()),
+            Err(_) => {
+                span.in_scope(|| {
+                    log_event!(self.config.log_level, Warn, "Timed out after {:?} waiting for the response", timeout)
+                });
+                // Some commands are never answered (e.g. reading UFrame 0);
+                // the guard drops the entry so it can't swallow a later response.
+                Err(FrcError::FailedToReceive(format!("Timeout waiting for {} response", name), None))
+            }
+        }
+    }
+
+    /// Read user frame `frame_number` (group 1).
+    ///
+    /// The controller never answers a read of UFrame 0 (the world frame), so
+    /// that read times out after the `FRC_ReadUFrameData` timeout (1 s by
+    /// default, see [`FanucDriverConfig::timeouts`]) and returns
+    /// `FrcError::NotReadable` instead of hanging.
+    ///
+    /// # Errors
+    /// * `FrcError::NotReadable` - no answer, as for frame 0
+    /// * `FrcError::FanucErrorCode` - the controller rejected the read
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use fanuc_rmi::drivers::FanucDriver;
+    /// # async fn example(driver: &FanucDriver) -> Result<(), fanuc_rmi::FrcError> {
+    /// let frame = driver.read_uframe(1).await?;
+    /// println!("UFrame 1 origin: {}, {}, {}", frame.x, frame.y, frame.z);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_uframe(&self, frame_number: u8) -> Result<FrameData, FrcError> {
+        match self.command(FrcReadUFrameData::new(None, frame_number as i8)).await {
+            Ok(CommandResponse::FrcReadUFrameData(resp)) if resp.error_id == 0 => Ok(resp.frame),
+            Ok(response) => Err(rejected(&response)),
+            Err(FrcError::FailedToReceive(..)) => Err(FrcError::NotReadable(format!(
+                "UFrame {} did not answer",
+                frame_number
+            ))),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Read user tool `tool_number` (group 1).
+    ///
+    /// Tool 0 does not exist; the controller rejects it with error 2556950,
+    /// returned as `FrcError::FanucErrorCode`.
+    ///
+    /// # Errors
+    /// * `FrcError::FanucErrorCode` - the controller rejected the read
+    /// * `FrcError::NotReadable` - no answer within the `FRC_ReadUToolData` timeout
+    pub async fn read_utool(&self, tool_number: u8) -> Result<FrameData, FrcError> {
+        match self.command(FrcReadUToolData::new(None, tool_number as i8)).await {
+            Ok(CommandResponse::FrcReadUToolData(resp)) if resp.error_id == 0 => Ok(resp.frame),
+            Ok(response) => Err(rejected(&response)),
+            // How the controller rejects tool 0
+            Err(FrcError::UnsupportedCommand(_, code)) => Err(FrcError::FanucErrorCode(code)),
+            Err(FrcError::FailedToReceive(..)) => Err(FrcError::NotReadable(format!(
+                "UTool {} did not answer",
+                tool_number
+            ))),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Read the controller's most recent error.
+    ///
+    /// The response's `error_id` is that error, 0 when there is none, and
+    /// `error_data` carries any text the controller attaches to it. Decode
+    /// the id with [`decode_error_id`](crate::decode_error_id).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use fanuc_rmi::drivers::FanucDriver;
+    /// # async fn example(driver: &FanucDriver) -> Result<(), fanuc_rmi::FrcError> {
+    /// let error = driver.read_error().await?;
+    /// if error.error_id != 0 {
+    ///     println!("Last error: {}", fanuc_rmi::format_error_id(error.error_id));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_error(&self) -> Result<FrcReadErrorResponse, FrcError> {
+        match self.command(FrcReadError::new(None)).await? {
+            CommandResponse::FrcReadError(resp) => Ok(resp),
+            response => Err(rejected(&response)),
+        }
+    }
+
+    /// Read analog input `port_number`.
+    ///
+    /// # Errors
+    /// * `FrcError::FanucErrorCode` - the controller rejected the read
+    pub async fn read_ain(&self, port_number: u16) -> Result<f64, FrcError> {
+        match self.command(FrcReadAIN::new(port_number)).await? {
+            CommandResponse::FrcReadAIN(resp) if resp.error_id == 0 => Ok(resp.port_value),
+            response => Err(rejected(&response)),
+        }
+    }
+
+    /// Read group input `port_number`.
+    ///
+    /// # Errors
+    /// * `FrcError::FanucErrorCode` - the controller rejected the read
+    pub async fn read_gin(&self, port_number: u16) -> Result<u32, FrcError> {
+        match self.command(FrcReadGIN::new(port_number)).await? {
+            CommandResponse::FrcReadGIN(resp) if resp.error_id == 0 => Ok(resp.port_value),
+            response => Err(rejected(&response)),
+        }
+    }
+
+    /// Set analog output `port_number` to `value`.
+    ///
+    /// # Errors
+    /// * `FrcError::FanucErrorCode` - the controller rejected the write
+    pub async fn write_aout(&self, port_number: u16, value: f64) -> Result<(), FrcError> {
+        match self.command(FrcWriteAOUT::new(port_number, value)).await? {
+            CommandResponse::FrcWriteAOUT(resp) if resp.error_id == 0 => Ok(()),
+            response => Err(rejected(&response)),
+        }
+    }
+
+    /// Set group output `port_number` to `value`.
+    ///
+    /// # Errors
+    /// * `FrcError::FanucErrorCode` - the controller rejected the write
+    pub async fn write_gout(&self, port_number: u16, value: u32) -> Result<(), FrcError> {
+        match self.command(FrcWriteGOUT::new(port_number, value)).await? {
+            CommandResponse::FrcWriteGOUT(resp) if resp.error_id == 0 => Ok(()),
+            response => Err(rejected(&response)),
+        }
+    }
+
+    /// Read each analog input in `ports`, in order, as `(port, value)`.
+    ///
+    /// The controller has no batch read, so this issues one `FRC_ReadAIN`
+    /// per port and stops at the first failure.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use fanuc_rmi::drivers::FanucDriver;
+    /// # async fn example(driver: &FanucDriver) -> Result<(), fanuc_rmi::FrcError> {
+    /// for (port, value) in driver.read_ain_batch(1..=4).await? {
+    ///     println!("AIN[{}] = {:.2}", port, value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_ain_batch(&self, ports: impl IntoIterator<Item = u16>) -> Result<Vec<(u16, f64)>, FrcError> {
+        let mut values = Vec::new();
+        for port in ports {
+            values.push((port, self.read_ain(port).await?));
+        }
+        Ok(values)
+    }
+
+    /// Read each group input in `ports`, in order, as `(port, value)`.
+    /// See [`read_ain_batch`](Self::read_ain_batch).
+    pub async fn read_gin_batch(&self, ports: impl IntoIterator<Item = u16>) -> Result<Vec<(u16, u32)>, FrcError> {
+        let mut values = Vec::new();
+        for port in ports {
+            values.push((port, self.read_gin(port).await?));
+        }
+        Ok(values)
+    }
+
+    /// Write each `(port, value)` analog output in order, stopping at the
+    /// first failure.
+    pub async fn write_aout_batch(&self, values: impl IntoIterator<Item = (u16, f64)>) -> Result<(), FrcError> {
+        for (port, value) in values {
+            self.write_aout(port, value).await?;
+        }
+        Ok(())
+    }
+
+    /// Write each `(port, value)` group output in order, stopping at the
+    /// first failure.
+    pub async fn write_gout_batch(&self, values: impl IntoIterator<Item = (u16, u32)>) -> Result<(), FrcError> {
+        for (port, value) in values {
+            self.write_gout(port, value).await?;
+        }
+        Ok(())
+    }
+
+    /// Set the speed override to `percent`, clamped to
+    /// [`FanucDriverConfig::override_range`] so that, by default, 0% (which
+    /// holds motion) and values over 100% are never sent.
+    ///
+    /// Returns the percentage applied. `FRC_SetOverRide` answers with only
+    /// an `ErrorID`, so this is the clamped value the controller accepted.
+    ///
+    /// # Errors
+    /// * `FrcError::FanucErrorCode` - the controller rejected the override
+    pub async fn set_override(&self, percent: u8) -> Result<u8, FrcError> {
+        let range = &self.config.override_range;
+        let applied = percent.clamp(*range.start(), *range.end());
+        if applied != percent {
+            log_event!(self.config.log_level, Warn, "Override {}% clamped to {}%", percent, applied);
+        }
+        match self.command(FrcSetOverRide::new(applied)).await? {
+            CommandResponse::FrcSetOverRide(resp) if resp.error_id == 0 => Ok(applied),
+            response => Err(rejected(&response)),
+        }
+    }
+
+    /// The simulator's current mode.
+    ///
+    /// # Errors
+    /// * `FrcError::UnsupportedCommand` - the peer is a real controller,
+    ///   which does not know `SIM_Mode`
+    pub async fn sim_mode(&self) -> Result<SimulatorMode, FrcError> {
+        self.exchange_sim_mode(None).await
+    }
+
+    /// Switch the simulator between immediate and realtime timing.
+    ///
+    /// Motions already executing keep the timing they started with; the new
+    /// mode applies from the next motion on. Returns the mode now in effect.
+    ///
+    /// # Errors
+    /// * `FrcError::UnsupportedCommand` - the peer is a real controller,
+    ///   which does not know `SIM_Mode`
+    pub async fn set_sim_mode(&self, mode: SimulatorMode) -> Result<SimulatorMode, FrcError> {
+        self.exchange_sim_mode(Some(mode)).await
+    }
+
+    async fn exchange_sim_mode(&self, mode: Option<SimulatorMode>) -> Result<SimulatorMode, FrcError> {
+        match self.command(SimMode::new(mode)).await? {
+            CommandResponse::SimMode(resp) if resp.error_id == 0 => Ok(resp.mode),
+            response => Err(rejected(&response)),
+        }
+    }
+
+    /// Hand a command response to the outstanding command it answers.
+    fn complete_command(&self, response: &CommandResponse) {
+        let waiter = match self.pending_commands.lock() {
+            Ok(mut pending) => pending.complete(response),
+            Err(_) => return,
+        };
+        if let Some(waiter) = waiter {
+            let _ = waiter.send(response.clone());
+        }
+    }
+
+    /// Packets sent and received on the data port since the driver
+    /// connected, and how many received lines failed to parse.
+    pub fn stats(&self) -> DriverStats {
+        self.counters.snapshot()
+    }
+
+    /// Number of instructions queued in the driver or sent to the
+    /// controller and not yet answered.
+    pub fn outstanding_instruction_count(&self) -> usize {
+        *self.outstanding_instructions.borrow()
+    }
+
+    /// Wait until every queued instruction has completed and the robot has
+    /// stopped moving.
+    ///
+    /// Resolves once no instruction is queued in the driver or awaiting its
+    /// response and `FRC_GetStatus` reports an empty instruction buffer.
+    /// RMI stays running between moves, so `RMIMotionStatus` alone does not
+    /// show the robot is idle. Use it after streaming a program, before
+    /// acting on the finished motion.
+    ///
+    /// # Errors
+    /// * `FrcError::FailedToReceive` - still moving after `timeout`, or the
+    ///   status could not be read
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use fanuc_rmi::drivers::FanucDriver;
+    /// # use fanuc_rmi::packets::{PacketPriority, SendPacket};
+    /// # async fn example(driver: &FanucDriver, program: Vec<SendPacket>) -> Result<(), fanuc_rmi::FrcError> {
+    /// for packet in program {
+    ///     driver.send_packet(packet, PacketPriority::Standard).map_err(|e| fanuc_rmi::FrcError::FailedToSend(e, None))?;
+    /// }
+    /// driver.wait_for_idle(Duration::from_secs(60)).await?;
+    /// println!("Program finished, closing the gripper");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_for_idle(&self, timeout: Duration) -> Result<(), FrcError> {
+        /// How often to re-check a buffer the controller still reports busy
+        const IDLE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+        let mut outstanding = self.outstanding_instructions.subscribe();
+        let idle = async {
+            loop {
+                outstanding.wait_for(|&count| count == 0).await.map_err(|_| FrcError::Disconnected())?;
+                let status = self.get_status().await.map_err(|e| FrcError::FailedToReceive(e, None))?;
+                if status.buffer_occupancy.unwrap_or(0) == 0 {
+                    return Ok(());
+                }
+                sleep(IDLE_POLL_INTERVAL).await;
+            }
+        };
+        tokio::time::timeout(timeout, idle)
+            .await
+            .map_err(|_| FrcError::FailedToReceive(format!("robot still moving after {:?}", timeout), None))?
+    }
+
+    /// Take `count` instructions off the outstanding count.
+    fn instructions_finished(&self, count: usize) {
+        if count > 0 {
+            self.outstanding_instructions.send_modify(|outstanding| *outstanding = outstanding.saturating_sub(count));
+        }
+    }
+
+    /// Empty the send queue, releasing the instructions in it.
+    fn drop_queued(&self, queue: &mut VecDeque<DriverPacket>) {
+        self.instructions_finished(queue.iter().filter(|p| matches!(p.packet, SendPacket::Instruction(_))).count());
+        queue.clear();
+    }
+
+    /// Number of commands written to the controller and not yet answered,
+    /// given up on, or cancelled.
+    pub fn pending_command_count(&self) -> usize {
+        self.pending_commands.lock().map(|pending| pending.len()).unwrap_or(0)
+    }
+
+    /// Whether `TCP_NODELAY` is set on the controller connection, as
+    /// configured by [`FanucDriverConfig::tcp_nodelay`].
+    pub async fn tcp_nodelay(&self) -> std::io::Result<bool> {
+        self.fanuc_write.lock().await.as_ref().nodelay()
+    }
+
+    /// Drop every outstanding command so its caller sees the disconnect.
+    fn fail_pending_commands(&self) {
+        if let Ok(mut pending) = self.pending_commands.lock() {
+            pending.by_name.clear();
+        }
+    }
+
+    /// Returns `true` unless the heartbeat (or the reader) has marked the
+    /// connection unhealthy.
+    ///
+    /// Without a configured `heartbeat_interval_ms` this only turns `false`
+    /// once the socket is closed.
+    pub fn is_healthy(&self) -> bool {
+        *self.health_tx.borrow() == ConnectionHealth::Healthy
+    }
+
+    /// Subscribe to connection health changes.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use fanuc_rmi::drivers::{ConnectionHealth, FanucDriver};
+    /// # async fn example(driver: &FanucDriver) {
+    /// let mut health_rx = driver.subscribe_health();
+    /// while health_rx.changed().await.is_ok() {
+    ///     if *health_rx.borrow() == ConnectionHealth::Unhealthy {
+    ///         println!("Robot stopped answering heartbeats");
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn subscribe_health(&self) -> watch::Receiver<ConnectionHealth> {
+        self.health_tx.subscribe()
+    }
+
+    /// Positions captured from `FRC_ReadCartesianPosition` responses, oldest
+    /// first, with the time each response was received.
+    ///
+    /// Empty unless `trajectory_capacity` is set in the config. Only the last
+    /// `trajectory_capacity` samples are kept.
+    pub fn trajectory_snapshot(&self) -> Vec<(Instant, Position)> {
+        match &self.trajectory {
+            Some(trajectory) => trajectory.lock().map(|t| t.snapshot()).unwrap_or_default(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Discard all captured trajectory samples, e.g. before a new run.
+    pub fn clear_trajectory(&self) {
+        if let Some(trajectory) = &self.trajectory {
+            if let Ok(mut trajectory) = trajectory.lock() {
+                trajectory.clear();
+            }
+        }
+    }
+
+    /// The most recent status reported by `FRC_GetStatus`, or `None` before
+    /// the first successful response.
+    pub fn robot_status(&self) -> Option<RobotStatus> {
+        self.last_status.lock().ok().and_then(|status| *status)
+    }
+
+    /// Record a status response and broadcast it on [`status_tx`](Self::status_tx)
+    /// if it differs from the previous one.
+    fn update_status(&self, response: &FrcGetStatusResponse) {
+        if response.error_id != 0 {
+            return;
+        }
+        let status = RobotStatus::from(response);
+        let Ok(mut last) = self.last_status.lock() else {
+            return;
+        };
+        let changed = last.is_some_and(|previous| previous != status);
+        *last = Some(status);
+        drop(last);
+        if changed {
+            let _ = self.status_tx.send(status);
+        }
+    }
+
+    fn set_health(&self, health: ConnectionHealth) {
+        self.health_tx.send_if_modified(|current| {
+            if *current == health {
+                false
+            } else {
+                *current = health;
+                true
+            }
+        });
+    }
+
+    /// Send `FRC_GetStatus` every `interval` and mark the connection unhealthy
+    /// after `max_missed` consecutive heartbeats see no status response within
+    /// one interval. Any status response counts, including ones requested by
+    /// other callers. Exits once the driver is disconnected.
+    async fn run_heartbeat(&self, interval: Duration, max_missed: u32) {
+        let mut missed = 0u32;
+        loop {
+            if !*self.connected.lock().await {
+                break;
+            }
+
+            let mut response_rx = self.response_tx.subscribe();
+            if let Err(e) = self.send_get_status() {
+                self.log_error(format!("Failed to send heartbeat: {}", e)).await;
+                self.set_health(ConnectionHealth::Unhealthy);
+                break;
+            }
+
+            let answered = tokio::time::timeout(interval, async {
+                while let Ok(response) = response_rx.recv().await {
+                    if let ResponsePacket::CommandResponse(CommandResponse::FrcGetStatus(_)) = response {
+                        return true;
+                    }
+                }
+                false
+            })
+            .await
+            .unwrap_or(false);
+
+            if answered {
+                if missed > 0 {
+                    self.log_info("Heartbeat recovered").await;
+                }
+                missed = 0;
+                self.set_health(ConnectionHealth::Healthy);
+                // Keep a steady cadence rather than firing immediately after a fast reply.
+                sleep(interval).await;
+            } else {
+                missed += 1;
+                self.log_warn(format!("Heartbeat missed ({}/{})", missed, max_missed)).await;
+                if missed >= max_missed {
+                    self.set_health(ConnectionHealth::Unhealthy);
+                }
+            }
+        }
+    }
+
     /// Send a disconnect communication to the FANUC controller
     ///
     /// Returns the request ID for tracking this request.
@@ -910,12 +1741,16 @@ impl FanucDriver {
     /// the B-84184EN_02 manual. It:
     /// 1. Checks the current robot status using FRC_GetStatus
     /// 2. Verifies the robot is ready (servo ready, AUTO mode)
-    /// 3. Only aborts if RMI is already running (avoids "RMI Command Failed" error)
-    /// 4. Initializes the RMI system
+    /// 3. Stops if RMI is running under this driver's own FRC_Initialize
+    /// 4. Only aborts if RMI is already running (avoids "RMI Command Failed" error)
+    /// 5. Initializes the RMI system
+    ///
+    /// Step 3 makes the sequence idempotent: calling it again right after it
+    /// succeeded sends nothing but FRC_GetStatus.
     ///
     /// # Returns
     ///
-    /// * `Ok(())` - Initialization successful
+    /// * `Ok(StartupReport)` - Robot is initialized; the report says which steps ran
     /// * `Err(String)` - Initialization failed with error message
     ///
     /// # Errors
@@ -937,18 +1772,20 @@ impl FanucDriver {
     ///     port: 16001,
     ///     max_messages: 30,
     ///     log_level: LogLevel::Info,
+    ///     ..Default::default()
     /// };
     ///
     /// let driver = FanucDriver::connect(config).await.map_err(|e| e.to_string())?;
     ///
     /// // Smart initialization - checks status first
-    /// driver.startup_sequence().await?;
+    /// let report = driver.startup_sequence().await?;
+    /// println!("Startup: {}", report);
     ///
     /// // Robot is now ready for motion commands
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn startup_sequence(&self) -> Result<(), String> {
+    pub async fn startup_sequence(&self) -> Result<StartupReport, String> {
         self.log_info("Starting robot initialization sequence...").await;
 
         // Step 1: Get current status
@@ -982,7 +1819,20 @@ impl FanucDriver {
             status.servo_ready, status.tp_mode, status.rmi_motion_status
         )).await;
 
-        // Step 3: Abort if RMI is already running
+        // Step 3: Nothing to do if RMI is running under our own FRC_Initialize
+        let initialized_here = self.rmi_initialized.load(Ordering::SeqCst);
+        let mut report = StartupReport {
+            status: RobotStatus::from(&status),
+            aborted: false,
+            initialized: false,
+            reinitialized: false,
+        };
+        if status.rmi_motion_status != 0 && initialized_here {
+            self.log_info("RMI already initialized by this driver, skipping abort and initialize").await;
+            return Ok(report);
+        }
+
+        // Step 4: Abort if RMI is already running
         // According to B-84184EN_02: FRC_Abort only works when RMI_MOVE is running
         if status.rmi_motion_status != 0 {
             self.log_info("RMI already running, aborting first...").await;
@@ -995,11 +1845,12 @@ impl FanucDriver {
             }
 
             self.log_info("Abort successful").await;
+            report.aborted = true;
         } else {
             self.log_info("RMI not running, skipping abort").await;
         }
 
-        // Step 4: Initialize
+        // Step 5: Initialize
         self.log_info("Initializing RMI...").await;
         let init_response = self.initialize().await?;
 
@@ -1023,7 +1874,9 @@ impl FanucDriver {
             init_response.group_mask
         )).await;
 
-        Ok(())
+        report.initialized = true;
+        report.reinitialized = initialized_here;
+        Ok(report)
     }
 
     /// Reset the sequence counter to 1
@@ -1061,7 +1914,7 @@ impl FanucDriver {
             Err(e) => {
                 self.log_error(format!("Failed to serialize packet: {}", e))
                     .await;
-                return Err(FrcError::Serialization(e.to_string()));
+                return Err(FrcError::Serialization(e.to_string(), Some(e.into())));
             }
         };
 
@@ -1075,17 +1928,22 @@ impl FanucDriver {
         ).await {
             Ok(result) => {
                 if let Err(e) = result {
-                    let err = FrcError::FailedToSend(format!("{}", e));
+                    let err = FrcError::FailedToSend(e.to_string(), Some(e.into()));
                     self.log_error(err.to_string()).await;
                     return Err(err);
                 }
             },
             Err(_) => {
-                let err = FrcError::FailedToSend("Write operation timed out".to_string());
+                let err = FrcError::FailedToSend("Write operation timed out".to_string(), None);
                 self.log_error(err.to_string()).await;
                 return Err(err);
             }
         }
+        self.log_trace(format!("Sent: {}", serialized_packet.trim_end())).await;
+        self.counters.sent();
+        if let Some(recorder) = &self.recorder {
+            recorder.record(Direction::Sent, &serialized_packet);
+        }
 
         Ok(())
     }
@@ -1114,6 +1972,12 @@ impl FanucDriver {
         packet: SendPacket,
         priority: PacketPriority,
     ) -> Result<u64, String> {
+        match &packet {
+            SendPacket::Command(cmd) => self.check_command(cmd)?,
+            SendPacket::Instruction(instruction) => self.check_instruction(instruction)?,
+            _ => {}
+        }
+
         // Generate unique request ID
         let request_id = REQUEST_COUNTER.fetch_add(1, Ordering::Relaxed);
 
@@ -1146,6 +2010,14 @@ impl FanucDriver {
                 // Send directly to controller - bypass instruction queue
                 let fanuc_write = Arc::clone(&self.fanuc_write);
                 let log_channel = self.log_channel.clone();
+                let log_level = self.config.log_level;
+                let recorder = self.recorder.clone();
+                let counters = Arc::clone(&self.counters);
+                let pending_commands = Arc::clone(&self.pending_commands);
+                let span = match &packet {
+                    SendPacket::Command(cmd) => self.packet_span(cmd.name(), None),
+                    _ => Span::none(),
+                };
 
                 tokio::spawn(async move {
                     let serialized_packet = match serde_json::to_string(&packet) {
@@ -1157,14 +2029,31 @@ impl FanucDriver {
                     };
 
                     let mut stream = fanuc_write.lock().await;
+                    // Keep the FIFO in step even though nobody awaits this one
+                    let name = match &packet {
+                        SendPacket::Command(cmd) => Some(cmd.name()),
+                        _ => None,
+                    };
+                    let ticket = name.and_then(|name| register_command(&pending_commands, name, None, span));
                     if let Err(e) = stream.write_all(serialized_packet.as_bytes()).await {
+                        if let Some(name) = name {
+                            unregister_command(&pending_commands, name, ticket);
+                        }
                         let _ = log_channel.send(format!("ERROR: Failed to send command: {}", e));
+                    } else {
+                        log_event!(log_level, Trace, "Sent: {}", serialized_packet.trim_end());
+                        let _ = log_channel.send(format!("[TRACE] Sent: {}", serialized_packet.trim_end()));
+                        counters.sent();
+                        if let Some(recorder) = &recorder {
+                            recorder.record(Direction::Sent, &serialized_packet);
+                        }
                     }
                 });
             }
             SendPacket::Instruction(_) | SendPacket::DriverCommand(_) => {
                 // Instructions go through the queue with backpressure
                 let sender = self.queue_tx.clone();
+                let is_instruction = matches!(packet, SendPacket::Instruction(_));
 
                 let driver_packet = DriverPacket {
                     priority,
@@ -1172,8 +2061,15 @@ impl FanucDriver {
                     request_id,
                 };
 
+                // Counted before the send task can see it, so it is never idle early
+                if is_instruction {
+                    self.outstanding_instructions.send_modify(|count| *count += 1);
+                }
                 if let Err(e) = sender.try_send(driver_packet) {
-                    println!("Failed to send packet: {}", e);
+                    if is_instruction {
+                        self.instructions_finished(1);
+                    }
+                    log_event!(self.config.log_level, Error, "Failed to send packet: {}", e);
                     return Err(format!("Failed to send packet: {}", e));
                 }
             }
@@ -1207,6 +2103,8 @@ impl FanucDriver {
         // Track in-flight instructions for program pause/resume replay
         // Stores (sequence_id, instruction) pairs for instructions sent but not yet completed
         let mut in_flight_instructions: VecDeque<(u32, Instruction)> = VecDeque::new();
+        // Open spans of sent instructions by sequence id; empty unless spans are enabled
+        let mut instruction_spans: HashMap<u32, Span> = HashMap::new();
 
         // Standard loop interval
         const LOOP_INTERVAL: Duration = Duration::from_millis(8);
@@ -1244,13 +2142,26 @@ impl FanucDriver {
                             // for aborted instructions.
                             let old_in_flight = in_flight;
                             in_flight = 0;
-                            println!("ClearInFlight: reset in_flight counter from {} to 0", old_in_flight);
+                            self.instructions_finished(old_in_flight as usize);
+                            // Aborted instructions are never answered
+                            instruction_spans.clear();
+                            log_event!(self.config.log_level, Debug, "ClearInFlight: reset in_flight counter from {} to 0", old_in_flight);
+                        }
+                        DriverCommand::Flush => {
+                            let queued = queue.len();
+                            queue.retain(|p| !matches!(p.packet, SendPacket::Instruction(_)));
+                            self.instructions_finished(queued - queue.len());
+                            log_event!(self.config.log_level, Debug, "Flush: dropped {} unsent instructions", queued - queue.len());
                         }
                         DriverCommand::ProgramPause => {
                             // Program pause: Set state to ProgramPaused, preserve in-flight instructions
                             // The abort + clear_in_flight is handled externally before this command
-                            println!("ProgramPause: transitioning to ProgramPaused state");
-                            println!("  Preserving {} in-flight instructions for replay", in_flight_instructions.len());
+                            log_event!(
+                                self.config.log_level,
+                                Debug,
+                                "ProgramPause: preserving {} in-flight instructions for replay",
+                                in_flight_instructions.len()
+                            );
 
                             // Copy in-flight instructions to shared state for later retrieval
                             if let Ok(mut stored) = self.program_pause_instructions.lock() {
@@ -1262,19 +2173,22 @@ impl FanucDriver {
 
                             state = DriverState::ProgramPaused;
                             // Reset counter since robot's buffer was cleared by abort
+                            self.instructions_finished(in_flight as usize);
                             in_flight = 0;
                             // Clear local tracking since we've stored them
                             in_flight_instructions.clear();
+                            instruction_spans.clear();
                         }
                         DriverCommand::ProgramResume { instructions_to_replay } => {
                             // Program resume: Re-queue instructions for replay, then set state to Running
-                            println!("ProgramResume: replaying {} instructions", instructions_to_replay.len());
+                            log_event!(self.config.log_level, Debug, "ProgramResume: replaying {} instructions", instructions_to_replay.len());
 
                             // Clear tracked in-flight since we're starting fresh
                             in_flight_instructions.clear();
 
                             // Re-queue instructions at the front (high priority) so they execute before
                             // any other queued instructions. Insert in reverse order to maintain order.
+                            self.outstanding_instructions.send_modify(|count| *count += instructions_to_replay.len());
                             for instr in instructions_to_replay.iter().rev() {
                                 let replay_packet = DriverPacket {
                                     priority: PacketPriority::High,
@@ -1285,10 +2199,10 @@ impl FanucDriver {
                             }
 
                             state = DriverState::Running;
-                            println!("ProgramResume: state set to Running, queue size: {}", queue.len());
+                            log_event!(self.config.log_level, Debug, "ProgramResume: state set to Running, queue size: {}", queue.len());
                         }
                         _ => {
-                            println!("GOT A DRIVER COMMAND: {:?}", cmd);
+                            log_event!(self.config.log_level, Debug, "Driver command: {:?}", cmd);
                         }
                     }
                     continue;
@@ -1302,7 +2216,7 @@ impl FanucDriver {
                         queue.push_front(new_packet)
                     }
                     PacketPriority::Termination => {
-                        queue.clear();
+                        self.drop_queued(&mut queue);
                         queue.push_front(new_packet);
                     }
                 }
@@ -1310,18 +2224,24 @@ impl FanucDriver {
 
             // Process completed packets
             while let Ok(pkt) = completed_packet_info.try_recv() {
-                in_flight = in_flight.saturating_sub(1);
+                // Answers to instructions dropped by ClearInFlight were already released
+                let answered = in_flight.min(1);
+                in_flight -= answered;
+                self.instructions_finished(answered as usize);
                 // Remove completed instruction from in-flight tracking
                 // Find and remove by sequence_id
                 if let Some(pos) = in_flight_instructions.iter().position(|(seq, _)| *seq == pkt.sequence_id) {
                     in_flight_instructions.remove(pos);
                 }
+                let span = instruction_spans.remove(&pkt.sequence_id).unwrap_or_else(Span::none);
                 // Log if error occurred
                 if pkt.error_id != 0 {
                     self.log_error(format!(
                         "Error in packet {}: error_id={}",
                         pkt.sequence_id, pkt.error_id
-                    )).await;
+                    ))
+                    .instrument(span)
+                    .await;
                 }
             }
 
@@ -1344,8 +2264,8 @@ impl FanucDriver {
                                     id
                                 }
                                 Err(poisoned) => {
-                                    // Can't await here, so just log to stderr and break
-                                    eprintln!("Sequence ID mutex poisoned: {}", poisoned);
+                                    // Can't await here, so skip the channel and break
+                                    log_event!(self.config.log_level, Error, "Sequence ID mutex poisoned: {}", poisoned);
                                     break;
                                 }
                             }
@@ -1369,6 +2289,8 @@ impl FanucDriver {
                             Instruction::FrcJointMotionJRep(ref mut instr) => instr.sequence_id = current_id,
                             Instruction::FrcJointRelativeJRep(ref mut instr) => instr.sequence_id = current_id,
                             Instruction::FrcLinearMotionJRep(ref mut instr) => instr.sequence_id = current_id,
+                            Instruction::FrcLinearMotionPR(ref mut instr) => instr.sequence_id = current_id,
+                            Instruction::FrcJointMotionPR(ref mut instr) => instr.sequence_id = current_id,
                         }
 
                         // Broadcast sent instruction info
@@ -1379,20 +2301,36 @@ impl FanucDriver {
                         });
                     }
 
-                    match self.send_packet_to_controller(driver_packet.packet.clone()).await {
+                    let span = match &driver_packet.packet {
+                        SendPacket::Instruction(instr) => {
+                            self.packet_span(instr.name(), Some(instr.get_sequence_id()))
+                        }
+                        _ => Span::none(),
+                    };
+                    match self
+                        .send_packet_to_controller(driver_packet.packet.clone())
+                        .instrument(span.clone())
+                        .await
+                    {
                         Err(e) => {
+                            if matches!(driver_packet.packet, SendPacket::Instruction(_)) {
+                                self.instructions_finished(1);
+                            }
                             self.log_error(format!("Failed to send packet: {:?}", e))
                                 .await;
                         }
                         Ok(()) => {
                             if driver_packet.packet == SendPacket::Communication(Communication::FrcDisconnect) {
                                 // immediate shutdown
-                                queue.clear();
+                                self.drop_queued(&mut queue);
                                 break;
                             }
                             if let SendPacket::Instruction(instr) = driver_packet.packet {
                                 let seq = instr.get_sequence_id();
                                 in_flight += 1;
+                                if !span.is_none() {
+                                    instruction_spans.insert(seq, span);
+                                }
 
                                 // Only track in-flight instructions when Running (not when ProgramPaused)
                                 // Instructions sent during ProgramPaused are jog commands, not program instructions
@@ -1450,13 +2388,17 @@ impl FanucDriver {
                 Ok(0) => {
                     // Connection closed by peer
                     *self.connected.lock().await = false;
+                    self.set_health(ConnectionHealth::Unhealthy);
+                    self.fail_pending_commands();
                     return Err(FrcError::Disconnected());
                 }
                 Ok(n) => n,
                 Err(e) => {
                     self.log_error(format!("Read error: {}", e)).await;
                     *self.connected.lock().await = false;
-                    return Err(FrcError::FailedToReceive(e.to_string()));
+                    self.set_health(ConnectionHealth::Unhealthy);
+                    self.fail_pending_commands();
+                    return Err(FrcError::FailedToReceive(e.to_string(), Some(e.into())));
                 }
             };
 
@@ -1481,37 +2423,35 @@ impl FanucDriver {
         line: String,
         completed_tx: &broadcast::Sender<CompletedPacketReturnInfo>,
     ) -> Result<(), FrcError> {
-        // HOT PATH: Only log at debug level to avoid flooding terminal
-        self.log_debug(format!("Received: {}", line)).await;
+        // HOT PATH: raw payloads only at trace level to avoid flooding terminal
+        self.log_trace(format!("Received: {}", line)).await;
+        self.counters.received();
+        if let Some(recorder) = &self.recorder {
+            recorder.record(Direction::Received, &line);
+        }
 
         match serde_json::from_str::<ResponsePacket>(&line) {
             Ok(packet) => {
-                // Log InstructionResponse at info level for debugging
                 if matches!(packet, ResponsePacket::InstructionResponse(_)) {
-                    info!("📥 Received InstructionResponse: {:?}", packet);
+                    log_event!(self.config.log_level, Debug, "📥 Received InstructionResponse: {:?}", packet);
                 }
 
                 // Send the response to the response_channel for all responses
                 if let Err(e) = self.response_tx.send(packet.clone()) {
-                    self.log_error(format!("Failed to send to response channel: {}", e))
+                    self.log_error(format!("Failed to send {:?} to response channel: {}", packet, e))
                         .await;
-                    info!(
-                        "Failed to send message to response channel {:?}: {:?}",
-                        packet.clone(),
-                        e
-                    );
                 } else {
-                    // Log InstructionResponse broadcast at info level
-                    if matches!(packet, ResponsePacket::InstructionResponse(_)) {
-                        info!("📤 Broadcast InstructionResponse to {} subscribers", self.response_tx.receiver_count());
-                    }
-                    // HOT PATH: Only log at debug level for other types
+                    // HOT PATH: Only log at debug level
                     self.log_debug(format!(
-                        "Sent response to backend: {:?}",
-                        packet.clone()
+                        "Sent response to {} subscribers: {:?}",
+                        self.response_tx.receiver_count(),
+                        packet
                     ))
                     .await;
-                    debug!("Sent message to response channel: {:?}", packet.clone())
+                }
+
+                if let ResponsePacket::CommandResponse(response) = &packet {
+                    self.complete_command(response);
                 }
 
                 match packet {
@@ -1530,7 +2470,9 @@ impl FanucDriver {
                             self.log_error(format!("Failed to send completion info: {}", e)).await;
                         }
                     }
-                    ResponsePacket::CommandResponse(CommandResponse::FrcGetStatus(_status_response)) => {
+                    ResponsePacket::CommandResponse(CommandResponse::FrcGetStatus(status_response)) => {
+                        self.update_status(&status_response);
+
                         // Per FANUC documentation B-84184EN/02 Section 2.4:
                         // "Start your SequenceID number from 1 after the FRC_Initialize packet."
                         //
@@ -1545,17 +2487,33 @@ impl FanucDriver {
                         //
                         // Use sync_sequence_counter() explicitly when recovering from errors.
                     }
+                    ResponsePacket::CommandResponse(CommandResponse::FrcInitialize(resp)) if resp.error_id == 0 => {
+                        self.rmi_initialized.store(true, Ordering::SeqCst);
+                    }
+                    ResponsePacket::CommandResponse(CommandResponse::FrcAbort(_)) => {
+                        self.rmi_initialized.store(false, Ordering::SeqCst);
+                    }
+                    ResponsePacket::CommandResponse(CommandResponse::FrcReadCartesianPosition(resp))
+                        if resp.error_id == 0 =>
+                    {
+                        if let Some(trajectory) = &self.trajectory {
+                            if let Ok(mut trajectory) = trajectory.lock() {
+                                trajectory.push(Instant::now(), resp.pos);
+                            }
+                        }
+                    }
                     ResponsePacket::CommandResponse(CommandResponse::FrcSetOverRide(
                         frc_set_override_response,
                     )) => {
-                        info!("Got set override response: {:?}", frc_set_override_response);
+                        log_event!(self.config.log_level, Debug, "Got set override response: {:?}", frc_set_override_response);
                     }
                     // handle other variants similarly...
                     _ => {}
                 }
             }
             Err(e) => {
-                let decoded = crate::extract_and_format_error_id(&line);
+                let error_id = crate::extract_error_id(&line);
+                let decoded = error_id.map(crate::format_error_id);
                 let error_msg = match &decoded {
                     Some(d) => format!("Invalid JSON ({}) [{}]: {}", e, d, line),
                     None => format!("Invalid JSON ({}): {}", e, line),
@@ -1569,11 +2527,13 @@ impl FanucDriver {
                         Some(d) => format!("Failed to parse robot response [{}]: {}", d, e),
                         None => format!("Failed to parse robot response: {}", e),
                     },
+                    error_id,
                     raw_data: Some(line.to_string()),
                 };
+                self.counters.protocol_error();
                 if let Err(send_err) = self.error_tx.send(protocol_error) {
                     // No subscribers - that's okay, just log it
-                    debug!("No error channel subscribers: {}", send_err);
+                    log_event!(self.config.log_level, Debug, "No error channel subscribers: {}", send_err);
                 }
             }
         }
@@ -1644,6 +2604,12 @@ impl FanucDriver {
                 Instruction::FrcLinearMotionJRep(ref mut instr) => {
                     instr.sequence_id = current_id;
                 }
+                Instruction::FrcLinearMotionPR(ref mut instr) => {
+                    instr.sequence_id = current_id;
+                }
+                Instruction::FrcJointMotionPR(ref mut instr) => {
+                    instr.sequence_id = current_id;
+                }
             }
 
             *sid += 1;
@@ -1675,19 +2641,21 @@ impl FanucDriver {
             match guard.try_recv() {
                 Ok(most_recent) => {
                     if most_recent.error_id != 0 {
-                        eprintln!("ROBOT MOTION ERROR: {}", most_recent.error_id);
+                        log_event!(self.config.log_level, Error, "Robot motion error: {}", most_recent.error_id);
                         break;
                     } else {
                         if most_recent.sequence_id >= sequence_id {
-                            println!("robot move done #{}", most_recent.sequence_id);
+                            log_event!(self.config.log_level, Debug, "Robot move done #{}", most_recent.sequence_id);
                             break;
                         }
                     }
                 }
                 Err(broadcast::error::TryRecvError::Empty) => {}
-                Err(broadcast::error::TryRecvError::Closed) => info!("Channel closed."),
+                Err(broadcast::error::TryRecvError::Closed) => {
+                    log_event!(self.config.log_level, Info, "Completion channel closed")
+                }
                 Err(broadcast::error::TryRecvError::Lagged(skipped)) => {
-                    info!("Channel lagged, skipped {} messages.", skipped)
+                    log_event!(self.config.log_level, Warn, "Completion channel lagged, skipped {} messages", skipped)
                 }
             }
             drop(guard);
@@ -1786,15 +2754,40 @@ impl FanucDriver {
         let request_id = self.send_packet(packet, priority)?;
         self.wait_on_request_completion(request_id).await
     }
+
+    /// Move to the configured [`home`](FanucDriverConfig::home) position and
+    /// wait for the move to complete.
+    ///
+    /// Sends an absolute `FRC_JointMotionJRep` at
+    /// [`home_speed`](FanucDriverConfig::home_speed) with `FINE` termination.
+    ///
+    /// # Returns
+    /// * `Ok(sequence_id)` - The sequence ID of the home move
+    /// * `Err(String)` - No home is configured, or the send or wait failed
+    pub async fn go_home(&self) -> Result<u32, String> {
+        let home = self.config.home.clone().ok_or("No home position configured.")?;
+        let instruction = FrcJointMotionJRep::new(0, home, SpeedType::MMSec, self.config.home_speed, TermType::FINE, 0);
+        self.send_and_wait_for_completion(
+            SendPacket::Instruction(Instruction::FrcJointMotionJRep(instruction)),
+            PacketPriority::Standard,
+        )
+        .await
+    }
 }
-async fn connect_with_retries(addr: &str, retries: u32) -> Result<TcpStream, FrcError> {
+async fn connect_with_retries(addr: &str, retries: u32, log_level: LogLevel, nodelay: bool) -> Result<TcpStream, FrcError> {
     for attempt in 0..retries {
         match TcpStream::connect(addr).await {
-            Ok(stream) => return Ok(stream),
+            Ok(stream) => {
+                stream
+                    .set_nodelay(nodelay)
+                    .map_err(|e| FrcError::Initialization(format!("Could not set TCP_NODELAY: {}", e), Some(e.into())))?;
+                return Ok(stream);
+            }
             Err(e) => {
-                eprintln!("Failed to connect (attempt {}): {}", attempt + 1, e);
+                log_event!(log_level, Warn, "Failed to connect to {} (attempt {}): {}", addr, attempt + 1, e);
                 if attempt + 1 == retries {
-                    return Err(FrcError::Disconnected());
+                    let message = format!("Could not connect to {} after {} attempts: {}", addr, retries, e);
+                    return Err(FrcError::Initialization(message, Some(e.into())));
                 }
                 sleep(Duration::from_secs(2)).await;
             }
@@ -1,17 +1,60 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::net::ToSocketAddrs;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::packets::CommandKind;
+use crate::JointAngles;
+
+/// How long [`FanucDriver::command`](super::FanucDriver::command) waits for
+/// the controller to answer a command with no entry in
+/// [`FanucDriverConfig::timeouts`].
+pub const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Joint speed of [`FanucDriver::go_home`](super::FanucDriver::go_home)
+/// unless [`FanucDriverConfig::home_speed`] says otherwise.
+pub const DEFAULT_HOME_SPEED: f64 = 20.0;
+
+/// Speed override percentages [`FanucDriver::set_override`](super::FanucDriver::set_override)
+/// commands unless [`FanucDriverConfig::override_range`] says otherwise.
+/// 0% would hold motion without pausing it.
+pub const DEFAULT_OVERRIDE_RANGE: RangeInclusive<u8> = 1..=100;
+
+/// Default per-command response timeouts, overriding [`COMMAND_TIMEOUT`]:
+///
+/// - `FRC_ReadUFrameData`: 1 s. The controller never answers a read of
+///   UFrame 0, and other frames answer within milliseconds, so a short
+///   timeout reports frame 0 as unreadable without a long stall.
+/// - `FRC_ReadUToolData`: 1 s, for the same reason.
+/// - `FRC_Initialize`: 10 s. The controller starts the RMI TP program
+///   before answering.
+pub fn default_command_timeouts() -> HashMap<CommandKind, Duration> {
+    HashMap::from([
+        (CommandKind::FrcReadUFrameData, Duration::from_secs(1)),
+        (CommandKind::FrcReadUToolData, Duration::from_secs(1)),
+        (CommandKind::FrcInitialize, Duration::from_secs(10)),
+    ])
+}
 
 /// Log level for filtering driver messages
+///
+/// Driver messages are `tracing` events; each level also admits the ones
+/// above it. A subscriber's own filter applies on top of this.
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LogLevel {
     /// Only errors (connection failures, serialization errors, etc.)
     Error = 0,
     /// Warnings and errors (timeouts, retries, etc.)
     Warn = 1,
-    /// Important info, warnings, and errors (connection events, initialization, etc.)
+    /// Lifecycle events, warnings, and errors (connection events, initialization, etc.)
     Info = 2,
-    /// All messages including debug (every packet sent/received)
+    /// Every packet sent/received, summarized. Also opens a `tracing` span
+    /// per packet until its response arrives.
     Debug = 3,
+    /// Everything, plus the raw JSON of every packet sent and received.
+    Trace = 4,
 }
 
 impl Default for LogLevel {
@@ -48,17 +91,98 @@ pub struct FanucDriverConfig {
     pub addr: String,
     pub port: u32,
     pub max_messages: usize,
-    /// Log level for terminal output (when "logging" feature is enabled)
+    /// Which driver messages are emitted as `tracing` events (when the
+    /// "logging" feature is enabled)
     ///
     /// - `Error`: Only critical errors (connection failures, serialization errors)
     /// - `Warn`: Warnings and errors (timeouts, performance issues)
-    /// - `Info`: Important events, warnings, and errors (default - connection, initialization)
-    /// - `Debug`: All messages including every packet sent/received (very verbose)
+    /// - `Info`: Lifecycle events, warnings, and errors (default - connection, initialization)
+    /// - `Debug`: A summary of every packet sent/received (very verbose),
+    ///   plus an `rmi_packet` tracing span per packet with `command` and `sequence_id` fields
+    /// - `Trace`: Everything in `Debug`, plus the raw JSON of every packet
     ///
     /// Note: All messages are always sent to the log_channel regardless of this setting.
-    /// This only controls what gets printed to the terminal.
+    /// This only controls what reaches the `tracing` subscriber.
     #[serde(default)]
     pub log_level: LogLevel,
+    /// Interval between `FRC_GetStatus` heartbeats, in milliseconds.
+    ///
+    /// `None` (the default) disables the heartbeat. When enabled, the driver
+    /// reports [`ConnectionHealth::Unhealthy`](super::ConnectionHealth) after
+    /// `heartbeat_max_missed` consecutive heartbeats go unanswered.
+    #[serde(default)]
+    pub heartbeat_interval_ms: Option<u64>,
+    /// Consecutive missed heartbeats before the connection is reported unhealthy.
+    #[serde(default = "default_heartbeat_max_missed")]
+    pub heartbeat_max_missed: u32,
+    /// Record every raw JSON line sent to and received from the controller
+    /// to this file (see [`replay_session`](super::replay_session)).
+    #[serde(default)]
+    pub record: Option<PathBuf>,
+    /// Keep the last this-many positions from `FRC_ReadCartesianPosition`
+    /// responses for [`trajectory_snapshot`](super::FanucDriver::trajectory_snapshot).
+    ///
+    /// `None` (the default) disables trajectory capture.
+    #[serde(default)]
+    pub trajectory_capacity: Option<usize>,
+    /// Response timeout per command type for
+    /// [`command`](super::FanucDriver::command). Commands without an entry
+    /// use [`COMMAND_TIMEOUT`]. Defaults to [`default_command_timeouts`].
+    #[serde(default = "default_command_timeouts")]
+    pub timeouts: HashMap<CommandKind, Duration>,
+    /// Number of motion groups on the controller (1-8). `FRC_Initialize`
+    /// masks naming any other group are rejected before they are sent.
+    #[serde(default = "default_group_count")]
+    pub group_count: u8,
+    /// Joint angles (degrees) that [`go_home`](super::FanucDriver::go_home)
+    /// moves to. `None` (the default) leaves the robot without a home.
+    #[serde(default)]
+    pub home: Option<JointAngles>,
+    /// Joint speed of [`go_home`](super::FanucDriver::go_home), sent with
+    /// `SpeedType` `mmSec`.
+    #[serde(default = "default_home_speed")]
+    pub home_speed: f64,
+    /// Turn off Nagle's algorithm (`TCP_NODELAY`) on the controller
+    /// sockets, so each packet goes out as soon as it is written instead of
+    /// waiting on the ACK of the last one. Defaults to `true`.
+    #[serde(default = "default_tcp_nodelay")]
+    pub tcp_nodelay: bool,
+    /// Run [`startup_sequence`](super::FanucDriver::startup_sequence), and
+    /// so send `FRC_Initialize`, as part of connecting. [`connect`](super::FanucDriver::connect)
+    /// itself only opens the connection; applications that initialize on
+    /// connect skip it when this is `false`, leaving the robot uninitialized
+    /// until an explicit [`initialize`](super::FanucDriver::initialize).
+    /// Defaults to `true`.
+    #[serde(default = "default_auto_initialize")]
+    pub auto_initialize: bool,
+    /// Speed override percentages [`set_override`](super::FanucDriver::set_override)
+    /// clamps to. Defaults to [`DEFAULT_OVERRIDE_RANGE`].
+    #[serde(default = "default_override_range")]
+    pub override_range: RangeInclusive<u8>,
+}
+
+fn default_heartbeat_max_missed() -> u32 {
+    3
+}
+
+fn default_group_count() -> u8 {
+    1
+}
+
+fn default_home_speed() -> f64 {
+    DEFAULT_HOME_SPEED
+}
+
+fn default_tcp_nodelay() -> bool {
+    true
+}
+
+fn default_auto_initialize() -> bool {
+    true
+}
+
+fn default_override_range() -> RangeInclusive<u8> {
+    DEFAULT_OVERRIDE_RANGE
 }
 
 impl FanucDriverConfig {
@@ -68,6 +192,17 @@ impl FanucDriverConfig {
             port,
             max_messages,
             log_level: LogLevel::default(),
+            heartbeat_interval_ms: None,
+            heartbeat_max_missed: default_heartbeat_max_missed(),
+            record: None,
+            trajectory_capacity: None,
+            timeouts: default_command_timeouts(),
+            group_count: default_group_count(),
+            home: None,
+            home_speed: default_home_speed(),
+            tcp_nodelay: default_tcp_nodelay(),
+            auto_initialize: default_auto_initialize(),
+            override_range: default_override_range(),
         }
     }
 
@@ -76,6 +211,73 @@ impl FanucDriverConfig {
         self
     }
 
+    /// Enable the `FRC_GetStatus` heartbeat.
+    pub fn with_heartbeat(mut self, interval_ms: u64, max_missed: u32) -> Self {
+        self.heartbeat_interval_ms = Some(interval_ms);
+        self.heartbeat_max_missed = max_missed;
+        self
+    }
+
+    /// Record the raw session to `path`.
+    pub fn with_record(mut self, path: impl Into<PathBuf>) -> Self {
+        self.record = Some(path.into());
+        self
+    }
+
+    /// Capture up to `capacity` reported positions.
+    pub fn with_trajectory_capture(mut self, capacity: usize) -> Self {
+        self.trajectory_capacity = Some(capacity);
+        self
+    }
+
+    /// Wait at most `timeout` for the answer to commands of type `kind`.
+    pub fn with_command_timeout(mut self, kind: CommandKind, timeout: Duration) -> Self {
+        self.timeouts.insert(kind, timeout);
+        self
+    }
+
+    /// Send the robot to `home` on [`go_home`](super::FanucDriver::go_home).
+    pub fn with_home(mut self, home: JointAngles, speed: f64) -> Self {
+        self.home = Some(home);
+        self.home_speed = speed;
+        self
+    }
+
+    /// Set `TCP_NODELAY` on the controller sockets to `nodelay`.
+    pub fn with_tcp_nodelay(mut self, nodelay: bool) -> Self {
+        self.tcp_nodelay = nodelay;
+        self
+    }
+
+    /// Run the startup sequence on connect only if `auto_initialize`.
+    pub fn with_auto_initialize(mut self, auto_initialize: bool) -> Self {
+        self.auto_initialize = auto_initialize;
+        self
+    }
+
+    /// Clamp [`set_override`](super::FanucDriver::set_override) to `range`.
+    pub fn with_override_range(mut self, range: RangeInclusive<u8>) -> Self {
+        self.override_range = range;
+        self
+    }
+
+    /// Response timeout for commands of type `kind`.
+    pub fn command_timeout(&self, kind: CommandKind) -> Duration {
+        self.timeouts.get(&kind).copied().unwrap_or(COMMAND_TIMEOUT)
+    }
+
+    /// Check that `group_mask` names at least one group and only groups
+    /// within [`group_count`](Self::group_count).
+    pub fn check_group_mask(&self, group_mask: u8) -> Result<(), String> {
+        if group_mask == 0 || u16::from(group_mask) >> self.group_count != 0 {
+            return Err(format!(
+                "Group mask {:#010b} does not fit {} motion group(s).",
+                group_mask, self.group_count
+            ));
+        }
+        Ok(())
+    }
+
     pub fn validate(&self) -> Result<(), String> {
         if self.addr.is_empty() {
             return Err("Address cannot be empty.".to_string());
@@ -86,6 +288,27 @@ impl FanucDriverConfig {
         if self.max_messages == 0 {
             return Err("Maximum messages must be greater than 0.".to_string());
         }
+        if self.heartbeat_interval_ms == Some(0) {
+            return Err("Heartbeat interval must be greater than 0.".to_string());
+        }
+        if self.heartbeat_interval_ms.is_some() && self.heartbeat_max_missed == 0 {
+            return Err("Heartbeat max missed must be greater than 0.".to_string());
+        }
+        if self.trajectory_capacity == Some(0) {
+            return Err("Trajectory capacity must be greater than 0.".to_string());
+        }
+        if !(1..=8).contains(&self.group_count) {
+            return Err("Group count must be between 1 and 8.".to_string());
+        }
+        if self.home.is_some() && !(self.home_speed.is_finite() && self.home_speed > 0.0) {
+            return Err("Home speed must be greater than 0.".to_string());
+        }
+        if self.override_range.is_empty() || *self.override_range.end() > 100 {
+            return Err("Override range must be non-empty and within 0-100%.".to_string());
+        }
+        if let Some((kind, _)) = self.timeouts.iter().find(|(_, timeout)| timeout.is_zero()) {
+            return Err(format!("Timeout for {:?} must be greater than 0.", kind));
+        }
         Ok(())
     }
 
@@ -109,6 +332,17 @@ impl Default for FanucDriverConfig {
             port: 16001,
             max_messages: 30,
             log_level: LogLevel::default(),
+            heartbeat_interval_ms: None,
+            heartbeat_max_missed: default_heartbeat_max_missed(),
+            record: None,
+            trajectory_capacity: None,
+            timeouts: default_command_timeouts(),
+            group_count: default_group_count(),
+            home: None,
+            home_speed: default_home_speed(),
+            tcp_nodelay: default_tcp_nodelay(),
+            auto_initialize: default_auto_initialize(),
+            override_range: default_override_range(),
         }
     }
 }
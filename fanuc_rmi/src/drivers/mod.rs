@@ -3,6 +3,26 @@ mod driver;
 #[cfg(feature="driver")]
 pub use driver::*;
 
+#[cfg(feature="driver")]
+mod recording;
+#[cfg(feature="driver")]
+pub use recording::{load_recording, replay_session, Direction, RecordedLine, ReplayError};
+
+#[cfg(feature="driver")]
+mod trajectory;
+#[cfg(feature="driver")]
+pub use trajectory::TrajectoryBuffer;
+
+#[cfg(feature="driver")]
+mod stats;
+#[cfg(feature="driver")]
+pub use stats::DriverStats;
+
+#[cfg(feature="driver")]
+mod motion;
+#[cfg(feature="driver")]
+pub use motion::MotionHandle;
+
 #[cfg(feature="driver")]
 mod models;
 #[cfg(feature="driver")]
@@ -11,4 +11,48 @@ impl Default for DriverState {
     fn default() -> Self {
         Self::Running
     }
-}
\ No newline at end of file
+}
+
+/// Connection health as observed by the driver heartbeat.
+#[derive(Copy, Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionHealth {
+    /// The controller answered the most recent heartbeat (or none has been missed yet).
+    Healthy,
+    /// Too many consecutive heartbeats went unanswered, or the socket closed.
+    Unhealthy,
+}
+/// What [`FanucDriver::startup_sequence`](super::FanucDriver::startup_sequence) did.
+#[derive(Copy, Debug, Clone, PartialEq, Eq)]
+pub struct StartupReport {
+    /// Controller state read with `FRC_GetStatus` before deciding.
+    pub status: crate::commands::RobotStatus,
+    /// RMI was running from another session and was aborted first.
+    pub aborted: bool,
+    /// `FRC_Initialize` was sent and succeeded.
+    pub initialized: bool,
+    /// This driver had initialized RMI before, but the controller no
+    /// longer reported it running, so it was initialized again.
+    pub reinitialized: bool,
+}
+
+impl StartupReport {
+    /// RMI was already initialized by this driver; nothing was sent after
+    /// the status check.
+    pub fn is_noop(&self) -> bool {
+        !self.aborted && !self.initialized
+    }
+}
+
+impl std::fmt::Display for StartupReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_noop() {
+            write!(f, "RMI already initialized by this driver, nothing to do")
+        } else if self.aborted {
+            write!(f, "RMI was running from another session (motion status {}), aborted and initialized", self.status.rmi_motion_status)
+        } else if self.reinitialized {
+            write!(f, "RMI stopped since the last initialize, initialized again")
+        } else {
+            write!(f, "RMI not running, initialized")
+        }
+    }
+}
@@ -0,0 +1,153 @@
+// High-level motion calls: build the instruction, send it, and hand back
+// something to await instead of a raw request id.
+
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+
+use tokio::sync::broadcast;
+
+use super::FanucDriver;
+use crate::instructions::{FrcLinearMotion, FrcLinearRelative};
+use crate::packets::{Instruction, PacketPriority, ResponsePacket, SendPacket, SentInstructionInfo};
+use crate::{Configuration, FanucErrorCode, FrcError, Position, SpeedType, TermType};
+
+/// Term value sent with `CNT` and `CR` moves: the smoothest blend.
+const BLEND_TERM_VALUE: u8 = 100;
+
+/// A motion sent by [`FanucDriver::move_to`] or [`FanucDriver::move_relative`].
+///
+/// Await it to wait for the controller to finish the move; it resolves to
+/// the instruction's sequence ID. Dropping it does not cancel the move.
+#[derive(Debug)]
+pub struct MotionHandle {
+    request_id: u64,
+    sent_rx: broadcast::Receiver<SentInstructionInfo>,
+    response_rx: broadcast::Receiver<ResponsePacket>,
+}
+
+impl MotionHandle {
+    /// The request ID `send_packet` returned for the move.
+    pub fn request_id(&self) -> u64 {
+        self.request_id
+    }
+
+    /// Wait for the move to complete.
+    ///
+    /// # Returns
+    /// * `Ok(sequence_id)` - The controller finished the move
+    /// * `Err(FrcError::FanucErrorCode)` - The controller rejected or failed the move
+    /// * `Err(FrcError::Disconnected)` - The driver shut down first
+    pub async fn completion(mut self) -> Result<u32, FrcError> {
+        let sequence_id = loop {
+            match self.sent_rx.recv().await {
+                Ok(sent) if sent.request_id == self.request_id => break sent.sequence_id,
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return Err(FrcError::Disconnected()),
+            }
+        };
+
+        loop {
+            match self.response_rx.recv().await {
+                Ok(ResponsePacket::InstructionResponse(resp)) if resp.get_sequence_id() == sequence_id => {
+                    return match resp.get_error_id() {
+                        0 => Ok(sequence_id),
+                        error_id => Err(FrcError::FanucErrorCode(
+                            FanucErrorCode::try_from(error_id).unwrap_or(FanucErrorCode::UnrecognizedFrcError),
+                        )),
+                    };
+                }
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return Err(FrcError::Disconnected()),
+            }
+        }
+    }
+}
+
+impl IntoFuture for MotionHandle {
+    type Output = Result<u32, FrcError>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.completion())
+    }
+}
+
+impl FanucDriver {
+    /// Move in a straight line to `position` (`FRC_LinearMotion`).
+    ///
+    /// `speed` is in mm/sec. `CNT` and `CR` moves blend at 100; send the
+    /// instruction yourself with [`send_packet`](Self::send_packet) for any
+    /// other term value. The driver assigns the sequence ID.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use fanuc_rmi::drivers::FanucDriver;
+    /// # use fanuc_rmi::{Configuration, FrcError, Position, TermType};
+    /// # async fn example(driver: &FanucDriver) -> Result<(), FrcError> {
+    /// let target = Position { x: 400.0, y: 0.0, z: 300.0, ..Default::default() };
+    /// let sequence_id = driver.move_to(target, Configuration::default(), 100.0, TermType::FINE)?.await?;
+    /// println!("Arrived (instruction {})", sequence_id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn move_to(
+        &self,
+        position: Position,
+        config: Configuration,
+        speed: f64,
+        term: TermType,
+    ) -> Result<MotionHandle, FrcError> {
+        let term_value = term_value(&term);
+        let instruction = FrcLinearMotion::new(0, config, position, SpeedType::MMSec, speed, term, term_value);
+        self.send_motion(Instruction::FrcLinearMotion(instruction))
+    }
+
+    /// Move in a straight line by `delta` from wherever the previous motion
+    /// ends (`FRC_LinearRelative`).
+    ///
+    /// Speed and termination work as in [`move_to`](Self::move_to).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use fanuc_rmi::drivers::FanucDriver;
+    /// # use fanuc_rmi::{Configuration, FrcError, Position, TermType};
+    /// # async fn example(driver: &FanucDriver) -> Result<(), FrcError> {
+    /// // Queue two 10 mm steps along X, then wait for both
+    /// let step = Position { x: 10.0, ..Default::default() };
+    /// let first = driver.move_relative(step, Configuration::default(), 50.0, TermType::CNT)?;
+    /// let second = driver.move_relative(step, Configuration::default(), 50.0, TermType::FINE)?;
+    /// first.await?;
+    /// second.await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn move_relative(
+        &self,
+        delta: Position,
+        config: Configuration,
+        speed: f64,
+        term: TermType,
+    ) -> Result<MotionHandle, FrcError> {
+        let term_value = term_value(&term);
+        let instruction = FrcLinearRelative::new(0, config, delta, SpeedType::MMSec, speed, term, term_value);
+        self.send_motion(Instruction::FrcLinearRelative(instruction))
+    }
+
+    /// Queue `instruction`, subscribing first so the handle can't miss its
+    /// sent notification or response.
+    fn send_motion(&self, instruction: Instruction) -> Result<MotionHandle, FrcError> {
+        let sent_rx = self.sent_instruction_tx.subscribe();
+        let response_rx = self.response_tx.subscribe();
+        let request_id = self
+            .send_packet(SendPacket::Instruction(instruction), PacketPriority::Standard)
+            .map_err(|e| FrcError::FailedToSend(e, None))?;
+        Ok(MotionHandle { request_id, sent_rx, response_rx })
+    }
+}
+
+fn term_value(term: &TermType) -> u8 {
+    match term {
+        TermType::FINE => 0,
+        TermType::CNT | TermType::CR => BLEND_TERM_VALUE,
+    }
+}
@@ -0,0 +1,212 @@
+//! Raw session recording and replay.
+//!
+//! When [`FanucDriverConfig::record`](super::FanucDriverConfig) is set, the
+//! driver appends every JSON line it sends to or receives from the controller
+//! to a file, one [`RecordedLine`] per line. [`replay_session`] plays such a
+//! recording back as a fake controller and checks that the client sends the
+//! same lines in the same order, which makes field issues reproducible.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::time::Instant;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader as AsyncBufReader};
+use tokio::net::TcpListener;
+
+/// Which side of the connection a recorded line came from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// Written by the driver to the controller.
+    Sent,
+    /// Read by the driver from the controller.
+    Received,
+}
+
+/// One raw JSON line of a recorded session.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedLine {
+    /// Milliseconds since the recording started.
+    pub elapsed_ms: u64,
+    pub direction: Direction,
+    /// The line as it went over the wire, without the `\r\n` terminator.
+    pub line: String,
+}
+
+/// Appends [`RecordedLine`]s to a file as the driver talks to the controller.
+#[derive(Debug)]
+pub(crate) struct SessionRecorder {
+    started: Instant,
+    file: std::sync::Mutex<File>,
+}
+
+impl SessionRecorder {
+    pub(crate) fn create(path: &Path) -> std::io::Result<Self> {
+        Ok(Self {
+            started: Instant::now(),
+            file: std::sync::Mutex::new(File::create(path)?),
+        })
+    }
+
+    /// Record one line. Failures are ignored: recording is a debugging aid
+    /// and must never take the connection down.
+    pub(crate) fn record(&self, direction: Direction, line: &str) {
+        let entry = RecordedLine {
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            direction,
+            line: line.trim_end_matches(['\r', '\n']).to_string(),
+        };
+        if let Ok(json) = serde_json::to_string(&entry) {
+            if let Ok(mut file) = self.file.lock() {
+                let _ = writeln!(file, "{}", json);
+            }
+        }
+    }
+}
+
+/// Read a recording written by the driver.
+pub fn load_recording(path: impl AsRef<Path>) -> std::io::Result<Vec<RecordedLine>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut entries = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = serde_json::from_str(&line)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Why a replay did not match the recording.
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(std::io::Error),
+    /// The recording does not start with the `FRC_Connect` handshake.
+    InvalidRecording(String),
+    /// The client sent a different line than the one recorded at `index`.
+    Mismatch {
+        index: usize,
+        expected: String,
+        actual: String,
+    },
+    /// The client closed the connection while line `index` was still expected.
+    ClientClosed { index: usize, expected: String },
+}
+
+impl std::error::Error for ReplayError {}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(e) => write!(f, "Replay IO error: {}", e),
+            ReplayError::InvalidRecording(msg) => write!(f, "Invalid recording: {}", msg),
+            ReplayError::Mismatch {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Line {} differs from recording\n  expected: {}\n  actual:   {}",
+                index, expected, actual
+            ),
+            ReplayError::ClientClosed { index, expected } => write!(
+                f,
+                "Client closed the connection at line {}, expected: {}",
+                index, expected
+            ),
+        }
+    }
+}
+
+impl From<std::io::Error> for ReplayError {
+    fn from(e: std::io::Error) -> Self {
+        ReplayError::Io(e)
+    }
+}
+
+/// Act as the controller for a recorded session.
+///
+/// `connect_listener` plays the RMI connect port: it accepts one client,
+/// checks its `FRC_Connect` against the first recorded line and answers with
+/// the recorded reply, rewritten to point at `data_listener`. The remaining
+/// lines are then replayed on the data connection in order: recorded
+/// `Received` lines are written to the client, and each recorded `Sent` line
+/// must match the next line the client sends, byte for byte.
+///
+/// Returns the number of client lines that matched.
+pub async fn replay_session(
+    connect_listener: TcpListener,
+    data_listener: TcpListener,
+    recording: &[RecordedLine],
+) -> Result<usize, ReplayError> {
+    let (connect, reply) = match recording {
+        [connect, reply, ..]
+            if connect.direction == Direction::Sent
+                && reply.direction == Direction::Received
+                && connect.line.contains("FRC_Connect") =>
+        {
+            (connect, reply)
+        }
+        _ => {
+            return Err(ReplayError::InvalidRecording(
+                "expected FRC_Connect followed by its reply".to_string(),
+            ))
+        }
+    };
+
+    let data_port = data_listener.local_addr()?.port();
+    let mut reply: serde_json::Value = serde_json::from_str(&reply.line)
+        .map_err(|e| ReplayError::InvalidRecording(e.to_string()))?;
+    reply["PortNumber"] = serde_json::Value::from(data_port);
+
+    let (socket, _) = connect_listener.accept().await?;
+    let (read_half, mut write_half) = socket.into_split();
+    let mut lines = AsyncBufReader::new(read_half).lines();
+    expect_line(&mut lines, 0, &connect.line).await?;
+    write_half
+        .write_all(format!("{}\r\n", reply).as_bytes())
+        .await?;
+
+    let (socket, _) = data_listener.accept().await?;
+    let (read_half, mut write_half) = socket.into_split();
+    let mut lines = AsyncBufReader::new(read_half).lines();
+    let mut matched = 1;
+    for (index, entry) in recording.iter().enumerate().skip(2) {
+        match entry.direction {
+            Direction::Sent => {
+                expect_line(&mut lines, index, &entry.line).await?;
+                matched += 1;
+            }
+            Direction::Received => {
+                write_half
+                    .write_all(format!("{}\r\n", entry.line).as_bytes())
+                    .await?;
+            }
+        }
+    }
+    Ok(matched)
+}
+
+async fn expect_line<R: tokio::io::AsyncBufRead + Unpin>(
+    lines: &mut tokio::io::Lines<R>,
+    index: usize,
+    expected: &str,
+) -> Result<(), ReplayError> {
+    match lines.next_line().await? {
+        Some(actual) if actual == expected => Ok(()),
+        Some(actual) => Err(ReplayError::Mismatch {
+            index,
+            expected: expected.to_string(),
+            actual,
+        }),
+        None => Err(ReplayError::ClientClosed {
+            index,
+            expected: expected.to_string(),
+        }),
+    }
+}
@@ -0,0 +1,63 @@
+//! Traffic counters for monitoring.
+//!
+//! Every driver counts the packets it writes and reads on the data port,
+//! so a server can report throughput and error rates without subscribing
+//! to the response channel itself. Read them with
+//! [`FanucDriver::stats`](super::FanucDriver::stats).
+
+use std::ops::Add;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Totals since the driver connected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DriverStats {
+    /// Packets written to the controller.
+    pub packets_sent: u64,
+    /// Lines read from the controller, parsed or not.
+    pub packets_received: u64,
+    /// Received lines that failed to parse, each also sent on `error_tx`.
+    pub protocol_errors: u64,
+}
+
+/// Field-wise sum, e.g. the totals of several drivers in turn.
+impl Add for DriverStats {
+    type Output = DriverStats;
+
+    fn add(self, rhs: DriverStats) -> DriverStats {
+        DriverStats {
+            packets_sent: self.packets_sent + rhs.packets_sent,
+            packets_received: self.packets_received + rhs.packets_received,
+            protocol_errors: self.protocol_errors + rhs.protocol_errors,
+        }
+    }
+}
+
+/// Shared counters behind [`DriverStats`].
+#[derive(Debug, Default)]
+pub(crate) struct DriverCounters {
+    packets_sent: AtomicU64,
+    packets_received: AtomicU64,
+    protocol_errors: AtomicU64,
+}
+
+impl DriverCounters {
+    pub(crate) fn sent(&self) {
+        self.packets_sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn received(&self) {
+        self.packets_received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn protocol_error(&self) {
+        self.protocol_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> DriverStats {
+        DriverStats {
+            packets_sent: self.packets_sent.load(Ordering::Relaxed),
+            packets_received: self.packets_received.load(Ordering::Relaxed),
+            protocol_errors: self.protocol_errors.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Trajectory capture for post-run analysis.
+//!
+//! When [`FanucDriverConfig::trajectory_capacity`](super::FanucDriverConfig)
+//! is set, the driver keeps the most recent Cartesian positions reported by
+//! `FRC_ReadCartesianPosition` responses in a [`TrajectoryBuffer`], so a UI can
+//! draw a path trace without storing every sample itself.
+
+use crate::Position;
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// Fixed-capacity ring buffer of timestamped positions. Once full, each new
+/// sample evicts the oldest one.
+#[derive(Debug, Clone)]
+pub struct TrajectoryBuffer {
+    capacity: usize,
+    samples: VecDeque<(Instant, Position)>,
+}
+
+impl TrajectoryBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            samples: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Append a sample, evicting the oldest if the buffer is full.
+    pub fn push(&mut self, at: Instant, position: Position) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((at, position));
+    }
+
+    /// All samples, oldest first.
+    pub fn snapshot(&self) -> Vec<(Instant, Position)> {
+        self.samples.iter().copied().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+}
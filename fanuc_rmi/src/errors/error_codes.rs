@@ -84,9 +84,9 @@ pub fn format_error_id(error_id: u32) -> String {
     }
 }
 
-/// Best-effort: scan a raw JSON snippet for `"ErrorID" : <number>` and decode it.
-/// Used in fallback log paths where full deserialization failed.
-pub fn extract_and_format_error_id(raw_json: &str) -> Option<String> {
+/// Best-effort: scan a raw JSON snippet for a non-zero `"ErrorID" : <number>`.
+/// Used where full deserialization failed.
+pub fn extract_error_id(raw_json: &str) -> Option<u32> {
     let key = "\"ErrorID\"";
     let idx = raw_json.find(key)?;
     let after = &raw_json[idx + key.len()..];
@@ -94,7 +94,13 @@ pub fn extract_and_format_error_id(raw_json: &str) -> Option<String> {
     let end = after.find(|c: char| !c.is_ascii_digit()).unwrap_or(after.len());
     let id: u32 = after[..end].parse().ok()?;
     if id == 0 { return None; }
-    Some(format_error_id(id))
+    Some(id)
+}
+
+/// Best-effort: scan a raw JSON snippet for `"ErrorID" : <number>` and decode it.
+/// Used in fallback log paths where full deserialization failed.
+pub fn extract_and_format_error_id(raw_json: &str) -> Option<String> {
+    extract_error_id(raw_json).map(format_error_id)
 }
 
 #[cfg(test)]
@@ -118,6 +124,14 @@ mod tests {
         assert!(s.contains("Wait for Command Done"));
     }
 
+    #[test]
+    fn extracts_id_from_unparseable_json() {
+        let raw = r#"{"Command" : "FRC_ReadJointAngles", "ErrorID" : 2556955, "TimeTag" : }"#;
+        assert_eq!(extract_error_id(raw), Some(2556955));
+        assert_eq!(extract_error_id(r#"{"Command" : "FRC_Abort", "ErrorID" : 0}"#), None);
+        assert_eq!(extract_error_id("not json"), None);
+    }
+
     #[test]
     fn ignores_zero_error() {
         let raw = r#"{"ErrorID" : 0, "TimeTag": 100}"#;
@@ -2,34 +2,94 @@ use int_enum::IntEnum;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::fmt;
+use std::sync::Arc;
 
+/// The error an [`FrcError`] was built from, such as the `std::io::Error`
+/// of a failed socket write or the `serde_json::Error` of a bad packet.
+///
+/// Shared so `FrcError` stays `Clone`. It is not serialized; a deserialized
+/// `FrcError` has no source.
+#[derive(Clone)]
+pub struct ErrorSource(Arc<dyn Error + Send + Sync>);
+
+impl ErrorSource {
+    /// The wrapped error, e.g. to `downcast_ref` it.
+    pub fn get(&self) -> &(dyn Error + Send + Sync + 'static) {
+        self.0.as_ref()
+    }
+}
+
+impl<E: Error + Send + Sync + 'static> From<E> for ErrorSource {
+    fn from(error: E) -> Self {
+        ErrorSource(Arc::new(error))
+    }
+}
+
+impl fmt::Debug for ErrorSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for ErrorSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// Errors from the driver and packet parsing.
+///
+/// Variants that wrap a lower-level failure carry it as an optional
+/// [`ErrorSource`], returned by [`Error::source`]; the message already
+/// includes its text.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum FrcError {
-    Serialization(String),
+    Serialization(String, #[serde(skip)] Option<ErrorSource>),
     UnrecognizedPacket,
     FanucErrorCode(FanucErrorCode),
-    FailedToSend(String),
-    FailedToReceive(String),
+    FailedToSend(String, #[serde(skip)] Option<ErrorSource>),
+    FailedToReceive(String, #[serde(skip)] Option<ErrorSource>),
     Disconnected(),
-    Initialization(String),
+    /// Connecting or setting up the driver failed, e.g. the controller
+    /// refused the connection.
+    Initialization(String, #[serde(skip)] Option<ErrorSource>),
+    /// The controller never answers reads of this item (e.g. UFrame 0).
+    NotReadable(String),
+    /// A teach-pendant style line that isn't a valid instruction (see
+    /// [`Instruction::parse_line`](crate::packets::Instruction::parse_line)).
+    Parse(String),
+    /// The controller answered the named command with `Unknown`: it does
+    /// not recognize the command, or could not parse it.
+    UnsupportedCommand(String, FanucErrorCode),
 }
 impl Error for FrcError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
-        None
+        match self {
+            FrcError::Serialization(_, source)
+            | FrcError::FailedToSend(_, source)
+            | FrcError::FailedToReceive(_, source)
+            | FrcError::Initialization(_, source) => source.as_ref().map(|source| source.get() as &(dyn Error + 'static)),
+            _ => None,
+        }
     }
 }
 impl fmt::Display for FrcError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
-            FrcError::Serialization(ref msg) => write!(f, "Serialization error: {}", msg),
+            FrcError::Serialization(ref msg, _) => write!(f, "Serialization error: {}", msg),
             FrcError::UnrecognizedPacket => write!(f, "Fanuc threw an unrecognized weeoe"),
             FrcError::FanucErrorCode(ref code) => {
                 write!(f, "fanuc returned  error#: {}", code.message())
             }
-            FrcError::FailedToSend(ref msg) => write!(f, "SendError: {}", msg),
-            FrcError::FailedToReceive(ref msg) => write!(f, "RecieveError: {}", msg),
+            FrcError::FailedToSend(ref msg, _) => write!(f, "SendError: {}", msg),
+            FrcError::FailedToReceive(ref msg, _) => write!(f, "RecieveError: {}", msg),
             FrcError::Disconnected() => write!(f, "Fanuc appears to be disconnected"),
-            FrcError::Initialization(ref msg) => write!(f, "Could not initialize: {}", msg),
+            FrcError::Initialization(ref msg, _) => write!(f, "Could not initialize: {}", msg),
+            FrcError::NotReadable(ref msg) => write!(f, "Not readable: {}", msg),
+            FrcError::Parse(ref msg) => write!(f, "Parse error: {}", msg),
+            FrcError::UnsupportedCommand(ref name, ref code) => {
+                write!(f, "Controller does not support {}: {}", name, code.message())
+            }
         }
     }
 }
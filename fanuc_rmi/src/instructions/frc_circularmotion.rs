@@ -1,5 +1,59 @@
 use serde::{Deserialize, Serialize};
-use crate::{Configuration, Position, SpeedType, TermType};
+use std::fmt;
+use crate::{Configuration, FanucErrorCode, Position, SpeedType, TermType};
+
+/// Default tolerance (mm) for [`check_arc`].
+pub const DEFAULT_ARC_TOLERANCE: f64 = 0.01;
+
+/// Why three control points do not define a circular arc.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArcError {
+    /// Two of the start, via and end points are within tolerance of each other.
+    CoincidentPoints,
+    /// The via point lies within tolerance of the line through start and end.
+    ColinearPoints,
+}
+
+impl ArcError {
+    /// The controller's error for the same input (RMIT-037 Invalid VIA Position).
+    pub fn error_code(self) -> FanucErrorCode {
+        FanucErrorCode::InvalidVIAPosition
+    }
+}
+
+impl std::error::Error for ArcError {}
+
+impl fmt::Display for ArcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArcError::CoincidentPoints => write!(f, "arc start, via and end points must be distinct"),
+            ArcError::ColinearPoints => write!(f, "arc via point is colinear with start and end"),
+        }
+    }
+}
+
+/// Check that `start`, `via` and `end` (X/Y/Z, mm) lie on exactly one circle.
+///
+/// Points closer than `tolerance` to each other count as coincident, and a
+/// via point closer than `tolerance` to the line through start and end
+/// counts as colinear. Either way the arc is undefined.
+pub fn check_arc(start: &Position, via: &Position, end: &Position, tolerance: f64) -> Result<(), ArcError> {
+    let sub = |p: &Position, q: &Position| [p.x - q.x, p.y - q.y, p.z - q.z];
+    let norm = |v: [f64; 3]| (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    let (to_via, chord) = (sub(via, start), sub(end, start));
+    if norm(to_via) < tolerance || norm(sub(end, via)) < tolerance || norm(chord) < tolerance {
+        return Err(ArcError::CoincidentPoints);
+    }
+    let cross = [
+        to_via[1] * chord[2] - to_via[2] * chord[1],
+        to_via[2] * chord[0] - to_via[0] * chord[2],
+        to_via[0] * chord[1] - to_via[1] * chord[0],
+    ];
+    if norm(cross) / norm(chord) < tolerance {
+        return Err(ArcError::ColinearPoints);
+    }
+    Ok(())
+}
 
 #[cfg_attr(feature = "DTO", crate::mirror_dto)]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -22,6 +76,10 @@ pub struct FrcCircularMotion {
     pub term_type: TermType,
     #[serde(rename = "TermValue")]
     pub term_value: u8,
+    /// Run a CNT move without waiting for the next motion (RMI v5+).
+    /// Left out of the packet when `false`.
+    #[serde(rename = "NoBlend", default, skip_serializing_if = "crate::is_false")]
+    pub no_blend: bool,
 }
 
 impl FrcCircularMotion{
@@ -46,8 +104,15 @@ impl FrcCircularMotion{
             speed,
             term_type,
             term_value,
+            no_blend: false,
         }
     }
+
+    /// Check the arc from `start`, the position the robot will move from,
+    /// through `via_position` to `position`. See [`check_arc`].
+    pub fn check_arc(&self, start: &Position, tolerance: f64) -> Result<(), ArcError> {
+        check_arc(start, &self.via_position, &self.position, tolerance)
+    }
 }
 
 #[cfg_attr(feature = "DTO", crate::mirror_dto)]
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use crate::{Configuration, Position, SpeedType, TermType};
+use super::{check_arc, ArcError};
 
 #[cfg_attr(feature = "DTO", crate::mirror_dto)]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -22,6 +23,10 @@ pub struct FrcCircularRelative {
     pub term_type: TermType,
     #[serde(rename = "TermValue")]
     pub term_value: u8,
+    /// Run a CNT move without waiting for the next motion (RMI v5+).
+    /// Left out of the packet when `false`.
+    #[serde(rename = "NoBlend", default, skip_serializing_if = "crate::is_false")]
+    pub no_blend: bool,
 }
 
 impl FrcCircularRelative{
@@ -47,9 +52,16 @@ impl FrcCircularRelative{
             speed,
             term_type,
             term_value,
+            no_blend: false,
         }
 
     }
+
+    /// Check the arc through the `via_position` and `position` offsets from
+    /// the robot's current position. See [`check_arc`].
+    pub fn check_arc(&self, tolerance: f64) -> Result<(), ArcError> {
+        check_arc(&Position::default(), &self.via_position, &self.position, tolerance)
+    }
 }
 
 #[cfg_attr(feature = "DTO", crate::mirror_dto)]
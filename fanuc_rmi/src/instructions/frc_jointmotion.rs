@@ -18,6 +18,10 @@ pub struct FrcJointMotion {
     pub term_type: TermType,
     #[serde(rename = "TermValue")]
     pub term_value: u8,
+    /// Run a CNT move without waiting for the next motion (RMI v5+).
+    /// Left out of the packet when `false`.
+    #[serde(rename = "NoBlend", default, skip_serializing_if = "crate::is_false")]
+    pub no_blend: bool,
 }
 
 
@@ -40,6 +44,7 @@ impl FrcJointMotion{
             speed,
             term_type,
             term_value,
+            no_blend: false,
         }
 
     }
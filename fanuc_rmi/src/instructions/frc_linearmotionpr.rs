@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use crate::{SpeedType, TermType};
+
+/// Linear motion to the pose stored in a position register. The
+/// controller reads the register when it executes the move, not when the
+/// instruction is sent.
+#[cfg_attr(feature = "DTO", crate::mirror_dto)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FrcLinearMotionPR {
+    #[serde(rename = "SequenceID")]
+    pub sequence_id: u32,
+    #[serde(rename = "RegisterNumber")]
+    pub register_number: u16,
+    #[serde(rename = "SpeedType")]
+    pub speed_type: SpeedType,
+    #[serde(rename = "Speed")]
+    pub speed: f64,
+    #[serde(rename = "TermType")]
+    pub term_type: TermType,
+    #[serde(rename = "TermValue")]
+    pub term_value: u8,
+    /// Run a CNT move without waiting for the next motion (RMI v5+).
+    /// Left out of the packet when `false`.
+    #[serde(rename = "NoBlend", default, skip_serializing_if = "crate::is_false")]
+    pub no_blend: bool,
+}
+
+impl FrcLinearMotionPR {
+    pub fn new(
+        sequence_id: u32,
+        register_number: u16,
+        speed_type: SpeedType,
+        speed: f64,
+        term_type: TermType,
+        term_value: u8,
+    ) -> Self {
+        Self {
+            sequence_id,
+            register_number,
+            speed_type,
+            speed,
+            term_type,
+            term_value,
+            no_blend: false,
+        }
+    }
+}
+
+#[cfg_attr(feature = "DTO", crate::mirror_dto)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FrcLinearMotionPRResponse {
+    #[serde(rename = "ErrorID")]
+    pub error_id: u32,
+    #[serde(rename = "SequenceID", default)]
+    pub sequence_id: u32,
+}
@@ -14,6 +14,8 @@ mod frc_circularrelative;
 mod frc_jointmotionjrep;
 mod frc_jointrelativejrep;
 mod frc_linearmotionjrep;
+mod frc_linearmotionpr;
+mod frc_jointmotionpr;
 
 pub use frc_waitdin::*;
 pub use frc_setuframe::*;
@@ -31,6 +33,8 @@ pub use frc_circularrelative::*;
 pub use frc_jointmotionjrep::*;
 pub use frc_jointrelativejrep::*;
 pub use frc_linearmotionjrep::*;
+pub use frc_linearmotionpr::*;
+pub use frc_jointmotionpr::*;
 
 
 #[cfg(feature = "DTO")]
@@ -51,6 +55,8 @@ pub mod dto {
     pub use super::frc_jointmotionjrep::FrcJointMotionJRepDto as FrcJointMotionJRep;
     pub use super::frc_jointrelativejrep::FrcJointRelativeJRepDto as FrcJointRelativeJRep;
     pub use super::frc_linearmotionjrep::FrcLinearMotionJRepDto as FrcLinearMotionJRep;
+    pub use super::frc_linearmotionpr::FrcLinearMotionPRDto as FrcLinearMotionPR;
+    pub use super::frc_jointmotionpr::FrcJointMotionPRDto as FrcJointMotionPR;
         pub use super::frc_waitdin::FrcWaitDINResponseDto as FrcWaitDINResponse;
         pub use super::frc_setuframe::FrcSetUFrameResponseDto as FrcSetUFrameResponse;
         pub use super::frc_setutool::FrcSetUToolResponseDto as FrcSetUToolResponse;
@@ -67,5 +73,7 @@ pub mod dto {
         pub use super::frc_jointmotionjrep::FrcJointMotionJRepResponseDto as FrcJointMotionJRepResponse;
         pub use super::frc_jointrelativejrep::FrcJointRelativeJRepResponseDto as FrcJointRelativeJRepResponse;
         pub use super::frc_linearmotionjrep::FrcLinearMotionJRepResponseDto as FrcLinearMotionJRepResponse;
+        pub use super::frc_linearmotionpr::FrcLinearMotionPRResponseDto as FrcLinearMotionPRResponse;
+        pub use super::frc_jointmotionpr::FrcJointMotionPRResponseDto as FrcJointMotionPRResponse;
 
 }
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::ops::{Add, Sub};
 
 // Extract module must be declared first so the macro is available to other modules
 #[macro_use]
@@ -30,7 +31,8 @@ pub use nalgebra;
 /// Use `fanuc_rmi::protocol` for the JSON/robot protocol types, and
 /// `fanuc_rmi::dto` for your app's binary wire. Variant and field order in DTOs
 /// affect binary compatibility; prefer additive changes at the end and avoid
-/// reordering existing items.
+/// reordering existing items. The web server and app tag every DTO frame with
+/// `web_common::PROTOCOL_VERSION`, which must be bumped on any breaking change.
 #[cfg(feature = "DTO")]
 pub mod dto;
 #[cfg(feature = "DTO")]
@@ -56,6 +58,87 @@ pub struct FrameData {
     pub r: f64,
 }
 
+impl FrameData {
+    /// This frame moved by `dx`, `dy`, `dz` (mm), orientation unchanged.
+    pub fn translate(&self, dx: f64, dy: f64, dz: f64) -> Self {
+        Self { x: self.x + dx, y: self.y + dy, z: self.z + dz, ..self.clone() }
+    }
+
+    /// Straight-line distance (mm) between the origins of `self` and `other`.
+    pub fn distance_to(&self, other: &FrameData) -> f64 {
+        ((other.x - self.x).powi(2) + (other.y - self.y).powi(2) + (other.z - self.z).powi(2)).sqrt()
+    }
+
+    /// Component-wise interpolation: `self` at `t = 0`, `other` at `t = 1`.
+    pub fn lerp(&self, other: &FrameData, t: f64) -> Self {
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+        Self {
+            x: lerp(self.x, other.x),
+            y: lerp(self.y, other.y),
+            z: lerp(self.z, other.z),
+            w: lerp(self.w, other.w),
+            p: lerp(self.p, other.p),
+            r: lerp(self.r, other.r),
+        }
+    }
+
+    /// Check the frame can be written to the controller: every component
+    /// finite and W, P, R within ±360 degrees.
+    ///
+    /// # Errors
+    /// A message naming the first component that is out of range.
+    pub fn validate(&self) -> Result<(), String> {
+        let components = [("X", self.x), ("Y", self.y), ("Z", self.z), ("W", self.w), ("P", self.p), ("R", self.r)];
+        for (index, (name, value)) in components.into_iter().enumerate() {
+            if !value.is_finite() {
+                return Err(format!("{} must be a finite number, got {}", name, value));
+            }
+            if index >= 3 && !(-MAX_FRAME_ROTATION..=MAX_FRAME_ROTATION).contains(&value) {
+                return Err(format!(
+                    "{} must be between -{} and {} degrees, got {}",
+                    name, MAX_FRAME_ROTATION, MAX_FRAME_ROTATION, value
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Largest W, P or R magnitude (degrees) accepted by [`FrameData::validate`].
+pub const MAX_FRAME_ROTATION: f64 = 360.0;
+
+/// Component-wise sum, e.g. a frame plus an offset.
+impl Add for FrameData {
+    type Output = FrameData;
+
+    fn add(self, rhs: FrameData) -> FrameData {
+        FrameData {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            z: self.z + rhs.z,
+            w: self.w + rhs.w,
+            p: self.p + rhs.p,
+            r: self.r + rhs.r,
+        }
+    }
+}
+
+/// Component-wise difference, e.g. the offset between two frames.
+impl Sub for FrameData {
+    type Output = FrameData;
+
+    fn sub(self, rhs: FrameData) -> FrameData {
+        FrameData {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+            w: self.w - rhs.w,
+            p: self.p - rhs.p,
+            r: self.r - rhs.r,
+        }
+    }
+}
+
 /// Robot configuration data structure
 ///
 /// Corresponds to the "Configuration" object in FANUC RMI JSON packets.
@@ -140,6 +223,163 @@ impl Default for Configuration {
     }
 }
 
+/// Arm and wrist configuration, independent of the active UFrame/UTool.
+///
+/// This is the canonical, validated form of the configuration bits that are
+/// otherwise carried as raw integers ([`Configuration`] on the wire, `i32`
+/// columns in the web app). Convert into it with `TryFrom` / [`ArmConfig::try_from_raw`]
+/// and back out with [`ArmConfig::to_configuration`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArmConfig {
+    /// Front (`true`) / Back (`false`)
+    pub front: bool,
+    /// Up (`true`) / Down (`false`)
+    pub up: bool,
+    /// Left (`true`) / Right (`false`)
+    pub left: bool,
+    /// Flip (`true`) / NoFlip (`false`)
+    pub flip: bool,
+    /// J4 turn number
+    pub turn4: i8,
+    /// J5 turn number
+    pub turn5: i8,
+    /// J6 turn number
+    pub turn6: i8,
+}
+
+impl ArmConfig {
+    /// Turn numbers the controller accepts for J4-J6.
+    pub const TURN_RANGE: std::ops::RangeInclusive<i32> = -8..=7;
+
+    /// Validate raw integer configuration values.
+    ///
+    /// Arguments are in the controller's order: front, up, left, flip,
+    /// turn4, turn5, turn6. Bits must be 0 or 1 and turns within
+    /// [`ArmConfig::TURN_RANGE`].
+    pub fn try_from_raw(
+        front: i32,
+        up: i32,
+        left: i32,
+        flip: i32,
+        turn4: i32,
+        turn5: i32,
+        turn6: i32,
+    ) -> Result<Self, ArmConfigError> {
+        fn bit(field: &'static str, value: i32) -> Result<bool, ArmConfigError> {
+            match value {
+                0 => Ok(false),
+                1 => Ok(true),
+                _ => Err(ArmConfigError::InvalidBit { field, value }),
+            }
+        }
+        fn turn(field: &'static str, value: i32) -> Result<i8, ArmConfigError> {
+            if ArmConfig::TURN_RANGE.contains(&value) {
+                Ok(value as i8)
+            } else {
+                Err(ArmConfigError::TurnOutOfRange { field, value })
+            }
+        }
+        Ok(Self {
+            front: bit("front", front)?,
+            up: bit("up", up)?,
+            left: bit("left", left)?,
+            flip: bit("flip", flip)?,
+            turn4: turn("turn4", turn4)?,
+            turn5: turn("turn5", turn5)?,
+            turn6: turn("turn6", turn6)?,
+        })
+    }
+
+    /// The raw values in the controller's order: front, up, left, flip,
+    /// turn4, turn5, turn6.
+    pub fn to_raw(&self) -> [i32; 7] {
+        [
+            self.front as i32,
+            self.up as i32,
+            self.left as i32,
+            self.flip as i32,
+            self.turn4 as i32,
+            self.turn5 as i32,
+            self.turn6 as i32,
+        ]
+    }
+
+    /// Build a wire [`Configuration`] for the given UTool/UFrame.
+    pub fn to_configuration(&self, u_tool_number: i8, u_frame_number: i8) -> Configuration {
+        Configuration {
+            u_tool_number,
+            u_frame_number,
+            front: self.front as i8,
+            up: self.up as i8,
+            left: self.left as i8,
+            flip: self.flip as i8,
+            turn4: self.turn4,
+            turn5: self.turn5,
+            turn6: self.turn6,
+        }
+    }
+}
+
+impl Default for ArmConfig {
+    /// Front, Up, Right, NoFlip with zero turns.
+    fn default() -> Self {
+        Self {
+            front: true,
+            up: true,
+            left: false,
+            flip: false,
+            turn4: 0,
+            turn5: 0,
+            turn6: 0,
+        }
+    }
+}
+
+impl TryFrom<&Configuration> for ArmConfig {
+    type Error = ArmConfigError;
+
+    fn try_from(config: &Configuration) -> Result<Self, Self::Error> {
+        ArmConfig::try_from_raw(
+            config.front as i32,
+            config.up as i32,
+            config.left as i32,
+            config.flip as i32,
+            config.turn4 as i32,
+            config.turn5 as i32,
+            config.turn6 as i32,
+        )
+    }
+}
+
+/// Why raw configuration values could not be turned into an [`ArmConfig`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArmConfigError {
+    /// A front/up/left/flip value other than 0 or 1.
+    InvalidBit { field: &'static str, value: i32 },
+    /// A turn number outside [`ArmConfig::TURN_RANGE`].
+    TurnOutOfRange { field: &'static str, value: i32 },
+}
+
+impl std::error::Error for ArmConfigError {}
+
+impl std::fmt::Display for ArmConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArmConfigError::InvalidBit { field, value } => {
+                write!(f, "{} must be 0 or 1, got {}", field, value)
+            }
+            ArmConfigError::TurnOutOfRange { field, value } => write!(
+                f,
+                "{} must be between {} and {}, got {}",
+                field,
+                ArmConfig::TURN_RANGE.start(),
+                ArmConfig::TURN_RANGE.end(),
+                value
+            ),
+        }
+    }
+}
+
 /// Represents a Cartesian position with orientation.
 ///
 /// # Fields
@@ -187,6 +427,82 @@ impl Default for Position {
     }
 }
 
+impl Position {
+    /// This position moved by `dx`, `dy`, `dz` (mm); orientation and
+    /// external axes unchanged.
+    pub fn translate(&self, dx: f64, dy: f64, dz: f64) -> Self {
+        Self { x: self.x + dx, y: self.y + dy, z: self.z + dz, ..*self }
+    }
+
+    /// This position with X/Y/Z multiplied by `factor`, e.g. to change
+    /// length units; orientation and external axes unchanged.
+    pub fn scale_translation(&self, factor: f64) -> Self {
+        Self { x: self.x * factor, y: self.y * factor, z: self.z * factor, ..*self }
+    }
+
+    /// Straight-line distance to `other` over X/Y/Z (mm) and the external
+    /// axes. Orientation is not included.
+    pub fn distance_to(&self, other: &Position) -> f64 {
+        let d = *other - *self;
+        (d.x.powi(2) + d.y.powi(2) + d.z.powi(2) + d.ext1.powi(2) + d.ext2.powi(2) + d.ext3.powi(2)).sqrt()
+    }
+
+    /// Component-wise interpolation, external axes included: `self` at
+    /// `t = 0`, `other` at `t = 1`.
+    pub fn lerp(&self, other: &Position, t: f64) -> Self {
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+        Self {
+            x: lerp(self.x, other.x),
+            y: lerp(self.y, other.y),
+            z: lerp(self.z, other.z),
+            w: lerp(self.w, other.w),
+            p: lerp(self.p, other.p),
+            r: lerp(self.r, other.r),
+            ext1: lerp(self.ext1, other.ext1),
+            ext2: lerp(self.ext2, other.ext2),
+            ext3: lerp(self.ext3, other.ext3),
+        }
+    }
+}
+
+/// Component-wise sum, e.g. a position plus a relative offset.
+impl Add for Position {
+    type Output = Position;
+
+    fn add(self, rhs: Position) -> Position {
+        Position {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            z: self.z + rhs.z,
+            w: self.w + rhs.w,
+            p: self.p + rhs.p,
+            r: self.r + rhs.r,
+            ext1: self.ext1 + rhs.ext1,
+            ext2: self.ext2 + rhs.ext2,
+            ext3: self.ext3 + rhs.ext3,
+        }
+    }
+}
+
+/// Component-wise difference, e.g. the offset between two positions.
+impl Sub for Position {
+    type Output = Position;
+
+    fn sub(self, rhs: Position) -> Position {
+        Position {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+            w: self.w - rhs.w,
+            p: self.p - rhs.p,
+            r: self.r - rhs.r,
+            ext1: self.ext1 - rhs.ext1,
+            ext2: self.ext2 - rhs.ext2,
+            ext3: self.ext3 - rhs.ext3,
+        }
+    }
+}
+
 #[cfg_attr(feature = "DTO", mirror_dto)]
 #[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
 #[serde(rename_all = "PascalCase")]
@@ -244,7 +560,8 @@ pub struct JointAngles {
 /// **Implications**:
 /// - Always ensure the last motion instruction uses `FINE` termination type
 /// - If the last instruction is CNT, it will never execute (robot will wait indefinitely)
-/// - For RMI version 5+: Setting the `NoBlend` flag allows CNT moves to execute without waiting
+/// - For RMI version 5+: Setting the `NoBlend` flag (the `no_blend` field of motion instructions)
+///   allows CNT moves to execute without waiting for the next motion instruction
 ///
 /// # Buffer System
 ///
@@ -294,6 +611,11 @@ pub enum TermType {
     CR,  // CR with a value from 1 to 100
 }
 
+/// `skip_serializing_if` predicate for optional protocol flags.
+pub(crate) fn is_false(value: &bool) -> bool {
+    !*value
+}
+
 
 /// Represents different types of speed measurements.
 ///
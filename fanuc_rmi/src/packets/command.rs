@@ -28,7 +28,7 @@ pub enum Command {
     #[serde(rename = "FRC_ReadPositionRegister")]
     FrcReadPositionRegister(FrcReadPositionRegister),
 
-    #[serde(rename = "FrcWritePositionRegister")]
+    #[serde(rename = "FRC_WritePositionRegister")]
     FrcWritePositionRegister(FrcWritePositionRegister),
 
     #[serde(rename = "FRC_SetOverRide")]
@@ -81,6 +81,10 @@ pub enum Command {
 
     #[serde(rename = "FRC_ReadTCPSpeed")]
     FrcReadTCPSpeed,
+
+    /// Simulator-only, see [`SimMode`].
+    #[serde(rename = "SIM_Mode")]
+    SimMode(SimMode),
 }
 
 #[cfg_attr(feature = "DTO", crate::mirror_dto)]
@@ -166,10 +170,232 @@ pub enum CommandResponse {
     /// Robot sends this when it doesn't recognize a command
     #[serde(rename = "Unknown")]
     Unknown(FrcUnknownResponse),
+
+    /// Simulator-only, see [`SimMode`]. Last, so adding it kept the
+    /// bincode variant indices of the other responses.
+    #[serde(rename = "SIM_Mode")]
+    SimMode(SimModeResponse),
 }
 
 impl Packet for Command {}
 
+/// The type of a [`Command`], without its payload.
+///
+/// Keys per-command settings such as
+/// [`FanucDriverConfig::timeouts`](crate::drivers::FanucDriverConfig::timeouts).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommandKind {
+    #[serde(rename = "FRC_Initialize")]
+    FrcInitialize,
+
+    #[serde(rename = "FRC_Abort")]
+    FrcAbort,
+
+    #[serde(rename = "FRC_Pause")]
+    FrcPause,
+
+    #[serde(rename = "FRC_ReadError")]
+    FrcReadError,
+
+    #[serde(rename = "FRC_Continue")]
+    FrcContinue,
+
+    #[serde(rename = "FRC_SetUFrameUTool")]
+    FrcSetUFrameUTool,
+
+    #[serde(rename = "FRC_ReadPositionRegister")]
+    FrcReadPositionRegister,
+
+    #[serde(rename = "FRC_WritePositionRegister")]
+    FrcWritePositionRegister,
+
+    #[serde(rename = "FRC_SetOverRide")]
+    FrcSetOverRide,
+
+    #[serde(rename = "FRC_GetStatus")]
+    FrcGetStatus,
+
+    #[serde(rename = "FRC_GetUFrameUTool")]
+    FrcGetUFrameUTool,
+
+    #[serde(rename = "FRC_WriteUToolData")]
+    FrcWriteUToolData,
+
+    #[serde(rename = "FRC_ReadUToolData")]
+    FrcReadUToolData,
+
+    #[serde(rename = "FRC_ReadUFrameData")]
+    FrcReadUFrameData,
+
+    #[serde(rename = "FRC_WriteUFrameData")]
+    FrcWriteUFrameData,
+
+    #[serde(rename = "FRC_Reset")]
+    FrcReset,
+
+    #[serde(rename = "FRC_ReadDIN")]
+    FrcReadDIN,
+
+    #[serde(rename = "FRC_WriteDOUT")]
+    FrcWriteDOUT,
+
+    #[serde(rename = "FRC_ReadAIN")]
+    FrcReadAIN,
+
+    #[serde(rename = "FRC_WriteAOUT")]
+    FrcWriteAOUT,
+
+    #[serde(rename = "FRC_ReadGIN")]
+    FrcReadGIN,
+
+    #[serde(rename = "FRC_WriteGOUT")]
+    FrcWriteGOUT,
+
+    #[serde(rename = "FRC_ReadCartesianPosition")]
+    FrcReadCartesianPosition,
+
+    #[serde(rename = "FRC_ReadJointAngles")]
+    FrcReadJointAngles,
+
+    #[serde(rename = "FRC_ReadTCPSpeed")]
+    FrcReadTCPSpeed,
+
+    #[serde(rename = "SIM_Mode")]
+    SimMode,
+}
+
+impl Command {
+    /// The `Command` name the controller echoes back in the response.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::FrcInitialize(_) => "FRC_Initialize",
+            Command::FrcAbort => "FRC_Abort",
+            Command::FrcPause => "FRC_Pause",
+            Command::FrcReadError(_) => "FRC_ReadError",
+            Command::FrcContinue => "FRC_Continue",
+            Command::FrcSetUFrameUTool(_) => "FRC_SetUFrameUTool",
+            Command::FrcReadPositionRegister(_) => "FRC_ReadPositionRegister",
+            Command::FrcWritePositionRegister(_) => "FRC_WritePositionRegister",
+            Command::FrcSetOverRide(_) => "FRC_SetOverRide",
+            Command::FrcGetStatus => "FRC_GetStatus",
+            Command::FrcGetUFrameUTool(_) => "FRC_GetUFrameUTool",
+            Command::FrcWriteUToolData(_) => "FRC_WriteUToolData",
+            Command::FrcReadUToolData(_) => "FRC_ReadUToolData",
+            Command::FrcReadUFrameData(_) => "FRC_ReadUFrameData",
+            Command::FrcWriteUFrameData(_) => "FRC_WriteUFrameData",
+            Command::FrcReset => "FRC_Reset",
+            Command::FrcReadDIN(_) => "FRC_ReadDIN",
+            Command::FrcWriteDOUT(_) => "FRC_WriteDOUT",
+            Command::FrcReadAIN(_) => "FRC_ReadAIN",
+            Command::FrcWriteAOUT(_) => "FRC_WriteAOUT",
+            Command::FrcReadGIN(_) => "FRC_ReadGIN",
+            Command::FrcWriteGOUT(_) => "FRC_WriteGOUT",
+            Command::FrcReadCartesianPosition(_) => "FRC_ReadCartesianPosition",
+            Command::FrcReadJointAngles(_) => "FRC_ReadJointAngles",
+            Command::FrcReadTCPSpeed => "FRC_ReadTCPSpeed",
+            Command::SimMode(_) => "SIM_Mode",
+        }
+    }
+    /// The type of this command.
+    pub fn kind(&self) -> CommandKind {
+        match self {
+            Command::FrcInitialize(_) => CommandKind::FrcInitialize,
+            Command::FrcAbort => CommandKind::FrcAbort,
+            Command::FrcPause => CommandKind::FrcPause,
+            Command::FrcReadError(_) => CommandKind::FrcReadError,
+            Command::FrcContinue => CommandKind::FrcContinue,
+            Command::FrcSetUFrameUTool(_) => CommandKind::FrcSetUFrameUTool,
+            Command::FrcReadPositionRegister(_) => CommandKind::FrcReadPositionRegister,
+            Command::FrcWritePositionRegister(_) => CommandKind::FrcWritePositionRegister,
+            Command::FrcSetOverRide(_) => CommandKind::FrcSetOverRide,
+            Command::FrcGetStatus => CommandKind::FrcGetStatus,
+            Command::FrcGetUFrameUTool(_) => CommandKind::FrcGetUFrameUTool,
+            Command::FrcWriteUToolData(_) => CommandKind::FrcWriteUToolData,
+            Command::FrcReadUToolData(_) => CommandKind::FrcReadUToolData,
+            Command::FrcReadUFrameData(_) => CommandKind::FrcReadUFrameData,
+            Command::FrcWriteUFrameData(_) => CommandKind::FrcWriteUFrameData,
+            Command::FrcReset => CommandKind::FrcReset,
+            Command::FrcReadDIN(_) => CommandKind::FrcReadDIN,
+            Command::FrcWriteDOUT(_) => CommandKind::FrcWriteDOUT,
+            Command::FrcReadAIN(_) => CommandKind::FrcReadAIN,
+            Command::FrcWriteAOUT(_) => CommandKind::FrcWriteAOUT,
+            Command::FrcReadGIN(_) => CommandKind::FrcReadGIN,
+            Command::FrcWriteGOUT(_) => CommandKind::FrcWriteGOUT,
+            Command::FrcReadCartesianPosition(_) => CommandKind::FrcReadCartesianPosition,
+            Command::FrcReadJointAngles(_) => CommandKind::FrcReadJointAngles,
+            Command::FrcReadTCPSpeed => CommandKind::FrcReadTCPSpeed,
+            Command::SimMode(_) => CommandKind::SimMode,
+        }
+    }
+
+}
+
+impl CommandResponse {
+    /// The `Command` name this response answers.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CommandResponse::FrcInitialize(_) => "FRC_Initialize",
+            CommandResponse::FrcAbort(_) => "FRC_Abort",
+            CommandResponse::FrcPause(_) => "FRC_Pause",
+            CommandResponse::FrcContinue(_) => "FRC_Continue",
+            CommandResponse::FrcReadError(_) => "FRC_ReadError",
+            CommandResponse::FrcSetUFrameUTool(_) => "FRC_SetUFrameUTool",
+            CommandResponse::FrcGetUFrameUTool(_) => "FRC_GetUFrameUTool",
+            CommandResponse::FrcGetStatus(_) => "FRC_GetStatus",
+            CommandResponse::FrcReadUFrameData(_) => "FRC_ReadUFrameData",
+            CommandResponse::FrcWriteUFrameData(_) => "FRC_WriteUFrameData",
+            CommandResponse::FrcReadUToolData(_) => "FRC_ReadUToolData",
+            CommandResponse::FrcWriteUToolData(_) => "FRC_WriteUToolData",
+            CommandResponse::FrcReadDIN(_) => "FRC_ReadDIN",
+            CommandResponse::FrcWriteDOUT(_) => "FRC_WriteDOUT",
+            CommandResponse::FrcReadAIN(_) => "FRC_ReadAIN",
+            CommandResponse::FrcWriteAOUT(_) => "FRC_WriteAOUT",
+            CommandResponse::FrcReadGIN(_) => "FRC_ReadGIN",
+            CommandResponse::FrcWriteGOUT(_) => "FRC_WriteGOUT",
+            CommandResponse::FrcReadCartesianPosition(_) => "FRC_ReadCartesianPosition",
+            CommandResponse::FrcReadJointAngles(_) => "FRC_ReadJointAngles",
+            CommandResponse::FrcSetOverRide(_) => "FRC_SetOverRide",
+            CommandResponse::FrcReadPositionRegister(_) => "FRC_ReadPositionRegister",
+            CommandResponse::FrcWritePositionRegister(_) => "FRC_WritePositionRegister",
+            CommandResponse::FrcReset(_) => "FRC_Reset",
+            CommandResponse::FrcReadTCPSpeed(_) => "FRC_ReadTCPSpeed",
+            CommandResponse::Unknown(_) => "Unknown",
+            CommandResponse::SimMode(_) => "SIM_Mode",
+        }
+    }
+}
+
+macro_rules! impl_command_from {
+    ($variant:ident, $payload:ty) => {
+        impl From<$payload> for Command {
+            fn from(payload: $payload) -> Self {
+                Command::$variant(payload)
+            }
+        }
+    };
+}
+
+impl_command_from!(FrcInitialize, FrcInitialize);
+impl_command_from!(FrcReadError, FrcReadError);
+impl_command_from!(FrcSetUFrameUTool, FrcSetUFrameUTool);
+impl_command_from!(FrcReadPositionRegister, FrcReadPositionRegister);
+impl_command_from!(FrcWritePositionRegister, FrcWritePositionRegister);
+impl_command_from!(FrcSetOverRide, FrcSetOverRide);
+impl_command_from!(FrcGetUFrameUTool, FrcGetUFrameUTool);
+impl_command_from!(FrcWriteUToolData, FrcWriteUToolData);
+impl_command_from!(FrcReadUToolData, FrcReadUToolData);
+impl_command_from!(FrcReadUFrameData, FrcReadUFrameData);
+impl_command_from!(FrcWriteUFrameData, FrcWriteUFrameData);
+impl_command_from!(FrcReadDIN, FrcReadDIN);
+impl_command_from!(FrcWriteDOUT, FrcWriteDOUT);
+impl_command_from!(FrcReadAIN, FrcReadAIN);
+impl_command_from!(FrcWriteAOUT, FrcWriteAOUT);
+impl_command_from!(FrcReadGIN, FrcReadGIN);
+impl_command_from!(FrcWriteGOUT, FrcWriteGOUT);
+impl_command_from!(FrcReadCartesianPosition, FrcReadCartesianPosition);
+impl_command_from!(FrcReadJointAngles, FrcReadJointAngles);
+impl_command_from!(SimMode, SimMode);
+
 // ExtractInner trait implementations for CommandResponse
 impl_extract_inner!(CommandResponse, FrcInitialize, FrcInitializeResponse);
 impl_extract_inner!(CommandResponse, FrcAbort, FrcAbortResponse);
@@ -196,4 +422,5 @@ impl_extract_inner!(CommandResponse, FrcReadPositionRegister, FrcReadPositionReg
 impl_extract_inner!(CommandResponse, FrcWritePositionRegister, FrcWritePositionRegisterResponse);
 impl_extract_inner!(CommandResponse, FrcReset, FrcResetResponse);
 impl_extract_inner!(CommandResponse, FrcReadTCPSpeed, FrcReadTCPSpeedResponse);
+impl_extract_inner!(CommandResponse, SimMode, SimModeResponse);
 impl_extract_inner!(CommandResponse, Unknown, FrcUnknownResponse);
@@ -29,6 +29,18 @@ pub enum CommunicationResponse {
     FrcSystemFault,
 }
 
+impl CommunicationResponse {
+    /// The `Communication` name this response answers.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CommunicationResponse::FrcConnect(_) => "FRC_Connect",
+            CommunicationResponse::FrcDisconnect(_) => "FRC_Disconnect",
+            CommunicationResponse::FrcTerminate => "FRC_Terminate",
+            CommunicationResponse::FrcSystemFault => "FRC_SystemFault",
+        }
+    }
+}
+
 #[cfg_attr(feature = "DTO", crate::mirror_dto)]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct FrcConnectResponse {
@@ -41,6 +53,49 @@ pub struct FrcConnectResponse {
     #[serde(rename = "MinorVersion")]
     pub minor_version: u16
 }
+impl FrcConnectResponse {
+    /// The RMI version the controller reported.
+    pub fn version(&self) -> RmiVersion {
+        RmiVersion::new(self.major_version, self.minor_version)
+    }
+}
+
+/// RMI protocol version, as reported in the `FRC_Connect` response.
+/// Orders by major, then minor version.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RmiVersion {
+    pub major: u16,
+    pub minor: u16,
+}
+
+impl RmiVersion {
+    /// First version accepting `NoBlend` on motion instructions.
+    pub const NO_BLEND: RmiVersion = RmiVersion::new(5, 0);
+
+    pub const fn new(major: u16, minor: u16) -> Self {
+        Self { major, minor }
+    }
+}
+
+impl std::fmt::Display for RmiVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// Parses `major` or `major.minor`, e.g. `5` or `5.0`.
+impl std::str::FromStr for RmiVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (major, minor) = s.split_once('.').unwrap_or((s, "0"));
+        match (major.trim().parse(), minor.trim().parse()) {
+            (Ok(major), Ok(minor)) => Ok(Self::new(major, minor)),
+            _ => Err(format!("invalid RMI version {:?}, expected e.g. 5 or 5.0", s)),
+        }
+    }
+}
+
 #[cfg_attr(feature = "DTO", crate::mirror_dto)]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct FrcDisconnectResponse {
@@ -14,6 +14,12 @@ pub enum DriverCommand {
     /// of in-flight packets, since the robot clears its motion queue on abort
     /// but doesn't send responses for aborted instructions.
     ClearInFlight,
+    /// Drops queued instructions that have not been sent yet.
+    ///
+    /// In-flight instructions are left to the controller, which answers them
+    /// (with an error after an abort). Complements `FRC_Abort`, which clears
+    /// the controller's buffer but not the driver's queue.
+    Flush,
     /// Program pause: Aborts the RMI program but preserves in-flight instructions for replay.
     /// Unlike Pause, this allows the robot to be jogged while the program is paused.
     ProgramPause,
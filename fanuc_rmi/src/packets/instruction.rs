@@ -53,9 +53,64 @@ pub enum Instruction {
 
     #[serde(rename = "FRC_LinearMotionJRep")]
     FrcLinearMotionJRep(FrcLinearMotionJRep), // Add Linear Motion with Joint Representation
+
+    #[serde(rename = "FRC_LinearMotionPR")]
+    FrcLinearMotionPR(FrcLinearMotionPR), // Add Linear Motion to a Position Register
+
+    #[serde(rename = "FRC_JointMotionPR")]
+    FrcJointMotionPR(FrcJointMotionPR), // Add Joint Motion to a Position Register
 }
 
 impl Instruction {
+    /// The `Instruction` name sent on the wire and echoed in the response.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Instruction::FrcWaitDIN(_) => "FRC_WaitDIN",
+            Instruction::FrcSetUFrame(_) => "FRC_SetUFrame",
+            Instruction::FrcSetUTool(_) => "FRC_SetUTool",
+            Instruction::FrcWaitTime(_) => "FRC_WaitTime",
+            Instruction::FrcSetPayLoad(_) => "FRC_SetPayLoad",
+            Instruction::FrcCall(_) => "FRC_Call",
+            Instruction::FrcLinearMotion(_) => "FRC_LinearMotion",
+            Instruction::FrcLinearRelative(_) => "FRC_LinearRelative",
+            Instruction::FrcLinearRelativeJRep(_) => "FRC_LinearRelativeJRep",
+            Instruction::FrcJointMotion(_) => "FRC_JointMotion",
+            Instruction::FrcJointRelative(_) => "FRC_JointRelative",
+            Instruction::FrcCircularMotion(_) => "FRC_CircularMotion",
+            Instruction::FrcCircularRelative(_) => "FRC_CircularRelative",
+            Instruction::FrcJointMotionJRep(_) => "FRC_JointMotionJRep",
+            Instruction::FrcJointRelativeJRep(_) => "FRC_JointRelativeJRep",
+            Instruction::FrcLinearMotionJRep(_) => "FRC_LinearMotionJRep",
+            Instruction::FrcLinearMotionPR(_) => "FRC_LinearMotionPR",
+            Instruction::FrcJointMotionPR(_) => "FRC_JointMotionPR",
+        }
+    }
+
+    /// Whether this is a motion instruction with `NoBlend` set. Always
+    /// false for the non-motion instructions, which have no such field.
+    pub fn no_blend(&self) -> bool {
+        match self {
+            Instruction::FrcWaitDIN(_)
+            | Instruction::FrcSetUFrame(_)
+            | Instruction::FrcSetUTool(_)
+            | Instruction::FrcWaitTime(_)
+            | Instruction::FrcSetPayLoad(_)
+            | Instruction::FrcCall(_) => false,
+            Instruction::FrcLinearMotion(instr) => instr.no_blend,
+            Instruction::FrcLinearRelative(instr) => instr.no_blend,
+            Instruction::FrcLinearRelativeJRep(instr) => instr.no_blend,
+            Instruction::FrcJointMotion(instr) => instr.no_blend,
+            Instruction::FrcJointRelative(instr) => instr.no_blend,
+            Instruction::FrcCircularMotion(instr) => instr.no_blend,
+            Instruction::FrcCircularRelative(instr) => instr.no_blend,
+            Instruction::FrcJointMotionJRep(instr) => instr.no_blend,
+            Instruction::FrcJointRelativeJRep(instr) => instr.no_blend,
+            Instruction::FrcLinearMotionJRep(instr) => instr.no_blend,
+            Instruction::FrcLinearMotionPR(instr) => instr.no_blend,
+            Instruction::FrcJointMotionPR(instr) => instr.no_blend,
+        }
+    }
+
     pub fn get_sequence_id(&self) -> u32 {
         match self {
             Instruction::FrcWaitDIN(resp) => resp.sequence_id,
@@ -74,6 +129,8 @@ impl Instruction {
             Instruction::FrcJointMotionJRep(resp) => resp.sequence_id,
             Instruction::FrcJointRelativeJRep(resp) => resp.sequence_id,
             Instruction::FrcLinearMotionJRep(resp) => resp.sequence_id,
+            Instruction::FrcLinearMotionPR(resp) => resp.sequence_id,
+            Instruction::FrcJointMotionPR(resp) => resp.sequence_id,
         }
     }
 }
@@ -114,9 +171,37 @@ pub enum InstructionResponse {
     FrcJointRelativeJRep(FrcJointRelativeJRepResponse),
     #[serde(rename = "FRC_LinearMotionJRep")]
     FrcLinearMotionJRep(FrcLinearMotionJRepResponse),
+    #[serde(rename = "FRC_LinearMotionPR")]
+    FrcLinearMotionPR(FrcLinearMotionPRResponse),
+    #[serde(rename = "FRC_JointMotionPR")]
+    FrcJointMotionPR(FrcJointMotionPRResponse),
 }
 
 impl InstructionResponse {
+    /// The `Instruction` name this response answers.
+    pub fn name(&self) -> &'static str {
+        match self {
+            InstructionResponse::FrcWaitDIN(_) => "FRC_WaitDIN",
+            InstructionResponse::FrcSetUFrame(_) => "FRC_SetUFrame",
+            InstructionResponse::FrcSetUTool(_) => "FRC_SetUTool",
+            InstructionResponse::FrcWaitTime(_) => "FRC_WaitTime",
+            InstructionResponse::FrcSetPayLoad(_) => "FRC_SetPayLoad",
+            InstructionResponse::FrcCall(_) => "FRC_Call",
+            InstructionResponse::FrcLinearMotion(_) => "FRC_LinearMotion",
+            InstructionResponse::FrcLinearRelative(_) => "FRC_LinearRelative",
+            InstructionResponse::FrcLinearRelativeJRep(_) => "FRC_LinearRelativeJRep",
+            InstructionResponse::FrcJointMotion(_) => "FRC_JointMotion",
+            InstructionResponse::FrcJointRelative(_) => "FRC_JointRelative",
+            InstructionResponse::FrcCircularMotion(_) => "FRC_CircularMotion",
+            InstructionResponse::FrcCircularRelative(_) => "FRC_CircularRelative",
+            InstructionResponse::FrcJointMotionJRep(_) => "FRC_JointMotionJRep",
+            InstructionResponse::FrcJointRelativeJRep(_) => "FRC_JointRelativeJRep",
+            InstructionResponse::FrcLinearMotionJRep(_) => "FRC_LinearMotionJRep",
+            InstructionResponse::FrcLinearMotionPR(_) => "FRC_LinearMotionPR",
+            InstructionResponse::FrcJointMotionPR(_) => "FRC_JointMotionPR",
+        }
+    }
+
     pub fn get_sequence_id(&self) -> u32 {
         match self {
             InstructionResponse::FrcWaitDIN(resp) => resp.sequence_id,
@@ -135,6 +220,8 @@ impl InstructionResponse {
             InstructionResponse::FrcJointMotionJRep(resp) => resp.sequence_id,
             InstructionResponse::FrcJointRelativeJRep(resp) => resp.sequence_id,
             InstructionResponse::FrcLinearMotionJRep(resp) => resp.sequence_id,
+            InstructionResponse::FrcLinearMotionPR(resp) => resp.sequence_id,
+            InstructionResponse::FrcJointMotionPR(resp) => resp.sequence_id,
         }
     }
 }
@@ -157,6 +244,8 @@ impl InstructionResponse {
             InstructionResponse::FrcJointMotionJRep(resp) => resp.error_id,
             InstructionResponse::FrcJointRelativeJRep(resp) => resp.error_id,
             InstructionResponse::FrcLinearMotionJRep(resp) => resp.error_id,
+            InstructionResponse::FrcLinearMotionPR(resp) => resp.error_id,
+            InstructionResponse::FrcJointMotionPR(resp) => resp.error_id,
         }
     }
 }
@@ -209,4 +298,6 @@ impl_extract_inner!(InstructionResponse, FrcCircularRelative, FrcCircularRelativ
 impl_extract_inner!(InstructionResponse, FrcJointMotionJRep, FrcJointMotionJRepResponse);
 impl_extract_inner!(InstructionResponse, FrcJointRelativeJRep, FrcJointRelativeJRepResponse);
 impl_extract_inner!(InstructionResponse, FrcLinearMotionJRep, FrcLinearMotionJRepResponse);
+impl_extract_inner!(InstructionResponse, FrcLinearMotionPR, FrcLinearMotionPRResponse);
+impl_extract_inner!(InstructionResponse, FrcJointMotionPR, FrcJointMotionPRResponse);
 
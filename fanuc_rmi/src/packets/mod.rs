@@ -2,6 +2,7 @@ mod command;
 mod communication;
 mod instruction;
 mod driver_command;
+mod tp_line;
 
 pub use command::*;
 pub use communication::*;
@@ -39,6 +40,17 @@ pub enum ResponsePacket {
     InstructionResponse(InstructionResponse),
 }
 
+impl ResponsePacket {
+    /// The `Communication`, `Command` or `Instruction` name of the response.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResponsePacket::CommunicationResponse(response) => response.name(),
+            ResponsePacket::CommandResponse(response) => response.name(),
+            ResponsePacket::InstructionResponse(response) => response.name(),
+        }
+    }
+}
+
 pub trait Packet: Serialize + for<'de> Deserialize<'de> {}
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
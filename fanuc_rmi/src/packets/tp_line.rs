@@ -0,0 +1,140 @@
+// Teach-pendant style motion lines such as `L P[1] 100mm/sec FINE`,
+// parsed into the position register motion instructions.
+
+use std::str::FromStr;
+
+use super::Instruction;
+use crate::instructions::{FrcJointMotionPR, FrcLinearMotionPR};
+use crate::{FrcError, SpeedType, TermType};
+
+/// Speed units accepted after the speed value, for error messages.
+const SPEED_UNITS: &str = "mm/sec, inch/min, sec or msec";
+
+impl Instruction {
+    /// Parse one teach-pendant style motion line.
+    ///
+    /// The line is `<motion> <position> <speed><unit> <termination>`:
+    ///
+    /// * motion: `L` (linear, `FRC_LinearMotionPR`) or `J` (joint,
+    ///   `FRC_JointMotionPR`)
+    /// * position: `PR[n]`, or `P[n]`, which also names position register
+    ///   `n` because RMI has no TP program to hold local positions
+    /// * speed: a number followed by `mm/sec`, `inch/min`, `sec` or `msec`;
+    ///   `sec` is sent as tenths of a second
+    /// * termination: `FINE`, `CNT0`-`CNT100` or `CR1`-`CR100`
+    ///
+    /// Keywords are case-insensitive. A leading line number (`1:`) and a
+    /// trailing `;` are ignored. The sequence ID is left at 0 for the driver
+    /// to assign.
+    ///
+    /// # Errors
+    /// `FrcError::Parse` naming the part of the line that is wrong.
+    ///
+    /// # Example
+    /// ```
+    /// use fanuc_rmi::packets::Instruction;
+    ///
+    /// let instruction = Instruction::parse_line("L P[1] 100mm/sec FINE").unwrap();
+    /// assert_eq!(instruction.name(), "FRC_LinearMotionPR");
+    /// ```
+    pub fn parse_line(line: &str) -> Result<Instruction, FrcError> {
+        let text = line.trim();
+        let text = text.strip_suffix(';').unwrap_or(text);
+        let text = match text.split_once(':') {
+            Some((number, rest)) if number.trim().parse::<u32>().is_ok() => rest,
+            _ => text,
+        };
+
+        let mut tokens = text.split_whitespace();
+        let motion = tokens.next().ok_or_else(|| parse_error(line, "empty line"))?;
+        let position = tokens.next().ok_or_else(|| parse_error(line, "missing position"))?;
+        let speed = tokens.next().ok_or_else(|| parse_error(line, "missing speed"))?;
+        let term = tokens.next().ok_or_else(|| parse_error(line, "missing termination (FINE, CNTn or CRn)"))?;
+        if let Some(extra) = tokens.next() {
+            return Err(parse_error(line, &format!("unexpected '{}' after the termination", extra)));
+        }
+
+        let register_number = parse_position(position).map_err(|reason| parse_error(line, &reason))?;
+        let (speed_type, speed) = parse_speed(speed).map_err(|reason| parse_error(line, &reason))?;
+        let (term_type, term_value) = parse_term(term).map_err(|reason| parse_error(line, &reason))?;
+
+        match motion.to_uppercase().as_str() {
+            "L" => Ok(Instruction::FrcLinearMotionPR(FrcLinearMotionPR::new(
+                0, register_number, speed_type, speed, term_type, term_value,
+            ))),
+            "J" => Ok(Instruction::FrcJointMotionPR(FrcJointMotionPR::new(
+                0, register_number, speed_type, speed, term_type, term_value,
+            ))),
+            _ => Err(parse_error(line, &format!("unknown motion type '{}' (expected L or J)", motion))),
+        }
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = FrcError;
+
+    /// See [`Instruction::parse_line`].
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        Instruction::parse_line(line)
+    }
+}
+
+fn parse_error(line: &str, reason: &str) -> FrcError {
+    FrcError::Parse(format!("{} in '{}'", reason, line.trim()))
+}
+
+/// Register number of `P[n]` or `PR[n]`.
+fn parse_position(token: &str) -> Result<u16, String> {
+    let upper = token.to_uppercase();
+    let index = upper
+        .strip_prefix("PR[")
+        .or_else(|| upper.strip_prefix("P["))
+        .and_then(|rest| rest.strip_suffix(']'))
+        .ok_or_else(|| format!("expected a position like P[1] or PR[1], got '{}'", token))?;
+    match index.parse::<u16>() {
+        Ok(0) => Err(format!("position register numbers start at 1, got '{}'", token)),
+        Ok(number) => Ok(number),
+        Err(_) => Err(format!("invalid position number in '{}'", token)),
+    }
+}
+
+/// Speed type and value of a speed such as `100mm/sec`.
+fn parse_speed(token: &str) -> Result<(SpeedType, f64), String> {
+    let split = token
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(token.len());
+    let (number, unit) = token.split_at(split);
+    let value: f64 = number
+        .parse()
+        .map_err(|_| format!("expected a speed like 100mm/sec, got '{}'", token))?;
+    if unit.is_empty() {
+        return Err(format!("speed '{}' has no unit (expected {})", token, SPEED_UNITS));
+    }
+    match unit.to_lowercase().as_str() {
+        "mm/sec" => Ok((SpeedType::MMSec, value)),
+        "inch/min" => Ok((SpeedType::InchMin, value)),
+        "sec" => Ok((SpeedType::Time, value * 10.0)),
+        "msec" => Ok((SpeedType::MilliSeconds, value)),
+        _ => Err(format!("unknown speed unit '{}' (expected {})", unit, SPEED_UNITS)),
+    }
+}
+
+/// Term type and value of `FINE`, `CNTn` or `CRn`.
+fn parse_term(token: &str) -> Result<(TermType, u8), String> {
+    let upper = token.to_uppercase();
+    if upper == "FINE" {
+        return Ok((TermType::FINE, 0));
+    }
+    let (term_type, value, min) = if let Some(value) = upper.strip_prefix("CNT") {
+        (TermType::CNT, value, 0)
+    } else if let Some(value) = upper.strip_prefix("CR") {
+        (TermType::CR, value, 1)
+    } else {
+        return Err(format!("unknown termination '{}' (expected FINE, CNTn or CRn)", token));
+    };
+    match value.parse::<u32>() {
+        Ok(value) if (min..=100).contains(&value) => Ok((term_type, value as u8)),
+        Ok(_) => Err(format!("term value in '{}' is out of range ({}-100)", token, min)),
+        Err(_) => Err(format!("missing or invalid term value in '{}'", token)),
+    }
+}
@@ -0,0 +1,92 @@
+//! Conversion and validation tests for `ArmConfig`.
+
+use fanuc_rmi::{ArmConfig, ArmConfigError, Configuration};
+
+fn flipped_with_turns() -> ArmConfig {
+    ArmConfig {
+        front: false,
+        up: true,
+        left: true,
+        flip: true,
+        turn4: -1,
+        turn5: 0,
+        turn6: 2,
+    }
+}
+
+#[test]
+fn test_configuration_to_arm_config() {
+    let config = Configuration {
+        u_tool_number: 3,
+        u_frame_number: 2,
+        front: 0,
+        up: 1,
+        left: 1,
+        flip: 1,
+        turn4: -1,
+        turn5: 0,
+        turn6: 2,
+    };
+    let arm = ArmConfig::try_from(&config).expect("valid configuration");
+    assert_eq!(arm, flipped_with_turns());
+}
+
+#[test]
+fn test_arm_config_to_configuration_keeps_frame_and_tool() {
+    let config = flipped_with_turns().to_configuration(3, 2);
+    assert_eq!(config.u_tool_number, 3);
+    assert_eq!(config.u_frame_number, 2);
+    assert_eq!(
+        (config.front, config.up, config.left, config.flip),
+        (0, 1, 1, 1)
+    );
+    assert_eq!((config.turn4, config.turn5, config.turn6), (-1, 0, 2));
+    assert_eq!(ArmConfig::try_from(&config).unwrap(), flipped_with_turns());
+}
+
+#[test]
+fn test_raw_roundtrip() {
+    let arm = flipped_with_turns();
+    let [front, up, left, flip, turn4, turn5, turn6] = arm.to_raw();
+    assert_eq!([front, up, left, flip, turn4, turn5, turn6], [0, 1, 1, 1, -1, 0, 2]);
+    assert_eq!(
+        ArmConfig::try_from_raw(front, up, left, flip, turn4, turn5, turn6).unwrap(),
+        arm
+    );
+}
+
+#[test]
+fn test_default_matches_wire_default_arm_bits() {
+    // Configuration::default() is Front/Up/Left; ArmConfig::default() is the
+    // web app's Front/Up/Right. Both must survive a round trip.
+    let wire = Configuration::default();
+    let arm = ArmConfig::try_from(&wire).unwrap();
+    assert_eq!(arm.to_configuration(wire.u_tool_number, wire.u_frame_number), wire);
+    assert_eq!(ArmConfig::default().to_raw(), [1, 1, 0, 0, 0, 0, 0]);
+}
+
+#[test]
+fn test_invalid_bit_is_rejected() {
+    assert_eq!(
+        ArmConfig::try_from_raw(1, 2, 0, 0, 0, 0, 0),
+        Err(ArmConfigError::InvalidBit { field: "up", value: 2 })
+    );
+
+    // Error-state values a real controller can report (see Configuration docs).
+    let config = Configuration {
+        left: -98,
+        flip: -32,
+        ..Configuration::default()
+    };
+    assert_eq!(
+        ArmConfig::try_from(&config),
+        Err(ArmConfigError::InvalidBit { field: "left", value: -98 })
+    );
+}
+
+#[test]
+fn test_turn_out_of_range_is_rejected() {
+    let err = ArmConfig::try_from_raw(1, 1, 0, 0, 0, 0, 100).unwrap_err();
+    assert_eq!(err, ArmConfigError::TurnOutOfRange { field: "turn6", value: 100 });
+    assert_eq!(err.to_string(), "turn6 must be between -8 and 7, got 100");
+}
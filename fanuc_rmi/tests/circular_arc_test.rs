@@ -0,0 +1,72 @@
+//! Tests for circular-motion via-point validation.
+
+use fanuc_rmi::instructions::{check_arc, ArcError, FrcCircularMotion, FrcCircularRelative, DEFAULT_ARC_TOLERANCE};
+use fanuc_rmi::{Configuration, FanucErrorCode, Position, SpeedType, TermType};
+
+fn point(x: f64, y: f64, z: f64) -> Position {
+    Position { x, y, z, ..Position::default() }
+}
+
+fn circular(via: Position, end: Position) -> FrcCircularMotion {
+    FrcCircularMotion::new(
+        1,
+        Configuration::default(),
+        end,
+        Configuration::default(),
+        via,
+        SpeedType::MMSec,
+        100.0,
+        TermType::FINE,
+        0,
+    )
+}
+
+#[test]
+fn test_colinear_points_rejected() {
+    let start = point(0.0, 0.0, 0.0);
+    let instruction = circular(point(50.0, 50.0, 50.0), point(100.0, 100.0, 100.0));
+    assert_eq!(instruction.check_arc(&start, DEFAULT_ARC_TOLERANCE), Err(ArcError::ColinearPoints));
+
+    // Via point just off the line is still colinear within a looser tolerance
+    let via = point(50.0, 0.5, 0.0);
+    let end = point(100.0, 0.0, 0.0);
+    assert_eq!(check_arc(&start, &via, &end, DEFAULT_ARC_TOLERANCE), Ok(()));
+    assert_eq!(check_arc(&start, &via, &end, 1.0), Err(ArcError::ColinearPoints));
+    assert!(matches!(ArcError::ColinearPoints.error_code(), FanucErrorCode::InvalidVIAPosition));
+}
+
+#[test]
+fn test_coincident_points_rejected() {
+    let start = point(10.0, 20.0, 30.0);
+    assert_eq!(
+        check_arc(&start, &start, &point(100.0, 0.0, 0.0), DEFAULT_ARC_TOLERANCE),
+        Err(ArcError::CoincidentPoints)
+    );
+    assert_eq!(
+        check_arc(&start, &point(0.0, 0.0, 0.0), &start, DEFAULT_ARC_TOLERANCE),
+        Err(ArcError::CoincidentPoints)
+    );
+}
+
+#[test]
+fn test_valid_arc_accepted() {
+    // Quarter circle of radius 100 around the origin in the XY plane
+    let start = point(100.0, 0.0, 0.0);
+    let via = point(100.0 * std::f64::consts::FRAC_1_SQRT_2, 100.0 * std::f64::consts::FRAC_1_SQRT_2, 0.0);
+    let instruction = circular(via, point(0.0, 100.0, 0.0));
+    assert_eq!(instruction.check_arc(&start, DEFAULT_ARC_TOLERANCE), Ok(()));
+
+    // Relative arcs are measured from the current position
+    let relative = FrcCircularRelative::new(
+        1,
+        Configuration::default(),
+        point(0.0, 100.0, 0.0),
+        Configuration::default(),
+        point(50.0, 50.0, 10.0),
+        SpeedType::MMSec,
+        100.0,
+        TermType::FINE,
+        0,
+    );
+    assert_eq!(relative.check_arc(DEFAULT_ARC_TOLERANCE), Ok(()));
+}
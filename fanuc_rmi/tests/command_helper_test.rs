@@ -0,0 +1,163 @@
+//! Tests for `FanucDriver::command`, the send-and-confirm command helper.
+//!
+//! The fake controller answers commands out of order and with distinguishable
+//! payloads, so each test can check that a caller gets the response to its own
+//! command rather than whichever response arrived first.
+
+use fanuc_rmi::commands::{FrcGetUFrameUTool, FrcInitialize};
+use fanuc_rmi::drivers::{FanucDriver, FanucDriverConfig};
+use fanuc_rmi::packets::{Command, CommandKind, CommandResponse, PacketPriority, SendPacket};
+use fanuc_rmi::FrcError;
+use std::time::{Duration, Instant};
+
+mod common;
+use common::{start_fake_controller, Reply};
+
+/// How long the fake controller holds each `FRC_GetStatus` answer.
+const STATUS_DELAY: Duration = Duration::from_millis(200);
+
+/// Start a fake controller on an ephemeral port.
+///
+/// `FRC_GetStatus` is answered after [`STATUS_DELAY`] with `NextSequenceID`
+/// counting up from 100 per request; `FRC_GetUFrameUTool` is answered at once
+/// with `UFrameNumber` 3 and `UToolNumber` 5. Everything else, like a real
+/// controller's read of UFrame 0, is never answered.
+async fn start_command_controller() -> u32 {
+    let mut next_sequence_id = 100;
+    start_fake_controller(move |packet| match packet["Command"].as_str() {
+        Some("FRC_GetStatus") => {
+            next_sequence_id += 1;
+            let reply = format!(
+                "{{\"Command\":\"FRC_GetStatus\",\"ErrorID\":0,\"NextSequenceID\":{}}}\r\n",
+                next_sequence_id
+            );
+            Some(Reply::after(STATUS_DELAY, reply))
+        }
+        Some("FRC_GetUFrameUTool") => Some(
+            "{\"Command\":\"FRC_GetUFrameUTool\",\"ErrorID\":0,\"UFrameNumber\":3,\"UToolNumber\":5,\"Group\":1}\r\n".into(),
+        ),
+        _ => None,
+    })
+    .await
+}
+
+async fn connect() -> FanucDriver {
+    connect_with(|config| config).await
+}
+
+async fn connect_with(configure: impl FnOnce(FanucDriverConfig) -> FanucDriverConfig) -> FanucDriver {
+    let port = start_command_controller().await;
+    let config = FanucDriverConfig {
+        addr: "127.0.0.1".to_string(),
+        port,
+        ..Default::default()
+    };
+    FanucDriver::connect(configure(config)).await.expect("connect to fake controller")
+}
+
+/// Overlapping commands of different types each return their own response,
+/// even when the later command is answered first.
+#[tokio::test]
+async fn test_overlapping_commands_get_their_own_response() {
+    let driver = connect().await;
+
+    let (status, frames) = tokio::join!(
+        driver.command(Command::FrcGetStatus),
+        driver.command(FrcGetUFrameUTool::new(None)),
+    );
+
+    match status.expect("get status") {
+        CommandResponse::FrcGetStatus(status) => assert_eq!(status.next_sequence_id, 101),
+        other => panic!("expected FRC_GetStatus response, got {:?}", other),
+    }
+    match frames.expect("get uframe/utool") {
+        CommandResponse::FrcGetUFrameUTool(frames) => {
+            assert_eq!(frames.u_frame_number, 3);
+            assert_eq!(frames.u_tool_number, 5);
+        }
+        other => panic!("expected FRC_GetUFrameUTool response, got {:?}", other),
+    }
+}
+
+/// Overlapping commands of the same type are answered in send order.
+#[tokio::test]
+async fn test_same_command_responses_match_in_send_order() {
+    let driver = connect().await;
+
+    let first = driver.command(Command::FrcGetStatus);
+    let second = async {
+        // Make sure the first request is on the wire before the second
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        driver.command(Command::FrcGetStatus).await
+    };
+    let (first, second) = tokio::join!(first, second);
+
+    let ids: Vec<u32> = [first, second]
+        .into_iter()
+        .map(|response| match response.expect("get status") {
+            CommandResponse::FrcGetStatus(status) => status.next_sequence_id,
+            other => panic!("expected FRC_GetStatus response, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(ids, vec![101, 102]);
+}
+
+/// Commands sent through `send_packet` take their place in the FIFO, so a
+/// `command` call issued after one still gets its own response.
+#[tokio::test]
+async fn test_fire_and_forget_command_does_not_steal_response() {
+    let driver = connect().await;
+
+    driver.send_get_status().expect("queue get status");
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    match driver.command(Command::FrcGetStatus).await.expect("get status") {
+        CommandResponse::FrcGetStatus(status) => assert_eq!(status.next_sequence_id, 102),
+        other => panic!("expected FRC_GetStatus response, got {:?}", other),
+    }
+}
+
+/// Dropping a `command` future before its response arrives removes its
+/// registration; the late response then completes nothing.
+#[tokio::test]
+async fn test_dropped_command_leaves_no_pending_entry() {
+    let driver = connect().await;
+
+    let command = driver.command(Command::FrcGetStatus);
+    let cancelled = tokio::time::timeout(STATUS_DELAY / 4, command).await;
+    assert!(cancelled.is_err(), "response must still be outstanding when dropped");
+    assert_eq!(driver.pending_command_count(), 0);
+
+    // The late answer to the dropped command finds no entry to complete
+    tokio::time::sleep(STATUS_DELAY).await;
+    assert_eq!(driver.pending_command_count(), 0);
+}
+
+/// A per-command timeout from the config bounds how long an unanswered read
+/// of UFrame 0 takes to fail.
+#[tokio::test]
+async fn test_configured_timeout_bounds_unanswered_uframe_read() {
+    let driver = connect_with(|config| {
+        config.with_command_timeout(CommandKind::FrcReadUFrameData, Duration::from_millis(100))
+    })
+    .await;
+
+    let started = Instant::now();
+    let result = driver.read_uframe(0).await;
+    assert!(matches!(result, Err(FrcError::NotReadable(_))), "got {:?}", result);
+    assert!(started.elapsed() < Duration::from_millis(500), "took {:?}", started.elapsed());
+    assert_eq!(driver.pending_command_count(), 0);
+}
+
+/// An `FRC_Initialize` mask naming a group beyond `group_count` fails
+/// locally instead of being sent.
+#[tokio::test]
+async fn test_initialize_mask_beyond_group_count_is_rejected() {
+    let driver = connect_with(|config| FanucDriverConfig { group_count: 2, ..config }).await;
+
+    let result = driver.command(FrcInitialize::new(Some(4))).await;
+    assert!(matches!(result, Err(FrcError::Initialization(..))), "got {:?}", result);
+    let packet = SendPacket::Command(Command::FrcInitialize(FrcInitialize::new(Some(4))));
+    assert!(driver.send_packet(packet, PacketPriority::Standard).is_err());
+    assert_eq!(driver.pending_command_count(), 0);
+}
@@ -0,0 +1,136 @@
+//! A minimal in-process fake controller shared by the driver tests.
+//!
+//! Each test file pulls this in with `mod common;` and uses the parts it
+//! needs, so not every helper is used by every test crate.
+#![allow(dead_code)]
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+
+/// A line for the fake controller to send back, optionally after a delay.
+pub struct Reply {
+    line: String,
+    delay: Duration,
+}
+
+impl Reply {
+    /// Send `line` after `delay`, without holding up replies to later packets.
+    pub fn after(delay: Duration, line: impl Into<String>) -> Self {
+        Self { line: line.into(), delay }
+    }
+}
+
+impl From<String> for Reply {
+    fn from(line: String) -> Self {
+        Self { line, delay: Duration::ZERO }
+    }
+}
+
+impl From<&str> for Reply {
+    fn from(line: &str) -> Self {
+        line.to_string().into()
+    }
+}
+
+/// Start a fake controller on an ephemeral port. Returns the connect port.
+///
+/// The connect port answers `FRC_Connect` with the data port. Every JSON
+/// packet received on the data port is passed to `answer`, and the replies
+/// it returns are written back in order.
+pub async fn start_fake_controller<F, I>(answer: F) -> u32
+where
+    F: FnMut(serde_json::Value) -> I + Send + 'static,
+    I: IntoIterator,
+    I::Item: Into<Reply>,
+{
+    start_streaming_controller(Vec::new(), answer).await
+}
+
+/// [`start_fake_controller`] that also sends `unsolicited` as soon as the
+/// data connection is open.
+pub async fn start_streaming_controller<F, I>(unsolicited: Vec<String>, mut answer: F) -> u32
+where
+    F: FnMut(serde_json::Value) -> I + Send + 'static,
+    I: IntoIterator,
+    I::Item: Into<Reply>,
+{
+    let connect_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let data_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let connect_port = connect_listener.local_addr().unwrap().port();
+    let data_port = data_listener.local_addr().unwrap().port();
+
+    tokio::spawn(async move {
+        let (mut socket, _) = connect_listener.accept().await.unwrap();
+        let mut reader = BufReader::new(&mut socket);
+        let mut line = String::new();
+        reader.read_line(&mut line).await.unwrap();
+        let reply = format!(
+            "{{\"Communication\":\"FRC_Connect\",\"ErrorID\":0,\"PortNumber\":{},\"MajorVersion\":1,\"MinorVersion\":0}}\r\n",
+            data_port
+        );
+        socket.write_all(reply.as_bytes()).await.unwrap();
+    });
+
+    tokio::spawn(async move {
+        let (socket, _) = data_listener.accept().await.unwrap();
+        let (read_half, write_half) = socket.into_split();
+        let write_half = Arc::new(Mutex::new(write_half));
+        for line in unsolicited {
+            if write_line(&write_half, &line).await.is_err() {
+                return;
+            }
+        }
+        let mut lines = BufReader::new(read_half).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let Ok(packet) = serde_json::from_str::<serde_json::Value>(&line) else {
+                continue;
+            };
+            let replies: Vec<Reply> = answer(packet).into_iter().map(Into::into).collect();
+            for Reply { line, delay } in replies {
+                if delay.is_zero() {
+                    if write_line(&write_half, &line).await.is_err() {
+                        return;
+                    }
+                } else {
+                    let write_half = Arc::clone(&write_half);
+                    tokio::spawn(async move {
+                        tokio::time::sleep(delay).await;
+                        let _ = write_line(&write_half, &line).await;
+                    });
+                }
+            }
+        }
+    });
+
+    connect_port as u32
+}
+
+/// A fake controller that completes the connect handshake and then reads
+/// every packet without answering.
+pub async fn start_silent_controller() -> u32 {
+    start_fake_controller(|_| None::<String>).await
+}
+
+/// A successful response to `packet` if it is an instruction, echoing its
+/// `SequenceID`.
+pub fn instruction_reply(packet: &serde_json::Value) -> Option<String> {
+    let instruction = packet["Instruction"].as_str()?;
+    Some(format!(
+        "{{\"Instruction\":\"{}\",\"ErrorID\":0,\"SequenceID\":{}}}\r\n",
+        instruction, packet["SequenceID"]
+    ))
+}
+
+/// A bare successful response to `packet` if it is a command.
+pub fn command_reply(packet: &serde_json::Value) -> Option<String> {
+    let command = packet["Command"].as_str()?;
+    Some(format!("{{\"Command\":\"{}\",\"ErrorID\":0}}\r\n", command))
+}
+
+async fn write_line(write_half: &Mutex<OwnedWriteHalf>, line: &str) -> std::io::Result<()> {
+    write_half.lock().await.write_all(line.as_bytes()).await
+}
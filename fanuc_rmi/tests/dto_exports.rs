@@ -0,0 +1,199 @@
+#![cfg(feature = "DTO")]
+//! Every `Command`/`Instruction` variant's payload is re-exported from
+//! `fanuc_rmi::dto`, and every variant survives DTO -> protocol -> JSON ->
+//! protocol -> DTO. Every response packet also survives bincode, the web
+//! server's wire format.
+
+use fanuc_rmi::{dto, packets};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Debug;
+
+/// Check `dto::$enum` against a list of its variants and their payloads.
+///
+/// The match is exhaustive, so a new variant fails to compile here until
+/// it is listed, and a listed payload fails to compile until `dto`
+/// re-exports it under its protocol name.
+macro_rules! assert_exported {
+    ($enum:ident { $($variant:ident $(($payload:ident))?),* $(,)? }) => {{
+        let _ = |packet: dto::$enum| match packet {
+            $(dto::$enum::$variant { .. } => {})*
+        };
+        $($(let _: fn(dto::$payload) -> dto::$enum = dto::$enum::$variant;)?)*
+    }};
+}
+
+/// Every variant of the DTO enum `T`, each with a zeroed payload.
+///
+/// Bincode writes the variant index as a u32 and reads zero bytes as 0,
+/// false, "", None, an empty Vec or a nested enum's first variant, so the
+/// variants are found without naming them.
+fn zeroed_variants<T: DeserializeOwned>() -> Vec<T> {
+    (0u32..)
+        .map_while(|index| {
+            let mut bytes = index.to_le_bytes().to_vec();
+            bytes.resize(1024, 0);
+            bincode::deserialize(&bytes).ok()
+        })
+        .collect()
+}
+
+/// Send every variant of `D` through its protocol type `P` and the wire
+/// format and back. Returns how many variants there were.
+fn assert_round_trips<D, P>() -> usize
+where
+    D: DeserializeOwned + From<P> + Into<P> + Clone + PartialEq + Debug,
+    P: Serialize + DeserializeOwned,
+{
+    let variants = zeroed_variants::<D>();
+    for dto in &variants {
+        let json = serde_json::to_string(&dto.clone().into()).unwrap();
+        let protocol: P = serde_json::from_str(&json).unwrap_or_else(|e| panic!("{} didn't parse back: {}", json, e));
+        assert_eq!(&D::from(protocol), dto, "{}", json);
+    }
+    variants.len()
+}
+
+#[test]
+fn every_response_packet_round_trips_through_bincode() {
+    // Exhaustive, so a new kind of response must be added below
+    let _ = |packet: dto::ResponsePacket| match packet {
+        dto::ResponsePacket::CommunicationResponse(_)
+        | dto::ResponsePacket::CommandResponse(_)
+        | dto::ResponsePacket::InstructionResponse(_) => {}
+    };
+    let packets: Vec<dto::ResponsePacket> = zeroed_variants::<dto::CommunicationResponse>()
+        .into_iter()
+        .map(dto::ResponsePacket::CommunicationResponse)
+        .chain(zeroed_variants().into_iter().map(dto::ResponsePacket::CommandResponse))
+        .chain(zeroed_variants().into_iter().map(dto::ResponsePacket::InstructionResponse))
+        .collect();
+    assert_eq!(packets.len(), 4 + 27 + 18);
+
+    for packet in &packets {
+        let binary = bincode::serialize(packet).unwrap_or_else(|e| panic!("{:?} didn't serialize: {}", packet, e));
+        let decoded: dto::ResponsePacket = bincode::deserialize(&binary).unwrap();
+        assert_eq!(&decoded, packet);
+    }
+}
+
+#[test]
+fn commands_are_exported_and_round_trip() {
+    assert_exported!(Command {
+        FrcInitialize(FrcInitialize),
+        FrcAbort,
+        FrcPause,
+        FrcReadError(FrcReadError),
+        FrcContinue,
+        FrcSetUFrameUTool(FrcSetUFrameUTool),
+        FrcReadPositionRegister(FrcReadPositionRegister),
+        FrcWritePositionRegister(FrcWritePositionRegister),
+        FrcSetOverRide(FrcSetOverRide),
+        FrcGetStatus,
+        FrcGetUFrameUTool(FrcGetUFrameUTool),
+        FrcWriteUToolData(FrcWriteUToolData),
+        FrcReadUToolData(FrcReadUToolData),
+        FrcReadUFrameData(FrcReadUFrameData),
+        FrcWriteUFrameData(FrcWriteUFrameData),
+        FrcReset,
+        FrcReadDIN(FrcReadDIN),
+        FrcWriteDOUT(FrcWriteDOUT),
+        FrcReadAIN(FrcReadAIN),
+        FrcWriteAOUT(FrcWriteAOUT),
+        FrcReadGIN(FrcReadGIN),
+        FrcWriteGOUT(FrcWriteGOUT),
+        FrcReadCartesianPosition(FrcReadCartesianPosition),
+        FrcReadJointAngles(FrcReadJointAngles),
+        FrcReadTCPSpeed,
+        SimMode(SimMode),
+    });
+    assert_eq!(assert_round_trips::<dto::Command, packets::Command>(), 26);
+}
+
+#[test]
+fn command_responses_are_exported_and_round_trip() {
+    assert_exported!(CommandResponse {
+        FrcInitialize(FrcInitializeResponse),
+        FrcAbort(FrcAbortResponse),
+        FrcPause(FrcPauseResponse),
+        FrcContinue(FrcContinueResponse),
+        FrcReadError(FrcReadErrorResponse),
+        FrcSetUFrameUTool(FrcSetUFrameUToolResponse),
+        FrcGetUFrameUTool(FrcGetUFrameUToolResponse),
+        FrcGetStatus(FrcGetStatusResponse),
+        FrcReadUFrameData(FrcReadUFrameDataResponse),
+        FrcWriteUFrameData(FrcWriteUFrameDataResponse),
+        FrcReadUToolData(FrcReadUToolDataResponse),
+        FrcWriteUToolData(FrcWriteUToolDataResponse),
+        FrcReadDIN(FrcReadDINResponse),
+        FrcWriteDOUT(FrcWriteDOUTResponse),
+        FrcReadAIN(FrcReadAINResponse),
+        FrcWriteAOUT(FrcWriteAOUTResponse),
+        FrcReadGIN(FrcReadGINResponse),
+        FrcWriteGOUT(FrcWriteGOUTResponse),
+        FrcReadCartesianPosition(FrcReadCartesianPositionResponse),
+        FrcReadJointAngles(FrcReadJointAnglesResponse),
+        FrcSetOverRide(FrcSetOverRideResponse),
+        FrcReadPositionRegister(FrcReadPositionRegisterResponse),
+        FrcWritePositionRegister(FrcWritePositionRegisterResponse),
+        FrcReset(FrcResetResponse),
+        FrcReadTCPSpeed(FrcReadTCPSpeedResponse),
+        Unknown(FrcUnknownResponse),
+        SimMode(SimModeResponse),
+    });
+    assert_eq!(assert_round_trips::<dto::CommandResponse, packets::CommandResponse>(), 27);
+
+    // New responses go after `Unknown`, so its bincode index never moves
+    let variants = zeroed_variants::<dto::CommandResponse>();
+    assert!(matches!(variants[25], dto::CommandResponse::Unknown(_)), "{:?}", variants[25]);
+}
+
+#[test]
+fn instructions_are_exported_and_round_trip() {
+    assert_exported!(Instruction {
+        FrcWaitDIN(FrcWaitDIN),
+        FrcSetUFrame(FrcSetUFrame),
+        FrcSetUTool(FrcSetUTool),
+        FrcWaitTime(FrcWaitTime),
+        FrcSetPayLoad(FrcSetPayLoad),
+        FrcCall(FrcCall),
+        FrcLinearMotion(FrcLinearMotion),
+        FrcLinearRelative(FrcLinearRelative),
+        FrcLinearRelativeJRep(FrcLinearRelativeJRep),
+        FrcJointMotion(FrcJointMotion),
+        FrcJointRelative(FrcJointRelative),
+        FrcCircularMotion(FrcCircularMotion),
+        FrcCircularRelative(FrcCircularRelative),
+        FrcJointMotionJRep(FrcJointMotionJRep),
+        FrcJointRelativeJRep(FrcJointRelativeJRep),
+        FrcLinearMotionJRep(FrcLinearMotionJRep),
+        FrcLinearMotionPR(FrcLinearMotionPR),
+        FrcJointMotionPR(FrcJointMotionPR),
+    });
+    assert_eq!(assert_round_trips::<dto::Instruction, packets::Instruction>(), 18);
+}
+
+#[test]
+fn instruction_responses_are_exported_and_round_trip() {
+    assert_exported!(InstructionResponse {
+        FrcWaitDIN(FrcWaitDINResponse),
+        FrcSetUFrame(FrcSetUFrameResponse),
+        FrcSetUTool(FrcSetUToolResponse),
+        FrcWaitTime(FrcWaitTimeResponse),
+        FrcSetPayLoad(FrcSetPayLoadResponse),
+        FrcCall(FrcCallResponse),
+        FrcLinearMotion(FrcLinearMotionResponse),
+        FrcLinearRelative(FrcLinearRelativeResponse),
+        FrcLinearRelativeJRep(FrcLinearRelativeJRepResponse),
+        FrcJointMotion(FrcJointMotionResponse),
+        FrcJointRelative(FrcJointRelativeResponse),
+        FrcCircularMotion(FrcCircularMotionResponse),
+        FrcCircularRelative(FrcCircularRelativeResponse),
+        FrcJointMotionJRep(FrcJointMotionJRepResponse),
+        FrcJointRelativeJRep(FrcJointRelativeJRepResponse),
+        FrcLinearMotionJRep(FrcLinearMotionJRepResponse),
+        FrcLinearMotionPR(FrcLinearMotionPRResponse),
+        FrcJointMotionPR(FrcJointMotionPRResponse),
+    });
+    assert_eq!(assert_round_trips::<dto::InstructionResponse, packets::InstructionResponse>(), 18);
+}
@@ -0,0 +1,191 @@
+#![cfg(feature = "DTO")]
+
+//! Property tests for the dual representation: a protocol value read from
+//! the controller's JSON, carried to a client as bincode DTO and converted
+//! back, must serialize to the JSON it started as.
+
+use fanuc_rmi::instructions::{
+    FrcCircularMotion, FrcJointMotionJRep, FrcLinearMotion, FrcLinearMotionPR, FrcSetUFrame, FrcWaitTime,
+};
+use fanuc_rmi::packets::Instruction;
+use fanuc_rmi::{dto, Configuration, FrameData, JointAngles, Position, SpeedType, TermType};
+use proptest::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Debug;
+
+/// protocol JSON → protocol type → DTO bincode → protocol type → protocol JSON
+fn double_roundtrip<P, D>(value: P) -> Result<(), TestCaseError>
+where
+    P: Serialize + DeserializeOwned + PartialEq + Debug + From<D>,
+    D: Serialize + DeserializeOwned + From<P>,
+{
+    let json = serde_json::to_string(&value).unwrap();
+    let parsed: P = serde_json::from_str(&json).unwrap();
+    let bytes = bincode::serialize(&D::from(parsed)).unwrap();
+    let back = P::from(bincode::deserialize::<D>(&bytes).unwrap());
+    prop_assert_eq!(serde_json::to_string(&back).unwrap(), json);
+    prop_assert_eq!(back, value);
+    Ok(())
+}
+
+/// Millimetres with micrometre resolution, short enough in decimal that
+/// JSON parsing reproduces them exactly.
+fn millimetres() -> impl Strategy<Value = f64> {
+    (-2_000_000i32..=2_000_000).prop_map(|um| um as f64 / 1000.0)
+}
+
+/// Degrees with hundredth resolution.
+fn degrees() -> impl Strategy<Value = f32> {
+    (-36_000i32..=36_000).prop_map(|centi| centi as f32 / 100.0)
+}
+
+fn position() -> impl Strategy<Value = Position> {
+    proptest::array::uniform9(millimetres())
+        .prop_map(|[x, y, z, w, p, r, ext1, ext2, ext3]| Position { x, y, z, w, p, r, ext1, ext2, ext3 })
+}
+
+fn configuration() -> impl Strategy<Value = Configuration> {
+    proptest::array::uniform9(any::<i8>()).prop_map(
+        |[u_tool_number, u_frame_number, front, up, left, flip, turn4, turn5, turn6]| Configuration {
+            u_tool_number,
+            u_frame_number,
+            front,
+            up,
+            left,
+            flip,
+            turn4,
+            turn5,
+            turn6,
+        },
+    )
+}
+
+fn frame_data() -> impl Strategy<Value = FrameData> {
+    proptest::array::uniform6(millimetres()).prop_map(|[x, y, z, w, p, r]| FrameData { x, y, z, w, p, r })
+}
+
+fn joint_angles() -> impl Strategy<Value = JointAngles> {
+    proptest::array::uniform9(degrees())
+        .prop_map(|[j1, j2, j3, j4, j5, j6, j7, j8, j9]| JointAngles { j1, j2, j3, j4, j5, j6, j7, j8, j9 })
+}
+
+fn speed_type() -> impl Strategy<Value = SpeedType> {
+    prop_oneof![
+        Just(SpeedType::MMSec),
+        Just(SpeedType::InchMin),
+        Just(SpeedType::Time),
+        Just(SpeedType::MilliSeconds),
+    ]
+}
+
+fn term_type() -> impl Strategy<Value = TermType> {
+    prop_oneof![Just(TermType::FINE), Just(TermType::CNT), Just(TermType::CR)]
+}
+
+/// Speed, termination and blending, shared by the motion instructions.
+fn motion() -> impl Strategy<Value = (SpeedType, f64, TermType, u8, bool)> {
+    (speed_type(), millimetres(), term_type(), any::<u8>(), any::<bool>())
+}
+
+fn instruction() -> impl Strategy<Value = Instruction> {
+    prop_oneof![
+        (any::<u32>(), configuration(), position(), motion()).prop_map(
+            |(sequence_id, configuration, position, (speed_type, speed, term_type, term_value, no_blend))| {
+                Instruction::FrcLinearMotion(FrcLinearMotion {
+                    sequence_id,
+                    configuration,
+                    position,
+                    speed_type,
+                    speed,
+                    term_type,
+                    term_value,
+                    no_blend,
+                })
+            }
+        ),
+        (any::<u32>(), configuration(), position(), configuration(), position(), motion()).prop_map(
+            |(
+                sequence_id,
+                configuration,
+                position,
+                via_configuration,
+                via_position,
+                (speed_type, speed, term_type, term_value, no_blend),
+            )| {
+                Instruction::FrcCircularMotion(FrcCircularMotion {
+                    sequence_id,
+                    configuration,
+                    position,
+                    via_configuration,
+                    via_position,
+                    speed_type,
+                    speed,
+                    term_type,
+                    term_value,
+                    no_blend,
+                })
+            }
+        ),
+        (any::<u32>(), joint_angles(), motion()).prop_map(
+            |(sequence_id, joint_angles, (speed_type, speed, term_type, term_value, no_blend))| {
+                Instruction::FrcJointMotionJRep(FrcJointMotionJRep {
+                    sequence_id,
+                    joint_angles,
+                    speed_type,
+                    speed,
+                    term_type,
+                    term_value,
+                    no_blend,
+                })
+            }
+        ),
+        (any::<u32>(), any::<u16>(), motion()).prop_map(
+            |(sequence_id, register_number, (speed_type, speed, term_type, term_value, no_blend))| {
+                Instruction::FrcLinearMotionPR(FrcLinearMotionPR {
+                    sequence_id,
+                    register_number,
+                    speed_type,
+                    speed,
+                    term_type,
+                    term_value,
+                    no_blend,
+                })
+            }
+        ),
+        (any::<u32>(), any::<u8>())
+            .prop_map(|(sequence_id, frame_number)| Instruction::FrcSetUFrame(FrcSetUFrame {
+                sequence_id,
+                frame_number
+            })),
+        (any::<u32>(), degrees())
+            .prop_map(|(sequence_id, time)| Instruction::FrcWaitTime(FrcWaitTime { sequence_id, time })),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn position_survives_double_roundtrip(value in position()) {
+        double_roundtrip::<Position, dto::Position>(value)?;
+    }
+
+    #[test]
+    fn configuration_survives_double_roundtrip(value in configuration()) {
+        double_roundtrip::<Configuration, dto::Configuration>(value)?;
+    }
+
+    #[test]
+    fn frame_data_survives_double_roundtrip(value in frame_data()) {
+        double_roundtrip::<FrameData, dto::FrameData>(value)?;
+    }
+
+    #[test]
+    fn joint_angles_survive_double_roundtrip(value in joint_angles()) {
+        double_roundtrip::<JointAngles, dto::JointAngles>(value)?;
+    }
+
+    #[test]
+    fn instruction_survives_double_roundtrip(value in instruction()) {
+        double_roundtrip::<Instruction, dto::Instruction>(value)?;
+    }
+}
@@ -75,3 +75,76 @@ fn convert_protocol_to_dto_and_back() {
     assert_eq!(p_roundtrip.group, 1);
 }
 
+#[test]
+fn position_register_motion_mirrors_to_dto() {
+    let p_instr = protocol::packets::Instruction::FrcLinearMotionPR(fanuc_rmi::instructions::FrcLinearMotionPR::new(
+        5,
+        10,
+        protocol::SpeedType::MMSec,
+        50.0,
+        protocol::TermType::FINE,
+        0,
+    ));
+
+    let dto_instr: dto::Instruction = p_instr.clone().into();
+    let enc = bincode::serialize(&dto_instr).unwrap();
+    let dec: dto::Instruction = bincode::deserialize(&enc).unwrap();
+    assert_eq!(dec, dto_instr);
+
+    let p_roundtrip: protocol::packets::Instruction = dec.into();
+    assert_eq!(p_roundtrip, p_instr);
+}
+
+
+/// Fields shared by several responses, flattened into them on the wire.
+#[fanuc_rmi::mirror_dto]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct ErrorHeader {
+    #[serde(rename = "ErrorID")]
+    pub error_id: u32,
+    #[serde(rename = "SequenceID", default)]
+    pub sequence_id: u32,
+}
+
+/// A response composed from the shared header.
+#[fanuc_rmi::mirror_dto]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct FrcHeaderedResponse {
+    #[serde(flatten)]
+    pub header: ErrorHeader,
+    #[serde(rename = "Group")]
+    pub group: u8,
+    #[serde(rename = "Previous", default)]
+    pub previous: Option<ErrorHeader>,
+    #[serde(rename = "History", default)]
+    pub history: Vec<ErrorHeader>,
+}
+
+#[test]
+fn composed_struct_mirrors_nested_dto() {
+    let header = ErrorHeader { error_id: 2556957, sequence_id: 12 };
+    let p_resp = FrcHeaderedResponse {
+        header: header.clone(),
+        group: 1,
+        previous: Some(ErrorHeader { error_id: 0, sequence_id: 11 }),
+        history: vec![ErrorHeader { error_id: 0, sequence_id: 10 }],
+    };
+
+    // The protocol side flattens the header into the packet
+    let json = serde_json::to_value(&p_resp).unwrap();
+    assert_eq!(json["ErrorID"], 2556957);
+    assert_eq!(json["SequenceID"], 12);
+
+    // The DTO keeps it as a nested mirrored struct
+    let dto_resp: FrcHeaderedResponseDto = p_resp.clone().into();
+    assert_eq!(dto_resp.header, ErrorHeaderDto { error_id: 2556957, sequence_id: 12 });
+    assert_eq!(dto_resp.previous, Some(ErrorHeaderDto { error_id: 0, sequence_id: 11 }));
+    assert_eq!(dto_resp.history, vec![ErrorHeaderDto { error_id: 0, sequence_id: 10 }]);
+
+    let enc = bincode::serialize(&dto_resp).unwrap();
+    let dec: FrcHeaderedResponseDto = bincode::deserialize(&enc).unwrap();
+    assert_eq!(dec, dto_resp);
+
+    let p_roundtrip: FrcHeaderedResponse = dec.into();
+    assert_eq!(p_roundtrip, p_resp);
+}
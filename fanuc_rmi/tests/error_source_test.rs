@@ -0,0 +1,46 @@
+//! `FrcError` keeps the io or serde error it was built from as its
+//! `source()`.
+
+use fanuc_rmi::drivers::{FanucDriver, FanucDriverConfig};
+use fanuc_rmi::FrcError;
+use std::error::Error;
+use std::io;
+use tokio::net::TcpListener;
+
+#[tokio::test]
+async fn connection_failure_exposes_the_io_error() {
+    // Bind and drop a listener to find a port nothing listens on
+    let port = {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap().port()
+    };
+    let config = FanucDriverConfig {
+        addr: "127.0.0.1".to_string(),
+        port: port as u32,
+        ..Default::default()
+    };
+
+    let Err(error) = FanucDriver::connect(config).await else {
+        panic!("connected, but nothing is listening");
+    };
+    assert!(matches!(error, FrcError::Initialization(..)), "got {:?}", error);
+    let source = error.source().expect("connection failure has a source");
+    let io_error = source.downcast_ref::<io::Error>().expect("source is an io::Error");
+    assert_eq!(io_error.kind(), io::ErrorKind::ConnectionRefused);
+
+    // Clones share the source
+    assert!(error.clone().source().is_some());
+}
+
+#[test]
+fn serialization_error_exposes_the_serde_error() {
+    let parse_error = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+    let error = FrcError::Serialization(parse_error.to_string(), Some(parse_error.into()));
+    assert!(error.source().unwrap().downcast_ref::<serde_json::Error>().unwrap().is_eof());
+
+    // The source isn't serialized, so a deserialized error has none
+    let json = serde_json::to_string(&error).unwrap();
+    let decoded: FrcError = serde_json::from_str(&json).unwrap();
+    assert!(decoded.source().is_none());
+    assert_eq!(decoded.to_string(), error.to_string());
+}
@@ -51,6 +51,7 @@ fn test_command_response_into_inner() {
         number_uframe: 1,
         next_sequence_id: 1,
         override_value: 100,
+        buffer_occupancy: None,
     });
 
     // Test into_inner with correct type
@@ -0,0 +1,75 @@
+//! Tests for `FanucDriver::flush_pending`.
+//!
+//! The fake controller records the instructions it receives and never
+//! answers them, so the driver keeps its 8 in-flight slots full and holds
+//! everything after them in its queue.
+
+use fanuc_rmi::drivers::{FanucDriver, FanucDriverConfig};
+use fanuc_rmi::instructions::FrcLinearMotion;
+use fanuc_rmi::packets::{Instruction, PacketPriority, SendPacket};
+use fanuc_rmi::{Configuration, Position, SpeedType, TermType};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+mod common;
+use common::start_fake_controller;
+
+/// Start a fake controller on an ephemeral port. Returns the port and the
+/// X of every instruction received, in order.
+async fn start_recording_controller() -> (u32, Arc<Mutex<Vec<f64>>>) {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let record = Arc::clone(&received);
+    let port = start_fake_controller(move |packet| {
+        if let Some(x) = packet.get("Instruction").and(packet["Position"]["X"].as_f64()) {
+            record.lock().unwrap().push(x);
+        }
+        None::<String>
+    })
+    .await;
+    (port, received)
+}
+
+fn linear_move(x: f64) -> SendPacket {
+    SendPacket::Instruction(Instruction::FrcLinearMotion(FrcLinearMotion::new(
+        0,
+        Configuration::default(),
+        Position { x, y: 0.0, z: 300.0, ..Default::default() },
+        SpeedType::MMSec,
+        50.0,
+        TermType::FINE,
+        0,
+    )))
+}
+
+/// Wait until the controller has received `count` instructions.
+async fn wait_for_received(received: &Mutex<Vec<f64>>, count: usize) {
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while received.lock().unwrap().len() < count {
+        assert!(Instant::now() < deadline, "received {:?}, expected {} instructions", received.lock().unwrap(), count);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_flushed_moves_never_reach_controller() {
+    let (port, received) = start_recording_controller().await;
+    let config = FanucDriverConfig { addr: "127.0.0.1".to_string(), port, ..Default::default() };
+    let driver = FanucDriver::connect(config).await.expect("connect to fake controller");
+
+    for x in 0..12 {
+        driver.send_packet(linear_move(x as f64), PacketPriority::Standard).unwrap();
+    }
+    // 8 in flight, 4 queued
+    wait_for_received(&received, 8).await;
+    driver.flush_pending().unwrap();
+
+    // As after an abort: the controller's buffer is empty, so the driver
+    // would send the queued moves now if they were still there
+    driver.clear_in_flight().unwrap();
+    driver.send_packet(linear_move(100.0), PacketPriority::Standard).unwrap();
+    wait_for_received(&received, 9).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let expected: Vec<f64> = (0..8).map(|x| x as f64).chain([100.0]).collect();
+    assert_eq!(*received.lock().unwrap(), expected);
+}
@@ -0,0 +1,91 @@
+//! Tests for the driver heartbeat / connection health reporting.
+//!
+//! These tests run against a minimal in-process fake controller rather than
+//! the simulator, so the controller can be told to go silent on demand while
+//! keeping the socket open (a "silently dead" link).
+
+use fanuc_rmi::drivers::{ConnectionHealth, FanucDriver, FanucDriverConfig};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+mod common;
+use common::start_fake_controller;
+
+/// Start a fake controller on an ephemeral port that answers every
+/// `FRC_GetStatus` until `responsive` is cleared, after which it keeps
+/// reading but never replies.
+async fn start_heartbeat_controller(responsive: Arc<AtomicBool>) -> u32 {
+    start_fake_controller(move |packet| {
+        (packet["Command"] == "FRC_GetStatus" && responsive.load(Ordering::SeqCst))
+            .then_some("{\"Command\":\"FRC_GetStatus\",\"ErrorID\":0}\r\n")
+    })
+    .await
+}
+
+/// The driver stays healthy while heartbeats are answered and flips to
+/// `Unhealthy` within `max_missed` intervals (plus slack) once the controller
+/// stops responding.
+#[tokio::test]
+async fn test_health_turns_unhealthy_when_controller_goes_silent() {
+    let responsive = Arc::new(AtomicBool::new(true));
+    let port = start_heartbeat_controller(Arc::clone(&responsive)).await;
+
+    let interval_ms = 100;
+    let max_missed = 3;
+    let config = FanucDriverConfig {
+        addr: "127.0.0.1".to_string(),
+        port,
+        ..Default::default()
+    }
+    .with_heartbeat(interval_ms, max_missed);
+
+    let driver = FanucDriver::connect(config).await.expect("connect to fake controller");
+    let mut health_rx = driver.subscribe_health();
+
+    // Several heartbeat intervals with a responsive controller: still healthy.
+    tokio::time::sleep(Duration::from_millis(interval_ms * 4)).await;
+    assert!(driver.is_healthy(), "answered heartbeats must keep the driver healthy");
+    assert_eq!(*health_rx.borrow_and_update(), ConnectionHealth::Healthy);
+
+    // Go silent without closing the socket.
+    responsive.store(false, Ordering::SeqCst);
+    let silenced_at = Instant::now();
+
+    tokio::time::timeout(Duration::from_secs(2), health_rx.changed())
+        .await
+        .expect("health should change within 2s")
+        .expect("health channel open");
+    let elapsed = silenced_at.elapsed();
+
+    assert_eq!(*health_rx.borrow(), ConnectionHealth::Unhealthy);
+    assert!(!driver.is_healthy());
+    // Needs `max_missed` full intervals of silence; allow one extra interval
+    // for the heartbeat that was in flight when the controller went quiet.
+    let min = Duration::from_millis(interval_ms * (max_missed as u64 - 1));
+    let max = Duration::from_millis(interval_ms * (max_missed as u64 + 2));
+    assert!(
+        elapsed >= min && elapsed <= max,
+        "expected Unhealthy between {:?} and {:?} after silence, got {:?}",
+        min,
+        max,
+        elapsed
+    );
+}
+
+/// Without a configured heartbeat the driver reports healthy for a live socket.
+#[tokio::test]
+async fn test_healthy_without_heartbeat() {
+    let responsive = Arc::new(AtomicBool::new(false));
+    let port = start_heartbeat_controller(responsive).await;
+
+    let config = FanucDriverConfig {
+        addr: "127.0.0.1".to_string(),
+        port,
+        ..Default::default()
+    };
+    let driver = FanucDriver::connect(config).await.expect("connect to fake controller");
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(driver.is_healthy(), "no heartbeat configured means nothing can be missed");
+}
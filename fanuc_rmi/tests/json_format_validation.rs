@@ -181,3 +181,53 @@ fn test_real_robot_cartesian_position_response() {
     println!("Parse result: {:?}", result);
     assert!(result.is_ok(), "Failed to parse: {:?}", result.err());
 }
+
+#[test]
+fn test_no_blend_flag_only_sent_when_set() {
+    use fanuc_rmi::instructions::FrcLinearMotion;
+    use fanuc_rmi::{SpeedType, TermType};
+
+    let mut motion = FrcLinearMotion::new(
+        1,
+        Configuration::default(),
+        Position::default(),
+        SpeedType::MMSec,
+        100.0,
+        TermType::CNT,
+        100,
+    );
+    // Controllers before RMI v5 don't know the field, so it is left out by default
+    let value = serde_json::to_value(&motion).unwrap();
+    assert!(value.get("NoBlend").is_none(), "NoBlend must be omitted when false");
+
+    motion.no_blend = true;
+    let value = serde_json::to_value(&motion).unwrap();
+    assert_eq!(value["NoBlend"], true);
+
+    let parsed: FrcLinearMotion = serde_json::from_value(value).unwrap();
+    assert!(parsed.no_blend);
+}
+
+#[test]
+fn test_position_register_motion_json_format() {
+    use fanuc_rmi::instructions::FrcJointMotionPR;
+    use fanuc_rmi::packets::{Instruction, SendPacket};
+    use fanuc_rmi::{SpeedType, TermType};
+
+    let packet = SendPacket::Instruction(Instruction::FrcJointMotionPR(FrcJointMotionPR::new(
+        3,
+        10,
+        SpeedType::MMSec,
+        100.0,
+        TermType::FINE,
+        0,
+    )));
+    let value = serde_json::to_value(&packet).unwrap();
+    assert_eq!(value["Instruction"], "FRC_JointMotionPR");
+    assert_eq!(value["SequenceID"], 3);
+    assert_eq!(value["RegisterNumber"], 10);
+    assert!(value.get("Position").is_none(), "the controller supplies the pose");
+
+    let parsed: SendPacket = serde_json::from_value(value).unwrap();
+    assert_eq!(parsed, packet);
+}
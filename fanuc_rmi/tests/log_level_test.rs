@@ -0,0 +1,178 @@
+//! Tests that `FanucDriverConfig::log_level` gates the driver's `tracing`
+//! events.
+//!
+//! A subscriber that admits everything records each event's level and
+//! message, so anything the driver emits shows up regardless of level. A
+//! fake controller answers instructions but never commands, which drives the
+//! driver through lifecycle (Info), per-packet (Debug), raw payload (Trace)
+//! and timeout (Warn) messages in one session.
+
+use fanuc_rmi::drivers::{FanucDriver, FanucDriverConfig, LogLevel};
+use fanuc_rmi::instructions::FrcLinearMotion;
+use fanuc_rmi::packets::{Command, Instruction, PacketPriority, SendPacket};
+use fanuc_rmi::{Configuration, Position, SpeedType, TermType};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+mod common;
+use common::{instruction_reply, start_fake_controller};
+
+#[derive(Default)]
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{:?}", value);
+        }
+    }
+}
+
+/// Subscriber that keeps the level and message of every driver event.
+#[derive(Clone, Default)]
+struct EventCapture {
+    events: Arc<Mutex<Vec<(Level, String)>>>,
+}
+
+impl EventCapture {
+    fn events(&self) -> Vec<(Level, String)> {
+        self.events.lock().unwrap().clone()
+    }
+
+    fn at(&self, level: Level) -> Vec<String> {
+        self.events()
+            .into_iter()
+            .filter(|(l, _)| *l == level)
+            .map(|(_, message)| message)
+            .collect()
+    }
+}
+
+impl Subscriber for EventCapture {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _attrs: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        if !event.metadata().target().starts_with("fanuc_rmi") {
+            return;
+        }
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        self.events.lock().unwrap().push((*event.metadata().level(), visitor.0));
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+/// Start a fake controller on an ephemeral port that answers instructions
+/// at once and ignores commands, so every command times out.
+async fn start_instruction_controller() -> u32 {
+    start_fake_controller(|packet| instruction_reply(&packet)).await
+}
+
+fn linear_motion() -> SendPacket {
+    SendPacket::Instruction(Instruction::FrcLinearMotion(FrcLinearMotion::new(
+        0, // sequence_id will be assigned by driver
+        Configuration::default(),
+        Position {
+            x: 100.0,
+            ..Default::default()
+        },
+        SpeedType::MMSec,
+        50.0,
+        TermType::FINE,
+        100,
+    )))
+}
+
+/// Connect at `log_level`, complete one instruction and let one command
+/// time out, returning what the driver emitted.
+async fn run_session(log_level: LogLevel) -> EventCapture {
+    let capture = EventCapture::default();
+    let _guard = tracing::subscriber::set_default(capture.clone());
+
+    let port = start_instruction_controller().await;
+    let config = FanucDriverConfig {
+        addr: "127.0.0.1".to_string(),
+        port,
+        ..Default::default()
+    }
+    .with_log_level(log_level);
+    let driver = FanucDriver::connect(config).await.expect("connect to fake controller");
+
+    driver
+        .send_and_wait_for_completion(linear_motion(), PacketPriority::Standard)
+        .await
+        .expect("instruction completes");
+    let timed_out = driver
+        .command_with_timeout(Command::FrcGetStatus, Duration::from_millis(100))
+        .await;
+    assert!(timed_out.is_err(), "the fake controller never answers commands");
+
+    capture
+}
+
+/// At `LogLevel::Warn` the timeout warning gets through but lifecycle and
+/// per-packet messages do not.
+#[tokio::test]
+async fn test_warn_level_suppresses_info_and_debug() {
+    let capture = run_session(LogLevel::Warn).await;
+
+    let warnings = capture.at(Level::WARN);
+    assert!(
+        warnings.iter().any(|m| m.contains("Timed out")),
+        "timeout warning should pass through: {:?}",
+        capture.events()
+    );
+    for level in [Level::INFO, Level::DEBUG, Level::TRACE] {
+        assert!(
+            capture.at(level).is_empty(),
+            "no {} events expected at LogLevel::Warn: {:?}",
+            level,
+            capture.events()
+        );
+    }
+}
+
+/// `LogLevel::Info` adds lifecycle events, and only `LogLevel::Trace`
+/// carries the raw JSON of each packet.
+#[tokio::test]
+async fn test_trace_level_logs_packet_payloads() {
+    let info = run_session(LogLevel::Info).await;
+    assert!(
+        info.at(Level::INFO).iter().any(|m| m.contains("Connecting")),
+        "connection is a lifecycle event: {:?}",
+        info.events()
+    );
+    assert!(info.at(Level::DEBUG).is_empty(), "{:?}", info.events());
+    assert!(info.at(Level::TRACE).is_empty(), "{:?}", info.events());
+
+    let trace = run_session(LogLevel::Trace).await;
+    let payloads = trace.at(Level::TRACE);
+    assert!(
+        payloads.iter().any(|m| m.starts_with("Sent: {\"Instruction\":\"FRC_LinearMotion\"")),
+        "sent instruction JSON expected: {:?}",
+        payloads
+    );
+    assert!(
+        payloads.iter().any(|m| m.starts_with("Received: {\"Instruction\":\"FRC_LinearMotion\"")),
+        "received response JSON expected: {:?}",
+        payloads
+    );
+    assert!(!trace.at(Level::DEBUG).is_empty(), "Trace admits Debug too");
+}
@@ -0,0 +1,52 @@
+//! Tests for `FanucDriver::set_override` clamping.
+
+use fanuc_rmi::drivers::{FanucDriver, FanucDriverConfig};
+use std::sync::{Arc, Mutex};
+
+mod common;
+use common::start_fake_controller;
+
+/// Start a fake controller that accepts every `FRC_SetOverRide` and records
+/// the value it was sent. Returns the port.
+async fn start_override_controller(values: Arc<Mutex<Vec<u64>>>) -> u32 {
+    start_fake_controller(move |packet| {
+        (packet["Command"] == "FRC_SetOverRide").then(|| {
+            values.lock().unwrap().push(packet["Value"].as_u64().unwrap());
+            "{\"Command\":\"FRC_SetOverRide\",\"ErrorID\":0}\r\n"
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_set_override_clamps_to_safe_range() {
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let port = start_override_controller(Arc::clone(&sent)).await;
+    let config = FanucDriverConfig { addr: "127.0.0.1".to_string(), port, ..Default::default() };
+    let driver = FanucDriver::connect(config).await.expect("connect to fake controller");
+
+    assert_eq!(driver.set_override(0).await.unwrap(), 1);
+    assert_eq!(driver.set_override(150).await.unwrap(), 100);
+    assert_eq!(driver.set_override(40).await.unwrap(), 40);
+    assert_eq!(*sent.lock().unwrap(), vec![1, 100, 40]);
+}
+
+#[tokio::test]
+async fn test_set_override_uses_configured_range() {
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let port = start_override_controller(Arc::clone(&sent)).await;
+    let config = FanucDriverConfig { addr: "127.0.0.1".to_string(), port, ..Default::default() }
+        .with_override_range(10..=50);
+    let driver = FanucDriver::connect(config).await.expect("connect to fake controller");
+
+    assert_eq!(driver.set_override(5).await.unwrap(), 10);
+    assert_eq!(driver.set_override(80).await.unwrap(), 50);
+    assert_eq!(*sent.lock().unwrap(), vec![10, 50]);
+}
+
+#[test]
+fn test_override_range_is_validated() {
+    let config = FanucDriverConfig::default().with_override_range(1..=150);
+    assert!(config.validate().is_err());
+    assert!(FanucDriverConfig::default().validate().is_ok());
+}
@@ -0,0 +1,61 @@
+//! Tests for `Position` / `FrameData` arithmetic.
+
+use fanuc_rmi::{FrameData, Position};
+
+fn position(x: f64, y: f64, z: f64, ext1: f64) -> Position {
+    Position { x, y, z, w: 180.0, p: -10.0, r: 45.0, ext1, ext2: 0.0, ext3: 0.0 }
+}
+
+#[test]
+fn test_position_distance_includes_external_axes() {
+    let a = position(0.0, 0.0, 0.0, 0.0);
+    assert_eq!(a.distance_to(&position(3.0, 4.0, 0.0, 0.0)), 5.0);
+    // 2-3-6 right triangle with the external axis as the third leg
+    assert_eq!(a.distance_to(&position(2.0, 3.0, 0.0, 6.0)), 7.0);
+    // Orientation is not distance
+    let turned = Position { w: 0.0, ..a };
+    assert_eq!(a.distance_to(&turned), 0.0);
+}
+
+#[test]
+fn test_frame_distance() {
+    let a = FrameData { x: 1.0, y: 2.0, z: 3.0, w: 0.0, p: 0.0, r: 90.0 };
+    assert_eq!(a.distance_to(&a.translate(0.0, 0.0, -4.0)), 4.0);
+}
+
+#[test]
+fn test_lerp_halfway() {
+    let a = position(0.0, 10.0, -20.0, 100.0);
+    let b = Position { w: 170.0, ..position(10.0, 20.0, 20.0, 200.0) };
+    let mid = a.lerp(&b, 0.5);
+    assert_eq!(mid, Position { w: 175.0, ..position(5.0, 15.0, 0.0, 150.0) });
+    assert_eq!(a.lerp(&b, 0.0), a);
+    assert_eq!(a.lerp(&b, 1.0), b);
+
+    let f = FrameData { x: 0.0, y: 0.0, z: 0.0, w: 0.0, p: 0.0, r: 0.0 };
+    let g = FrameData { x: 2.0, y: -4.0, z: 6.0, w: 10.0, p: 20.0, r: -30.0 };
+    assert_eq!(f.lerp(&g, 0.5), FrameData { x: 1.0, y: -2.0, z: 3.0, w: 5.0, p: 10.0, r: -15.0 });
+}
+
+#[test]
+fn test_add_sub_round_trip() {
+    let a = position(100.5, -20.25, 300.0, 12.0);
+    let offset = Position { x: 1.5, y: 2.5, z: -3.5, w: 0.0, p: 0.0, r: 0.0, ext1: 0.5, ext2: -1.0, ext3: 2.0 };
+    let moved = a + offset;
+    assert_eq!(moved.ext2, -1.0);
+    assert_eq!(moved - offset, a);
+    assert_eq!(moved - a, offset);
+    assert_eq!(a.translate(1.5, 2.5, -3.5), Position { ext1: a.ext1, ext2: a.ext2, ext3: a.ext3, ..moved });
+
+    let f = FrameData { x: 1.0, y: 2.0, z: 3.0, w: 4.0, p: 5.0, r: 6.0 };
+    let g = FrameData { x: 0.5, y: 0.25, z: -1.0, w: 90.0, p: 0.0, r: -6.0 };
+    assert_eq!((f.clone() + g.clone()) - g, f);
+}
+
+#[test]
+fn test_scale_translation_leaves_orientation() {
+    let a = position(2.0, -4.0, 0.5, 7.0);
+    let scaled = a.scale_translation(0.5);
+    assert_eq!(scaled, position(1.0, -2.0, 0.25, 7.0));
+    assert_eq!(scaled.scale_translation(2.0), a);
+}
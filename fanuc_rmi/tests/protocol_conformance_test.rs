@@ -0,0 +1,109 @@
+//! Protocol conformance against recorded controller transcripts.
+//!
+//! Each file in `tests/fixtures/protocol` holds one request/response pair as
+//! captured on the wire. Both sides must parse into the expected packet
+//! variant and serialize back to the same JSON, so a dropped or misspelled
+//! serde rename shows up here rather than on a real controller.
+
+use fanuc_rmi::packets::{ResponsePacket, SendPacket};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Debug;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/protocol")
+}
+
+fn load_fixture(name: &str) -> Value {
+    let path = fixtures_dir().join(format!("{name}.json"));
+    let text = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()));
+    serde_json::from_str(&text).unwrap_or_else(|e| panic!("{} is not valid JSON: {e}", path.display()))
+}
+
+/// `Outer::Inner` path of a packet, taken from its `Debug` output.
+fn variant_path<T: Debug>(packet: &T) -> String {
+    let debug = format!("{packet:?}");
+    let mut names = debug
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|s| !s.is_empty());
+    let outer = names.next().unwrap_or_default();
+    let inner = names.next().unwrap_or_default();
+    format!("{outer}::{inner}")
+}
+
+/// Controllers write floats with fixed decimals (`500.000`) and integers for
+/// whole speeds, so compare every number by value.
+fn normalize(value: &Value) -> Value {
+    match value {
+        Value::Number(n) => Value::from(n.as_f64().expect("number fits in f64")),
+        Value::Array(items) => Value::Array(items.iter().map(normalize).collect()),
+        Value::Object(map) => Value::Object(map.iter().map(|(k, v)| (k.clone(), normalize(v))).collect()),
+        other => other.clone(),
+    }
+}
+
+fn check_side<T>(fixture: &str, recorded: &Value, expected_variant: &Value)
+where
+    T: DeserializeOwned + Serialize + Debug,
+{
+    let packet: T = serde_json::from_value(recorded.clone())
+        .unwrap_or_else(|e| panic!("{fixture}: failed to parse {recorded}: {e}"));
+
+    let expected_variant = expected_variant.as_str().expect("variant must be a string");
+    assert_eq!(variant_path(&packet), expected_variant, "{fixture}: parsed into the wrong variant");
+
+    let reserialized = serde_json::to_value(&packet).unwrap();
+    assert_eq!(
+        normalize(&reserialized),
+        normalize(recorded),
+        "{fixture}: re-serialized JSON differs from the transcript"
+    );
+}
+
+fn check_fixture(name: &str) {
+    let fixture = load_fixture(name);
+    check_side::<SendPacket>(name, &fixture["request"], &fixture["request_variant"]);
+    check_side::<ResponsePacket>(name, &fixture["response"], &fixture["response_variant"]);
+}
+
+#[test]
+fn test_connect_transcript() {
+    check_fixture("connect");
+}
+
+#[test]
+fn test_initialize_transcript() {
+    check_fixture("initialize");
+}
+
+#[test]
+fn test_getstatus_transcript() {
+    check_fixture("getstatus");
+}
+
+#[test]
+fn test_linear_motion_transcript() {
+    check_fixture("linear_motion");
+}
+
+#[test]
+fn test_every_fixture_conforms() {
+    // Picks up transcripts dropped into the directory without their own test
+    let mut names: Vec<String> = fs::read_dir(fixtures_dir())
+        .unwrap()
+        .filter_map(|entry| {
+            let path = entry.unwrap().path();
+            (path.extension()? == "json").then(|| path.file_stem()?.to_str().map(String::from))?
+        })
+        .collect();
+    names.sort();
+    assert!(names.len() >= 4, "expected at least 4 fixtures, found {names:?}");
+
+    for name in &names {
+        check_fixture(name);
+    }
+}
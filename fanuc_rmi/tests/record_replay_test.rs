@@ -0,0 +1,127 @@
+//! Tests for raw session recording (`FanucDriverConfig::record`) and replay.
+//!
+//! A session is recorded against a minimal fake controller, then replayed
+//! with `replay_session` against a fresh driver performing the same calls.
+
+use fanuc_rmi::drivers::{
+    load_recording, replay_session, Direction, FanucDriver, FanucDriverConfig, ReplayError,
+};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+mod common;
+use common::start_fake_controller;
+
+/// Start a fake controller that answers `FRC_GetStatus` and `FRC_Initialize`.
+async fn start_session_controller() -> u32 {
+    start_fake_controller(|packet| match packet["Command"].as_str() {
+        Some("FRC_GetStatus") => Some("{\"Command\":\"FRC_GetStatus\",\"ErrorID\":0,\"ServoReady\":1,\"TPMode\":0,\"RMIMotionStatus\":0,\"ProgramStatus\":0,\"SingleStepMode\":0,\"NumberUTool\":10,\"NextSequenceID\":1,\"NumberUFrame\":9,\"Override\":100}\r\n"),
+        Some("FRC_Initialize") => Some("{\"Command\":\"FRC_Initialize\",\"ErrorID\":0,\"GroupMask\":1}\r\n"),
+        _ => None,
+    })
+    .await
+}
+
+fn recording_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("fanuc_rmi_{}_{}.jsonl", name, std::process::id()))
+}
+
+/// The session every test drives: status, initialize, status.
+async fn run_session(driver: &FanucDriver) {
+    driver.get_status().await.expect("get_status");
+    driver.initialize().await.expect("initialize");
+    driver.get_status().await.expect("get_status");
+}
+
+async fn replay_listeners() -> (TcpListener, TcpListener, u32) {
+    let connect_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let data_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = connect_listener.local_addr().unwrap().port() as u32;
+    (connect_listener, data_listener, port)
+}
+
+async fn record_session(name: &str) -> PathBuf {
+    let path = recording_path(name);
+    let port = start_session_controller().await;
+    let config = FanucDriverConfig {
+        addr: "127.0.0.1".to_string(),
+        port,
+        ..Default::default()
+    }
+    .with_record(&path);
+    let driver = FanucDriver::connect(config).await.expect("connect to fake controller");
+    run_session(&driver).await;
+    path
+}
+
+/// A recorded round-trip replays against an identical client and matches.
+#[tokio::test]
+async fn test_recorded_session_replays_and_matches() {
+    let path = record_session("roundtrip").await;
+    let recording = load_recording(&path).expect("load recording");
+    let _ = std::fs::remove_file(&path);
+
+    // Handshake plus three request/response pairs, in causal order.
+    let directions: Vec<Direction> = recording.iter().map(|e| e.direction).collect();
+    assert_eq!(directions.len(), 8, "recording: {:#?}", recording);
+    for pair in directions.chunks(2) {
+        assert_eq!(pair, [Direction::Sent, Direction::Received]);
+    }
+    assert!(recording[0].line.contains("FRC_Connect"));
+    assert!(recording[4].line.contains("FRC_Initialize"));
+    assert!(recording.windows(2).all(|w| w[0].elapsed_ms <= w[1].elapsed_ms));
+
+    let (connect_listener, data_listener, port) = replay_listeners().await;
+    let replay = tokio::spawn(async move {
+        replay_session(connect_listener, data_listener, &recording).await
+    });
+
+    let config = FanucDriverConfig {
+        addr: "127.0.0.1".to_string(),
+        port,
+        ..Default::default()
+    };
+    let driver = FanucDriver::connect(config).await.expect("connect to replay");
+    tokio::time::timeout(Duration::from_secs(5), run_session(&driver))
+        .await
+        .expect("replayed session completes");
+
+    let matched = replay.await.unwrap().expect("replay matches recording");
+    assert_eq!(matched, 4);
+}
+
+/// A client that deviates from the recording is reported as a mismatch.
+#[tokio::test]
+async fn test_replay_reports_diverging_client() {
+    let path = record_session("diverge").await;
+    let recording = load_recording(&path).expect("load recording");
+    let _ = std::fs::remove_file(&path);
+
+    let (connect_listener, data_listener, port) = replay_listeners().await;
+    let replay = tokio::spawn(async move {
+        replay_session(connect_listener, data_listener, &recording).await
+    });
+
+    let config = FanucDriverConfig {
+        addr: "127.0.0.1".to_string(),
+        port,
+        ..Default::default()
+    };
+    let driver = FanucDriver::connect(config).await.expect("connect to replay");
+    // Recording starts with FRC_GetStatus; send FRC_Initialize instead.
+    let _ = driver.send_initialize();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), replay)
+        .await
+        .expect("replay finishes")
+        .unwrap();
+    match result {
+        Err(ReplayError::Mismatch { index, expected, actual }) => {
+            assert_eq!(index, 2);
+            assert!(expected.contains("FRC_GetStatus"), "expected: {}", expected);
+            assert!(actual.contains("FRC_Initialize"), "actual: {}", actual);
+        }
+        other => panic!("expected a mismatch, got {:?}", other),
+    }
+}
@@ -0,0 +1,84 @@
+//! Tests for the derived robot status-change stream.
+//!
+//! A minimal in-process fake controller answers each `FRC_GetStatus` with the
+//! next response from a script, so the test controls exactly which statuses
+//! the driver sees.
+
+use fanuc_rmi::commands::RobotStatus;
+use fanuc_rmi::drivers::{FanucDriver, FanucDriverConfig};
+use fanuc_rmi::packets::Command;
+use std::time::Duration;
+use tokio::sync::broadcast::error::TryRecvError;
+
+mod common;
+use common::start_fake_controller;
+
+/// A successful `FRC_GetStatus` response.
+fn status_json(servo_ready: i8, rmi_motion_status: i8, next_sequence_id: u32) -> String {
+    format!(
+        "{{\"Command\":\"FRC_GetStatus\",\"ErrorID\":0,\"ServoReady\":{},\"TPMode\":0,\"RMIMotionStatus\":{},\"ProgramStatus\":0,\"SingleStepMode\":0,\"NumberUTool\":1,\"NumberUFrame\":0,\"NextSequenceID\":{},\"Override\":100}}\r\n",
+        servo_ready, rmi_motion_status, next_sequence_id
+    )
+}
+
+/// Start a fake controller on an ephemeral port that answers the n-th
+/// `FRC_GetStatus` with `script[n]`.
+async fn start_status_controller(script: Vec<String>) -> u32 {
+    let mut script = script.into_iter();
+    start_fake_controller(move |packet| {
+        if packet["Command"] == "FRC_GetStatus" {
+            script.next()
+        } else {
+            None
+        }
+    })
+    .await
+}
+
+/// Two identical statuses followed by one where the motion status changed
+/// produce exactly one change event. The sequence id advancing between the
+/// identical statuses is not a change.
+#[tokio::test]
+async fn test_status_change_fires_once_per_change() {
+    let port = start_status_controller(vec![
+        status_json(1, 0, 1),
+        status_json(1, 0, 4),
+        status_json(1, 2, 4),
+    ])
+    .await;
+    let config = FanucDriverConfig {
+        addr: "127.0.0.1".to_string(),
+        port,
+        ..Default::default()
+    };
+    let driver = FanucDriver::connect(config).await.expect("connect to fake controller");
+    let mut status_rx = driver.status_tx.subscribe();
+
+    assert_eq!(driver.robot_status(), None);
+    for _ in 0..3 {
+        driver.command(Command::FrcGetStatus).await.expect("status response");
+    }
+
+    let change = tokio::time::timeout(Duration::from_secs(2), status_rx.recv())
+        .await
+        .expect("change event")
+        .expect("open channel");
+    let expected = RobotStatus {
+        servo_ready: true,
+        tp_mode: false,
+        rmi_motion_status: 2,
+        program_status: 0,
+        single_step_mode: false,
+        number_utool: 1,
+        number_uframe: 0,
+        override_value: 100,
+    };
+    assert_eq!(change, expected);
+    assert_eq!(driver.robot_status(), Some(expected));
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(
+        matches!(status_rx.try_recv(), Err(TryRecvError::Empty)),
+        "exactly one change event expected"
+    );
+}
@@ -0,0 +1,32 @@
+//! Tests for `FanucDriverConfig::tcp_nodelay`.
+
+use fanuc_rmi::drivers::{FanucDriver, FanucDriverConfig};
+
+mod common;
+use common::start_silent_controller;
+
+#[test]
+fn test_tcp_nodelay_defaults_on() {
+    assert!(FanucDriverConfig::default().tcp_nodelay);
+
+    let config: FanucDriverConfig = serde_json::from_str(r#"{"addr":"127.0.0.1","port":16001,"max_messages":30}"#).unwrap();
+    assert!(config.tcp_nodelay);
+}
+
+#[tokio::test]
+async fn test_connected_stream_disables_nagle_by_default() {
+    let port = start_silent_controller().await;
+    let config = FanucDriverConfig { addr: "127.0.0.1".to_string(), port, ..Default::default() };
+    let driver = FanucDriver::connect(config).await.expect("connect to fake controller");
+
+    assert!(driver.tcp_nodelay().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_connected_stream_keeps_nagle_when_disabled() {
+    let port = start_silent_controller().await;
+    let config = FanucDriverConfig { addr: "127.0.0.1".to_string(), port, ..Default::default() }.with_tcp_nodelay(false);
+    let driver = FanucDriver::connect(config).await.expect("connect to fake controller");
+
+    assert!(!driver.tcp_nodelay().await.unwrap());
+}
@@ -0,0 +1,69 @@
+use fanuc_rmi::instructions::{FrcJointMotionPR, FrcLinearMotionPR};
+use fanuc_rmi::packets::Instruction;
+use fanuc_rmi::{FrcError, SpeedType, TermType};
+
+/// The `FrcError::Parse` message for `line`, failing if it parsed.
+fn parse_error(line: &str) -> String {
+    match Instruction::parse_line(line) {
+        Err(FrcError::Parse(message)) => message,
+        other => panic!("expected a parse error for '{}', got {:?}", line, other),
+    }
+}
+
+#[test]
+fn test_linear_line_to_position_register_motion() {
+    assert_eq!(
+        Instruction::parse_line("L P[1] 100mm/sec FINE").unwrap(),
+        Instruction::FrcLinearMotionPR(FrcLinearMotionPR::new(0, 1, SpeedType::MMSec, 100.0, TermType::FINE, 0))
+    );
+    assert_eq!(
+        Instruction::parse_line("  3:l pr[12] 250.5inch/min cnt50 ;").unwrap(),
+        Instruction::FrcLinearMotionPR(FrcLinearMotionPR::new(0, 12, SpeedType::InchMin, 250.5, TermType::CNT, 50))
+    );
+}
+
+#[test]
+fn test_joint_line_to_position_register_motion() {
+    assert_eq!(
+        "J PR[2] 2sec CNT100".parse::<Instruction>().unwrap(),
+        // Seconds are sent in 0.1 s steps
+        Instruction::FrcJointMotionPR(FrcJointMotionPR::new(0, 2, SpeedType::Time, 20.0, TermType::CNT, 100))
+    );
+    assert_eq!(
+        Instruction::parse_line("J P[7] 500msec CR25").unwrap(),
+        Instruction::FrcJointMotionPR(FrcJointMotionPR::new(0, 7, SpeedType::MilliSeconds, 500.0, TermType::CR, 25))
+    );
+}
+
+#[test]
+fn test_unknown_mnemonic_is_rejected() {
+    assert_eq!(parse_error("C P[1] 100mm/sec FINE"), "unknown motion type 'C' (expected L or J) in 'C P[1] 100mm/sec FINE'");
+}
+
+#[test]
+fn test_speed_without_unit_is_rejected() {
+    assert_eq!(
+        parse_error("L P[1] 100 FINE"),
+        "speed '100' has no unit (expected mm/sec, inch/min, sec or msec) in 'L P[1] 100 FINE'"
+    );
+    assert!(parse_error("L P[1] 100mm/min FINE").starts_with("unknown speed unit 'mm/min'"));
+}
+
+#[test]
+fn test_out_of_range_term_value_is_rejected() {
+    assert_eq!(
+        parse_error("L P[1] 100mm/sec CNT101"),
+        "term value in 'CNT101' is out of range (0-100) in 'L P[1] 100mm/sec CNT101'"
+    );
+    assert!(parse_error("J P[1] 100mm/sec CR0").starts_with("term value in 'CR0' is out of range (1-100)"));
+    assert!(parse_error("J P[1] 100mm/sec CNT").starts_with("missing or invalid term value in 'CNT'"));
+}
+
+#[test]
+fn test_malformed_lines_are_rejected() {
+    assert!(parse_error("").starts_with("empty line"));
+    assert!(parse_error("L P[1] 100mm/sec").starts_with("missing termination"));
+    assert!(parse_error("L P[0] 100mm/sec FINE").starts_with("position register numbers start at 1"));
+    assert!(parse_error("L X[1] 100mm/sec FINE").starts_with("expected a position like P[1] or PR[1]"));
+    assert!(parse_error("L P[1] 100mm/sec FINE ACC50").starts_with("unexpected 'ACC50' after the termination"));
+}
@@ -0,0 +1,197 @@
+//! Tests for the per-packet `tracing` spans opened at `LogLevel::Debug`.
+//!
+//! A minimal in-process subscriber records every span's fields and whether it
+//! has closed, and a fake controller answers instructions after a delay so
+//! the span can be observed both open and closed.
+
+use fanuc_rmi::drivers::{FanucDriver, FanucDriverConfig, LogLevel};
+use fanuc_rmi::instructions::FrcLinearMotion;
+use fanuc_rmi::packets::{Instruction, PacketPriority, SendPacket};
+use fanuc_rmi::{Configuration, Position, SpeedType, TermType};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+mod common;
+use common::{instruction_reply, start_fake_controller, Reply};
+
+/// How long the fake controller waits before answering an instruction.
+const RESPONSE_DELAY: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Default)]
+struct CapturedSpan {
+    name: &'static str,
+    fields: HashMap<&'static str, String>,
+    refs: usize,
+    closed: bool,
+}
+
+impl Visit for CapturedSpan {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.fields.insert(field.name(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.fields.insert(field.name(), format!("{:?}", value));
+    }
+}
+
+/// Subscriber that keeps every span it sees, tracking handle counts so it
+/// knows when a span closes.
+#[derive(Clone, Default)]
+struct SpanCapture {
+    next_id: Arc<AtomicU64>,
+    spans: Arc<Mutex<HashMap<u64, CapturedSpan>>>,
+}
+
+impl SpanCapture {
+    /// Snapshot of `(fields, closed)` for every span named `name`.
+    fn spans_named(&self, name: &str) -> Vec<(HashMap<&'static str, String>, bool)> {
+        self.spans
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|span| span.name == name)
+            .map(|span| (span.fields.clone(), span.closed))
+            .collect()
+    }
+}
+
+impl Subscriber for SpanCapture {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut span = CapturedSpan {
+            name: attrs.metadata().name(),
+            refs: 1,
+            ..Default::default()
+        };
+        attrs.record(&mut span);
+        self.spans.lock().unwrap().insert(id, span);
+        Id::from_u64(id)
+    }
+
+    fn record(&self, id: &Id, values: &Record<'_>) {
+        if let Some(span) = self.spans.lock().unwrap().get_mut(&id.into_u64()) {
+            values.record(span);
+        }
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+
+    fn clone_span(&self, id: &Id) -> Id {
+        if let Some(span) = self.spans.lock().unwrap().get_mut(&id.into_u64()) {
+            span.refs += 1;
+        }
+        id.clone()
+    }
+
+    fn try_close(&self, id: Id) -> bool {
+        let mut spans = self.spans.lock().unwrap();
+        let Some(span) = spans.get_mut(&id.into_u64()) else {
+            return false;
+        };
+        span.refs -= 1;
+        span.closed = span.refs == 0;
+        span.closed
+    }
+}
+
+/// Start a fake controller on an ephemeral port that answers every
+/// instruction successfully after [`RESPONSE_DELAY`].
+async fn start_delayed_controller() -> u32 {
+    start_fake_controller(|packet| {
+        instruction_reply(&packet).map(|reply| Reply::after(RESPONSE_DELAY, reply))
+    })
+    .await
+}
+
+fn linear_motion() -> SendPacket {
+    SendPacket::Instruction(Instruction::FrcLinearMotion(FrcLinearMotion::new(
+        0, // sequence_id will be assigned by driver
+        Configuration::default(),
+        Position {
+            x: 100.0,
+            ..Default::default()
+        },
+        SpeedType::MMSec,
+        50.0,
+        TermType::FINE,
+        100,
+    )))
+}
+
+async fn connect(log_level: LogLevel) -> FanucDriver {
+    let port = start_delayed_controller().await;
+    let config = FanucDriverConfig {
+        addr: "127.0.0.1".to_string(),
+        port,
+        ..Default::default()
+    }
+    .with_log_level(log_level);
+    FanucDriver::connect(config).await.expect("connect to fake controller")
+}
+
+/// A linear motion sent at `LogLevel::Debug` opens an `rmi_packet` span named
+/// after the instruction and carrying its sequence id, which stays open until
+/// the controller answers.
+#[tokio::test]
+async fn test_linear_motion_span_carries_command_and_sequence_id() {
+    let capture = SpanCapture::default();
+    let _guard = tracing::subscriber::set_default(capture.clone());
+
+    let driver = connect(LogLevel::Debug).await;
+    let mut sent_rx = driver.sent_instruction_tx.subscribe();
+    driver.send_packet(linear_motion(), PacketPriority::Standard).expect("queue instruction");
+    let sent = tokio::time::timeout(Duration::from_secs(2), sent_rx.recv())
+        .await
+        .expect("instruction sent")
+        .expect("open channel");
+
+    tokio::time::sleep(RESPONSE_DELAY / 4).await;
+    let spans = capture.spans_named("rmi_packet");
+    assert_eq!(spans.len(), 1, "one span per packet: {:?}", spans);
+    let (fields, closed) = &spans[0];
+    assert_eq!(fields.get("command").map(String::as_str), Some("FRC_LinearMotion"));
+    assert_eq!(fields.get("sequence_id"), Some(&sent.sequence_id.to_string()));
+    assert!(!closed, "span must stay open until the response arrives");
+
+    let closed = tokio::time::timeout(Duration::from_secs(2), async {
+        while !capture.spans_named("rmi_packet")[0].1 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+    assert!(closed.is_ok(), "span must close once the response arrives");
+}
+
+/// Below `LogLevel::Debug` no spans are opened at all.
+#[tokio::test]
+async fn test_no_spans_below_debug_level() {
+    let capture = SpanCapture::default();
+    let _guard = tracing::subscriber::set_default(capture.clone());
+
+    let driver = connect(LogLevel::Info).await;
+    let mut sent_rx = driver.sent_instruction_tx.subscribe();
+    driver.send_packet(linear_motion(), PacketPriority::Standard).expect("queue instruction");
+    tokio::time::timeout(Duration::from_secs(2), sent_rx.recv())
+        .await
+        .expect("instruction sent")
+        .expect("open channel");
+
+    assert!(capture.spans_named("rmi_packet").is_empty());
+}
@@ -0,0 +1,90 @@
+//! Tests for trajectory capture of reported Cartesian positions.
+//!
+//! A minimal in-process fake controller streams position responses straight
+//! into the driver's receive path.
+
+use fanuc_rmi::drivers::{FanucDriver, FanucDriverConfig, TrajectoryBuffer};
+use fanuc_rmi::Position;
+use std::time::{Duration, Instant};
+
+mod common;
+use common::start_streaming_controller;
+
+/// Start a fake controller that, once the data connection is open, sends
+/// `count` `FRC_ReadCartesianPosition` responses with X = 0, 1, 2, ...
+async fn start_position_stream(count: usize) -> u32 {
+    let positions = (0..count)
+        .map(|i| {
+            format!(
+                "{{\"Command\":\"FRC_ReadCartesianPosition\",\"ErrorID\":0,\"TimeTag\":{},\"Position\":{{\"X\":{},\"Y\":0.0,\"Z\":300.0}},\"Group\":1}}\r\n",
+                i, i
+            )
+        })
+        .collect();
+    start_streaming_controller(positions, |_| None::<String>).await
+}
+
+fn position(x: f64) -> Position {
+    Position {
+        x,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_buffer_evicts_oldest() {
+    let mut buffer = TrajectoryBuffer::new(3);
+    let start = Instant::now();
+    for i in 0..5 {
+        buffer.push(start + Duration::from_millis(i), position(i as f64));
+    }
+    let xs: Vec<f64> = buffer.snapshot().iter().map(|(_, p)| p.x).collect();
+    assert_eq!(xs, [2.0, 3.0, 4.0]);
+    assert_eq!(buffer.capacity(), 3);
+}
+
+/// 500 reported positions into a 256-sample buffer keep the newest 256, in order.
+#[tokio::test]
+async fn test_driver_keeps_newest_positions() {
+    let port = start_position_stream(500).await;
+    let config = FanucDriverConfig {
+        addr: "127.0.0.1".to_string(),
+        port,
+        ..Default::default()
+    }
+    .with_trajectory_capture(256);
+    let driver = FanucDriver::connect(config).await.expect("connect to fake controller");
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let snapshot = loop {
+        let snapshot = driver.trajectory_snapshot();
+        if snapshot.last().map(|(_, p)| p.x) == Some(499.0) {
+            break snapshot;
+        }
+        assert!(Instant::now() < deadline, "timed out waiting for all positions");
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    };
+
+    assert_eq!(snapshot.len(), 256);
+    let xs: Vec<f64> = snapshot.iter().map(|(_, p)| p.x).collect();
+    let expected: Vec<f64> = (244..500).map(|x| x as f64).collect();
+    assert_eq!(xs, expected, "eviction must keep the newest samples in order");
+    assert!(snapshot.windows(2).all(|w| w[0].0 <= w[1].0));
+
+    driver.clear_trajectory();
+    assert!(driver.trajectory_snapshot().is_empty());
+}
+
+/// Without `trajectory_capacity` nothing is captured.
+#[tokio::test]
+async fn test_capture_disabled_by_default() {
+    let port = start_position_stream(10).await;
+    let config = FanucDriverConfig {
+        addr: "127.0.0.1".to_string(),
+        port,
+        ..Default::default()
+    };
+    let driver = FanucDriver::connect(config).await.expect("connect to fake controller");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(driver.trajectory_snapshot().is_empty());
+}
@@ -1,5 +1,10 @@
-use fanuc_rmi::packets::{ResponsePacket, CommandResponse};
-use fanuc_rmi::commands::FrcUnknownResponse;
+use fanuc_rmi::packets::{Command, ResponsePacket, CommandResponse};
+use fanuc_rmi::commands::{FrcUnknownResponse, SimMode};
+use fanuc_rmi::drivers::{FanucDriver, FanucDriverConfig};
+use fanuc_rmi::{FanucErrorCode, FrcError};
+
+mod common;
+use common::start_fake_controller;
 
 #[test]
 fn test_unknown_command_response_deserialization() {
@@ -59,3 +64,35 @@ fn test_unknown_command_serialization() {
     }
 }
 
+/// Start a fake controller that answers `FRC_GetStatus` and, like a real
+/// controller, answers everything else with `Unknown` (InvalidTextString).
+/// Returns the port.
+async fn start_strict_controller() -> u32 {
+    start_fake_controller(|packet| {
+        Some(if packet["Command"] == "FRC_GetStatus" {
+            "{\"Command\":\"FRC_GetStatus\",\"ErrorID\":0,\"NextSequenceID\":1}\r\n"
+        } else {
+            "{\"Command\" : \"Unknown\", \"ErrorID\" : 2556950}\r\n"
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_unknown_response_is_an_unsupported_command_error() {
+    let port = start_strict_controller().await;
+    let config = FanucDriverConfig { addr: "127.0.0.1".to_string(), port, ..Default::default() };
+    let driver = FanucDriver::connect(config).await.expect("connect to fake controller");
+
+    // SIM_Mode is simulator-only; a real controller cannot parse it
+    match driver.command(SimMode::new(None)).await {
+        Err(FrcError::UnsupportedCommand(name, FanucErrorCode::InvalidTextString)) => assert_eq!(name, "SIM_Mode"),
+        other => panic!("expected UnsupportedCommand, got {:?}", other),
+    }
+    let err = driver.sim_mode().await.expect_err("SIM_Mode is not supported");
+    assert_eq!(err.to_string(), "Controller does not support SIM_Mode: Invalid Text String.");
+
+    // The Unknown answers were matched to their commands, not left pending
+    assert_eq!(driver.pending_command_count(), 0);
+    assert!(matches!(driver.command(Command::FrcGetStatus).await, Ok(CommandResponse::FrcGetStatus(_))));
+}
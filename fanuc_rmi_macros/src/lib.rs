@@ -32,7 +32,43 @@ fn is_primitive_ident(name: &str) -> bool {
     )
 }
 
+/// The element type of an `Option<T>` or `Vec<T>` field, if `ty` is one.
+fn wrapped_type(ty: &Type) -> Option<(&'static str, &Type)> {
+    let Type::Path(type_path) = ty else { return None };
+    let seg = type_path.path.segments.last()?;
+    let wrapper = match seg.ident.to_string().as_str() {
+        "Option" => "Option",
+        "Vec" => "Vec",
+        _ => return None,
+    };
+    let syn::PathArguments::AngleBracketed(args) = &seg.arguments else { return None };
+    match args.args.first() {
+        Some(syn::GenericArgument::Type(inner)) if args.args.len() == 1 => Some((wrapper, inner)),
+        _ => None,
+    }
+}
+
+/// Mirror the element type of an `Option<T>` or `Vec<T>` with `map_inner`.
+fn map_wrapped_type(ty: &mut Type, map_inner: impl FnOnce(&mut Type)) -> bool {
+    if wrapped_type(ty).is_none() {
+        return false;
+    }
+    if let Type::Path(type_path) = ty {
+        if let Some(seg) = type_path.path.segments.last_mut() {
+            if let syn::PathArguments::AngleBracketed(args) = &mut seg.arguments {
+                if let Some(syn::GenericArgument::Type(inner)) = args.args.first_mut() {
+                    map_inner(inner);
+                }
+            }
+        }
+    }
+    true
+}
+
 fn map_type_to_dto(ty: &mut Type) {
+    if map_wrapped_type(ty, map_type_to_dto) {
+        return;
+    }
     if let Type::Path(type_path) = ty {
         if let Some(seg) = type_path.path.segments.last_mut() {
             let ident_str = seg.ident.to_string();
@@ -68,6 +104,20 @@ fn field_type_needs_into(ty: &Type) -> bool {
     false
 }
 
+/// Convert `value` between a field's protocol and DTO types.
+///
+/// Nested mirrored structs convert with `.into()`; inside an `Option` or
+/// `Vec` each element is converted.
+fn convert_field(value: proc_macro2::TokenStream, ty: &Type) -> proc_macro2::TokenStream {
+    match wrapped_type(ty) {
+        Some((_, inner)) if !field_type_needs_into(inner) => value,
+        Some(("Option", _)) => quote! { #value.map(::core::convert::Into::into) },
+        Some(_) => quote! { #value.into_iter().map(::core::convert::Into::into).collect() },
+        None if field_type_needs_into(ty) => quote! { #value.into() },
+        None => value,
+    }
+}
+
 #[proc_macro_attribute]
 pub fn mirror_dto(_attr: TokenStream, item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
@@ -117,10 +167,10 @@ fn mirror_struct(
         }
     };
 
-    // Determine if fields likely need Into for nested DTOs
+    // Nested mirrored structs (composed, or `#[serde(flatten)]` on the
+    // protocol side) stay nested in the DTO and convert with `.into()`
     let field_names: Vec<_> = fields.iter().map(|f| f.ident.clone().unwrap()).collect();
     let field_types: Vec<_> = fields.iter().map(|f| f.ty.clone()).collect();
-    let nested_flags: Vec<_> = field_types.iter().map(|t| field_type_needs_into(t)).collect();
 
     let dto_fields = fields.iter().map(|f| {
         let mut f2 = f.clone();
@@ -131,12 +181,14 @@ fn mirror_struct(
         quote! { #f2 }
     });
 
-    let into_fields = field_names.iter().enumerate().map(|(i, name)| {
-        if nested_flags[i] { quote! { #name: src.#name.into() } } else { quote! { #name: src.#name } }
+    let into_fields = field_names.iter().zip(&field_types).map(|(name, ty)| {
+        let value = convert_field(quote! { src.#name }, ty);
+        quote! { #name: #value }
     });
 
-    let from_fields = field_names.iter().enumerate().map(|(i, name)| {
-        if nested_flags[i] { quote! { #name: src.#name.into() } } else { quote! { #name: src.#name } }
+    let from_fields = field_names.iter().zip(&field_types).map(|(name, ty)| {
+        let value = convert_field(quote! { src.#name }, ty);
+        quote! { #name: #value }
     });
 
     quote! {
@@ -178,7 +230,6 @@ fn mirror_enum(
             Fields::Unnamed(unnamed) => {
                 let field_idents: Vec<Ident> = (0..unnamed.unnamed.len()).map(|i| format_ident!("f{}", i)).collect();
                 let field_types: Vec<Type> = unnamed.unnamed.iter().map(|f| f.ty.clone()).collect();
-                let nested_flags: Vec<_> = field_types.iter().map(|t| field_type_needs_into(t)).collect();
 
                 let dto_fields = unnamed.unnamed.iter().map(|f| {
                     let mut f2 = f.clone();
@@ -189,8 +240,8 @@ fn mirror_enum(
                     quote! { #f2 }
                 });
 
-                let into_exprs = field_idents.iter().enumerate().map(|(i, id)| if nested_flags[i] { quote! { #id.into() } } else { quote! { #id } });
-                let from_exprs = field_idents.iter().enumerate().map(|(i, id)| if nested_flags[i] { quote! { #id.into() } } else { quote! { #id } });
+                let into_exprs = field_idents.iter().zip(&field_types).map(|(id, ty)| convert_field(quote! { #id }, ty));
+                let from_exprs = field_idents.iter().zip(&field_types).map(|(id, ty)| convert_field(quote! { #id }, ty));
 
                 dto_variants.push(quote! { #(#v_attrs)* #v_name( #( #dto_fields ),* ) });
                 into_arms.push(quote! { #original::#v_name( #( #field_idents ),* ) => #dto_name::#v_name( #( #into_exprs ),* ) });
@@ -199,7 +250,6 @@ fn mirror_enum(
             Fields::Named(named) => {
                 let field_names: Vec<_> = named.named.iter().map(|f| f.ident.clone().unwrap()).collect();
                 let field_types: Vec<_> = named.named.iter().map(|f| f.ty.clone()).collect();
-                let nested_flags: Vec<_> = field_types.iter().map(|t| field_type_needs_into(t)).collect();
 
                 let dto_fields = named.named.iter().map(|f| {
                     let mut f2 = f.clone();
@@ -213,11 +263,13 @@ fn mirror_enum(
                 let pat_bindings: Vec<Ident> = field_names.iter().map(|n| format_ident!("b_{}", n)).collect();
                 let into_kvs = field_names.iter().enumerate().map(|(i, n)| {
                     let bind = &pat_bindings[i];
-                    if nested_flags[i] { quote! { #n: #bind.into() } } else { quote! { #n: #bind } }
+                    let value = convert_field(quote! { #bind }, &field_types[i]);
+                    quote! { #n: #value }
                 });
                 let from_kvs = field_names.iter().enumerate().map(|(i, n)| {
                     let bind = &pat_bindings[i];
-                    if nested_flags[i] { quote! { #n: #bind.into() } } else { quote! { #n: #bind } }
+                    let value = convert_field(quote! { #bind }, &field_types[i]);
+                    quote! { #n: #value }
                 });
 
                 dto_variants.push(quote! { #(#v_attrs)* #v_name { #( #dto_fields ),* } });
@@ -241,6 +293,9 @@ fn mirror_enum(
 }
 
 fn map_type_to_dto_in_enum(ty: &mut Type, enum_name: &Ident) {
+    if map_wrapped_type(ty, |inner| map_type_to_dto_in_enum(inner, enum_name)) {
+        return;
+    }
     if let Type::Path(type_path) = ty {
         if let Some(seg) = type_path.path.segments.last_mut() {
             let ident_str = seg.ident.to_string();
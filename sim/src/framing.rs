@@ -0,0 +1,133 @@
+//! Line framing for the RMI socket protocol.
+//!
+//! Every RMI packet is one JSON object terminated by `\r\n`. TCP delivers a
+//! byte stream, so one read may hold part of a packet, several packets, or a
+//! packet larger than the read buffer. [`LineFramer`] accumulates bytes until
+//! a full line is present and yields it without its terminator. Clients that
+//! send a bare `\n` are accepted too, and blank lines are skipped.
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Size of each socket read. Lines may be longer; they are accumulated.
+pub const READ_CHUNK: usize = 1024;
+
+/// Accumulates received bytes and splits them into lines.
+#[derive(Debug, Default)]
+pub struct LineFramer {
+    pending: Vec<u8>,
+}
+
+impl LineFramer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append bytes received from the socket.
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.pending.extend_from_slice(bytes);
+    }
+
+    /// The next complete line, without its `\n` or `\r\n` terminator, or
+    /// `None` until more bytes arrive.
+    pub fn next_line(&mut self) -> Option<String> {
+        loop {
+            let end = self.pending.iter().position(|&b| b == b'\n')?;
+            let mut line: Vec<u8> = self.pending.drain(..=end).collect();
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            if !line.iter().all(u8::is_ascii_whitespace) {
+                return Some(String::from_utf8_lossy(&line).into_owned());
+            }
+        }
+    }
+}
+
+/// Read from `reader` until `framer` holds a complete line and return it.
+///
+/// Returns `Ok(None)` if the stream ends first.
+pub async fn read_line<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    framer: &mut LineFramer,
+) -> std::io::Result<Option<String>> {
+    let mut buffer = [0u8; READ_CHUNK];
+    loop {
+        if let Some(line) = framer.next_line() {
+            return Ok(Some(line));
+        }
+        let n = reader.read(&mut buffer).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        framer.push(&buffer[..n]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncWriteExt;
+
+    const PACKET: &str = r#"{"Command":"FRC_ReadDIN","PortNumber":7}"#;
+
+    fn assert_packet(line: &str) {
+        let json: serde_json::Value = serde_json::from_str(line).expect("complete JSON");
+        assert_eq!(json["Command"], "FRC_ReadDIN");
+        assert_eq!(json["PortNumber"], 7);
+    }
+
+    #[test]
+    fn test_packet_fed_byte_by_byte() {
+        let mut framer = LineFramer::new();
+        let bytes = format!("{}\r\n", PACKET).into_bytes();
+        for (i, byte) in bytes.iter().enumerate() {
+            assert_eq!(framer.next_line(), None, "no line before byte {}", i);
+            framer.push(std::slice::from_ref(byte));
+        }
+        assert_packet(&framer.next_line().expect("line after terminator"));
+        assert!(framer.pending.is_empty());
+    }
+
+    #[test]
+    fn test_packet_split_across_two_reads() {
+        let mut framer = LineFramer::new();
+        let bytes = format!("{}\r\n", PACKET).into_bytes();
+        // Split between the `\r` and the `\n` so the terminator straddles reads
+        let (first, second) = bytes.split_at(bytes.len() - 1);
+        framer.push(first);
+        assert_eq!(framer.next_line(), None);
+        framer.push(second);
+        assert_packet(&framer.next_line().expect("line after second read"));
+    }
+
+    #[test]
+    fn test_mixed_terminators_and_blank_lines() {
+        let mut framer = LineFramer::new();
+        framer.push(format!("{}\n\r\n{}\r\n{}", PACKET, PACKET, PACKET).as_bytes());
+        assert_packet(&framer.next_line().unwrap());
+        assert_packet(&framer.next_line().unwrap());
+        assert_eq!(framer.next_line(), None, "last packet is not terminated yet");
+        assert_eq!(framer.pending, PACKET.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_read_line_longer_than_read_chunk() {
+        let padding = "x".repeat(READ_CHUNK * 3);
+        let packet = format!(r#"{{"Command":"FRC_ReadDIN","PortNumber":7,"Padding":"{}"}}"#, padding);
+        let (mut client, mut server) = tokio::io::duplex(64);
+        let payload = format!("{}\r\n", packet);
+        tokio::spawn(async move {
+            // Small, uneven writes
+            for chunk in payload.as_bytes().chunks(37) {
+                client.write_all(chunk).await.unwrap();
+            }
+        });
+
+        let mut framer = LineFramer::new();
+        let line = read_line(&mut server, &mut framer).await.unwrap().expect("a line");
+        assert_packet(&line);
+        assert_eq!(line.len(), packet.len());
+        assert_eq!(read_line(&mut server, &mut framer).await.unwrap(), None);
+    }
+}
@@ -3,7 +3,143 @@
 // by Manel Abbes and Gérard Poisson, Robotics 2024, 13, 91
 // https://doi.org/10.3390/robotics13060091
 
-use crate::robot_config::RobotConfig;
+use crate::planar_kinematics::PlanarTwoLinkKinematics;
+use crate::robot_config::{within_limits, JointLimit, KinematicsModel, RobotConfig};
+use fanuc_rmi::ArmConfig;
+use nalgebra::{Matrix3, Matrix6, Rotation3, Vector6};
+use std::sync::Arc;
+
+/// Iteration limit of the numerical IK refinement.
+const REFINE_MAX_ITERATIONS: usize = 100;
+/// Position tolerance (mm) at which refinement has converged.
+const REFINE_POSITION_TOLERANCE: f64 = 1e-3;
+/// Orientation tolerance (radians) at which refinement has converged.
+const REFINE_ORIENTATION_TOLERANCE: f64 = 1e-6;
+/// Joint step (radians) for the finite-difference Jacobian.
+const REFINE_JACOBIAN_STEP: f64 = 1e-7;
+/// Damping factor of the least-squares step, which keeps it bounded near
+/// singularities.
+const REFINE_DAMPING: f64 = 1e-3;
+/// Joint step (radians) for the singularity measure's Jacobian.
+const SINGULARITY_JACOBIAN_STEP: f64 = 1e-6;
+/// Length (mm) dividing the Jacobian's translational rows so they are
+/// comparable with its rotational (radian) rows when conditioning.
+const JACOBIAN_LENGTH_SCALE: f64 = 540.0;
+/// Iteration limit of the singular value decomposition behind
+/// [`CRXKinematics::singularity_measure`].
+const SVD_MAX_ITERATIONS: usize = 200;
+/// Inverse condition number of the Jacobian below which a pose is inside a
+/// singularity neighborhood.
+pub const SINGULARITY_THRESHOLD: f64 = 0.01;
+/// Position error (mm) within which an IK solution reaches its target.
+const REACH_POSITION_TOLERANCE: f64 = 1.0;
+/// Orientation error (radians) within which an IK solution reaches its target.
+const REACH_ORIENTATION_TOLERANCE: f64 = 0.0175;
+
+/// How well the arm can reach a pose; see [`CRXKinematics::reachability`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reachability {
+    /// Reached by `joints`, away from any singularity.
+    Reachable { joints: [f64; 6] },
+    /// Reached by `joints`, but inside a singularity neighborhood where
+    /// small Cartesian moves need large joint moves. `condition` is the
+    /// inverse condition number (see [`CRXKinematics::singularity_measure`]).
+    NearSingularity { joints: [f64; 6], condition: f64 },
+    /// No solution reaches both the position and the orientation.
+    Unreachable,
+}
+
+/// Distances (mm) from the base origin between which the tool point can
+/// be; a target outside them is unreachable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReachBounds {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl ReachBounds {
+    /// Whether `position` lies between the bounds.
+    pub fn contains(&self, position: &[f64; 3]) -> bool {
+        let distance = position.iter().map(|c| c * c).sum::<f64>().sqrt();
+        distance >= self.min - REACH_POSITION_TOLERANCE && distance <= self.max + REACH_POSITION_TOLERANCE
+    }
+}
+
+/// Kinematic model of a simulated arm.
+///
+/// Joints are J1-J6 in radians, positions [x, y, z] in mm and orientations
+/// [w, p, r] Cardan angles in radians. Arms with fewer axes ignore the
+/// joints they don't have.
+pub trait Kinematics: std::fmt::Debug + Send + Sync {
+    /// Tool position and orientation at `joints`.
+    fn forward_kinematics(&self, joints: &[f64; 6]) -> ([f64; 3], [f64; 3]);
+
+    /// Joints reaching `position` (and `orientation`, where the arm can
+    /// orient the tool), choosing the solution closest to `current_joints`
+    /// and, with a `target_config`, on that configuration's branch. `None`
+    /// if the pose is unreachable.
+    fn inverse_kinematics(
+        &self,
+        position: &[f64; 3],
+        orientation: Option<&[f64; 3]>,
+        current_joints: &[f64; 6],
+        target_config: Option<&ArmConfig>,
+    ) -> Option<[f64; 6]>;
+
+    /// Travel range of J1-J6, in degrees.
+    fn joint_limits(&self) -> &[JointLimit; 6];
+
+    /// Arm configuration of `joints`, as reported by
+    /// `FRC_ReadCartesianPosition`. Arms without configuration branches
+    /// report the default.
+    fn arm_config(&self, _joints: &[f64; 6]) -> ArmConfig {
+        ArmConfig::default()
+    }
+
+    /// Distances from the base origin the tool point can reach.
+    fn reach_bounds(&self) -> ReachBounds;
+
+    /// Whether every joint of `joints` lies within [`joint_limits`](Self::joint_limits).
+    fn within_joint_limits(&self, joints: &[f64; 6]) -> bool {
+        within_limits(self.joint_limits(), joints)
+    }
+
+    /// Whether `position` can be reached, checked by solving for it and
+    /// following the solution back through forward kinematics. Only the
+    /// position is checked and singularities are not detected; models that
+    /// can do better override this.
+    fn reachability(
+        &self,
+        position: &[f64; 3],
+        orientation: &[f64; 3],
+        current_joints: &[f64; 6],
+        target_config: Option<&ArmConfig>,
+    ) -> Reachability {
+        if !self.reach_bounds().contains(position) {
+            return Reachability::Unreachable;
+        }
+        let Some(joints) = self.inverse_kinematics(position, Some(orientation), current_joints, target_config) else {
+            return Reachability::Unreachable;
+        };
+        let (reached, _) = self.forward_kinematics(&joints);
+        let error = reached.iter().zip(position).map(|(a, b)| (a - b).powi(2)).sum::<f64>().sqrt();
+        if error <= REACH_POSITION_TOLERANCE {
+            Reachability::Reachable { joints }
+        } else {
+            Reachability::Unreachable
+        }
+    }
+}
+
+/// The kinematic model `config` selects.
+pub fn kinematics_for(config: RobotConfig) -> Arc<dyn Kinematics> {
+    match config.kinematics {
+        KinematicsModel::Crx => Arc::new(CRXKinematics::from_config(config)),
+        KinematicsModel::PlanarTwoLink { l1, l2 } => {
+            Arc::new(PlanarTwoLinkKinematics::new(l1, l2, config.joint_limits))
+        }
+    }
+}
 
 /// Modified Denavit-Hartenberg (DHm) Parameters for FANUC CRX series
 ///
@@ -148,7 +284,7 @@ impl CRXKinematics {
     /// Convert Cardan angles (W, P, R) to rotation matrix
     /// Implements Equation (6) from the paper: R = Rz(R) * Ry(P) * Rx(W)
     /// FANUC uses Cardan angles: W (yaw around X), P (pitch around Y), R (roll around Z)
-    fn cardan_to_rotation_matrix(w: f64, p: f64, r: f64) -> [[f64; 3]; 3] {
+    pub(crate) fn cardan_to_rotation_matrix(w: f64, p: f64, r: f64) -> [[f64; 3]; 3] {
         let cw = w.cos();
         let sw = w.sin();
         let cp = p.cos();
@@ -438,6 +574,26 @@ impl CRXKinematics {
     /// +Y = right (when facing forward)
     /// +Z = up (vertical)
     pub fn forward_kinematics(&self, joints: &[f64; 6]) -> ([f64; 3], [f64; 3]) {
+        let t0_tool = self.tool_transform(joints);
+
+        // Extract position from T0^tool
+        let position = [t0_tool[0][3], t0_tool[1][3], t0_tool[2][3]];
+
+        // Extract rotation matrix from T0^tool
+        let r0_tool = [
+            [t0_tool[0][0], t0_tool[0][1], t0_tool[0][2]],
+            [t0_tool[1][0], t0_tool[1][1], t0_tool[1][2]],
+            [t0_tool[2][0], t0_tool[2][1], t0_tool[2][2]],
+        ];
+
+        // Convert rotation matrix to Cardan angles
+        let orientation = Self::rotation_matrix_to_cardan(&r0_tool);
+
+        (position, orientation)
+    }
+
+    /// The homogeneous transform T0^tool of the tool frame at `joints`.
+    fn tool_transform(&self, joints: &[f64; 6]) -> [[f64; 4]; 4] {
         let [j1, j2, j3, j4, j5, j6] = *joints;
 
         // Build the complete transformation chain T0^tool using DHm parameters
@@ -488,22 +644,7 @@ impl CRXKinematics {
         let t04 = Self::mat_mult(&t03, &t34);
         let t05 = Self::mat_mult(&t04, &t45);
         let t06 = Self::mat_mult(&t05, &t56);
-        let t0_tool = Self::mat_mult(&t06, &t6_tool);
-
-        // Extract position from T0^tool
-        let position = [t0_tool[0][3], t0_tool[1][3], t0_tool[2][3]];
-
-        // Extract rotation matrix from T0^tool
-        let r0_tool = [
-            [t0_tool[0][0], t0_tool[0][1], t0_tool[0][2]],
-            [t0_tool[1][0], t0_tool[1][1], t0_tool[1][2]],
-            [t0_tool[2][0], t0_tool[2][1], t0_tool[2][2]],
-        ];
-
-        // Convert rotation matrix to Cardan angles
-        let orientation = Self::rotation_matrix_to_cardan(&r0_tool);
-
-        (position, orientation)
+        Self::mat_mult(&t06, &t6_tool)
     }
 
     /// Inverse kinematics: Calculate joint angles from end effector pose
@@ -514,10 +655,16 @@ impl CRXKinematics {
     /// If no solution is found, it falls back to the simplified geometric solver
     /// which works for a wider range of poses but with lower accuracy.
     ///
+    /// With a `target_config`, only solutions on that arm configuration's branch
+    /// are considered (see [`CRXKinematics::arm_config`]); the one closest to
+    /// `current_joints` is returned, or `None` if the pose is unreachable in that
+    /// configuration.
+    ///
     /// # Arguments
     /// * `position` - Target position [x, y, z] in mm
     /// * `orientation` - Target orientation [w, p, r] in radians (Cardan angles)
     /// * `current_joints` - Current joint configuration for solution selection
+    /// * `target_config` - Required front/up/flip configuration, if any
     ///
     /// # Returns
     /// * Joint angles in radians [j1, j2, j3, j4, j5, j6], or None if unreachable
@@ -526,50 +673,38 @@ impl CRXKinematics {
         position: &[f64; 3],
         orientation: Option<&[f64; 3]>,
         current_joints: &[f64; 6],
+        target_config: Option<&ArmConfig>,
     ) -> Option<[f64; 6]> {
         // Use default orientation if not provided
         let ori = orientation.copied().unwrap_or([0.0, 0.0, 0.0]);
 
+        if let Some(config) = target_config {
+            return self.inverse_kinematics_in_config(position, &ori, current_joints, config);
+        }
+
         // Try the full geometric approach first (production-ready, sub-millimeter accuracy)
         let solutions = self.inverse_kinematics_full(position, &ori);
 
         // If full solver finds solutions, use them
         if !solutions.is_empty() {
-            // Select the solution closest to the current configuration
-            let mut best_solution = solutions[0];
-            let mut min_distance = Self::joint_distance(&solutions[0], current_joints);
-
-            for solution in &solutions[1..] {
-                let distance = Self::joint_distance(solution, current_joints);
-                if distance < min_distance {
-                    min_distance = distance;
-                    best_solution = *solution;
-                }
-            }
-
-            return Some(best_solution);
+            return Self::closest_solution(&solutions, current_joints);
         }
 
         // Fall back to simplified geometric solver for poses that don't satisfy Z4·Z5 = 0
         let solutions = self.inverse_kinematics_geometric(position, Some(&ori))?;
 
-        if solutions.is_empty() {
-            return None;
-        }
-
         // Select the solution closest to the current configuration
-        let mut best_solution = solutions[0];
-        let mut min_distance = Self::joint_distance(&solutions[0], current_joints);
-
-        for solution in &solutions[1..] {
-            let distance = Self::joint_distance(solution, current_joints);
-            if distance < min_distance {
-                min_distance = distance;
-                best_solution = *solution;
-            }
-        }
+        Self::closest_solution(&solutions, current_joints)
+    }
 
-        Some(best_solution)
+    /// The solution closest to `current_joints`, or `None` if there are none.
+    fn closest_solution(solutions: &[[f64; 6]], current_joints: &[f64; 6]) -> Option<[f64; 6]> {
+        solutions
+            .iter()
+            .min_by(|a, b| {
+                Self::joint_distance(a, current_joints).total_cmp(&Self::joint_distance(b, current_joints))
+            })
+            .copied()
     }
 
     /// Calculate the distance between two joint configurations
@@ -581,6 +716,258 @@ impl CRXKinematics {
             .sqrt()
     }
 
+    // ============================================================================
+    // Arm Configuration (Front/Back, Up/Down, Flip/NoFlip)
+    // ============================================================================
+
+    /// The arm configuration of a joint solution.
+    ///
+    /// * `front` - the wrist (O4) is ahead of the J1 axis along J1's heading
+    /// * `up` - the elbow (O3) is above the line from the shoulder to the wrist
+    /// * `flip` - J5 is positive (the wrist is flipped)
+    ///
+    /// The CRX has no J1 offset, so `left` is always `false` and never
+    /// constrains a solution. Turn numbers are left at zero.
+    pub fn arm_config(&self, joints: &[f64; 6]) -> ArmConfig {
+        let [j1, j2, j3, _, j5, _] = *joints;
+        let t04 = self.compute_transform_0_to_4(j1, j2, j3);
+        let t03 = Self::mat_mult(
+            &Self::mat_mult(
+                &Self::dh_transform(0.0, 0.0, j1, 0.0),
+                &Self::dh_transform(0.0, self.alpha2, j2 - std::f64::consts::FRAC_PI_2, 0.0),
+            ),
+            &Self::dh_transform(self.a3, self.alpha3, j2 + j3, 0.0),
+        );
+
+        // Elbow and wrist in the arm plane: distance along J1's heading and height
+        let heading = [j1.cos(), j1.sin()];
+        let in_plane = |t: &[[f64; 4]; 4]| (t[0][3] * heading[0] + t[1][3] * heading[1], t[2][3]);
+        let (elbow_r, elbow_z) = in_plane(&t03);
+        let (wrist_r, wrist_z) = in_plane(&t04);
+
+        // Sign of the elbow relative to the shoulder→wrist line, taken so that
+        // positive means above it whichever side of J1 the wrist is on
+        let side = wrist_r * elbow_z - wrist_z * elbow_r;
+        ArmConfig {
+            front: wrist_r >= 0.0,
+            up: side * wrist_r.signum() >= 0.0,
+            left: false,
+            flip: Self::normalize_angle_rad(j5) > 0.0,
+            turn4: 0,
+            turn5: 0,
+            turn6: 0,
+        }
+    }
+
+    /// Whether `joints` is on the branch `config` asks for (front, up and flip).
+    fn matches_config(&self, joints: &[f64; 6], config: &ArmConfig) -> bool {
+        let actual = self.arm_config(joints);
+        actual.front == config.front && actual.up == config.up && actual.flip == config.flip
+    }
+
+    /// [`inverse_kinematics`](Self::inverse_kinematics) restricted to one
+    /// configuration.
+    ///
+    /// Exact solutions from the full solver are used when one is on the
+    /// requested branch. Otherwise each candidate (the current joints, the
+    /// full and simplified solutions, and the wrist-flipped variant of each)
+    /// seeds a numerical refinement, and the refined solutions on the branch
+    /// compete on distance to `current_joints`.
+    fn inverse_kinematics_in_config(
+        &self,
+        position: &[f64; 3],
+        orientation: &[f64; 3],
+        current_joints: &[f64; 6],
+        config: &ArmConfig,
+    ) -> Option<[f64; 6]> {
+        let exact = self.inverse_kinematics_full(position, orientation);
+        let matching: Vec<[f64; 6]> = exact
+            .iter()
+            .filter(|solution| self.matches_config(solution, config))
+            .copied()
+            .collect();
+        if !matching.is_empty() {
+            return Self::closest_solution(&matching, current_joints);
+        }
+
+        // Staying on the current branch is the common case while interpolating
+        if let Some(solution) = self.refine_solution(current_joints, position, orientation) {
+            if self.matches_config(&solution, config) {
+                return Some(solution);
+            }
+        }
+
+        let mut seeds = vec![*current_joints];
+        seeds.extend(exact);
+        seeds.extend(self.inverse_kinematics_geometric(position, Some(orientation)).unwrap_or_default());
+        let flipped: Vec<[f64; 6]> = seeds.iter().map(Self::wrist_flipped).collect();
+        seeds.extend(flipped);
+
+        let refined: Vec<[f64; 6]> = seeds
+            .iter()
+            .filter_map(|seed| self.refine_solution(seed, position, orientation))
+            .filter(|solution| self.matches_config(solution, config))
+            .collect();
+        Self::closest_solution(&refined, current_joints)
+    }
+
+    /// The same arm with the wrist turned over: J4 + 180°, -J5, J6 + 180°.
+    /// Exact for a spherical wrist; for the CRX's offset wrist it is a seed
+    /// on the other flip branch.
+    fn wrist_flipped(joints: &[f64; 6]) -> [f64; 6] {
+        let [j1, j2, j3, j4, j5, j6] = *joints;
+        [
+            j1,
+            j2,
+            j3,
+            Self::normalize_angle_rad(j4 + std::f64::consts::PI),
+            -j5,
+            Self::normalize_angle_rad(j6 + std::f64::consts::PI),
+        ]
+    }
+
+    /// Position (mm) and orientation (rotation vector, radians) error of
+    /// `joints` against the target pose.
+    fn pose_error(&self, joints: &[f64; 6], position: &[f64; 3], target_rotation: &Matrix3<f64>) -> Vector6<f64> {
+        let (pos, ori) = self.forward_kinematics(joints);
+        let rotation = Matrix3::from(Self::cardan_to_rotation_matrix(ori[0], ori[1], ori[2])).transpose();
+        let rotation_error = Rotation3::from_matrix_unchecked(target_rotation * rotation.transpose()).scaled_axis();
+        Vector6::new(
+            position[0] - pos[0],
+            position[1] - pos[1],
+            position[2] - pos[2],
+            rotation_error[0],
+            rotation_error[1],
+            rotation_error[2],
+        )
+    }
+
+    /// Finite-difference Jacobian of the TCP velocity at `joints`: linear
+    /// velocity divided by [`JACOBIAN_LENGTH_SCALE`], then angular velocity.
+    fn scaled_jacobian(&self, joints: &[f64; 6]) -> Matrix6<f64> {
+        // Straight from the transform: Cardan angles are degenerate at P = ±90°
+        let pose = |joints: &[f64; 6]| {
+            let t = self.tool_transform(joints);
+            let rotation = Matrix3::from_fn(|i, j| t[i][j]);
+            ([t[0][3], t[1][3], t[2][3]], rotation)
+        };
+        let (position, rotation) = pose(joints);
+        let mut jacobian = Matrix6::zeros();
+        for i in 0..6 {
+            let mut stepped = *joints;
+            stepped[i] += SINGULARITY_JACOBIAN_STEP;
+            let (stepped_position, stepped_rotation) = pose(&stepped);
+            // The small rotation taking `rotation` to `stepped_rotation`
+            let delta = stepped_rotation * rotation.transpose();
+            let column = Vector6::new(
+                (stepped_position[0] - position[0]) / JACOBIAN_LENGTH_SCALE,
+                (stepped_position[1] - position[1]) / JACOBIAN_LENGTH_SCALE,
+                (stepped_position[2] - position[2]) / JACOBIAN_LENGTH_SCALE,
+                (delta[(2, 1)] - delta[(1, 2)]) / 2.0,
+                (delta[(0, 2)] - delta[(2, 0)]) / 2.0,
+                (delta[(1, 0)] - delta[(0, 1)]) / 2.0,
+            );
+            jacobian.set_column(i, &(column / SINGULARITY_JACOBIAN_STEP));
+        }
+        jacobian
+    }
+
+    /// Inverse condition number of the Jacobian at `joints`: 1 for an
+    /// isotropic pose, 0 at a singularity. Translational rows are scaled by
+    /// [`JACOBIAN_LENGTH_SCALE`] so millimetres and radians weigh alike.
+    pub fn singularity_measure(&self, joints: &[f64; 6]) -> f64 {
+        let jacobian = self.scaled_jacobian(joints);
+        if jacobian.iter().any(|v| !v.is_finite()) {
+            return 0.0;
+        }
+        let Some(svd) = jacobian.try_svd(false, false, f64::EPSILON, SVD_MAX_ITERATIONS) else {
+            return 0.0;
+        };
+        let max = svd.singular_values.max();
+        if max <= 0.0 {
+            return 0.0;
+        }
+        svd.singular_values.min() / max
+    }
+
+    /// Solve for `position` and `orientation` like
+    /// [`inverse_kinematics`](Self::inverse_kinematics), then check that the
+    /// solution really reaches the pose and how close it is to a singularity.
+    ///
+    /// The simplified fallback solver may miss the orientation, and the full
+    /// solver finds nothing at a singularity, so a missing or inexact
+    /// solution is refined numerically (seeded with itself and with
+    /// `current_joints`) before the pose is called unreachable.
+    pub fn reachability(
+        &self,
+        position: &[f64; 3],
+        orientation: &[f64; 3],
+        current_joints: &[f64; 6],
+        target_config: Option<&ArmConfig>,
+    ) -> Reachability {
+        let target_rotation =
+            Matrix3::from(Self::cardan_to_rotation_matrix(orientation[0], orientation[1], orientation[2])).transpose();
+        let reaches = |joints: &[f64; 6]| {
+            let error = self.pose_error(joints, position, &target_rotation);
+            error.fixed_rows::<3>(0).norm() <= REACH_POSITION_TOLERANCE
+                && error.fixed_rows::<3>(3).norm() <= REACH_ORIENTATION_TOLERANCE
+        };
+
+        let solution = self.inverse_kinematics(position, Some(orientation), current_joints, target_config);
+        let joints = solution.filter(|joints| reaches(joints)).or_else(|| {
+            solution
+                .iter()
+                .chain(std::iter::once(current_joints))
+                .filter_map(|seed| self.refine_solution(seed, position, orientation))
+                .find(|joints| target_config.is_none_or(|config| self.matches_config(joints, config)))
+        });
+        let Some(joints) = joints else {
+            return Reachability::Unreachable;
+        };
+        let condition = self.singularity_measure(&joints);
+        if condition < SINGULARITY_THRESHOLD {
+            Reachability::NearSingularity { joints, condition }
+        } else {
+            Reachability::Reachable { joints }
+        }
+    }
+
+    /// Damped least-squares refinement of `seed` onto the target pose.
+    ///
+    /// Returns the converged joints (normalized to [-π, π]), or `None` if the
+    /// seed does not converge within [`REFINE_MAX_ITERATIONS`].
+    fn refine_solution(&self, seed: &[f64; 6], position: &[f64; 3], orientation: &[f64; 3]) -> Option<[f64; 6]> {
+        let target_rotation =
+            Matrix3::from(Self::cardan_to_rotation_matrix(orientation[0], orientation[1], orientation[2])).transpose();
+        let mut joints = *seed;
+
+        for _ in 0..REFINE_MAX_ITERATIONS {
+            let error = self.pose_error(&joints, position, &target_rotation);
+            if error.fixed_rows::<3>(0).norm() < REFINE_POSITION_TOLERANCE
+                && error.fixed_rows::<3>(3).norm() < REFINE_ORIENTATION_TOLERANCE
+            {
+                return Some(joints.map(Self::normalize_angle_rad));
+            }
+
+            // Finite-difference Jacobian of the pose with respect to the joints
+            let mut jacobian = Matrix6::zeros();
+            for i in 0..6 {
+                let mut stepped = joints;
+                stepped[i] += REFINE_JACOBIAN_STEP;
+                let stepped_error = self.pose_error(&stepped, position, &target_rotation);
+                jacobian.set_column(i, &((error - stepped_error) / REFINE_JACOBIAN_STEP));
+            }
+
+            let damped = jacobian * jacobian.transpose() + Matrix6::identity() * REFINE_DAMPING.powi(2);
+            let step = jacobian.transpose() * damped.try_inverse()? * error;
+            for (joint, delta) in joints.iter_mut().zip(step.iter()) {
+                *joint += delta;
+            }
+        }
+
+        None
+    }
+
     /// Geometric inverse kinematics approach
     /// Returns all valid IK solutions (typically 2-4 solutions for simplified model)
     ///
@@ -1221,6 +1608,46 @@ impl CRXKinematics {
 
 }
 
+impl Kinematics for CRXKinematics {
+    fn forward_kinematics(&self, joints: &[f64; 6]) -> ([f64; 3], [f64; 3]) {
+        CRXKinematics::forward_kinematics(self, joints)
+    }
+
+    fn inverse_kinematics(
+        &self,
+        position: &[f64; 3],
+        orientation: Option<&[f64; 3]>,
+        current_joints: &[f64; 6],
+        target_config: Option<&ArmConfig>,
+    ) -> Option<[f64; 6]> {
+        CRXKinematics::inverse_kinematics(self, position, orientation, current_joints, target_config)
+    }
+
+    fn joint_limits(&self) -> &[JointLimit; 6] {
+        &self.config().joint_limits
+    }
+
+    fn arm_config(&self, joints: &[f64; 6]) -> ArmConfig {
+        CRXKinematics::arm_config(self, joints)
+    }
+
+    /// Every link and offset laid end to end: a bound, not the data sheet
+    /// reach.
+    fn reach_bounds(&self) -> ReachBounds {
+        ReachBounds { min: 0.0, max: self.a3.abs() + self.r4.abs() + self.r5.abs() + self.r6.abs() }
+    }
+
+    fn reachability(
+        &self,
+        position: &[f64; 3],
+        orientation: &[f64; 3],
+        current_joints: &[f64; 6],
+        target_config: Option<&ArmConfig>,
+    ) -> Reachability {
+        CRXKinematics::reachability(self, position, orientation, current_joints, target_config)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1329,7 +1756,7 @@ mod tests {
             pos[0], pos[1], pos[2], ori[0].to_degrees(), ori[1].to_degrees(), ori[2].to_degrees());
 
         // Compute inverse kinematics
-        let computed_joints = kin.inverse_kinematics(&pos, Some(&ori), &original_joints);
+        let computed_joints = kin.inverse_kinematics(&pos, Some(&ori), &original_joints, None);
 
         assert!(computed_joints.is_some(), "IK should find a solution");
 
@@ -1556,5 +1983,88 @@ mod tests {
 
         println!("\n✓ Both robot models work correctly with sub-millimeter accuracy!");
     }
-}
 
+    /// Pose of `joints` and the default arm configuration with `flip` set.
+    fn pose_and_config(kin: &CRXKinematics, joints: &[f64; 6], flip: bool) -> ([f64; 3], [f64; 3], ArmConfig) {
+        let (pos, ori) = kin.forward_kinematics(joints);
+        let config = ArmConfig { flip, ..ArmConfig::default() };
+        (pos, ori, config)
+    }
+
+    fn assert_reaches(kin: &CRXKinematics, joints: &[f64; 6], pos: &[f64; 3], ori: &[f64; 3]) {
+        let (pos_check, ori_check) = kin.forward_kinematics(joints);
+        for i in 0..3 {
+            assert!((pos_check[i] - pos[i]).abs() < 0.01, "position {} mismatch: {:?} vs {:?}", i, pos_check, pos);
+            let ori_error = CRXKinematics::normalize_angle_rad(ori_check[i] - ori[i]).abs();
+            assert!(ori_error < 1e-4, "orientation {} mismatch: {:?} vs {:?}", i, ori_check, ori);
+        }
+    }
+
+    #[test]
+    fn test_arm_config_of_home_pose() {
+        let kin = CRXKinematics::default();
+        let home = [0.0, 0.0, 0.0, 0.0, -90.0_f64.to_radians(), 0.0];
+        let config = kin.arm_config(&home);
+        assert!(config.front && config.up && !config.flip, "home is front/up/no-flip: {:?}", config);
+
+        // The J1 dual of the home pose reaches back over the base
+        let dual = kin.compute_dual_solution(&home).unwrap();
+        let config = kin.arm_config(&dual);
+        assert!(!config.front && config.up && !config.flip, "dual is back/up/no-flip: {:?}", config);
+    }
+
+    #[test]
+    fn test_ik_honors_flip_configuration() {
+        let kin = CRXKinematics::default();
+        let joints = [0.0, 20.0_f64.to_radians(), -10.0_f64.to_radians(), 0.0, -60.0_f64.to_radians(), 0.0];
+
+        let (pos, ori, no_flip) = pose_and_config(&kin, &joints, false);
+        let (_, _, flip) = pose_and_config(&kin, &joints, true);
+
+        let no_flip_joints = kin
+            .inverse_kinematics(&pos, Some(&ori), &joints, Some(&no_flip))
+            .expect("pose is reachable without flip");
+        let flip_joints = kin
+            .inverse_kinematics(&pos, Some(&ori), &joints, Some(&flip))
+            .expect("pose is reachable with flip");
+
+        assert!(no_flip_joints[4] < 0.0, "NoFlip solution has J5 < 0: {:?}", no_flip_joints);
+        assert!(flip_joints[4] > 0.0, "Flip solution has J5 > 0: {:?}", flip_joints);
+        assert!(CRXKinematics::joint_distance(&no_flip_joints, &flip_joints) > 1.0);
+        assert_reaches(&kin, &no_flip_joints, &pos, &ori);
+        assert_reaches(&kin, &flip_joints, &pos, &ori);
+        assert!(kin.matches_config(&no_flip_joints, &no_flip));
+        assert!(kin.matches_config(&flip_joints, &flip));
+    }
+
+    #[test]
+    fn test_ik_unreachable_configuration_returns_none() {
+        let kin = CRXKinematics::default();
+        let config = ArmConfig { flip: true, ..ArmConfig::default() };
+        let current = [0.0; 6];
+
+        // Beyond the arm's reach in every configuration
+        assert_eq!(kin.inverse_kinematics(&[3000.0, 0.0, 0.0], Some(&[0.0; 3]), &current, Some(&config)), None);
+    }
+
+    #[test]
+    fn test_wrist_singularity_is_flagged() {
+        let kin = CRXKinematics::default();
+        // J5 = 0 lines J4 and J6 up: the wrist singularity
+        let singular = [0.0, 20.0_f64.to_radians(), -10.0_f64.to_radians(), 30.0_f64.to_radians(), 0.0, 0.0];
+        let regular = [0.0, 20.0_f64.to_radians(), -10.0_f64.to_radians(), 30.0_f64.to_radians(), -60.0_f64.to_radians(), 0.0];
+
+        assert!(kin.singularity_measure(&singular) < SINGULARITY_THRESHOLD, "measure {}", kin.singularity_measure(&singular));
+        assert!(kin.singularity_measure(&regular) >= SINGULARITY_THRESHOLD, "measure {}", kin.singularity_measure(&regular));
+
+        let (pos, ori) = kin.forward_kinematics(&singular);
+        let reach = kin.reachability(&pos, &ori, &singular, None);
+        assert!(matches!(reach, Reachability::NearSingularity { .. }), "{:?}", reach);
+
+        let (pos, ori) = kin.forward_kinematics(&regular);
+        let reach = kin.reachability(&pos, &ori, &regular, None);
+        assert!(matches!(reach, Reachability::Reachable { .. }), "{:?}", reach);
+
+        assert_eq!(kin.reachability(&[3000.0, 0.0, 0.0], &[0.0; 3], &regular, None), Reachability::Unreachable);
+    }
+}
@@ -1,8 +1,13 @@
 // Library exports for the FANUC CRX simulator
 
 pub mod robot_config;
+pub mod framing;
 pub mod kinematics;
+pub mod noise;
+pub mod planar_kinematics;
+pub mod profile;
 
-pub use robot_config::{RobotConfig, RobotModel};
-pub use kinematics::CRXKinematics;
+pub use robot_config::{KinematicsModel, RobotConfig, RobotModel};
+pub use kinematics::{kinematics_for, CRXKinematics, Kinematics};
+pub use planar_kinematics::PlanarTwoLinkKinematics;
 
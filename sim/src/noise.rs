@@ -0,0 +1,71 @@
+//! Seeded measurement noise for reported robot positions.
+//!
+//! Real controllers never report exactly the same position twice. When
+//! enabled, the simulator adds zero-mean Gaussian noise to the values it
+//! *reports* (`FRC_ReadCartesianPosition`, `FRC_ReadJointAngles`) so clients
+//! can exercise their smoothing and filtering. The simulated robot state is
+//! never perturbed, so motions still converge on their targets.
+//!
+//! The generator is a SplitMix64 stream seeded from `--seed`, so a given seed
+//! always produces the same sequence of offsets.
+
+use std::f64::consts::TAU;
+
+/// Gaussian noise source for reported values.
+///
+/// A standard deviation of `0.0` disables the noise: [`ReportNoise::apply`]
+/// then returns its input unchanged and never advances the generator.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReportNoise {
+    stddev: f64,
+    state: u64,
+}
+
+impl Default for ReportNoise {
+    fn default() -> Self {
+        Self::disabled()
+    }
+}
+
+impl ReportNoise {
+    /// Noise with standard deviation `stddev` (in the reported units: mm,
+    /// degrees, or radians for joint angles) from a generator seeded with
+    /// `seed`.
+    pub fn new(stddev: f64, seed: u64) -> Self {
+        Self { stddev, state: seed }
+    }
+
+    /// No noise; reported values are exactly the simulated ones.
+    pub fn disabled() -> Self {
+        Self::new(0.0, 0)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.stddev > 0.0
+    }
+
+    /// `value` plus one Gaussian sample, or `value` itself when disabled.
+    pub fn apply(&mut self, value: f64) -> f64 {
+        if !self.is_enabled() {
+            return value;
+        }
+        value + self.stddev * self.standard_normal()
+    }
+
+    /// One sample from N(0, 1) via the Box-Muller transform.
+    fn standard_normal(&mut self) -> f64 {
+        // `u1` is in (0, 1] so the logarithm stays finite
+        let u1 = ((self.next_u64() >> 11) + 1) as f64 / (1u64 << 53) as f64;
+        let u2 = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        (-2.0 * u1.ln()).sqrt() * (TAU * u2).cos()
+    }
+
+    /// SplitMix64 step.
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
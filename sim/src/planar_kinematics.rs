@@ -0,0 +1,145 @@
+// Two-link planar arm: the smallest useful Kinematics model, for arms
+// that aren't CRX-shaped and for exercising the trait itself.
+
+use crate::kinematics::{Kinematics, ReachBounds};
+use crate::robot_config::JointLimit;
+use fanuc_rmi::ArmConfig;
+
+/// Distance (mm) off the XY plane within which a target counts as in it.
+const PLANE_TOLERANCE: f64 = 1e-3;
+
+/// Two links in the XY plane. J1 turns the first link about the base Z
+/// axis, J2 turns the second about the elbow, and the tool sits at the end
+/// of the second link facing along it: R is J1 + J2, W and P are zero.
+///
+/// J3-J6 move nothing; inverse kinematics carries them over from the
+/// current joints. The tool orientation follows from the position, so
+/// requested orientations and arm configurations are ignored.
+#[derive(Debug, Clone)]
+pub struct PlanarTwoLinkKinematics {
+    /// Shoulder to elbow, in mm
+    pub l1: f64,
+    /// Elbow to tool, in mm
+    pub l2: f64,
+    joint_limits: [JointLimit; 6],
+}
+
+impl PlanarTwoLinkKinematics {
+    pub fn new(l1: f64, l2: f64, joint_limits: [JointLimit; 6]) -> Self {
+        Self { l1, l2, joint_limits }
+    }
+}
+
+impl Kinematics for PlanarTwoLinkKinematics {
+    fn forward_kinematics(&self, joints: &[f64; 6]) -> ([f64; 3], [f64; 3]) {
+        let (j1, j2) = (joints[0], joints[1]);
+        let x = self.l1 * j1.cos() + self.l2 * (j1 + j2).cos();
+        let y = self.l1 * j1.sin() + self.l2 * (j1 + j2).sin();
+        let r = (j1 + j2).sin().atan2((j1 + j2).cos());
+        ([x, y, 0.0], [0.0, 0.0, r])
+    }
+
+    /// Of the elbow-left and elbow-right solutions, the one closest to
+    /// `current_joints` in J1/J2.
+    fn inverse_kinematics(
+        &self,
+        position: &[f64; 3],
+        _orientation: Option<&[f64; 3]>,
+        current_joints: &[f64; 6],
+        _target_config: Option<&ArmConfig>,
+    ) -> Option<[f64; 6]> {
+        let [x, y, z] = *position;
+        if z.abs() > PLANE_TOLERANCE || !self.reach_bounds().contains(position) {
+            return None;
+        }
+
+        // Law of cosines for the elbow; clamped because the bounds check
+        // lets targets a hair outside the annulus through
+        let cos_j2 = ((x * x + y * y - self.l1 * self.l1 - self.l2 * self.l2) / (2.0 * self.l1 * self.l2)).clamp(-1.0, 1.0);
+        let elbow = cos_j2.acos();
+
+        [elbow, -elbow]
+            .into_iter()
+            .map(|j2| {
+                let j1 = y.atan2(x) - (self.l2 * j2.sin()).atan2(self.l1 + self.l2 * j2.cos());
+                let j1 = j1.sin().atan2(j1.cos());
+                let mut joints = *current_joints;
+                joints[0] = j1;
+                joints[1] = j2;
+                joints
+            })
+            .min_by(|a, b| {
+                let distance = |j: &[f64; 6]| (j[0] - current_joints[0]).hypot(j[1] - current_joints[1]);
+                distance(a).total_cmp(&distance(b))
+            })
+    }
+
+    fn joint_limits(&self) -> &[JointLimit; 6] {
+        &self.joint_limits
+    }
+
+    /// The annulus between the links folded and stretched out.
+    fn reach_bounds(&self) -> ReachBounds {
+        ReachBounds { min: (self.l1 - self.l2).abs(), max: self.l1 + self.l2 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kinematics::{kinematics_for, Reachability};
+    use crate::robot_config::{KinematicsModel, RobotConfig};
+
+    fn planar() -> PlanarTwoLinkKinematics {
+        PlanarTwoLinkKinematics::new(400.0, 300.0, RobotConfig::default().joint_limits)
+    }
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-6, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn stretched_out_along_x_at_zero() {
+        let (pos, ori) = planar().forward_kinematics(&[0.0; 6]);
+        assert_close(&pos, &[700.0, 0.0, 0.0]);
+        assert_close(&ori, &[0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn inverse_kinematics_round_trips_both_elbows() {
+        let kin = planar();
+        for joints in [[0.3, 0.8, 0.1, 0.2, 0.3, 0.4], [-1.2, -0.5, 0.0, 0.0, 0.0, 0.0]] {
+            let (pos, _) = kin.forward_kinematics(&joints);
+            let solution = kin.inverse_kinematics(&pos, None, &joints, None).expect("reachable");
+            assert_close(&solution, &joints);
+        }
+    }
+
+    #[test]
+    fn targets_off_the_plane_or_out_of_reach_have_no_solution() {
+        let kin = planar();
+        assert_eq!(kin.inverse_kinematics(&[500.0, 0.0, 10.0], None, &[0.0; 6], None), None);
+        assert_eq!(kin.inverse_kinematics(&[800.0, 0.0, 0.0], None, &[0.0; 6], None), None);
+        assert_eq!(kin.inverse_kinematics(&[50.0, 0.0, 0.0], None, &[0.0; 6], None), None);
+    }
+
+    /// Selected through the config and used only through the trait.
+    #[test]
+    fn config_selects_planar_model_behind_the_trait() {
+        let config = RobotConfig {
+            kinematics: KinematicsModel::PlanarTwoLink { l1: 400.0, l2: 300.0 },
+            ..RobotConfig::default()
+        };
+        let kin = kinematics_for(config);
+
+        assert_eq!(kin.reach_bounds(), ReachBounds { min: 100.0, max: 700.0 });
+        assert!(matches!(
+            kin.reachability(&[0.0, 500.0, 0.0], &[0.0; 3], &[0.0; 6], None),
+            Reachability::Reachable { .. }
+        ));
+        assert_eq!(kin.reachability(&[0.0, 750.0, 0.0], &[0.0; 3], &[0.0; 6], None), Reachability::Unreachable);
+        assert!(!kin.within_joint_limits(&[200.0_f64.to_radians(), 0.0, 0.0, 0.0, 0.0, 0.0]));
+    }
+}
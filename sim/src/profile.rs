@@ -0,0 +1,309 @@
+//! Velocity profiles for realtime motion.
+//!
+//! The motion executor advances each move in fixed time steps. With the
+//! [`VelocityProfile::Linear`] profile every step covers the same distance,
+//! so the TCP jumps straight to the commanded speed and stops dead at the
+//! end. [`VelocityProfile::Trapezoidal`] ramps up at a constant acceleration,
+//! cruises, and ramps down again like a real controller, so the steps grow
+//! and shrink and reported TCP speed follows a realistic curve.
+//!
+//! A move is planned at the speed override in effect when it starts. A
+//! [`MotionClock`] steps it and eases later override changes in through an
+//! [`OverrideRamp`], so the speed never jumps mid-move.
+//!
+//! Distances and speeds are in the move's own units: mm and mm/s for
+//! Cartesian moves, degrees and deg/s for joint moves. Accelerations use the
+//! same units per second squared.
+
+/// Smallest speed override a move runs at, as a fraction; a 0% override
+/// would never finish.
+pub const MIN_OVERRIDE: f64 = 0.01;
+
+/// Default time, in seconds, an override change takes to ramp in.
+pub const DEFAULT_OVERRIDE_RAMP: f64 = 0.25;
+
+/// How speed evolves over the course of one move.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum VelocityProfile {
+    /// Constant speed from start to end.
+    #[default]
+    Linear,
+    /// Constant acceleration up to the commanded speed, then constant
+    /// deceleration to a stop. Moves too short to reach the commanded speed
+    /// follow a triangular profile.
+    Trapezoidal {
+        /// Acceleration and deceleration, in units/s².
+        accel: f64,
+    },
+}
+
+impl VelocityProfile {
+    /// Duration in seconds of a move over `distance` at `speed`.
+    ///
+    /// Never below 10 ms; a non-positive speed gives 100 ms.
+    pub fn duration(&self, distance: f64, speed: f64) -> f64 {
+        if speed <= 0.0 {
+            return 0.1;
+        }
+        let duration = match self.ramp(distance, speed) {
+            None => distance / speed,
+            Some(ramp) => 2.0 * ramp.accel_time + ramp.cruise_time,
+        };
+        duration.max(0.01)
+    }
+
+    /// Fraction of `distance` covered after the fraction `time_fraction` of
+    /// the move's [`duration`](Self::duration) has elapsed. Both are in
+    /// `[0, 1]`.
+    pub fn progress(&self, distance: f64, speed: f64, time_fraction: f64) -> f64 {
+        let time_fraction = time_fraction.clamp(0.0, 1.0);
+        let Some(ramp) = self.ramp(distance, speed) else {
+            return time_fraction;
+        };
+
+        let total = 2.0 * ramp.accel_time + ramp.cruise_time;
+        let elapsed = time_fraction * total;
+        let accel_distance = 0.5 * ramp.accel * ramp.accel_time.powi(2);
+        let covered = if elapsed < ramp.accel_time {
+            0.5 * ramp.accel * elapsed.powi(2)
+        } else if elapsed < ramp.accel_time + ramp.cruise_time {
+            accel_distance + ramp.peak_speed * (elapsed - ramp.accel_time)
+        } else {
+            distance - 0.5 * ramp.accel * (total - elapsed).powi(2)
+        };
+        (covered / distance).clamp(0.0, 1.0)
+    }
+
+    /// Ramp timings of a trapezoidal move, or `None` when the move is
+    /// linear (including degenerate moves with no distance, speed, or
+    /// acceleration).
+    fn ramp(&self, distance: f64, speed: f64) -> Option<Ramp> {
+        let Self::Trapezoidal { accel } = *self else {
+            return None;
+        };
+        if distance <= 0.0 || speed <= 0.0 || accel <= 0.0 {
+            return None;
+        }
+
+        // Distance spent reaching `speed` and stopping again
+        let ramp_distance = speed * speed / accel;
+        Some(if ramp_distance <= distance {
+            Ramp {
+                accel,
+                peak_speed: speed,
+                accel_time: speed / accel,
+                cruise_time: (distance - ramp_distance) / speed,
+            }
+        } else {
+            let accel_time = (distance / accel).sqrt();
+            Ramp {
+                accel,
+                peak_speed: accel * accel_time,
+                accel_time,
+                cruise_time: 0.0,
+            }
+        })
+    }
+}
+
+/// Eases the speed override toward a new value instead of jumping to it.
+///
+/// Each change ramps linearly from the override in effect to the new one
+/// over `time_constant` seconds, however large the change. A change made
+/// mid-ramp starts a new ramp from wherever the old one had got to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OverrideRamp {
+    time_constant: f64,
+    from: f64,
+    target: f64,
+    current: f64,
+}
+
+impl OverrideRamp {
+    /// A ramp resting at `speed_override`. A `time_constant` of zero applies
+    /// changes at once.
+    pub fn new(speed_override: f64, time_constant: f64) -> Self {
+        Self { time_constant, from: speed_override, target: speed_override, current: speed_override }
+    }
+
+    /// Advance `dt` seconds toward `target` and return the override reached.
+    pub fn step(&mut self, target: f64, dt: f64) -> f64 {
+        if target != self.target {
+            self.from = self.current;
+            self.target = target;
+        }
+        if self.time_constant <= 0.0 {
+            self.current = target;
+        } else {
+            let max_change = (self.target - self.from).abs() / self.time_constant * dt;
+            self.current += (target - self.current).clamp(-max_change, max_change);
+        }
+        self.current
+    }
+}
+
+/// Fixed time steps through one realtime move.
+///
+/// The move is planned with its profile at the override in effect when it
+/// starts. Each step then advances the profile's clock in proportion to the
+/// ramped override, so a lower override stretches what is left of the move
+/// without a jump in speed.
+#[derive(Clone, Copy, Debug)]
+pub struct MotionClock {
+    profile: VelocityProfile,
+    distance: f64,
+    planned_speed: f64,
+    planned_override: f64,
+    step_seconds: f64,
+    total_steps: f64,
+    elapsed_steps: f64,
+    ramp: OverrideRamp,
+}
+
+impl MotionClock {
+    /// Plan a move over `distance` at `speed` under `speed_override` (a
+    /// fraction, raised to [`MIN_OVERRIDE`]), stepped every `step_seconds`
+    /// with override changes ramped in over `ramp_seconds`.
+    pub fn new(
+        profile: VelocityProfile,
+        distance: f64,
+        speed: f64,
+        speed_override: f64,
+        step_seconds: f64,
+        ramp_seconds: f64,
+    ) -> Self {
+        let planned_override = speed_override.max(MIN_OVERRIDE);
+        let planned_speed = speed * planned_override;
+        let total_steps = (profile.duration(distance, planned_speed) / step_seconds).floor().max(1.0);
+        Self {
+            profile,
+            distance,
+            planned_speed,
+            planned_override,
+            step_seconds,
+            total_steps,
+            elapsed_steps: 0.0,
+            ramp: OverrideRamp::new(planned_override, ramp_seconds),
+        }
+    }
+
+    /// The speed the move was planned at, with the starting override applied.
+    pub fn planned_speed(&self) -> f64 {
+        self.planned_speed
+    }
+
+    /// Steps the move takes if the override never changes.
+    pub fn planned_steps(&self) -> u64 {
+        self.total_steps as u64
+    }
+
+    /// Take one step under `speed_override` and return the fraction of the
+    /// distance covered so far.
+    pub fn advance(&mut self, speed_override: f64) -> f64 {
+        let speed_override = self.ramp.step(speed_override.max(MIN_OVERRIDE), self.step_seconds);
+        self.elapsed_steps = (self.elapsed_steps + speed_override / self.planned_override).min(self.total_steps);
+        self.profile.progress(self.distance, self.planned_speed, self.elapsed_steps / self.total_steps)
+    }
+
+    /// Whether the last step reached the end of the move.
+    pub fn is_finished(&self) -> bool {
+        self.elapsed_steps >= self.total_steps
+    }
+}
+
+/// Timings of a trapezoidal move.
+struct Ramp {
+    accel: f64,
+    peak_speed: f64,
+    accel_time: f64,
+    cruise_time: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Average speed over each of `steps` equal time steps of a move.
+    fn step_speeds(profile: VelocityProfile, distance: f64, speed: f64, steps: usize) -> Vec<f64> {
+        let duration = profile.duration(distance, speed);
+        let dt = duration / steps as f64;
+        (1..=steps)
+            .map(|step| {
+                let before = profile.progress(distance, speed, (step - 1) as f64 / steps as f64);
+                let after = profile.progress(distance, speed, step as f64 / steps as f64);
+                (after - before) * distance / dt
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_trapezoidal_mid_motion_speed_exceeds_start_and_end() {
+        let profile = VelocityProfile::Trapezoidal { accel: 1000.0 };
+        let speeds = step_speeds(profile, 500.0, 200.0, 50);
+        let (start, mid, end) = (speeds[0], speeds[speeds.len() / 2], speeds[speeds.len() - 1]);
+
+        assert!(mid > start, "mid {} should exceed start {}", mid, start);
+        assert!(mid > end, "mid {} should exceed end {}", mid, end);
+        assert!((mid - 200.0).abs() < 1e-6, "cruises at the commanded speed, got {}", mid);
+        assert!((profile.progress(500.0, 200.0, 1.0) - 1.0).abs() < 1e-12);
+        // 0.2 s up, 2.3 s cruising, 0.2 s down
+        assert!((profile.duration(500.0, 200.0) - 2.7).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_short_trapezoidal_move_is_triangular() {
+        let profile = VelocityProfile::Trapezoidal { accel: 1000.0 };
+        // Reaching 200 mm/s needs 40 mm; a 10 mm move peaks at 100 mm/s
+        let speeds = step_speeds(profile, 10.0, 200.0, 100);
+        let peak = speeds.iter().cloned().fold(0.0, f64::max);
+        assert!(peak < 100.0 + 1e-6 && peak > 90.0, "peak speed {}", peak);
+        assert!((profile.duration(10.0, 200.0) - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_override_drop_mid_move_ramps_speed() {
+        let (step_seconds, ramp_seconds, distance) = (0.05, 0.5, 1000.0);
+        let mut clock = MotionClock::new(VelocityProfile::Linear, distance, 200.0, 1.0, step_seconds, ramp_seconds);
+
+        let mut speeds = Vec::new();
+        let mut covered = 0.0;
+        while !clock.is_finished() {
+            // 100% for the first half second, then 20%
+            let speed_override = if speeds.len() < 10 { 1.0 } else { 0.2 };
+            let t = clock.advance(speed_override);
+            speeds.push((t - covered) * distance / step_seconds);
+            covered = t;
+        }
+
+        // 200 mm/s down to 40 mm/s over half a second
+        let max_change = (200.0 - 40.0) / ramp_seconds * step_seconds;
+        // The last step stops short where the move ends
+        let ramped = &speeds[..speeds.len() - 1];
+        for pair in ramped.windows(2) {
+            assert!((pair[1] - pair[0]).abs() <= max_change + 1e-9, "{:?}", speeds);
+        }
+        assert!((ramped[0] - 200.0).abs() < 1e-9);
+        assert!((ramped[ramped.len() - 1] - 40.0).abs() < 1e-9);
+        assert_eq!(covered, 1.0);
+        // Slower than the planned 5 s once the override drops
+        assert!(speeds.len() as f64 * step_seconds > 5.0 + 1.0);
+    }
+
+    #[test]
+    fn test_override_ramp_of_zero_applies_at_once() {
+        let mut ramp = OverrideRamp::new(1.0, 0.0);
+        assert_eq!(ramp.step(0.2, 0.05), 0.2);
+
+        let mut ramp = OverrideRamp::new(1.0, 0.5);
+        assert!((ramp.step(0.2, 0.05) - 0.92).abs() < 1e-12);
+        // Changing course mid-ramp ramps from where it got to
+        assert!((ramp.step(1.0, 0.05) - 0.928).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_linear_profile_is_constant_speed() {
+        let speeds = step_speeds(VelocityProfile::Linear, 500.0, 200.0, 10);
+        assert!(speeds.iter().all(|speed| (speed - 200.0).abs() < 1e-9), "{:?}", speeds);
+        assert_eq!(VelocityProfile::Linear.duration(500.0, 200.0), 2.5);
+    }
+}
@@ -5,7 +5,9 @@
 /// research paper "Geometric Approach for Inverse Kinematics of the FANUC CRX
 /// Collaborative Robot" by Manel Abbes and Gérard Poisson (Robotics 2024, 13, 91).
 
+use fanuc_rmi::JointAngles;
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 // Re-export RobotModel from web_common for convenience
 pub use web_common::RobotModel;
@@ -39,6 +41,100 @@ pub struct RobotConfig {
     pub alpha4: f64,  // α3 = -90°
     pub alpha5: f64,  // α4 = +90°
     pub alpha6: f64,  // α5 = -90°
+
+    /// Safe pose the robot starts in and returns to, in degrees
+    #[serde(default = "default_home")]
+    pub home: JointAngles,
+
+    /// Travel range of J1-J6, in degrees
+    #[serde(default = "default_joint_limits")]
+    pub joint_limits: [JointLimit; 6],
+
+    /// Kinematic model the simulator solves for this robot
+    #[serde(default)]
+    pub kinematics: KinematicsModel,
+
+    /// Number of user frames, UFrame 1 to UFrame n (UFrame 0 is the world
+    /// frame)
+    #[serde(default = "default_uframe_count")]
+    pub uframe_count: u8,
+
+    /// Number of user tools, UTool 1 to UTool n
+    #[serde(default = "default_utool_count")]
+    pub utool_count: u8,
+}
+
+/// Kinematic model behind a [`RobotConfig`]; see
+/// [`kinematics_for`](crate::kinematics::kinematics_for).
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum KinematicsModel {
+    /// CRX six-axis arm built from the config's DHm parameters.
+    #[default]
+    Crx,
+    /// Two links in the XY plane driven by J1 and J2, lengths in mm.
+    PlanarTwoLink { l1: f64, l2: f64 },
+}
+
+/// CRX link lengths and offsets in mm (see [`RobotConfig`]), replacing
+/// the model's in a config file.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DhParameters {
+    pub a3: f64,
+    pub r4: f64,
+    pub r5: f64,
+    pub r6: f64,
+}
+
+/// Travel range of one joint, in degrees.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct JointLimit {
+    pub min: f64,
+    pub max: f64,
+}
+
+/// Robot config file passed to the simulator with `--robot-config`.
+///
+/// The DHm parameters come from `model`; `dh`, `home`, `joint_limits`,
+/// `kinematics`, `uframe_count` and `utool_count` override the model
+/// defaults when present.
+#[derive(Debug, Deserialize)]
+struct RobotConfigFile {
+    model: RobotModel,
+    #[serde(default)]
+    dh: Option<DhParameters>,
+    #[serde(default)]
+    home: Option<JointAngles>,
+    #[serde(default)]
+    joint_limits: Option<[JointLimit; 6]>,
+    #[serde(default)]
+    kinematics: Option<KinematicsModel>,
+    #[serde(default)]
+    uframe_count: Option<u8>,
+    #[serde(default)]
+    utool_count: Option<u8>,
+}
+
+/// J2 = 45° (shoulder up), J3 = -90° (elbow bent): a comfortable
+/// mid-workspace pose.
+fn default_home() -> JointAngles {
+    JointAngles { j2: 45.0, j3: -90.0, ..JointAngles::default() }
+}
+
+/// The controller's 9 user frames.
+fn default_uframe_count() -> u8 {
+    9
+}
+
+/// The controller's 10 user tools.
+fn default_utool_count() -> u8 {
+    10
+}
+
+/// CRX joint ranges from the data sheet: J1/J2 ±180°, J3 ±270°,
+/// J4 ±190°, J5 ±180°, J6 ±225°.
+fn default_joint_limits() -> [JointLimit; 6] {
+    [180.0, 180.0, 270.0, 190.0, 180.0, 225.0].map(|range| JointLimit { min: -range, max: range })
 }
 
 impl RobotConfig {
@@ -66,6 +162,11 @@ impl RobotConfig {
             alpha4: -90.0_f64.to_radians(),
             alpha5: 90.0_f64.to_radians(),
             alpha6: -90.0_f64.to_radians(),
+            home: default_home(),
+            joint_limits: default_joint_limits(),
+            kinematics: KinematicsModel::Crx,
+            uframe_count: default_uframe_count(),
+            utool_count: default_utool_count(),
         }
     }
 
@@ -95,6 +196,11 @@ impl RobotConfig {
             alpha4: -90.0_f64.to_radians(),
             alpha5: 90.0_f64.to_radians(),
             alpha6: -90.0_f64.to_radians(),
+            home: default_home(),
+            joint_limits: default_joint_limits(),
+            kinematics: KinematicsModel::Crx,
+            uframe_count: default_uframe_count(),
+            utool_count: default_utool_count(),
         }
     }
 
@@ -105,6 +211,99 @@ impl RobotConfig {
             RobotModel::CRX30iA => Self::crx_30ia(),
         }
     }
+
+    /// Load a configuration from a JSON [`RobotConfigFile`], e.g.
+    ///
+    /// ```json
+    /// {
+    ///   "model": "CRX30iA",
+    ///   "home": { "J1": 0, "J2": 30, "J3": -60, "J4": 0, "J5": -30, "J6": 0 },
+    ///   "joint_limits": [
+    ///     { "min": -170, "max": 170 }, { "min": -180, "max": 180 },
+    ///     { "min": -270, "max": 270 }, { "min": -190, "max": 190 },
+    ///     { "min": -180, "max": 180 }, { "min": -225, "max": 225 }
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Another arm with the CRX's structure is described by its link
+    /// lengths, e.g. `"dh": { "a3": 600, "r4": -620, "r5": 150, "r6": -160 }`,
+    /// and a different structure by its kinematic model, e.g.
+    /// `"kinematics": { "type": "planar_two_link", "l1": 400, "l2": 300 }`.
+    /// A controller with other frame and tool tables sets
+    /// `"uframe_count"` and `"utool_count"` (1-127).
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        let file: RobotConfigFile = serde_json::from_str(&text)
+            .map_err(|e| format!("invalid robot config {}: {}", path.display(), e))?;
+
+        let mut config = Self::from_model(file.model);
+        if let Some(dh) = file.dh {
+            config.set_dh_parameters(dh);
+        }
+        if let Some(kinematics) = file.kinematics {
+            config.kinematics = kinematics;
+        }
+        if matches!(config.kinematics, KinematicsModel::PlanarTwoLink { l1, l2 } if l1 <= 0.0 || l2 <= 0.0) {
+            return Err("planar link lengths must be positive".to_string());
+        }
+        if let Some(joint_limits) = file.joint_limits {
+            config.joint_limits = joint_limits;
+        }
+        if let Some(home) = file.home {
+            config.home = home;
+        }
+        for (name, count, value) in [
+            ("uframe_count", file.uframe_count, &mut config.uframe_count),
+            ("utool_count", file.utool_count, &mut config.utool_count),
+        ] {
+            match count {
+                Some(count) if !(1..=i8::MAX as u8).contains(&count) => {
+                    return Err(format!("{} must be between 1 and {}", name, i8::MAX));
+                }
+                Some(count) => *value = count,
+                None => {}
+            }
+        }
+        if let Some(i) = config.joint_limits.iter().position(|limit| limit.min > limit.max) {
+            return Err(format!("J{} limit has min above max", i + 1));
+        }
+        if !config.within_joint_limits(&config.home_radians()) {
+            return Err("home position is outside the joint limits".to_string());
+        }
+        Ok(config)
+    }
+
+    /// Replace the CRX link lengths and offsets.
+    pub fn set_dh_parameters(&mut self, dh: DhParameters) {
+        self.a3 = dh.a3;
+        self.r4 = dh.r4;
+        self.r5 = dh.r5;
+        self.r6 = dh.r6;
+    }
+
+    /// [`home`](Self::home) as J1-J6 in radians.
+    pub fn home_radians(&self) -> [f64; 6] {
+        let h = &self.home;
+        [h.j1, h.j2, h.j3, h.j4, h.j5, h.j6].map(|deg| (deg as f64).to_radians())
+    }
+
+    /// Whether every joint of `joints_rad` lies within [`joint_limits`](Self::joint_limits).
+    pub fn within_joint_limits(&self, joints_rad: &[f64; 6]) -> bool {
+        within_limits(&self.joint_limits, joints_rad)
+    }
+}
+
+/// Whether every joint of `joints_rad` lies within its limit in `limits`.
+pub fn within_limits(limits: &[JointLimit; 6], joints_rad: &[f64; 6]) -> bool {
+    // Allow for the f32 round trip of reported joint angles
+    const SLACK_DEG: f64 = 1e-3;
+    joints_rad.iter().zip(limits).all(|(joint, limit)| {
+        let deg = joint.to_degrees();
+        deg >= limit.min - SLACK_DEG && deg <= limit.max + SLACK_DEG
+    })
 }
 
 impl Default for RobotConfig {
@@ -0,0 +1,82 @@
+//! The `example` binary's flow — connect, initialize, move, abort,
+//! disconnect — run against the `sim` binary on free local ports.
+
+use fanuc_rmi::commands::FrcReadCartesianPosition;
+use fanuc_rmi::drivers::{FanucDriver, FanucDriverConfig};
+use fanuc_rmi::packets::CommandResponse;
+use fanuc_rmi::{Configuration, Position, TermType};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::process::{Child, Command};
+
+/// A local port nothing is listening on.
+async fn free_port() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    listener.local_addr().unwrap().port()
+}
+
+/// Start the simulator binary and wait until it accepts connections on the
+/// returned port. The process is killed when the child is dropped.
+async fn spawn_sim() -> (Child, u16) {
+    let port = free_port().await;
+    let secondary_port_base = free_port().await;
+    let child = Command::new(env!("CARGO_BIN_EXE_sim"))
+        .args(["--addr", &format!("127.0.0.1:{}", port)])
+        .args(["--secondary-port-base", &secondary_port_base.to_string()])
+        .args(["--io-sidecar-port", "0", "--quiet"])
+        .kill_on_drop(true)
+        .spawn()
+        .expect("start sim");
+
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while TcpStream::connect(("127.0.0.1", port)).await.is_err() {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("sim listening");
+    (child, port)
+}
+
+async fn read_position(driver: &FanucDriver) -> Position {
+    match driver.command(FrcReadCartesianPosition::new(None)).await {
+        Ok(CommandResponse::FrcReadCartesianPosition(resp)) => {
+            assert_eq!(resp.error_id, 0);
+            resp.pos
+        }
+        other => panic!("expected FRC_ReadCartesianPosition response, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn example_flow_runs_against_sim() {
+    let (_sim, port) = spawn_sim().await;
+    let config = FanucDriverConfig {
+        addr: "127.0.0.1".to_string(),
+        port: port as u32,
+        max_messages: 30,
+        ..Default::default()
+    };
+    let driver = FanucDriver::connect(config).await.expect("connect to sim");
+
+    let initialized = driver.initialize().await.expect("initialize");
+    assert_eq!(initialized.error_id, 0);
+
+    let start = read_position(&driver).await;
+    let delta = Position { x: 10.0, ..Default::default() };
+    let configuration = Configuration { left: 0, ..Default::default() };
+    let motion = driver.move_relative(delta, configuration, 100.0, TermType::FINE).expect("queue move");
+    let sequence_id = tokio::time::timeout(Duration::from_secs(5), motion)
+        .await
+        .expect("move completes")
+        .expect("move succeeds");
+    assert_eq!(sequence_id, 1);
+    let arrived = read_position(&driver).await;
+    assert!((arrived.x - (start.x + 10.0)).abs() < 0.01, "moved from {} to {}", start.x, arrived.x);
+
+    let aborted = driver.abort().await.expect("abort");
+    assert_eq!(aborted.error_id, 0);
+
+    let disconnected = driver.disconnect().await.expect("disconnect");
+    assert_eq!(disconnected.error_id, 0);
+}
@@ -60,6 +60,11 @@ pub fn CommandComposerModal() -> impl IntoView {
     let current_joints = ws.joint_angles;
     let active_config = ws.active_configuration;
 
+    // X/Y/Z are entered in the active connection's unit and sent in mm
+    let unit = ws.get_active_connection()
+        .map(|c| c.coordinate_unit)
+        .unwrap_or_default();
+
     // Position inputs (Cartesian: X,Y,Z,W,P,R or Joint: J1-J6)
     let (x, set_x) = signal(0.0f64);
     let (y, set_y) = signal(0.0f64);
@@ -115,9 +120,9 @@ pub fn CommandComposerModal() -> impl IntoView {
             // This ensures we only set the initial values, not continuously override user input
             if itype.is_cartesian() {
                 if let Some((px, py, pz)) = current_pos.get_untracked() {
-                    set_x.set(px);
-                    set_y.set(py);
-                    set_z.set(pz);
+                    set_x.set(unit.from_mm(px));
+                    set_y.set(unit.from_mm(py));
+                    set_z.set(unit.from_mm(pz));
                 }
                 if let Some((pw, pp, pr)) = current_orient.get_untracked() {
                     set_w.set(pw);
@@ -156,6 +161,7 @@ pub fn CommandComposerModal() -> impl IntoView {
     let apply_command = move || {
         // Add to recent commands
         let new_id = js_sys::Date::now() as usize;
+        let to_mm = |v: f64| if instr_type.get_untracked().is_cartesian() { unit.to_mm(v) } else { v };
         let cmd = RecentCommand {
             id: new_id,
             name: format!("{} ({:.1}, {:.1}, {:.1})",
@@ -166,9 +172,9 @@ pub fn CommandComposerModal() -> impl IntoView {
             ),
             command_type: instr_type.get_untracked().code().to_string(),
             description: format!("{} {}", speed.get_untracked(), term_type.get_untracked()),
-            x: x.get_untracked(),
-            y: y.get_untracked(),
-            z: z.get_untracked(),
+            x: to_mm(x.get_untracked()),
+            y: to_mm(y.get_untracked()),
+            z: to_mm(z.get_untracked()),
             w: w.get_untracked(),
             p: p.get_untracked(),
             r: r.get_untracked(),
@@ -241,9 +247,9 @@ pub fn CommandComposerModal() -> impl IntoView {
                             // Cartesian position (X,Y,Z,W,P,R)
                             view! {
                                 <div class="grid grid-cols-6 gap-1">
-                                    <NumberInput label="X" value=x set_value=set_x unit="mm"/>
-                                    <NumberInput label="Y" value=y set_value=set_y unit="mm"/>
-                                    <NumberInput label="Z" value=z set_value=set_z unit="mm"/>
+                                    <NumberInput label="X" value=x set_value=set_x unit=unit.suffix()/>
+                                    <NumberInput label="Y" value=y set_value=set_y unit=unit.suffix()/>
+                                    <NumberInput label="Z" value=z set_value=set_z unit=unit.suffix()/>
                                     <NumberInput label="W" value=w set_value=set_w unit="°"/>
                                     <NumberInput label="P" value=p set_value=set_p unit="°"/>
                                     <NumberInput label="R" value=r set_value=set_r unit="°"/>
@@ -247,6 +247,7 @@ fn ProgramTable(
     lines: RwSignal<Vec<ProgramLine>>,
     executing: RwSignal<i32>,
 ) -> impl IntoView {
+    let ws = use_context::<WebSocketManager>().expect("WebSocketManager context");
     view! {
         <div class="flex-1 overflow-y-auto">
             <Show
@@ -278,15 +279,16 @@ fn ProgramTable(
                             children=move |line| {
                                 let line_num = line.line_number;
                                 let term = line.term_type.clone();
+                                let (x, y, z) = (line.x, line.y, line.z);
                                 view! {
                                     <tr class=move || format!(
                                         "border-b border-[#ffffff05] {}",
                                         if executing.get() == line_num as i32 { "bg-[#00d9ff20] text-[#00d9ff]" } else { "text-[#cccccc]" }
                                     )>
                                         <td class="px-1.5 py-0.5 text-[#555555] font-mono">{line_num}</td>
-                                        <td class="px-1.5 py-0.5 text-right font-mono tabular-nums">{format!("{:.2}", line.x)}</td>
-                                        <td class="px-1.5 py-0.5 text-right font-mono tabular-nums">{format!("{:.2}", line.y)}</td>
-                                        <td class="px-1.5 py-0.5 text-right font-mono tabular-nums">{format!("{:.2}", line.z)}</td>
+                                        <td class="px-1.5 py-0.5 text-right font-mono tabular-nums">{move || format!("{:.2}", ws.length_unit().from_mm(x))}</td>
+                                        <td class="px-1.5 py-0.5 text-right font-mono tabular-nums">{move || format!("{:.2}", ws.length_unit().from_mm(y))}</td>
+                                        <td class="px-1.5 py-0.5 text-right font-mono tabular-nums">{move || format!("{:.2}", ws.length_unit().from_mm(z))}</td>
                                         <td class="px-1.5 py-0.5 text-right font-mono tabular-nums text-[#888888]">{format!("{:.1}", line.w)}</td>
                                         <td class="px-1.5 py-0.5 text-right font-mono tabular-nums text-[#888888]">{format!("{:.1}", line.p)}</td>
                                         <td class="px-1.5 py-0.5 text-right font-mono tabular-nums text-[#888888]">{format!("{:.1}", line.r)}</td>
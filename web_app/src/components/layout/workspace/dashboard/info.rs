@@ -106,6 +106,7 @@ pub fn InfoTab() -> impl IntoView {
 fn ActiveConfigurationPanel() -> impl IntoView {
     let ws = use_context::<WebSocketManager>().expect("WebSocketManager context");
     let active_config = ws.active_configuration;
+    let configuration_diff = ws.configuration_diff;
     let robot_configs = ws.robot_configurations;
     let robot_connected = ws.robot_connected;
     let program_running = ws.program_running;
@@ -185,6 +186,7 @@ fn ActiveConfigurationPanel() -> impl IntoView {
                                 <button
                                     class="px-2 py-1 text-[9px] bg-[#22c55e20] text-[#22c55e] border border-[#22c55e] rounded hover:bg-[#22c55e30] disabled:opacity-50 disabled:cursor-not-allowed"
                                     on:click=move |_| {
+                                        ws.get_configuration_diff();
                                         set_show_save_modal.set(true);
                                     }
                                     title="Save current configuration to database"
@@ -319,6 +321,25 @@ fn ActiveConfigurationPanel() -> impl IntoView {
                                     }
                                 }
                             }}
+                            // Net difference from the saved configuration
+                            <Show when=move || !configuration_diff.get().is_empty()>
+                                <div class="bg-[#111111] rounded p-3 mb-4 border border-[#ffffff08] max-h-64 overflow-y-auto">
+                                    <div class="text-[9px] text-[#666666] mb-2">"Differs from the saved configuration:"</div>
+                                    <div class="space-y-1">
+                                        {move || configuration_diff.get().into_iter().map(|entry| {
+                                            view! {
+                                                <div class="text-[9px] text-[#aaaaaa]">
+                                                    <span class="text-[#22c55e]">{entry.field_name}</span>
+                                                    ": "
+                                                    <span class="text-[#ff6b6b]">{entry.old_value}</span>
+                                                    " → "
+                                                    <span class="text-[#22c55e]">{entry.new_value}</span>
+                                                </div>
+                                            }
+                                        }).collect_view()}
+                                    </div>
+                                </div>
+                            </Show>
                             <div class="flex justify-end gap-2">
                                 <button
                                     class="px-3 py-1.5 text-[10px] bg-[#1a1a1a] border border-[#ffffff08] text-[#888888] rounded hover:text-white"
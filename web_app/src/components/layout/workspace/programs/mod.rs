@@ -515,9 +515,15 @@ fn ProgramDetails(
                     } else {
                         prog.updated_at.chars().take(10).collect()
                     };
+                    let run_count = prog.run_count;
+                    let last_run_str: String = match &prog.last_run_at {
+                        Some(last_run_at) => last_run_at.chars().take(10).collect(),
+                        None => "Never".to_string(),
+                    };
 
                     // Clone instructions for the table display
                     let instructions_for_table = prog.instructions.clone();
+                    let unit = ws.length_unit();
 
                     Either::Left(view! {
                         <div class="h-full flex flex-col">
@@ -566,6 +572,18 @@ fn ProgramDetails(
                                 </div>
                             </div>
 
+                            // Metadata - Row 2: Runs, Last Run
+                            <div class="px-3 pb-2 grid grid-cols-3 gap-3">
+                                <div>
+                                    <div class="text-[8px] text-[#555555] uppercase">"Runs"</div>
+                                    <div class="text-[11px] text-white font-mono">{run_count}</div>
+                                </div>
+                                <div>
+                                    <div class="text-[8px] text-[#555555] uppercase">"Last Run"</div>
+                                    <div class="text-[11px] text-white font-mono">{last_run_str}</div>
+                                </div>
+                            </div>
+
                             // Motion Settings - Start Position
                             <div class="px-3 pb-2">
                                 <div class="flex items-center gap-2 mb-1">
@@ -838,9 +856,9 @@ fn ProgramDetails(
                                                 <thead class="bg-[#1a1a1a] sticky top-0">
                                                     <tr class="text-[#666666] text-left">
                                                         <th class="px-2 py-1.5 font-medium">"#"</th>
-                                                        <th class="px-2 py-1.5 font-medium">"X"</th>
-                                                        <th class="px-2 py-1.5 font-medium">"Y"</th>
-                                                        <th class="px-2 py-1.5 font-medium">"Z"</th>
+                                                        <th class="px-2 py-1.5 font-medium">{format!("X ({})", unit.suffix())}</th>
+                                                        <th class="px-2 py-1.5 font-medium">{format!("Y ({})", unit.suffix())}</th>
+                                                        <th class="px-2 py-1.5 font-medium">{format!("Z ({})", unit.suffix())}</th>
                                                         <th class="px-2 py-1.5 font-medium">"W"</th>
                                                         <th class="px-2 py-1.5 font-medium">"P"</th>
                                                         <th class="px-2 py-1.5 font-medium">"R"</th>
@@ -862,9 +880,9 @@ fn ProgramDetails(
                                                         view! {
                                                             <tr class="border-t border-[#ffffff08] hover:bg-[#ffffff05]">
                                                                 <td class="px-2 py-1 text-[#00d9ff]">{instr.line_number}</td>
-                                                                <td class="px-2 py-1 text-white">{format!("{:.2}", instr.x)}</td>
-                                                                <td class="px-2 py-1 text-white">{format!("{:.2}", instr.y)}</td>
-                                                                <td class="px-2 py-1 text-white">{format!("{:.2}", instr.z)}</td>
+                                                                <td class="px-2 py-1 text-white">{format!("{:.2}", unit.from_mm(instr.x))}</td>
+                                                                <td class="px-2 py-1 text-white">{format!("{:.2}", unit.from_mm(instr.y))}</td>
+                                                                <td class="px-2 py-1 text-white">{format!("{:.2}", unit.from_mm(instr.z))}</td>
                                                                 <td class="px-2 py-1 text-[#888888]">{w_str}</td>
                                                                 <td class="px-2 py-1 text-[#888888]">{p_str}</td>
                                                                 <td class="px-2 py-1 text-[#888888]">{r_str}</td>
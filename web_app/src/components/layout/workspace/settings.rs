@@ -9,7 +9,7 @@
 //! - Robot Settings: Per-robot motion defaults, orientation, I/O config
 
 use leptos::prelude::*;
-use crate::websocket::WebSocketManager;
+use crate::websocket::{LengthUnit, WebSocketManager};
 
 /// Settings view with two-panel layout.
 #[component]
@@ -290,6 +290,7 @@ where
     // Motion defaults (required - no global fallback)
     let (edit_speed, set_edit_speed) = signal::<String>("100.0".to_string());
     let (edit_speed_type, set_edit_speed_type) = signal::<String>("mmSec".to_string());
+    let (edit_unit, set_edit_unit) = signal(LengthUnit::default());
     let (edit_term, set_edit_term) = signal::<String>("CNT".to_string());
     let (edit_w, set_edit_w) = signal::<String>("0.0".to_string());
     let (edit_p, set_edit_p) = signal::<String>("0.0".to_string());
@@ -336,6 +337,7 @@ where
             // Motion defaults
             set_edit_speed.set(robot.default_speed.to_string());
             set_edit_speed_type.set(robot.default_speed_type.clone());
+            set_edit_unit.set(robot.coordinate_unit);
             set_edit_term.set(robot.default_term_type.clone());
             set_edit_w.set(robot.default_w.to_string());
             set_edit_p.set(robot.default_p.to_string());
@@ -480,6 +482,7 @@ where
                                                 p,
                                                 r,
                                             );
+                                            ws.update_robot_connection_unit(id, edit_unit.get());
                                             set_has_changes.set(false);
                                             set_save_status.set(Some("✓ Saved".to_string()));
                                             ws.list_robot_connections();
@@ -589,6 +592,25 @@ where
                                             <option value="mSec" selected=move || edit_speed_type.get() == "mSec">"milliseconds"</option>
                                         </select>
                                     </div>
+                                    <div>
+                                        <label
+                                            class="block text-[#666666] text-[9px] mb-0.5"
+                                            title="Unit positions are shown and entered in, and CSV files are imported and exported in. Programs are always stored in mm."
+                                        >
+                                            "Units"
+                                        </label>
+                                        <select
+                                            class="w-full bg-[#111111] border border-[#ffffff08] rounded px-2 py-1.5 text-[10px] text-white focus:border-[#00d9ff] focus:outline-none"
+                                            on:change=move |ev| {
+                                                let unit = if event_target_value(&ev) == "inch" { LengthUnit::Inches } else { LengthUnit::Millimeters };
+                                                set_edit_unit.set(unit);
+                                                set_has_changes.set(true);
+                                            }
+                                        >
+                                            <option value="mm" selected=move || edit_unit.get() == LengthUnit::Millimeters>"Millimeters"</option>
+                                            <option value="inch" selected=move || edit_unit.get() == LengthUnit::Inches>"Inches"</option>
+                                        </select>
+                                    </div>
                                     <div>
                                         <label class="block text-[#666666] text-[9px] mb-0.5">"Termination"</label>
                                         <select
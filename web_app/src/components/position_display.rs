@@ -26,22 +26,24 @@ pub fn PositionDisplay() -> impl IntoView {
                 }
             >
                 {move || {
+                    let unit = ws.length_unit();
                     let (x, y, z) = position.get().unwrap();
+                    let (x, y, z) = (unit.from_mm(x), unit.from_mm(y), unit.from_mm(z));
                     let (w, p, r) = orientation.get().unwrap_or((0.0, 0.0, 0.0));
                     view! {
                         <div class="space-y-0.5">
                             // Position (X, Y, Z)
                             <div class="flex justify-between items-center bg-[#111111] rounded px-1.5 py-1">
                                 <span class="text-[#666666] text-[10px] font-medium">"X"</span>
-                                <span class="text-[11px] font-mono text-white tabular-nums">{format!("{:.2}", x)}<span class="text-[#555555] ml-0.5">"mm"</span></span>
+                                <span class="text-[11px] font-mono text-white tabular-nums">{format!("{:.2}", x)}<span class="text-[#555555] ml-0.5">{unit.suffix()}</span></span>
                             </div>
                             <div class="flex justify-between items-center bg-[#111111] rounded px-1.5 py-1">
                                 <span class="text-[#666666] text-[10px] font-medium">"Y"</span>
-                                <span class="text-[11px] font-mono text-white tabular-nums">{format!("{:.2}", y)}<span class="text-[#555555] ml-0.5">"mm"</span></span>
+                                <span class="text-[11px] font-mono text-white tabular-nums">{format!("{:.2}", y)}<span class="text-[#555555] ml-0.5">{unit.suffix()}</span></span>
                             </div>
                             <div class="flex justify-between items-center bg-[#111111] rounded px-1.5 py-1">
                                 <span class="text-[#666666] text-[10px] font-medium">"Z"</span>
-                                <span class="text-[11px] font-mono text-white tabular-nums">{format!("{:.2}", z)}<span class="text-[#555555] ml-0.5">"mm"</span></span>
+                                <span class="text-[11px] font-mono text-white tabular-nums">{format!("{:.2}", z)}<span class="text-[#555555] ml-0.5">{unit.suffix()}</span></span>
                             </div>
                             // Orientation (W, P, R)
                             <div class="flex justify-between items-center bg-[#111111] rounded px-1.5 py-1">
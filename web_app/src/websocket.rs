@@ -12,8 +12,16 @@ pub use web_common::{
     StartPosition, ProgramInfo, ProgramDetail,
     RobotConnectionDto, RobotConfigurationDto, NewRobotConfigurationDto,
     RobotSettingsDto, IoDisplayConfigDto, ChangeLogEntryDto,
+    SafetyLimitsDto, LengthUnit,
+    JogAxis, JogDirection, IoPortRange, IoPoint, IoType, ExecutionState,
+    ConfigurationField, AbortReason,
+    PROTOCOL_VERSION, encode_frame, decode_robot_frame,
 };
 
+/// How often the control holder sends `Heartbeat`. Well inside the
+/// server's default deadman timeout of 2 seconds.
+const HEARTBEAT_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
 /// Frame or Tool coordinate data (X, Y, Z, W, P, R)
 /// (client-only type for local state management)
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -139,6 +147,9 @@ pub struct WebSocketManager {
     /// Active configuration for the connected robot
     pub active_configuration: ReadSignal<Option<ActiveConfigurationData>>,
     set_active_configuration: WriteSignal<Option<ActiveConfigurationData>>,
+    /// Fields where the active configuration differs from the saved one
+    pub configuration_diff: ReadSignal<Vec<ChangeLogEntryDto>>,
+    set_configuration_diff: WriteSignal<Vec<ChangeLogEntryDto>>,
     /// List of saved robot configurations
     pub robot_configurations: ReadSignal<Vec<RobotConfigurationDto>>,
     set_robot_configurations: WriteSignal<Vec<RobotConfigurationDto>>,
@@ -315,6 +326,7 @@ impl WebSocketManager {
         let (has_control, set_has_control) = signal(false);
         // Active configuration state
         let (active_configuration, set_active_configuration) = signal::<Option<ActiveConfigurationData>>(None);
+        let (configuration_diff, set_configuration_diff) = signal::<Vec<ChangeLogEntryDto>>(Vec::new());
         let (robot_configurations, set_robot_configurations) = signal::<Vec<RobotConfigurationDto>>(Vec::new());
         // Active jog settings (server-driven state)
         let (active_jog_settings, set_active_jog_settings) = signal::<Option<ActiveJogSettingsData>>(None);
@@ -400,6 +412,8 @@ impl WebSocketManager {
             set_has_control,
             active_configuration,
             set_active_configuration,
+            configuration_diff,
+            set_configuration_diff,
             robot_configurations,
             set_robot_configurations,
             active_jog_settings,
@@ -464,6 +478,7 @@ impl WebSocketManager {
         let set_io_config = self.set_io_config;
         let set_has_control = self.set_has_control;
         let set_active_configuration = self.set_active_configuration;
+        let set_configuration_diff = self.set_configuration_diff;
         let set_robot_configurations = self.set_robot_configurations;
         let set_active_jog_settings = self.set_active_jog_settings;
         let set_console_messages = self.set_console_messages;
@@ -483,8 +498,17 @@ impl WebSocketManager {
             if let Ok(array_buffer) = e.data().dyn_into::<js_sys::ArrayBuffer>() {
                 let uint8_array = js_sys::Uint8Array::new(&array_buffer);
                 let bytes = uint8_array.to_vec();
+                let payload = match decode_robot_frame(&bytes) {
+                    Ok((None, payload)) => payload,
+                    // Frames of additional robots; this client shows the active robot only
+                    Ok((Some(_), _)) => return,
+                    Err(e) => {
+                        log::error!("Dropping binary frame: {}", e);
+                        return;
+                    }
+                };
 
-                if let Ok(response) = bincode::deserialize::<ResponsePacket>(&bytes) {
+                if let Ok(response) = bincode::deserialize::<ResponsePacket>(payload) {
                     match response {
                         ResponsePacket::InstructionResponse(resp) => {
                             let (seq_id, error_id) = get_response_ids(&resp);
@@ -597,6 +621,11 @@ impl WebSocketManager {
                             // Clear connecting states on error
                             set_robot_connecting.set(false);
                         }
+                        ServerResponse::ValidationError { message } => {
+                            log::warn!("API Validation Error: {}", message);
+                            set_api_message.set(Some(format!("Error: {}", message)));
+                            set_api_error.set(Some(message));
+                        }
                         ServerResponse::Programs { programs } => {
                             log::info!("Received {} programs", programs.len());
                             set_programs.set(programs);
@@ -605,6 +634,18 @@ impl WebSocketManager {
                             log::info!("Received program: {}", program.name);
                             set_current_program.set(Some(program));
                         }
+                        ServerResponse::ProgramCsv { filename, content } => {
+                            log::info!("Received {} ({} bytes)", filename, content.len());
+                        }
+                        ServerResponse::CsvPreview { instructions, warnings, errors } => {
+                            log::info!(
+                                "CSV preview: {} rows, {} warnings, {} errors",
+                                instructions.len(), warnings.len(), errors.len()
+                            );
+                        }
+                        ServerResponse::EventLog { events } => {
+                            log::info!("Received {} event log entries", events.len());
+                        }
                         ServerResponse::Settings { settings } => {
                             log::info!("Received settings");
                             set_settings.set(Some(settings));
@@ -632,6 +673,26 @@ impl WebSocketManager {
                                 set_api_message.set(Some(msg));
                             }
                         }
+                        ServerResponse::ProgramEstimate { program_id, seconds, segment_count } => {
+                            log::info!("Program {} estimate: {:.1}s over {} segments", program_id, seconds, segment_count);
+                            set_api_message.set(Some(format!(
+                                "Estimated run time: {:.1}s ({} segments)", seconds, segment_count
+                            )));
+                        }
+                        ServerResponse::Breakpoints { program_id, lines } => {
+                            log::info!("Program {} breakpoints: {:?}", program_id, lines);
+                        }
+                        ServerResponse::ConfigurationWarnings { program_id, warnings } => {
+                            log::warn!("Program {} loaded with {} frame/tool mismatches", program_id, warnings.len());
+                            let lines: Vec<String> = warnings.iter().map(|w| {
+                                let field = match w.field {
+                                    ConfigurationField::UFrame => "UFrame",
+                                    ConfigurationField::UTool => "UTool",
+                                };
+                                format!("line {} uses {} {} (active {})", w.line_number, field, w.program_value, w.active_value)
+                            }).collect();
+                            set_api_message.set(Some(format!("Configuration mismatch: {}", lines.join(", "))));
+                        }
                         ServerResponse::InstructionProgress { current_line, total_lines } => {
                             log::debug!("Progress: {}/{}", current_line, total_lines);
                             set_program_progress.set(Some((current_line, total_lines)));
@@ -640,6 +701,12 @@ impl WebSocketManager {
                             log::debug!("Executing: {}/{}", current_line, total_lines);
                             set_executing_line.set(Some(current_line));
                         }
+                        ServerResponse::Metrics { packets_sent, packets_received, protocol_errors, reconnects, .. } => {
+                            log::info!(
+                                "Metrics: {} packets sent, {} received, {} protocol errors, {} reconnects",
+                                packets_sent, packets_received, protocol_errors, reconnects
+                            );
+                        }
                         ServerResponse::ConnectionStatus { connected, robot_addr, robot_port, connection_name, connection_id, tp_program_initialized } => {
                             log::info!("Robot connection status: connected={}, addr={}:{}, name={:?}, tp_initialized={}", connected, robot_addr, robot_port, connection_name, tp_program_initialized);
                             set_robot_connected.set(connected);
@@ -734,6 +801,17 @@ impl WebSocketManager {
                                 });
                             });
                         }
+                        ServerResponse::ConfigurationDiff { configuration_name, differences, .. } => {
+                            log::info!("Configuration '{}' differs in {} fields", configuration_name, differences.len());
+                            set_configuration_diff.set(differences);
+                        }
+                        ServerResponse::ControllerError { error_id, message, cause_code } => {
+                            log::debug!("Controller error {}: {} ({:?})", error_id, message, cause_code);
+                            if error_id != 0 {
+                                let code = cause_code.map(|code| format!("{} ", code)).unwrap_or_default();
+                                set_api_error.set(Some(format!("Controller error: {}{}", code, message)));
+                            }
+                        }
                         ServerResponse::DinValue { port_number, port_value } => {
                             log::debug!("DIN[{}] = {}", port_number, if port_value { "ON" } else { "OFF" });
                             set_din_values.update(|map| {
@@ -748,8 +826,8 @@ impl WebSocketManager {
                                 }
                             });
                         }
-                        ServerResponse::AinValue { port_number, port_value } => {
-                            log::debug!("AIN[{}] = {:.3}", port_number, port_value);
+                        ServerResponse::AinValue { port_number, port_value, alarm_state } => {
+                            log::debug!("AIN[{}] = {:.3} ({:?})", port_number, port_value, alarm_state);
                             set_ain_values.update(|map| {
                                 map.insert(port_number, port_value);
                             });
@@ -761,24 +839,52 @@ impl WebSocketManager {
                             });
                         }
                         // Output values - broadcast from server after successful write
-                        ServerResponse::DoutValue { port_number, port_value } => {
+                        ServerResponse::DoutValue { port_number, port_value, robot_id: None } => {
                             log::debug!("DOUT[{}] = {} (confirmed)", port_number, if port_value { "ON" } else { "OFF" });
                             set_dout_values.update(|map| {
                                 map.insert(port_number, port_value);
                             });
                         }
-                        ServerResponse::AoutValue { port_number, port_value } => {
-                            log::debug!("AOUT[{}] = {:.3} (confirmed)", port_number, port_value);
+                        ServerResponse::AoutValue { port_number, port_value, alarm_state, robot_id: None } => {
+                            log::debug!("AOUT[{}] = {:.3} (confirmed, {:?})", port_number, port_value, alarm_state);
                             set_aout_values.update(|map| {
                                 map.insert(port_number, port_value);
                             });
                         }
-                        ServerResponse::GoutValue { port_number, port_value } => {
+                        ServerResponse::GoutValue { port_number, port_value, robot_id: None } => {
                             log::debug!("GOUT[{}] = {} (confirmed)", port_number, port_value);
                             set_gout_values.update(|map| {
                                 map.insert(port_number, port_value);
                             });
                         }
+                        ServerResponse::IoSnapshot { din, dout, ain, aout, gin, gout } => {
+                            log::debug!(
+                                "I/O snapshot: {} DIN, {} DOUT, {} AIN, {} AOUT, {} GIN, {} GOUT",
+                                din.len(), dout.len(), ain.len(), aout.len(), gin.len(), gout.len()
+                            );
+                            set_din_values.update(|map| map.extend(din));
+                            set_dout_values.update(|map| map.extend(dout));
+                            set_ain_values.update(|map| map.extend(ain));
+                            set_aout_values.update(|map| map.extend(aout));
+                            set_gin_values.update(|map| map.extend(gin));
+                            set_gout_values.update(|map| map.extend(gout));
+                        }
+                        ServerResponse::IoChanged { point, value } if point.robot_id.is_none() => {
+                            log::debug!("{:?}[{}] changed to {}", point.io_type, point.port, value);
+                            match point.io_type {
+                                IoType::Din => set_din_values.update(|map| { map.insert(point.port, value != 0.0); }),
+                                IoType::Dout => set_dout_values.update(|map| { map.insert(point.port, value != 0.0); }),
+                                IoType::Ain => set_ain_values.update(|map| { map.insert(point.port, value); }),
+                                IoType::Aout => set_aout_values.update(|map| { map.insert(point.port, value); }),
+                                IoType::Gin => set_gin_values.update(|map| { map.insert(point.port, value as u32); }),
+                                IoType::Gout => set_gout_values.update(|map| { map.insert(point.port, value as u32); }),
+                            }
+                        }
+                        // I/O of robots other than the active one
+                        ServerResponse::DoutValue { .. }
+                        | ServerResponse::AoutValue { .. }
+                        | ServerResponse::GoutValue { .. }
+                        | ServerResponse::IoChanged { .. } => {}
                         ServerResponse::IoConfig { configs } => {
                             log::debug!("Received I/O config: {} entries", configs.len());
                             set_io_config.update(|map| {
@@ -788,13 +894,38 @@ impl WebSocketManager {
                                 }
                             });
                         }
-                        ServerResponse::ExecutionStateChanged { state, program_id, current_line, total_lines, message } => {
-                            log::info!("Execution state changed: {} (program={:?}, line={:?}/{:?})", state, program_id, current_line, total_lines);
-                            // Update loaded program ID if provided
-                            set_loaded_program_id.set(program_id);
+                        ServerResponse::SafetyLimits { robot_connection_id, limits } => {
+                            log::debug!("Safety limits for robot {}: {:?}", robot_connection_id, limits);
+                        }
+                        ServerResponse::SafetyViolation { message } => {
+                            log::warn!("Safety violation: {}", message);
+                            set_api_message.set(Some(format!("Safety limit: {}", message)));
+                            set_api_error.set(Some(message));
+                        }
+                        ServerResponse::JointLimitWarning { joint, remaining_deg } => {
+                            log::warn!("J{} is {:.1}° from its limit", joint, remaining_deg);
+                            set_api_message.set(Some(format!("J{} is {:.1}° from its limit", joint, remaining_deg)));
+                        }
+                        ServerResponse::OverspeedWarning { speed, limit } => {
+                            log::warn!("TCP speed {:.1} mm/s exceeds the limit of {:.1} mm/s", speed, limit);
+                            set_api_message.set(Some(format!("TCP speed {:.1} mm/s exceeds the limit of {:.1} mm/s", speed, limit)));
+                        }
+                        ServerResponse::MotionAborted { reason } => {
+                            log::warn!("Motion aborted: {:?}", reason);
+                            let message = format!("Motion aborted: {}", reason.description());
+                            if reason != AbortReason::UserRequested {
+                                set_api_error.set(Some(message.clone()));
+                            }
+                            set_api_message.set(Some(message));
+                        }
+                        ServerResponse::ExecutionStateChanged { execution_state, program_id, current_line, total_lines, message, subprogram, .. } => {
+                            log::info!("Execution state changed: {:?} (program={:?}, line={:?}/{:?}, subprogram={:?})", execution_state, program_id, current_line, total_lines, subprogram);
+                            // Update loaded program ID if provided. An aborted program
+                            // has to be loaded again before it can run.
+                            set_loaded_program_id.set(program_id.filter(|_| execution_state != ExecutionState::Aborted));
                             // Update execution status based on broadcast state
-                            match state.as_str() {
-                                "loaded" => {
+                            match execution_state {
+                                ExecutionState::Loaded => {
                                     // Program is loaded but not running yet
                                     set_program_running.set(false);
                                     set_program_paused.set(false);
@@ -806,14 +937,14 @@ impl WebSocketManager {
                                         set_api_message.set(Some(msg));
                                     }
                                 }
-                                "running" => {
+                                ExecutionState::Running => {
                                     set_program_running.set(true);
                                     set_program_paused.set(false);
                                     if let (Some(line), Some(total)) = (current_line, total_lines) {
                                         set_program_progress.set(Some((line, total)));
                                     }
                                 }
-                                "paused" => {
+                                ExecutionState::Paused => {
                                     set_program_running.set(true); // Still running, just paused
                                     set_program_paused.set(true);
                                     if let (Some(line), Some(total)) = (current_line, total_lines) {
@@ -823,7 +954,10 @@ impl WebSocketManager {
                                         set_api_message.set(Some(msg));
                                     }
                                 }
-                                "idle" | "completed" | "stopping" => {
+                                ExecutionState::Idle
+                                | ExecutionState::Completed
+                                | ExecutionState::Aborted
+                                | ExecutionState::Stopping => {
                                     set_program_running.set(false);
                                     set_program_paused.set(false);
                                     set_program_progress.set(None);
@@ -832,7 +966,7 @@ impl WebSocketManager {
                                         set_api_message.set(Some(msg));
                                     }
                                 }
-                                "error" => {
+                                ExecutionState::Error => {
                                     set_program_running.set(false);
                                     set_program_paused.set(false);
                                     set_program_progress.set(None);
@@ -841,9 +975,6 @@ impl WebSocketManager {
                                         set_api_error.set(Some(msg));
                                     }
                                 }
-                                _ => {
-                                    log::warn!("Unknown execution state: {}", state);
-                                }
                             }
                         }
                         // Control lock responses
@@ -877,6 +1008,39 @@ impl WebSocketManager {
                             log::info!("Control status: has_control={}, holder={:?}", has_control, holder_id);
                             set_has_control.set(has_control);
                         }
+                        ServerResponse::HandoffRequested { from } => {
+                            log::info!("Client {} asked for control", from);
+                            set_api_message.set(Some("Another client is asking for control".to_string()));
+                        }
+                        ServerResponse::HandoffPending { holder_id } => {
+                            log::info!("Handoff requested from {}", holder_id);
+                            set_api_message.set(Some("Asked the current holder to hand over control".to_string()));
+                        }
+                        ServerResponse::HeartbeatAck { has_control } => {
+                            if !has_control {
+                                set_has_control.set(false);
+                            }
+                        }
+                        ServerResponse::Hello { protocol_version } => {
+                            log::info!("Server speaks protocol v{}", protocol_version);
+                        }
+                        ServerResponse::ProtocolMismatch { server_version, client_version } => {
+                            let message = format!(
+                                "Protocol mismatch: server v{}, this client v{}. Reload the page to update the client.",
+                                server_version, client_version
+                            );
+                            log::error!("{}", message);
+                            set_api_error.set(Some(message));
+                        }
+                        ServerResponse::StatusChanged { status } => {
+                            log::info!("Robot status changed: {:?}", status);
+                            set_status.set(Some(RobotStatusData {
+                                servo_ready: status.servo_ready as i8,
+                                tp_mode: status.tp_mode as i8,
+                                motion_status: status.rmi_motion_status,
+                                speed_override: status.override_value,
+                            }));
+                        }
                         ServerResponse::RobotDisconnected { reason } => {
                             log::warn!("Robot disconnected: {}", reason);
                             // Update connection state
@@ -1105,6 +1269,11 @@ impl WebSocketManager {
         // Use a small delay to ensure the WebSocket is fully ready
         let ws_ref = self.ws.get_value();
         if let Some(ws) = ws_ref {
+            // Announce our protocol version before anything else
+            let hello = ClientRequest::Hello { protocol_version: PROTOCOL_VERSION };
+            if let Ok(json) = serde_json::to_string(&hello) {
+                let _ = ws.send_with_str(&json);
+            }
             // Request robot connection status
             if let Ok(json) = serde_json::to_string(&ClientRequest::GetConnectionStatus) {
                 let _ = ws.send_with_str(&json);
@@ -1121,6 +1290,31 @@ impl WebSocketManager {
             if let Ok(json) = serde_json::to_string(&ClientRequest::GetActiveConfiguration) {
                 let _ = ws.send_with_str(&json);
             }
+
+            // While we hold control, heartbeat so the server knows we are alive;
+            // it aborts motion if the holder goes quiet
+            let has_control = self.has_control;
+            let heartbeat = StoredValue::new(None::<IntervalHandle>);
+            let heartbeat_result = set_interval_with_handle(
+                move || {
+                    if ws.ready_state() == WebSocket::CLOSED {
+                        if let Some(handle) = heartbeat.get_value() {
+                            handle.clear();
+                        }
+                        return;
+                    }
+                    if ws.ready_state() == WebSocket::OPEN && has_control.get_untracked() {
+                        if let Ok(json) = serde_json::to_string(&ClientRequest::Heartbeat) {
+                            let _ = ws.send_with_str(&json);
+                        }
+                    }
+                },
+                HEARTBEAT_INTERVAL,
+            );
+            match heartbeat_result {
+                Ok(handle) => heartbeat.set_value(Some(handle)),
+                Err(e) => log::error!("Failed to start control heartbeat: {:?}", e),
+            }
         }
     }
 
@@ -1134,7 +1328,7 @@ impl WebSocketManager {
 
         if let Some(ws) = self.ws.get_value() {
             if let Ok(binary) = bincode::serialize(&packet) {
-                let _ = ws.send_with_u8_array(&binary);
+                let _ = ws.send_with_u8_array(&encode_frame(&binary));
             }
         }
     }
@@ -1191,6 +1385,7 @@ impl WebSocketManager {
                     Command::FrcReadCartesianPosition(_) => "FRC_ReadCartesianPosition",
                     Command::FrcReadJointAngles(_) => "FRC_ReadJointAngles",
                     Command::FrcReadTCPSpeed => "FRC_ReadTCPSpeed",
+                    Command::SimMode(_) => "SIM_Mode",
                 };
                 (name.to_string(), None)
             }
@@ -1212,6 +1407,8 @@ impl WebSocketManager {
                     Instruction::FrcJointMotionJRep(i) => ("FRC_JointMotionJRep", i.sequence_id),
                     Instruction::FrcJointRelativeJRep(i) => ("FRC_JointRelativeJRep", i.sequence_id),
                     Instruction::FrcLinearMotionJRep(i) => ("FRC_LinearMotionJRep", i.sequence_id),
+                    Instruction::FrcLinearMotionPR(i) => ("FRC_LinearMotionPR", i.sequence_id),
+                    Instruction::FrcJointMotionPR(i) => ("FRC_JointMotionPR", i.sequence_id),
                 };
                 (name.to_string(), Some(seq_id))
             }
@@ -1291,6 +1488,17 @@ impl WebSocketManager {
         });
     }
 
+    /// Fetch recorded operator actions newer than event `since`; answered
+    /// with an `EventLog`.
+    pub fn get_event_log(&self, since: Option<i64>, limit: Option<u32>) {
+        self.send_api_request(ClientRequest::GetEventLog { since, limit });
+    }
+
+    /// Parse CSV content without saving it; answered with a `CsvPreview`.
+    pub fn preview_csv(&self, csv_content: String) {
+        self.send_api_request(ClientRequest::PreviewCsv { csv_content });
+    }
+
     /// Upload CSV content to a program.
     ///
     /// CSV contains generic waypoints. Robot-specific configuration is applied
@@ -1303,6 +1511,11 @@ impl WebSocketManager {
         });
     }
 
+    /// Export a program as CSV in the upload format.
+    pub fn export_program_csv(&self, program_id: i64) {
+        self.send_api_request(ClientRequest::ExportProgramCsv { program_id });
+    }
+
     /// Load a program into the executor (without starting execution)
     pub fn load_program(&self, program_id: i64) {
         self.send_api_request(ClientRequest::LoadProgram { program_id });
@@ -1338,6 +1551,21 @@ impl WebSocketManager {
         self.send_api_request(ClientRequest::GetExecutionState);
     }
 
+    /// Estimate a program's run time (answered with ProgramEstimate)
+    pub fn estimate_program(&self, program_id: i64) {
+        self.send_api_request(ClientRequest::EstimateProgram { program_id });
+    }
+
+    /// Pause the program before a line (answered with Breakpoints)
+    pub fn set_breakpoint(&self, program_id: i64, line: usize) {
+        self.send_api_request(ClientRequest::SetBreakpoint { program_id, line });
+    }
+
+    /// Remove a breakpoint (answered with Breakpoints)
+    pub fn clear_breakpoint(&self, program_id: i64, line: usize) {
+        self.send_api_request(ClientRequest::ClearBreakpoint { program_id, line });
+    }
+
     /// Get robot settings
     pub fn get_settings(&self) {
         self.send_api_request(ClientRequest::GetSettings);
@@ -1663,6 +1891,13 @@ impl WebSocketManager {
         });
     }
 
+    /// Set the length unit of a saved robot connection, then refresh the
+    /// connection list so displays pick it up.
+    pub fn update_robot_connection_unit(&self, id: i64, coordinate_unit: LengthUnit) {
+        self.send_api_request(ClientRequest::UpdateRobotConnectionUnit { id, coordinate_unit });
+        self.list_robot_connections();
+    }
+
     /// Update jog controls (from Control panel - updates active jog controls only, does NOT update defaults or increment changes_count)
     pub fn update_jog_controls(
         &self,
@@ -1771,11 +2006,39 @@ impl WebSocketManager {
         self.send_api_request(ClientRequest::WriteDout { port_number, port_value });
     }
 
+    /// Pulse a digital output port on for `duration_ms`
+    pub fn pulse_dout(&self, port_number: u16, duration_ms: u32) {
+        self.send_api_request(ClientRequest::PulseDout { port_number, duration_ms });
+    }
+
     /// Read multiple digital input ports at once
     pub fn read_din_batch(&self, port_numbers: Vec<u16>) {
         self.send_api_request(ClientRequest::ReadDinBatch { port_numbers });
     }
 
+    /// Fetch the server's cached value of every port in the given ranges
+    pub fn read_io_snapshot(
+        &self,
+        din: Option<IoPortRange>,
+        dout: Option<IoPortRange>,
+        ain: Option<IoPortRange>,
+        aout: Option<IoPortRange>,
+        gin: Option<IoPortRange>,
+        gout: Option<IoPortRange>,
+    ) {
+        self.send_api_request(ClientRequest::ReadIoSnapshot { din, dout, ain, aout, gin, gout });
+    }
+
+    /// Have the server push changes of `points` instead of polling them
+    pub fn subscribe_io(&self, points: Vec<IoPoint>) {
+        self.send_api_request(ClientRequest::SubscribeIo { points });
+    }
+
+    /// Stop server pushes for `points`
+    pub fn unsubscribe_io(&self, points: Vec<IoPoint>) {
+        self.send_api_request(ClientRequest::UnsubscribeIo { points });
+    }
+
     /// Clear the cached I/O values
     pub fn clear_io_cache(&self) {
         self.set_din_values.set(std::collections::HashMap::new());
@@ -1839,6 +2102,7 @@ impl WebSocketManager {
     }
 
     /// Update I/O display configuration
+    #[allow(clippy::too_many_arguments)]
     pub fn update_io_config(
         &self,
         robot_connection_id: i64,
@@ -1847,6 +2111,8 @@ impl WebSocketManager {
         display_name: Option<String>,
         is_visible: bool,
         display_order: Option<i32>,
+        warning_threshold: Option<f64>,
+        alarm_threshold: Option<f64>,
     ) {
         self.send_api_request(ClientRequest::UpdateIoConfig {
             robot_connection_id,
@@ -1855,9 +2121,23 @@ impl WebSocketManager {
             display_name,
             is_visible,
             display_order,
+            warning_threshold,
+            alarm_threshold,
         });
     }
 
+    // ========== Safety Limits ==========
+
+    /// Get the safety limits for a robot
+    pub fn get_safety_limits(&self, robot_connection_id: i64) {
+        self.send_api_request(ClientRequest::GetSafetyLimits { robot_connection_id });
+    }
+
+    /// Save the safety limits for a robot (requires control)
+    pub fn update_safety_limits(&self, robot_connection_id: i64, limits: SafetyLimitsDto) {
+        self.send_api_request(ClientRequest::UpdateSafetyLimits { robot_connection_id, limits });
+    }
+
     // ========== Control Lock ==========
 
     /// Request control of the robot
@@ -1875,6 +2155,33 @@ impl WebSocketManager {
         self.send_api_request(ClientRequest::GetControlStatus);
     }
 
+    /// Ask the current control holder to hand control over
+    pub fn request_control_handoff(&self) {
+        self.send_api_request(ClientRequest::RequestControlHandoff);
+    }
+
+    /// Hand control to the client whose handoff request is pending
+    pub fn grant_control(&self) {
+        self.send_api_request(ClientRequest::GrantControl);
+    }
+
+    /// Refuse the pending handoff request
+    pub fn deny_control(&self) {
+        self.send_api_request(ClientRequest::DenyControl);
+    }
+
+    // ========== Jogging ==========
+
+    /// Start a server-paced jog that runs until [`Self::jog_stop`]
+    pub fn jog_continuous(&self, axis: JogAxis, direction: JogDirection, speed: f64) {
+        self.send_api_request(ClientRequest::JogContinuous { axis, direction, speed });
+    }
+
+    /// Stop the running server-paced jog
+    pub fn jog_stop(&self) {
+        self.send_api_request(ClientRequest::JogStop);
+    }
+
     /// Get the currently active robot connection (if any)
     pub fn get_active_connection(&self) -> Option<RobotConnectionDto> {
         let active_id = self.active_connection_id.get_untracked();
@@ -1882,6 +2189,19 @@ impl WebSocketManager {
         active_id.and_then(|id| connections.into_iter().find(|c| c.id == id))
     }
 
+    /// Length unit of the active robot connection (tracked), millimetres
+    /// when none is active. Positions from the server are mm; convert with
+    /// [`LengthUnit::from_mm`] for display and [`LengthUnit::to_mm`] for input.
+    pub fn length_unit(&self) -> LengthUnit {
+        let active_id = self.active_connection_id.get();
+        self.robot_connections.with(|connections| {
+            active_id
+                .and_then(|id| connections.iter().find(|c| c.id == id))
+                .map(|c| c.coordinate_unit)
+                .unwrap_or_default()
+        })
+    }
+
     // ========== Robot Configurations ==========
 
     /// List all configurations for a robot
@@ -1894,6 +2214,13 @@ impl WebSocketManager {
         self.send_api_request(ClientRequest::GetActiveConfiguration);
     }
 
+    /// Compare the active configuration with the saved one it was loaded from
+    pub fn get_configuration_diff(&self) {
+        // Drop the previous answer, which may be for another configuration
+        self.set_configuration_diff.set(Vec::new());
+        self.send_api_request(ClientRequest::GetConfigurationDiff);
+    }
+
     /// Load a saved configuration as active
     pub fn load_configuration(&self, configuration_id: i64) {
         self.send_api_request(ClientRequest::LoadConfiguration { configuration_id });
@@ -1985,6 +2312,8 @@ fn get_response_ids(resp: &InstructionResponse) -> (u32, u32) {
         InstructionResponse::FrcJointRelativeJRep(r) => (r.sequence_id, r.error_id),
         InstructionResponse::FrcLinearMotion(r) => (r.sequence_id, r.error_id),
         InstructionResponse::FrcCircularMotion(r) => (r.sequence_id, r.error_id),
+        InstructionResponse::FrcLinearMotionPR(r) => (r.sequence_id, r.error_id),
+        InstructionResponse::FrcJointMotionPR(r) => (r.sequence_id, r.error_id),
         InstructionResponse::FrcWaitTime(r) => (r.sequence_id, r.error_id),
         InstructionResponse::FrcSetUFrame(r) => (r.sequence_id, r.error_id),
         InstructionResponse::FrcSetUTool(r) => (r.sequence_id, r.error_id),
@@ -2011,6 +2340,8 @@ fn get_instruction_response_name(resp: &InstructionResponse) -> &'static str {
         InstructionResponse::FrcJointMotionJRep(_) => "FRC_JointMotionJRep",
         InstructionResponse::FrcJointRelativeJRep(_) => "FRC_JointRelativeJRep",
         InstructionResponse::FrcLinearMotionJRep(_) => "FRC_LinearMotionJRep",
+        InstructionResponse::FrcLinearMotionPR(_) => "FRC_LinearMotionPR",
+        InstructionResponse::FrcJointMotionPR(_) => "FRC_JointMotionPR",
     }
 }
 
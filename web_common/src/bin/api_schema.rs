@@ -0,0 +1,10 @@
+//! Print the WebSocket API JSON Schema to stdout.
+//!
+//! ```text
+//! cargo run -p web_common --bin api_schema > api_schema.json
+//! ```
+
+fn main() {
+    let schema = web_common::api_schema();
+    println!("{}", serde_json::to_string_pretty(&schema).expect("schema serializes"));
+}
@@ -0,0 +1,65 @@
+//! Event log DTOs.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// What an [`EventLogEntryDto`] records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Connect,
+    Disconnect,
+    Abort,
+    ControlAcquired,
+    ControlReleased,
+    ProgramStarted,
+    ProgramStopped,
+    /// One of the actions above failed.
+    Error,
+}
+
+impl EventKind {
+    /// Name stored in the database.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventKind::Connect => "connect",
+            EventKind::Disconnect => "disconnect",
+            EventKind::Abort => "abort",
+            EventKind::ControlAcquired => "control_acquired",
+            EventKind::ControlReleased => "control_released",
+            EventKind::ProgramStarted => "program_started",
+            EventKind::ProgramStopped => "program_stopped",
+            EventKind::Error => "error",
+        }
+    }
+
+    /// Parse a name stored by [`as_str`](Self::as_str).
+    pub fn from_db(name: &str) -> Option<Self> {
+        [
+            EventKind::Connect,
+            EventKind::Disconnect,
+            EventKind::Abort,
+            EventKind::ControlAcquired,
+            EventKind::ControlReleased,
+            EventKind::ProgramStarted,
+            EventKind::ProgramStopped,
+            EventKind::Error,
+        ]
+        .into_iter()
+        .find(|kind| kind.as_str() == name)
+    }
+}
+
+/// A recorded operator action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct EventLogEntryDto {
+    /// Increases with every event; pass the last one seen as `since` to
+    /// fetch only newer events.
+    pub id: i64,
+    /// UTC, `YYYY-MM-DD HH:MM:SS`.
+    pub timestamp: String,
+    pub kind: EventKind,
+    /// Client whose request caused the event.
+    pub client_id: Option<String>,
+    pub message: String,
+}
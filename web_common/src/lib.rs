@@ -16,12 +16,15 @@
 //! - `FrameData` - 6-DOF coordinate data (x, y, z, w, p, r)
 //! - `Configuration` - Robot arm configuration (frame/tool numbers, arm config bits)
 //! - `Position` - Full Cartesian position with orientation and external axes
+//! - `RobotStatus` - Decoded `FRC_GetStatus` state (servo ready, motion status, ...)
 //!
 //! # Usage
 //!
 //! ```rust
 //! use web_common::{ClientRequest, ServerResponse, ProgramInfo, FrameData, Configuration};
 //! ```
+//!
+//! A JSON Schema for the whole API is available from [`api_schema`].
 
 mod requests;
 mod responses;
@@ -29,6 +32,9 @@ mod programs;
 mod robots;
 mod settings;
 mod models;
+mod protocol;
+mod events;
+mod schema;
 
 pub use requests::*;
 pub use responses::*;
@@ -36,7 +42,10 @@ pub use programs::*;
 pub use robots::*;
 pub use settings::*;
 pub use models::*;
+pub use protocol::*;
+pub use events::*;
+pub use schema::*;
 
 // Re-export fanuc_rmi DTO types that are used in the API
-pub use fanuc_rmi::dto::{FrameData, Configuration, Position};
+pub use fanuc_rmi::dto::{FrameData, Configuration, Position, RobotStatus};
 
@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::Configuration;
+
 /// Robot model identifier.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RobotModel {
@@ -40,6 +42,49 @@ impl RobotModel {
             RobotModel::CRX30iA => "CRX-30iA",
         }
     }
+
+    /// Number of user frames on the controller, UFrame 1 to this.
+    pub fn uframe_count(&self) -> u8 {
+        match self {
+            RobotModel::CRX10iA | RobotModel::CRX30iA => 9,
+        }
+    }
+
+    /// Number of user tools on the controller, UTool 1 to this.
+    pub fn utool_count(&self) -> u8 {
+        match self {
+            RobotModel::CRX10iA | RobotModel::CRX30iA => 10,
+        }
+    }
+
+    /// Frame, tool and arm configuration a new connection starts with.
+    /// Both CRX arms start in UFrame 1 and UTool 1, Front Up Right NoFlip,
+    /// with no turns.
+    pub fn default_configuration(&self) -> Configuration {
+        match self {
+            RobotModel::CRX10iA | RobotModel::CRX30iA => Configuration {
+                u_tool_number: 1,
+                u_frame_number: 1,
+                front: 1,
+                up: 1,
+                left: 0,
+                flip: 0,
+                turn4: 0,
+                turn5: 0,
+                turn6: 0,
+            },
+        }
+    }
+
+    /// Default joint jog speed in deg/s. The CRX-30iA jogs its joints
+    /// slower, so that its longer arm moves the TCP about as fast as the
+    /// CRX-10iA's at full reach.
+    pub fn default_joint_jog_speed(&self) -> f64 {
+        match self {
+            RobotModel::CRX10iA => 10.0,
+            RobotModel::CRX30iA => 6.0,
+        }
+    }
 }
 
 impl std::fmt::Display for RobotModel {
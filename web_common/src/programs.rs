@@ -1,9 +1,10 @@
 //! Program-related DTOs.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Optional start position for program execution.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct StartPosition {
     pub x: f64,
     pub y: f64,
@@ -11,7 +12,7 @@ pub struct StartPosition {
 }
 
 /// Program summary info for listing.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProgramInfo {
     pub id: i64,
     pub name: String,
@@ -19,10 +20,14 @@ pub struct ProgramInfo {
     pub instruction_count: i64,
     pub created_at: String,
     pub updated_at: String,
+    /// Times the program has run to completion.
+    pub run_count: i64,
+    /// When the program last ran to completion.
+    pub last_run_at: Option<String>,
 }
 
 /// Full program detail including instructions.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProgramDetail {
     pub id: i64,
     pub name: String,
@@ -51,10 +56,15 @@ pub struct ProgramDetail {
     // Timestamps
     pub created_at: String,
     pub updated_at: String,
+    // Run history
+    /// Times the program has run to completion.
+    pub run_count: i64,
+    /// When the program last ran to completion.
+    pub last_run_at: Option<String>,
 }
 
 /// Instruction DTO for client.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct InstructionDto {
     pub line_number: i32,
     pub x: f64,
@@ -71,3 +81,84 @@ pub struct InstructionDto {
     pub utool: Option<i32>,
 }
 
+
+/// Frame or tool number a [`ConfigurationWarning`] refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigurationField {
+    UFrame,
+    UTool,
+}
+
+/// A program line whose explicit frame or tool number differs from the
+/// robot's active configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ConfigurationWarning {
+    pub line_number: i32,
+    pub field: ConfigurationField,
+    /// Number set on the program line.
+    pub program_value: i32,
+    /// Number in the active configuration.
+    pub active_value: i32,
+}
+
+/// Program execution state carried by
+/// [`ServerResponse::ExecutionStateChanged`](crate::ServerResponse::ExecutionStateChanged).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionState {
+    /// No program loaded.
+    #[default]
+    Idle,
+    /// Program loaded but not started.
+    Loaded,
+    Running,
+    Paused,
+    /// Stop requested; waiting for the robot to abort.
+    Stopping,
+    /// The response to the last instruction was received.
+    Completed,
+    /// Execution was stopped with `FRC_Abort` before the program finished.
+    Aborted,
+    Error,
+}
+
+/// Why the server stopped the robot with `FRC_Abort`, carried by
+/// [`ServerResponse::MotionAborted`](crate::ServerResponse::MotionAborted).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AbortReason {
+    /// A client asked to abort motion or stop the program.
+    UserRequested,
+    /// A motion packet broke the robot's safety limits.
+    SafetyViolation,
+    /// The control holder stopped sending heartbeats during motion.
+    HeartbeatLost,
+}
+
+impl AbortReason {
+    /// Short description for operators.
+    pub fn description(self) -> &'static str {
+        match self {
+            AbortReason::UserRequested => "requested by a user",
+            AbortReason::SafetyViolation => "safety limit violated",
+            AbortReason::HeartbeatLost => "no heartbeat from the control holder",
+        }
+    }
+}
+
+impl ExecutionState {
+    /// The legacy `state` string for this state.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExecutionState::Idle => "idle",
+            ExecutionState::Loaded => "loaded",
+            ExecutionState::Running => "running",
+            ExecutionState::Paused => "paused",
+            ExecutionState::Stopping => "stopping",
+            ExecutionState::Completed => "completed",
+            ExecutionState::Aborted => "aborted",
+            ExecutionState::Error => "error",
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! Wire protocol versioning.
+//!
+//! Text frames carry JSON and bincode frames carry robot protocol DTOs. JSON
+//! tolerates additive changes, but bincode encodes enum variants by index, so
+//! a server and client built from different DTO definitions would silently
+//! misread each other. Two guards prevent that:
+//!
+//! - On connect the client sends [`ClientRequest::Hello`](crate::ClientRequest::Hello) with its
+//!   [`PROTOCOL_VERSION`]; the server answers with [`ServerResponse::Hello`]
+//!   or, if the versions differ, [`ServerResponse::ProtocolMismatch`].
+//! - Every binary frame starts with a one-byte version tag
+//!   ([`encode_frame`] / [`decode_frame`]), so a stale frame is rejected
+//!   instead of being deserialized into the wrong variant.
+//!
+//! After the version tag, a binary frame names the robot it belongs to: a
+//! `0` byte for the active robot, or a `1` byte followed by the robot's
+//! connection id as a little-endian `i64` ([`encode_robot_frame`]). Text
+//! requests pick a robot with [`RoutedRequest::robot_id`](crate::RoutedRequest).
+//!
+//! Bump [`PROTOCOL_VERSION`] whenever a change to the DTO types or the API
+//! enums breaks binary compatibility.
+
+use crate::ServerResponse;
+use std::fmt;
+
+/// Version of the WebSocket wire protocol.
+///
+/// v4 added `BufferOccupancy` to the `FRC_GetStatus` response and widened
+/// the `FRC_ReadError` response's `ErrorID` from `u16` to `u32`.
+pub const PROTOCOL_VERSION: u8 = 4;
+
+/// Robot tag of a frame for the active robot.
+const ACTIVE_ROBOT: u8 = 0;
+
+/// Robot tag of a frame followed by a robot connection id.
+const ROBOT_ID: u8 = 1;
+
+/// Error decoding a versioned binary frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameError {
+    /// The frame has no version tag.
+    Empty,
+    /// The frame was encoded with a different protocol version.
+    VersionMismatch { expected: u8, actual: u8 },
+    /// The robot tag is missing, unknown, or cut short.
+    BadRobotTag,
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::Empty => write!(f, "Empty binary frame"),
+            FrameError::VersionMismatch { expected, actual } => write!(
+                f,
+                "Binary frame has protocol version {}, expected {}",
+                actual, expected
+            ),
+            FrameError::BadRobotTag => write!(f, "Binary frame has an invalid robot tag"),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+/// Frame an encoded payload for the active robot.
+pub fn encode_frame(payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(payload.len() + 2);
+    frame.extend_from_slice(&[PROTOCOL_VERSION, ACTIVE_ROBOT]);
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Frame an encoded payload for the robot with connection id `robot_id`.
+pub fn encode_robot_frame(robot_id: i64, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(payload.len() + 10);
+    frame.extend_from_slice(&[PROTOCOL_VERSION, ROBOT_ID]);
+    frame.extend_from_slice(&robot_id.to_le_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Check the version tag of a binary frame and return its payload, whichever
+/// robot it belongs to.
+pub fn decode_frame(frame: &[u8]) -> Result<&[u8], FrameError> {
+    decode_robot_frame(frame).map(|(_, payload)| payload)
+}
+
+/// Check the version tag of a binary frame and return the robot it belongs
+/// to (`None` for the active robot) and its payload.
+pub fn decode_robot_frame(frame: &[u8]) -> Result<(Option<i64>, &[u8]), FrameError> {
+    let rest = match frame.split_first() {
+        None => return Err(FrameError::Empty),
+        Some((&PROTOCOL_VERSION, rest)) => rest,
+        Some((&actual, _)) => {
+            return Err(FrameError::VersionMismatch {
+                expected: PROTOCOL_VERSION,
+                actual,
+            })
+        }
+    };
+    match rest.split_first() {
+        Some((&ACTIVE_ROBOT, payload)) => Ok((None, payload)),
+        Some((&ROBOT_ID, rest)) if rest.len() >= 8 => {
+            let (id, payload) = rest.split_at(8);
+            let id = i64::from_le_bytes(id.try_into().expect("split at 8 bytes"));
+            Ok((Some(id), payload))
+        }
+        _ => Err(FrameError::BadRobotTag),
+    }
+}
+
+/// The server's answer to a client's `Hello`.
+pub fn negotiate_protocol(client_version: u8) -> ServerResponse {
+    if client_version == PROTOCOL_VERSION {
+        ServerResponse::Hello {
+            protocol_version: PROTOCOL_VERSION,
+        }
+    } else {
+        ServerResponse::ProtocolMismatch {
+            server_version: PROTOCOL_VERSION,
+            client_version,
+        }
+    }
+}
@@ -1,11 +1,12 @@
 //! Client request types for WebSocket API.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use fanuc_rmi::dto::FrameData;
-use crate::{StartPosition, NewRobotConfigurationDto};
+use crate::{StartPosition, NewRobotConfigurationDto, SafetyLimitsDto, InstructionDto, LengthUnit};
 
 /// Client requests to the server.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type")]
 pub enum ClientRequest {
     // Program Management
@@ -46,6 +47,11 @@ pub enum ClientRequest {
         default_term_value: Option<u8>,
     },
 
+    /// Parse CSV content as `UploadCsv` would and answer with a `CsvPreview`,
+    /// without saving anything.
+    #[serde(rename = "preview_csv")]
+    PreviewCsv { csv_content: String },
+
     /// Upload CSV content to a program.
     #[serde(rename = "upload_csv")]
     UploadCsv {
@@ -54,6 +60,38 @@ pub enum ClientRequest {
         start_position: Option<StartPosition>,
     },
 
+    /// Export a program's instructions as CSV in the upload format.
+    #[serde(rename = "export_program_csv")]
+    ExportProgramCsv { program_id: i64 },
+
+    /// Insert an instruction so it becomes line `instruction.line_number`;
+    /// that line and the ones after it move down by one. A line number past
+    /// the end appends. Answered with the updated `Program`.
+    #[serde(rename = "insert_instruction")]
+    InsertInstruction {
+        program_id: i64,
+        instruction: InstructionDto,
+    },
+
+    /// Replace line `instruction.line_number`. Answered with the updated
+    /// `Program`.
+    #[serde(rename = "update_instruction")]
+    UpdateInstruction {
+        program_id: i64,
+        instruction: InstructionDto,
+    },
+
+    /// Delete a line; the lines after it move up by one. Answered with the
+    /// updated `Program`.
+    #[serde(rename = "delete_instruction")]
+    DeleteInstruction { program_id: i64, line_number: i32 },
+
+    /// Put the lines in a new order. `order` lists every current line
+    /// number once, in the order the lines should run. Answered with the
+    /// updated `Program`.
+    #[serde(rename = "reorder_instructions")]
+    ReorderInstructions { program_id: i64, order: Vec<i32> },
+
     // Program Execution
     #[serde(rename = "load_program")]
     LoadProgram { program_id: i64 },
@@ -76,6 +114,20 @@ pub enum ClientRequest {
     #[serde(rename = "get_execution_state")]
     GetExecutionState,
 
+    /// Estimate how long a program takes to run, without loading it.
+    #[serde(rename = "estimate_program")]
+    EstimateProgram { program_id: i64 },
+
+    /// Pause the program before `line` is sent, each time it runs; resume
+    /// with `resume_program`. Answered with the program's `Breakpoints`.
+    #[serde(rename = "set_breakpoint")]
+    SetBreakpoint { program_id: i64, line: usize },
+
+    /// Remove a breakpoint set with `set_breakpoint`. Answered with the
+    /// program's remaining `Breakpoints`.
+    #[serde(rename = "clear_breakpoint")]
+    ClearBreakpoint { program_id: i64, line: usize },
+
     // Robot Control Commands
     #[serde(rename = "robot_abort")]
     RobotAbort,
@@ -104,10 +156,20 @@ pub enum ClientRequest {
     #[serde(rename = "reset_database")]
     ResetDatabase,
 
+    /// Recorded operator actions with an id greater than `since` (all if
+    /// `None`), at most `limit` of them (100 if `None`), oldest first. When
+    /// more match, the newest are returned. Answered with `EventLog`.
+    #[serde(rename = "get_event_log")]
+    GetEventLog { since: Option<i64>, limit: Option<u32> },
+
     // Connection Management
     #[serde(rename = "get_connection_status")]
     GetConnectionStatus,
 
+    /// Traffic and usage counters for monitoring. Read-only.
+    #[serde(rename = "get_metrics")]
+    GetMetrics,
+
     #[serde(rename = "connect_robot")]
     ConnectRobot { robot_addr: String, robot_port: u32 },
 
@@ -185,6 +247,14 @@ pub enum ClientRequest {
         rotation_jog_step: f64,
     },
 
+    /// Set the length unit a saved robot connection's positions are shown
+    /// and CSV files are read and written in.
+    #[serde(rename = "update_robot_connection_unit")]
+    UpdateRobotConnectionUnit {
+        id: i64,
+        coordinate_unit: LengthUnit,
+    },
+
     #[serde(rename = "update_jog_controls")]
     UpdateJogControls {
         cartesian_jog_speed: f64,
@@ -264,6 +334,11 @@ pub enum ClientRequest {
     #[serde(rename = "load_configuration")]
     LoadConfiguration { configuration_id: i64 },
 
+    /// Compare the active configuration with the saved configuration it
+    /// was loaded from.
+    #[serde(rename = "get_configuration_diff")]
+    GetConfigurationDiff,
+
     // Frame/Tool Management
     #[serde(rename = "get_active_frame_tool")]
     GetActiveFrameTool,
@@ -282,6 +357,7 @@ pub enum ClientRequest {
     WriteFrameData {
         frame_number: u8,
         #[serde(flatten)]
+        #[schemars(with = "crate::schema::FrameDataSchema")]
         data: FrameData,
     },
 
@@ -290,9 +366,15 @@ pub enum ClientRequest {
     WriteToolData {
         tool_number: u8,
         #[serde(flatten)]
+        #[schemars(with = "crate::schema::FrameDataSchema")]
         data: FrameData,
     },
 
+    // Controller errors
+    /// Read the controller's most recent error (`FRC_ReadError`).
+    #[serde(rename = "read_controller_error")]
+    ReadControllerError,
+
     // I/O Management - Digital
     #[serde(rename = "read_din")]
     ReadDin { port_number: u16 },
@@ -300,6 +382,11 @@ pub enum ClientRequest {
     #[serde(rename = "write_dout")]
     WriteDout { port_number: u16, port_value: bool },
 
+    /// Turn a digital output on, then off again after `duration_ms`. A
+    /// second pulse on the port before the first ends restarts the timer.
+    #[serde(rename = "pulse_dout")]
+    PulseDout { port_number: u16, duration_ms: u32 },
+
     #[serde(rename = "read_din_batch")]
     ReadDinBatch { port_numbers: Vec<u16> },
 
@@ -317,6 +404,36 @@ pub enum ClientRequest {
     #[serde(rename = "write_gout")]
     WriteGout { port_number: u16, port_value: u32 },
 
+    // I/O Management - Snapshot
+    /// Current value of every port in the given ranges, answered from the
+    /// server's I/O cache without reading the robot. Kinds left out are not
+    /// reported.
+    #[serde(rename = "read_io_snapshot")]
+    ReadIoSnapshot {
+        #[serde(default)]
+        din: Option<IoPortRange>,
+        #[serde(default)]
+        dout: Option<IoPortRange>,
+        #[serde(default)]
+        ain: Option<IoPortRange>,
+        #[serde(default)]
+        aout: Option<IoPortRange>,
+        #[serde(default)]
+        gin: Option<IoPortRange>,
+        #[serde(default)]
+        gout: Option<IoPortRange>,
+    },
+
+    // I/O Management - Subscriptions
+    /// Push an [`IoChanged`](crate::ServerResponse::IoChanged) to this client
+    /// whenever the server sees one of `points` change value. Subscriptions
+    /// are per client and end when it disconnects.
+    #[serde(rename = "subscribe_io")]
+    SubscribeIo { points: Vec<IoPoint> },
+    /// Stop pushing changes of `points` to this client.
+    #[serde(rename = "unsubscribe_io")]
+    UnsubscribeIo { points: Vec<IoPoint> },
+
     // I/O Configuration
     #[serde(rename = "get_io_config")]
     GetIoConfig { robot_connection_id: i64 },
@@ -329,6 +446,20 @@ pub enum ClientRequest {
         display_name: Option<String>,
         is_visible: bool,
         display_order: Option<i32>,
+        #[serde(default)]
+        warning_threshold: Option<f64>,
+        #[serde(default)]
+        alarm_threshold: Option<f64>,
+    },
+
+    // Safety limits
+    #[serde(rename = "get_safety_limits")]
+    GetSafetyLimits { robot_connection_id: i64 },
+
+    #[serde(rename = "update_safety_limits")]
+    UpdateSafetyLimits {
+        robot_connection_id: i64,
+        limits: SafetyLimitsDto,
     },
 
     // Control Locking
@@ -340,5 +471,132 @@ pub enum ClientRequest {
 
     #[serde(rename = "get_control_status")]
     GetControlStatus,
+
+    /// Ask the current holder to hand control over. The holder answers
+    /// with `GrantControl` or `DenyControl`; if it does not answer in time,
+    /// control stays where it is.
+    #[serde(rename = "request_control_handoff")]
+    RequestControlHandoff,
+
+    /// Hand control to the client whose handoff request is pending.
+    #[serde(rename = "grant_control")]
+    GrantControl,
+
+    /// Refuse the pending handoff request and keep control.
+    #[serde(rename = "deny_control")]
+    DenyControl,
+
+    /// Sent periodically by the control holder. If the holder goes quiet
+    /// for longer than the server's deadman timeout while the robot is
+    /// moving, the motion is aborted and control is released.
+    #[serde(rename = "heartbeat")]
+    Heartbeat,
+
+    // Jogging (requires control)
+    /// Jog `axis` until `JogStop`, loss of control or robot disconnect. The
+    /// server streams small relative moves at a fixed rate. `speed` is mm/s
+    /// for X/Y/Z and deg/s for W/P/R and the joints.
+    #[serde(rename = "jog_continuous")]
+    JogContinuous {
+        axis: JogAxis,
+        direction: JogDirection,
+        speed: f64,
+    },
+
+    #[serde(rename = "jog_stop")]
+    JogStop,
+
+    // Protocol handshake
+    /// First message after connecting; see [`crate::PROTOCOL_VERSION`].
+    #[serde(rename = "hello")]
+    Hello { protocol_version: u8 },
+}
+
+/// A [`ClientRequest`] addressed to one robot.
+///
+/// Every text request may carry a `robot_id` next to its `type`; it is the
+/// saved connection id of the robot the request is for. Requests without one
+/// go to the active robot, so clients that only drive one robot never set it.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RoutedRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub robot_id: Option<i64>,
+    #[serde(flatten)]
+    pub request: ClientRequest,
+}
+
+/// `count` consecutive I/O ports starting at `start`, as requested by
+/// [`ClientRequest::ReadIoSnapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct IoPortRange {
+    pub start: u16,
+    pub count: u16,
 }
 
+impl IoPortRange {
+    /// Port numbers in the range, stopping at `u16::MAX`.
+    pub fn ports(self) -> impl Iterator<Item = u16> {
+        (self.start..=u16::MAX).take(self.count as usize)
+    }
+}
+
+/// Kind of I/O port, named as in [`IoDisplayConfigDto::io_type`](crate::IoDisplayConfigDto::io_type).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum IoType {
+    Din,
+    Dout,
+    Ain,
+    Aout,
+    Gin,
+    Gout,
+}
+
+/// One I/O port, as subscribed to by [`ClientRequest::SubscribeIo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub struct IoPoint {
+    pub io_type: IoType,
+    pub port: u16,
+    /// Saved connection id of the robot the port belongs to, as in
+    /// [`RoutedRequest::robot_id`]; `None` for the active robot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub robot_id: Option<i64>,
+}
+
+/// Axis moved by a [`ClientRequest::JogContinuous`].
+///
+/// X/Y/Z/W/P/R jog the TCP in the active user frame; J1-J6 jog one joint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JogAxis {
+    X,
+    Y,
+    Z,
+    W,
+    P,
+    R,
+    J1,
+    J2,
+    J3,
+    J4,
+    J5,
+    J6,
+}
+
+/// Direction of a [`ClientRequest::JogContinuous`] along its axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JogDirection {
+    Positive,
+    Negative,
+}
+
+impl JogDirection {
+    /// `1.0` for [`JogDirection::Positive`], `-1.0` for [`JogDirection::Negative`].
+    pub fn sign(self) -> f64 {
+        match self {
+            JogDirection::Positive => 1.0,
+            JogDirection::Negative => -1.0,
+        }
+    }
+}
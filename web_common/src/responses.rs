@@ -1,14 +1,16 @@
 //! Server response types for WebSocket API.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use fanuc_rmi::dto::FrameData;
+use fanuc_rmi::dto::{FrameData, RobotStatus};
 use crate::{
     ProgramInfo, ProgramDetail, RobotSettingsDto, RobotConnectionDto,
-    RobotConfigurationDto, ChangeLogEntryDto, IoDisplayConfigDto,
+    RobotConfigurationDto, ChangeLogEntryDto, IoDisplayConfigDto, AlarmState, SafetyLimitsDto,
+    ExecutionState, IoPoint, ConfigurationWarning, AbortReason, InstructionDto, EventLogEntryDto,
 };
 
 /// Server responses to client.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type")]
 pub enum ServerResponse {
     #[serde(rename = "success")]
@@ -17,15 +19,38 @@ pub enum ServerResponse {
     #[serde(rename = "error")]
     Error { message: String },
 
+    /// A request rejected for invalid values before anything was sent to
+    /// the robot.
+    #[serde(rename = "validation_error")]
+    ValidationError { message: String },
+
     #[serde(rename = "programs")]
     Programs { programs: Vec<ProgramInfo> },
 
     #[serde(rename = "program")]
     Program { program: ProgramDetail },
 
+    /// A program exported as CSV, ready to save as `filename`.
+    #[serde(rename = "program_csv")]
+    ProgramCsv { filename: String, content: String },
+
+    /// Parsed CSV rows (X/Y/Z in millimetres) and the warnings and errors
+    /// an upload of the same content would produce. `errors` non-empty means
+    /// the upload would be rejected.
+    #[serde(rename = "csv_preview")]
+    CsvPreview {
+        instructions: Vec<InstructionDto>,
+        warnings: Vec<String>,
+        errors: Vec<String>,
+    },
+
     #[serde(rename = "settings")]
     Settings { settings: RobotSettingsDto },
 
+    /// Answer to `GetEventLog`.
+    #[serde(rename = "event_log")]
+    EventLog { events: Vec<EventLogEntryDto> },
+
     #[serde(rename = "execution_status")]
     ExecutionStatus {
         status: String,
@@ -47,6 +72,30 @@ pub enum ServerResponse {
         message: Option<String>,
     },
 
+    /// Estimated run time of a program (travel plus stop time per segment).
+    #[serde(rename = "program_estimate")]
+    ProgramEstimate {
+        program_id: i64,
+        seconds: f64,
+        segment_count: usize,
+    },
+
+    /// Lines a program pauses before, in order.
+    #[serde(rename = "breakpoints")]
+    Breakpoints {
+        program_id: i64,
+        lines: Vec<usize>,
+    },
+
+    /// Lines of a just-loaded program whose frame or tool number differs
+    /// from the active configuration. Sent in place of `Success` by
+    /// `load_program` when there are any.
+    #[serde(rename = "configuration_warnings")]
+    ConfigurationWarnings {
+        program_id: i64,
+        warnings: Vec<ConfigurationWarning>,
+    },
+
     #[serde(rename = "instruction_progress")]
     InstructionProgress {
         current_line: usize,
@@ -69,6 +118,24 @@ pub enum ServerResponse {
         tp_program_initialized: bool,
     },
 
+    /// Answer to [`ClientRequest::GetMetrics`](crate::ClientRequest::GetMetrics).
+    ///
+    /// Packet and error counts cover every driver the robot has had since
+    /// the server started.
+    #[serde(rename = "metrics")]
+    Metrics {
+        packets_sent: u64,
+        packets_received: u64,
+        /// Robot responses that failed to parse
+        protocol_errors: u64,
+        /// Connects after the first
+        reconnects: u64,
+        active_clients: usize,
+        control_holder: Option<String>,
+        /// Programs started on the active robot
+        program_runs: u64,
+    },
+
     #[serde(rename = "robot_connected")]
     RobotConnected {
         connection_id: i64,
@@ -105,13 +172,27 @@ pub enum ServerResponse {
         message: Option<String>,
     },
 
+    /// The robot's `FRC_GetStatus` state changed since the previous poll.
+    #[serde(rename = "status_changed")]
+    StatusChanged {
+        #[schemars(with = "crate::schema::RobotStatusSchema")]
+        status: RobotStatus,
+    },
+
     #[serde(rename = "execution_state_changed")]
     ExecutionStateChanged {
+        #[serde(default)]
+        execution_state: ExecutionState,
+        /// Deprecated: [`ExecutionState::as_str`] of `execution_state`, kept
+        /// for older clients. Match on `execution_state` instead.
         state: String,
         program_id: Option<i64>,
         current_line: Option<usize>,
         total_lines: Option<usize>,
         message: Option<String>,
+        /// Program called from `current_line`, while a `CALL` line runs.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        subprogram: Option<String>,
     },
 
     #[serde(rename = "robot_connections")]
@@ -156,6 +237,17 @@ pub enum ServerResponse {
         default_rotation_jog_step: f64,
     },
 
+    /// Answer to [`ClientRequest::GetConfigurationDiff`](crate::ClientRequest::GetConfigurationDiff).
+    /// One entry per field where the active configuration differs from the
+    /// saved one: `old_value` is the saved value, `new_value` the active one.
+    /// Empty when nothing has changed.
+    #[serde(rename = "configuration_diff")]
+    ConfigurationDiff {
+        configuration_id: i64,
+        configuration_name: String,
+        differences: Vec<ChangeLogEntryDto>,
+    },
+
     #[serde(rename = "active_jog_settings")]
     ActiveJogSettings {
         cartesian_jog_speed: f64,
@@ -175,6 +267,7 @@ pub enum ServerResponse {
     FrameDataResponse {
         frame_number: u8,
         #[serde(flatten)]
+        #[schemars(with = "crate::schema::FrameDataSchema")]
         data: FrameData,
     },
 
@@ -183,9 +276,22 @@ pub enum ServerResponse {
     ToolDataResponse {
         tool_number: u8,
         #[serde(flatten)]
+        #[schemars(with = "crate::schema::FrameDataSchema")]
         data: FrameData,
     },
 
+    /// Answer to [`ClientRequest::ReadControllerError`](crate::ClientRequest::ReadControllerError).
+    /// `error_id` is 0 when the controller has no error. `message` is the
+    /// error's description and `cause_code` its code (e.g. `RMIT-009`), both
+    /// from the RMI error table; unknown ids fall back to the controller's
+    /// own error text and have no code.
+    #[serde(rename = "controller_error")]
+    ControllerError {
+        error_id: u32,
+        message: String,
+        cause_code: Option<String>,
+    },
+
     // I/O responses (inputs - read only)
     #[serde(rename = "din_value")]
     DinValue { port_number: u16, port_value: bool },
@@ -194,25 +300,97 @@ pub enum ServerResponse {
     DinBatch { values: Vec<(u16, bool)> },
 
     #[serde(rename = "ain_value")]
-    AinValue { port_number: u16, port_value: f64 },
+    AinValue {
+        port_number: u16,
+        port_value: f64,
+        #[serde(default)]
+        alarm_state: AlarmState,
+    },
 
     #[serde(rename = "gin_value")]
     GinValue { port_number: u16, port_value: u32 },
 
-    // I/O responses (outputs - broadcast after successful write)
+    // I/O responses (outputs - broadcast after successful write). `robot_id`
+    // names the robot written to, as in `RoutedRequest::robot_id`; `None`
+    // for the active robot.
     #[serde(rename = "dout_value")]
-    DoutValue { port_number: u16, port_value: bool },
+    DoutValue {
+        port_number: u16,
+        port_value: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        robot_id: Option<i64>,
+    },
 
     #[serde(rename = "aout_value")]
-    AoutValue { port_number: u16, port_value: f64 },
+    AoutValue {
+        port_number: u16,
+        port_value: f64,
+        #[serde(default)]
+        alarm_state: AlarmState,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        robot_id: Option<i64>,
+    },
 
     #[serde(rename = "gout_value")]
-    GoutValue { port_number: u16, port_value: u32 },
+    GoutValue {
+        port_number: u16,
+        port_value: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        robot_id: Option<i64>,
+    },
+
+    /// Answer to [`ClientRequest::ReadIoSnapshot`](crate::ClientRequest::ReadIoSnapshot):
+    /// `(port, value)` for every requested port. Ports the server has never
+    /// seen read or written report `false` / `0`.
+    #[serde(rename = "io_snapshot")]
+    IoSnapshot {
+        din: Vec<(u16, bool)>,
+        dout: Vec<(u16, bool)>,
+        ain: Vec<(u16, f64)>,
+        aout: Vec<(u16, f64)>,
+        gin: Vec<(u16, u32)>,
+        gout: Vec<(u16, u32)>,
+    },
+
+    /// A subscribed I/O point changed value (see
+    /// [`ClientRequest::SubscribeIo`](crate::ClientRequest::SubscribeIo)).
+    /// Digital values are `0.0` / `1.0`.
+    #[serde(rename = "io_changed")]
+    IoChanged { point: IoPoint, value: f64 },
 
     // I/O configuration responses
     #[serde(rename = "io_config")]
     IoConfig { configs: Vec<IoDisplayConfigDto> },
 
+    // Safety limit responses
+    #[serde(rename = "safety_limits")]
+    SafetyLimits {
+        robot_connection_id: i64,
+        limits: SafetyLimitsDto,
+    },
+
+    /// A motion packet was refused because it breaks the connection's
+    /// safety limits. The packet was not sent, and motion already under way
+    /// is aborted (see [`ServerResponse::MotionAborted`]).
+    #[serde(rename = "safety_violation")]
+    SafetyViolation { message: String },
+
+    /// A polled joint angle came within the warning margin of that joint's
+    /// limit. `joint` is the joint number (2 for J2) and `remaining_deg` the
+    /// distance left to the nearer limit. Sent once each time the joint
+    /// enters the margin.
+    #[serde(rename = "joint_limit_warning")]
+    JointLimitWarning { joint: u8, remaining_deg: f64 },
+
+    /// A reported TCP speed (mm/s) exceeded the cell's TCP speed `limit`.
+    /// Sent once each time the speed goes over the limit.
+    #[serde(rename = "overspeed_warning")]
+    OverspeedWarning { speed: f64, limit: f64 },
+
+    /// Broadcast when the server has stopped the robot with `FRC_Abort`.
+    #[serde(rename = "motion_aborted")]
+    MotionAborted { reason: AbortReason },
+
     // Control lock responses
     #[serde(rename = "control_acquired")]
     ControlAcquired,
@@ -239,5 +417,33 @@ pub enum ServerResponse {
         has_control: bool,
         holder_id: Option<String>,
     },
+
+    /// Sent to the control holder: client `from` asks for control. Answer
+    /// with `GrantControl` or `DenyControl`.
+    #[serde(rename = "handoff_requested")]
+    HandoffRequested { from: String },
+
+    /// The handoff request was passed to `holder_id`. The outcome arrives
+    /// later as `ControlAcquired` or `ControlDenied`.
+    #[serde(rename = "handoff_pending")]
+    HandoffPending { holder_id: String },
+
+    /// Answer to `Heartbeat`. `has_control` is false once the sender no
+    /// longer holds control.
+    #[serde(rename = "heartbeat_ack")]
+    HeartbeatAck { has_control: bool },
+
+    // Protocol handshake responses
+    /// The client's protocol version matches the server's.
+    #[serde(rename = "hello")]
+    Hello { protocol_version: u8 },
+
+    /// The client and server speak different protocol versions. The server
+    /// closes the connection after sending this.
+    #[serde(rename = "protocol_mismatch")]
+    ProtocolMismatch {
+        server_version: u8,
+        client_version: u8,
+    },
 }
 
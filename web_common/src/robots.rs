@@ -1,11 +1,14 @@
 //! Robot connection and configuration DTOs.
 
+use fanuc_rmi::{ArmConfig, ArmConfigError};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use crate::LengthUnit;
 
 /// Robot connection DTO (for saved connections).
 /// Motion defaults (speed, term_type, w/p/r) and jog defaults are stored here.
 /// Frame/tool/arm configuration is stored in robot_configurations table.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct RobotConnectionDto {
     pub id: i64,
     pub name: String,
@@ -26,10 +29,13 @@ pub struct RobotConnectionDto {
     pub default_joint_jog_step: f64,
     pub default_rotation_jog_speed: f64,
     pub default_rotation_jog_step: f64,
+    /// Unit positions are shown and CSV files are read and written in.
+    #[serde(default)]
+    pub coordinate_unit: LengthUnit,
 }
 
 /// Robot configuration DTO (named configurations per robot).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RobotConfigurationDto {
     pub id: i64,
     pub robot_connection_id: i64,
@@ -47,7 +53,7 @@ pub struct RobotConfigurationDto {
 }
 
 /// New robot configuration DTO (for creating configurations without ID).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NewRobotConfigurationDto {
     pub name: String,
     pub is_default: bool,
@@ -62,3 +68,35 @@ pub struct NewRobotConfigurationDto {
     pub turn6: i32,
 }
 
+
+impl TryFrom<&RobotConfigurationDto> for ArmConfig {
+    type Error = ArmConfigError;
+
+    fn try_from(config: &RobotConfigurationDto) -> Result<Self, Self::Error> {
+        ArmConfig::try_from_raw(
+            config.front,
+            config.up,
+            config.left,
+            config.flip,
+            config.turn4,
+            config.turn5,
+            config.turn6,
+        )
+    }
+}
+
+impl TryFrom<&NewRobotConfigurationDto> for ArmConfig {
+    type Error = ArmConfigError;
+
+    fn try_from(config: &NewRobotConfigurationDto) -> Result<Self, Self::Error> {
+        ArmConfig::try_from_raw(
+            config.front,
+            config.up,
+            config.left,
+            config.flip,
+            config.turn4,
+            config.turn5,
+            config.turn6,
+        )
+    }
+}
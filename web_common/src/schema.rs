@@ -0,0 +1,60 @@
+//! JSON Schema (draft 2020-12) for the WebSocket API.
+//!
+//! [`api_schema`] describes every [`RoutedRequest`] and [`ServerResponse`]
+//! message together with the DTOs they carry. The schemas are derived with
+//! `schemars` from the same serde attributes that define the wire format.
+//!
+//! Print the schema with `cargo run -p web_common --bin api_schema`.
+
+use crate::{ClientRequest, RoutedRequest, ServerResponse};
+use schemars::generate::SchemaSettings;
+use schemars::JsonSchema;
+use serde_json::{json, Value};
+
+/// JSON Schema dialect emitted by [`api_schema`].
+pub const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// The full WebSocket API schema: one of a [`RoutedRequest`] or a
+/// [`ServerResponse`], with all referenced types under `$defs`.
+pub fn api_schema() -> Value {
+    let mut generator = SchemaSettings::draft2020_12().into_generator();
+    let client_request = generator.subschema_for::<RoutedRequest>();
+    let server_response = generator.subschema_for::<ServerResponse>();
+    // `RoutedRequest` flattens the request into itself; list it on its own too.
+    generator.subschema_for::<ClientRequest>();
+    json!({
+        "$schema": SCHEMA_DIALECT,
+        "title": "FANUC RMI WebSocket API",
+        "description": "Messages exchanged over the web server WebSocket. Clients send ClientRequest (optionally addressed to a robot), the server sends ServerResponse.",
+        "oneOf": [client_request, server_response],
+        "$defs": generator.take_definitions(true),
+    })
+}
+
+/// Schema for [`fanuc_rmi::dto::FrameData`], which lives outside this crate.
+#[allow(dead_code)] // only describes the shape, never constructed
+#[derive(JsonSchema)]
+#[schemars(rename = "FrameData")]
+pub(crate) struct FrameDataSchema {
+    x: f64,
+    y: f64,
+    z: f64,
+    w: f64,
+    p: f64,
+    r: f64,
+}
+
+/// Schema for [`fanuc_rmi::dto::RobotStatus`], which lives outside this crate.
+#[allow(dead_code)] // only describes the shape, never constructed
+#[derive(JsonSchema)]
+#[schemars(rename = "RobotStatus")]
+pub(crate) struct RobotStatusSchema {
+    servo_ready: bool,
+    tp_mode: bool,
+    rmi_motion_status: i8,
+    program_status: i8,
+    single_step_mode: bool,
+    number_utool: i8,
+    number_uframe: i8,
+    override_value: u32,
+}
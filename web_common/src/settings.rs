@@ -1,9 +1,10 @@
 //! Settings and configuration DTOs.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Robot settings DTO.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RobotSettingsDto {
     pub default_w: f64,
     pub default_p: f64,
@@ -15,7 +16,7 @@ pub struct RobotSettingsDto {
 }
 
 /// A single change entry in the changelog.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ChangeLogEntryDto {
     pub field_name: String,
     pub old_value: String,
@@ -23,12 +24,145 @@ pub struct ChangeLogEntryDto {
 }
 
 /// I/O display configuration DTO.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct IoDisplayConfigDto {
     pub io_type: String,
     pub io_index: i32,
     pub display_name: Option<String>,
     pub is_visible: bool,
     pub display_order: Option<i32>,
+    /// Analog value at or above which the port is in [`AlarmState::Warning`].
+    #[serde(default)]
+    pub warning_threshold: Option<f64>,
+    /// Analog value at or above which the port is in [`AlarmState::Alarm`].
+    #[serde(default)]
+    pub alarm_threshold: Option<f64>,
 }
 
+/// Alarm state of an analog I/O value, computed by the server from the
+/// thresholds in the I/O display configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+pub enum AlarmState {
+    #[default]
+    Normal,
+    Warning,
+    Alarm,
+}
+
+impl AlarmState {
+    /// Classify `value` against the configured thresholds.
+    ///
+    /// A missing threshold never triggers, so a port without thresholds is
+    /// always `Normal`.
+    pub fn evaluate(value: f64, warning_threshold: Option<f64>, alarm_threshold: Option<f64>) -> Self {
+        if alarm_threshold.is_some_and(|t| value >= t) {
+            AlarmState::Alarm
+        } else if warning_threshold.is_some_and(|t| value >= t) {
+            AlarmState::Warning
+        } else {
+            AlarmState::Normal
+        }
+    }
+}
+
+
+/// Server-side motion guardrails for one saved robot connection.
+///
+/// The server checks motion packets sent over the binary channel against
+/// these limits before they reach the robot. A missing bound is not enforced.
+/// Position bounds (mm, in the active user frame) apply to absolute
+/// Cartesian targets only.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SafetyLimitsDto {
+    pub x_min: Option<f64>,
+    pub x_max: Option<f64>,
+    pub y_min: Option<f64>,
+    pub y_max: Option<f64>,
+    pub z_min: Option<f64>,
+    pub z_max: Option<f64>,
+    /// Maximum TCP speed in mm/s.
+    pub max_speed: Option<f64>,
+    /// What to do with a move faster than `max_speed`.
+    #[serde(default)]
+    pub speed_limit_action: SpeedLimitAction,
+}
+
+/// Handling of a move that exceeds [`SafetyLimitsDto::max_speed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+pub enum SpeedLimitAction {
+    /// Refuse the move with a [`ServerResponse::SafetyViolation`](crate::ServerResponse::SafetyViolation).
+    #[default]
+    Reject,
+    /// Send the move at `max_speed` instead.
+    Clamp,
+}
+
+/// Length unit a robot connection's positions are shown, entered and
+/// imported in. Stored programs and everything sent to the controller stay
+/// in millimetres; only the presentation and import layers convert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+pub enum LengthUnit {
+    #[default]
+    Millimeters,
+    Inches,
+}
+
+impl LengthUnit {
+    /// Millimetres in one inch.
+    pub const MM_PER_INCH: f64 = 25.4;
+
+    /// Millimetres in one of this unit.
+    pub fn mm_per_unit(self) -> f64 {
+        match self {
+            LengthUnit::Millimeters => 1.0,
+            LengthUnit::Inches => Self::MM_PER_INCH,
+        }
+    }
+
+    /// A length in this unit, in millimetres.
+    pub fn to_mm(self, value: f64) -> f64 {
+        value * self.mm_per_unit()
+    }
+
+    /// A length in millimetres, in this unit.
+    pub fn from_mm(self, value: f64) -> f64 {
+        value / self.mm_per_unit()
+    }
+
+    /// A position given in this unit, in millimetres. Orientation and
+    /// external axes are unchanged.
+    pub fn position_to_mm(self, position: &fanuc_rmi::Position) -> fanuc_rmi::Position {
+        position.scale_translation(self.mm_per_unit())
+    }
+
+    /// A position given in millimetres, in this unit. Orientation and
+    /// external axes are unchanged.
+    pub fn position_from_mm(self, position: &fanuc_rmi::Position) -> fanuc_rmi::Position {
+        position.scale_translation(1.0 / self.mm_per_unit())
+    }
+
+    /// Short label shown after values, e.g. "mm".
+    pub fn suffix(self) -> &'static str {
+        match self {
+            LengthUnit::Millimeters => "mm",
+            LengthUnit::Inches => "in",
+        }
+    }
+
+    /// Name stored in the database.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LengthUnit::Millimeters => "mm",
+            LengthUnit::Inches => "inch",
+        }
+    }
+
+    /// Parse a name stored by [`as_str`](Self::as_str). Unknown names are
+    /// millimetres, the controller's unit.
+    pub fn from_db(name: &str) -> Self {
+        match name {
+            "inch" => LengthUnit::Inches,
+            _ => LengthUnit::Millimeters,
+        }
+    }
+}
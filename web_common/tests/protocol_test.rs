@@ -0,0 +1,86 @@
+//! Tests for protocol version negotiation and versioned binary frames.
+
+use web_common::{
+    decode_frame, decode_robot_frame, encode_frame, encode_robot_frame, negotiate_protocol,
+    ClientRequest, FrameError, RoutedRequest, ServerResponse, PROTOCOL_VERSION,
+};
+
+#[test]
+fn test_frame_roundtrip() {
+    let payload = [0xde, 0xad, 0xbe, 0xef];
+    let frame = encode_frame(&payload);
+    assert_eq!(frame[0], PROTOCOL_VERSION);
+    assert_eq!(decode_frame(&frame), Ok(&payload[..]));
+    assert_eq!(decode_robot_frame(&frame), Ok((None, &payload[..])));
+}
+
+#[test]
+fn test_robot_frame_roundtrip() {
+    let payload = [0xde, 0xad, 0xbe, 0xef];
+    let frame = encode_robot_frame(-42, &payload);
+    assert_eq!(frame[0], PROTOCOL_VERSION);
+    assert_eq!(decode_robot_frame(&frame), Ok((Some(-42), &payload[..])));
+    assert_eq!(decode_frame(&frame), Ok(&payload[..]));
+
+    // A robot id cut short, or an unknown tag, is rejected
+    assert_eq!(decode_robot_frame(&frame[..6]), Err(FrameError::BadRobotTag));
+    assert_eq!(decode_robot_frame(&[PROTOCOL_VERSION, 7, 0]), Err(FrameError::BadRobotTag));
+    assert_eq!(decode_robot_frame(&[PROTOCOL_VERSION]), Err(FrameError::BadRobotTag));
+}
+
+#[test]
+fn test_mismatched_frame_version_is_rejected() {
+    let stale = PROTOCOL_VERSION.wrapping_add(1);
+    let frame = [stale, 0, 0, 0, 0];
+    assert_eq!(
+        decode_frame(&frame),
+        Err(FrameError::VersionMismatch {
+            expected: PROTOCOL_VERSION,
+            actual: stale,
+        })
+    );
+    assert_eq!(decode_frame(&[]), Err(FrameError::Empty));
+}
+
+#[test]
+fn test_matching_hello_is_accepted() {
+    match negotiate_protocol(PROTOCOL_VERSION) {
+        ServerResponse::Hello { protocol_version } => assert_eq!(protocol_version, PROTOCOL_VERSION),
+        other => panic!("expected Hello, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_mismatched_hello_is_rejected() {
+    let stale = PROTOCOL_VERSION.wrapping_add(1);
+    let request: ClientRequest =
+        serde_json::from_str(&format!(r#"{{"type":"hello","protocol_version":{}}}"#, stale)).unwrap();
+    let ClientRequest::Hello { protocol_version } = request else {
+        panic!("expected Hello request");
+    };
+
+    let response = negotiate_protocol(protocol_version);
+    let json = serde_json::to_string(&response).unwrap();
+    assert_eq!(
+        json,
+        format!(
+            r#"{{"type":"protocol_mismatch","server_version":{},"client_version":{}}}"#,
+            PROTOCOL_VERSION, stale
+        )
+    );
+}
+
+#[test]
+fn test_routed_request_defaults_to_active_robot() {
+    let routed: RoutedRequest = serde_json::from_str(r#"{"type":"robot_abort"}"#).unwrap();
+    assert_eq!(routed.robot_id, None);
+    assert!(matches!(routed.request, ClientRequest::RobotAbort));
+
+    let routed: RoutedRequest =
+        serde_json::from_str(r#"{"type":"read_din","port_number":3,"robot_id":7}"#).unwrap();
+    assert_eq!(routed.robot_id, Some(7));
+    assert!(matches!(routed.request, ClientRequest::ReadDin { port_number: 3 }));
+
+    let json = serde_json::to_string(&RoutedRequest { robot_id: None, request: ClientRequest::RobotAbort }).unwrap();
+    assert_eq!(json, r#"{"type":"robot_abort"}"#);
+}
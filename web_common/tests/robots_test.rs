@@ -0,0 +1,77 @@
+//! Tests for converting saved robot configurations into `ArmConfig`.
+
+use fanuc_rmi::{ArmConfig, ArmConfigError};
+use web_common::{NewRobotConfigurationDto, RobotConfigurationDto};
+
+fn flipped_with_turns() -> ArmConfig {
+    ArmConfig {
+        front: false,
+        up: true,
+        left: true,
+        flip: true,
+        turn4: -1,
+        turn5: 0,
+        turn6: 2,
+    }
+}
+
+fn saved_configuration(arm: ArmConfig) -> RobotConfigurationDto {
+    let [front, up, left, flip, turn4, turn5, turn6] = arm.to_raw();
+    RobotConfigurationDto {
+        id: 1,
+        robot_connection_id: 1,
+        name: "Default".to_string(),
+        is_default: true,
+        u_frame_number: 2,
+        u_tool_number: 3,
+        front,
+        up,
+        left,
+        flip,
+        turn4,
+        turn5,
+        turn6,
+    }
+}
+
+#[test]
+fn test_saved_configuration_round_trip() {
+    let config = saved_configuration(flipped_with_turns());
+    assert_eq!(ArmConfig::try_from(&config), Ok(flipped_with_turns()));
+}
+
+#[test]
+fn test_new_configuration_round_trip() {
+    let [front, up, left, flip, turn4, turn5, turn6] = flipped_with_turns().to_raw();
+    let config = NewRobotConfigurationDto {
+        name: "Flipped".to_string(),
+        is_default: false,
+        u_frame_number: 0,
+        u_tool_number: 1,
+        front,
+        up,
+        left,
+        flip,
+        turn4,
+        turn5,
+        turn6,
+    };
+    assert_eq!(ArmConfig::try_from(&config), Ok(flipped_with_turns()));
+}
+
+#[test]
+fn test_invalid_saved_configuration_is_rejected() {
+    let mut config = saved_configuration(flipped_with_turns());
+    config.up = -1;
+    assert_eq!(
+        ArmConfig::try_from(&config),
+        Err(ArmConfigError::InvalidBit { field: "up", value: -1 })
+    );
+
+    let mut config = saved_configuration(flipped_with_turns());
+    config.turn6 = -9;
+    assert_eq!(
+        ArmConfig::try_from(&config),
+        Err(ArmConfigError::TurnOutOfRange { field: "turn6", value: -9 })
+    );
+}
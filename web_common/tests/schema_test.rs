@@ -0,0 +1,162 @@
+//! Tests for the WebSocket API JSON Schema export.
+//!
+//! The variant tags are taken from serde itself (the "unknown variant" error
+//! lists every accepted tag), so these tests catch a schema that drifts from
+//! the real wire format.
+
+use serde_json::Value;
+use web_common::{api_schema, ClientRequest, FrameData, RobotStatus, ServerResponse, SCHEMA_DIALECT};
+
+/// Every tag serde accepts for an internally tagged enum `T`.
+fn serde_tags<T: serde::de::DeserializeOwned + std::fmt::Debug>() -> Vec<String> {
+    let err = serde_json::from_str::<T>(r#"{"type":"__probe__"}"#).unwrap_err();
+    let message = err.to_string();
+    let expected = message
+        .split("expected one of ")
+        .nth(1)
+        .unwrap_or_else(|| panic!("unexpected serde error: {}", message));
+    expected
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .map(str::to_string)
+        .collect()
+}
+
+/// The `type` consts listed by the `oneOf` of definition `name`.
+fn schema_tags(schema: &Value, name: &str) -> Vec<String> {
+    schema["$defs"][name]["oneOf"]
+        .as_array()
+        .unwrap_or_else(|| panic!("{} has no oneOf", name))
+        .iter()
+        .map(|variant| {
+            variant["properties"]["type"]["const"]
+                .as_str()
+                .unwrap_or_else(|| panic!("variant without a type const: {}", variant))
+                .to_string()
+        })
+        .collect()
+}
+
+fn collect_refs<'a>(value: &'a Value, refs: &mut Vec<&'a str>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(r)) = map.get("$ref") {
+                refs.push(r);
+            }
+            map.values().for_each(|v| collect_refs(v, refs));
+        }
+        Value::Array(items) => items.iter().for_each(|v| collect_refs(v, refs)),
+        _ => {}
+    }
+}
+
+#[test]
+fn test_schema_declares_draft_2020_12() {
+    let schema = api_schema();
+    assert_eq!(schema["$schema"], SCHEMA_DIALECT);
+    assert_eq!(SCHEMA_DIALECT, "https://json-schema.org/draft/2020-12/schema");
+}
+
+#[test]
+fn test_every_client_request_variant_is_described() {
+    let schema = api_schema();
+    let tags = serde_tags::<ClientRequest>();
+    assert!(!tags.is_empty());
+    let described = schema_tags(&schema, "ClientRequest");
+    for tag in &tags {
+        assert!(described.contains(tag), "ClientRequest variant `{}` missing from schema", tag);
+    }
+    assert_eq!(described.len(), tags.len(), "schema lists unknown ClientRequest variants");
+}
+
+#[test]
+fn test_every_server_response_variant_is_described() {
+    let schema = api_schema();
+    let tags = serde_tags::<ServerResponse>();
+    let described = schema_tags(&schema, "ServerResponse");
+    for tag in &tags {
+        assert!(described.contains(tag), "ServerResponse variant `{}` missing from schema", tag);
+    }
+    assert_eq!(described.len(), tags.len(), "schema lists unknown ServerResponse variants");
+}
+
+#[test]
+fn test_all_refs_resolve() {
+    let schema = api_schema();
+    let mut refs = Vec::new();
+    collect_refs(&schema, &mut refs);
+    assert!(!refs.is_empty());
+    for r in refs {
+        let name = r
+            .strip_prefix("#/$defs/")
+            .unwrap_or_else(|| panic!("non-local $ref {}", r));
+        assert!(schema["$defs"].get(name).is_some(), "unresolved $ref {}", r);
+    }
+}
+
+#[test]
+fn test_optional_fields_are_not_required() {
+    let schema = api_schema();
+    let create = schema["$defs"]["ClientRequest"]["oneOf"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|v| v["properties"]["type"]["const"] == "create_program")
+        .expect("create_program variant");
+    let required: Vec<&str> = create["required"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(Value::as_str)
+        .collect();
+    assert_eq!(required, ["type", "name"]);
+}
+
+/// Property names of `value` serialized as a JSON object.
+fn wire_fields(value: impl serde::Serialize) -> Vec<String> {
+    let mut fields: Vec<String> = serde_json::to_value(value)
+        .unwrap()
+        .as_object()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect();
+    fields.sort();
+    fields
+}
+
+fn schema_fields(schema: &Value) -> Vec<String> {
+    let mut fields: Vec<String> = schema["properties"].as_object().unwrap().keys().cloned().collect();
+    fields.sort();
+    fields
+}
+
+#[test]
+fn test_fanuc_rmi_types_match_their_schemas() {
+    let schema = api_schema();
+
+    let status = RobotStatus {
+        servo_ready: true,
+        tp_mode: false,
+        rmi_motion_status: 0,
+        program_status: 0,
+        single_step_mode: false,
+        number_utool: 1,
+        number_uframe: 0,
+        override_value: 100,
+    };
+    assert_eq!(schema_fields(&schema["$defs"]["RobotStatus"]), wire_fields(status));
+
+    let frame_data = schema["$defs"]["ServerResponse"]["oneOf"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|v| v["properties"]["type"]["const"] == "frame_data")
+        .expect("frame_data variant");
+    let data = FrameData { x: 1.0, y: 2.0, z: 3.0, w: 0.0, p: 0.0, r: 0.0 };
+    let mut expected = wire_fields(data);
+    expected.extend(["frame_number".to_string(), "type".to_string()]);
+    expected.sort();
+    assert_eq!(schema_fields(frame_data), expected);
+}
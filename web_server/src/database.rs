@@ -3,7 +3,7 @@
 //! Database location: `./data/fanuc_rmi.db` (relative to executable)
 //! The directory is created automatically if it doesn't exist.
 
-use rusqlite::{Connection, Result, params};
+use rusqlite::{Connection, OptionalExtension, Result, params};
 use std::path::Path;
 use std::fs;
 
@@ -46,10 +46,15 @@ pub struct Program {
     pub move_speed: Option<f64>,
     pub created_at: String,
     pub updated_at: String,
+    // Run history
+    /// Times the program has run to completion.
+    pub run_count: i64,
+    /// When the program last ran to completion, `None` if it never has.
+    pub last_run_at: Option<String>,
 }
 
 /// A single instruction in a program.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 #[allow(dead_code)]
 pub struct ProgramInstruction {
     pub id: i64,
@@ -72,6 +77,9 @@ pub struct ProgramInstruction {
     pub term_value: Option<u8>,
     pub uframe: Option<i32>,
     pub utool: Option<i32>,
+    /// Control instruction text (e.g. `LBL[1]`, `JMP LBL[1]`, `REPEAT 3`, `CALL name`).
+    /// When set, the row is a control line and its position fields are unused.
+    pub control: Option<String>,
 }
 
 /// Robot default settings (per-robot configuration).
@@ -114,6 +122,9 @@ pub struct RobotConnection {
     pub default_joint_jog_step: f64,
     pub default_rotation_jog_speed: f64,
     pub default_rotation_jog_step: f64,
+    /// Length unit for display and CSV import/export ("mm" or "inch").
+    /// Stored positions are always mm.
+    pub coordinate_unit: String,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -153,6 +164,23 @@ pub struct IoDisplayConfig {
     pub display_name: Option<String>,
     pub is_visible: bool,
     pub display_order: Option<i32>,
+    /// Analog warning threshold (AIN/AOUT only).
+    pub warning_threshold: Option<f64>,
+    /// Analog alarm threshold (AIN/AOUT only).
+    pub alarm_threshold: Option<f64>,
+}
+
+/// Motion safety limits for a robot. `None` means the bound is not enforced.
+#[derive(Debug, Clone, Default)]
+pub struct SafetyLimits {
+    pub x_min: Option<f64>,
+    pub x_max: Option<f64>,
+    pub y_min: Option<f64>,
+    pub y_max: Option<f64>,
+    pub z_min: Option<f64>,
+    pub z_max: Option<f64>,
+    pub max_speed: Option<f64>,
+    pub speed_limit_action: String,  // 'Reject', 'Clamp'
 }
 
 /// Server setting key-value pair.
@@ -165,6 +193,16 @@ pub struct ServerSetting {
     pub description: Option<String>,
 }
 
+/// A recorded operator action.
+#[derive(Debug, Clone)]
+pub struct EventLogEntry {
+    pub id: i64,
+    pub timestamp: String,
+    pub kind: String,
+    pub client_id: Option<String>,
+    pub message: String,
+}
+
 impl Database {
     /// Default database path.
     pub const DEFAULT_PATH: &'static str = "./data/fanuc_rmi.db";
@@ -203,6 +241,7 @@ impl Database {
             ("default_joint_jog_step", "REAL"),
             ("default_rotation_jog_speed", "REAL"),
             ("default_rotation_jog_step", "REAL"),
+            ("coordinate_unit", "TEXT"),  // mm, inch
         ];
 
         for (column_name, column_type) in columns_to_add {
@@ -259,6 +298,8 @@ impl Database {
             ("end_y", "REAL"),
             ("end_z", "REAL"),
             ("move_speed", "REAL DEFAULT 100.0"),
+            ("run_count", "INTEGER NOT NULL DEFAULT 0"),
+            ("last_run_at", "TIMESTAMP"),
         ];
 
         for (column_name, column_type) in program_columns_to_add {
@@ -310,6 +351,39 @@ impl Database {
             tracing::info!("Migration: Added column term_value to program_instructions");
         }
 
+        // Migration: Add control column to program_instructions if it doesn't exist
+        let column_exists = self
+            .conn
+            .prepare("SELECT control FROM program_instructions LIMIT 1")
+            .is_ok();
+
+        if !column_exists {
+            self.conn.execute(
+                "ALTER TABLE program_instructions ADD COLUMN control TEXT",
+                [],
+            )?;
+            tracing::info!("Migration: Added column control to program_instructions");
+        }
+
+        // Migration: Add analog alarm thresholds to io_display_config if they don't exist
+        for column_name in ["warning_threshold", "alarm_threshold"] {
+            let column_exists = self
+                .conn
+                .prepare(&format!(
+                    "SELECT {} FROM io_display_config LIMIT 1",
+                    column_name
+                ))
+                .is_ok();
+
+            if !column_exists {
+                self.conn.execute(
+                    &format!("ALTER TABLE io_display_config ADD COLUMN {} REAL", column_name),
+                    [],
+                )?;
+                tracing::info!("Migration: Added column {} to io_display_config", column_name);
+            }
+        }
+
         // Migration: Add default_term_value column to programs if it doesn't exist
         let column_exists = self
             .conn
@@ -358,7 +432,10 @@ impl Database {
                 end_r REAL,
                 move_speed REAL DEFAULT 100.0,
                 created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-                updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+                updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                -- Run history
+                run_count INTEGER NOT NULL DEFAULT 0,
+                last_run_at TIMESTAMP
             );
 
             CREATE TABLE IF NOT EXISTS program_instructions (
@@ -379,6 +456,7 @@ impl Database {
                 term_value INTEGER,
                 uframe INTEGER,
                 utool INTEGER,
+                control TEXT,
                 FOREIGN KEY (program_id) REFERENCES programs(id) ON DELETE CASCADE
             );
 
@@ -422,10 +500,26 @@ impl Database {
                 display_name TEXT,
                 is_visible INTEGER DEFAULT 1,
                 display_order INTEGER,
+                warning_threshold REAL,
+                alarm_threshold REAL,
                 FOREIGN KEY (robot_connection_id) REFERENCES robot_connections(id) ON DELETE CASCADE,
                 UNIQUE(robot_connection_id, io_type, io_index)
             );
 
+            -- Motion safety limits per robot
+            CREATE TABLE IF NOT EXISTS safety_limits (
+                robot_connection_id INTEGER PRIMARY KEY,
+                x_min REAL,
+                x_max REAL,
+                y_min REAL,
+                y_max REAL,
+                z_min REAL,
+                z_max REAL,
+                max_speed REAL,
+                speed_limit_action TEXT NOT NULL DEFAULT 'Reject',  -- 'Reject', 'Clamp'
+                FOREIGN KEY (robot_connection_id) REFERENCES robot_connections(id) ON DELETE CASCADE
+            );
+
             -- Global server settings
             CREATE TABLE IF NOT EXISTS server_settings (
                 id INTEGER PRIMARY KEY,
@@ -434,6 +528,15 @@ impl Database {
                 description TEXT
             );
 
+            -- Audit trail of operator actions
+            CREATE TABLE IF NOT EXISTS event_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                kind TEXT NOT NULL,
+                client_id TEXT,
+                message TEXT NOT NULL
+            );
+
             -- Insert default robot settings if not exists
             INSERT OR IGNORE INTO robot_settings (name) VALUES ('default');
 
@@ -446,12 +549,14 @@ impl Database {
     }
 
     /// Reset database - IRREVERSIBLE! Drops all tables and recreates them.
+    /// The event log is kept, so the reset itself stays auditable.
     pub fn reset(&mut self) -> Result<()> {
         self.conn.execute_batch(
             "DROP TABLE IF EXISTS program_instructions;
              DROP TABLE IF EXISTS programs;
              DROP TABLE IF EXISTS robot_settings;
              DROP TABLE IF EXISTS io_display_config;
+             DROP TABLE IF EXISTS safety_limits;
              DROP TABLE IF EXISTS server_settings;
              DROP TABLE IF EXISTS robot_configurations;
              DROP TABLE IF EXISTS robot_connections;"
@@ -478,7 +583,8 @@ impl Database {
                     default_speed, default_term_type, default_term_value, default_uframe, default_utool,
                     start_x, start_y, start_z, start_w, start_p, start_r,
                     end_x, end_y, end_z, end_w, end_p, end_r,
-                    COALESCE(move_speed, 100.0), created_at, updated_at
+                    COALESCE(move_speed, 100.0), created_at, updated_at,
+                    run_count, last_run_at
              FROM programs WHERE id = ?1"
         )?;
 
@@ -511,12 +617,26 @@ impl Database {
                 move_speed: row.get(23)?,
                 created_at: row.get(24)?,
                 updated_at: row.get(25)?,
+                run_count: row.get(26)?,
+                last_run_at: row.get(27)?,
             }))
         } else {
             Ok(None)
         }
     }
 
+    /// Get a program by its (unique) name.
+    pub fn get_program_by_name(&self, name: &str) -> Result<Option<Program>> {
+        let id = self
+            .conn
+            .query_row("SELECT id FROM programs WHERE name = ?1", params![name], |row| row.get(0))
+            .optional()?;
+        match id {
+            Some(id) => self.get_program(id),
+            None => Ok(None),
+        }
+    }
+
     /// List all programs.
     pub fn list_programs(&self) -> Result<Vec<Program>> {
         let mut stmt = self.conn.prepare(
@@ -524,7 +644,8 @@ impl Database {
                     default_speed, default_term_type, default_term_value, default_uframe, default_utool,
                     start_x, start_y, start_z, start_w, start_p, start_r,
                     end_x, end_y, end_z, end_w, end_p, end_r,
-                    COALESCE(move_speed, 100.0), created_at, updated_at
+                    COALESCE(move_speed, 100.0), created_at, updated_at,
+                    run_count, last_run_at
              FROM programs ORDER BY name"
         )?;
 
@@ -556,6 +677,8 @@ impl Database {
                 move_speed: row.get(23)?,
                 created_at: row.get(24)?,
                 updated_at: row.get(25)?,
+                run_count: row.get(26)?,
+                last_run_at: row.get(27)?,
             })
         })?;
 
@@ -606,8 +729,8 @@ impl Database {
     pub fn add_instruction(&self, program_id: i64, instruction: &ProgramInstruction) -> Result<i64> {
         self.conn.execute(
             "INSERT INTO program_instructions
-                (program_id, line_number, x, y, z, w, p, r, ext1, ext2, ext3, speed, speed_type, term_type, term_value, uframe, utool)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+                (program_id, line_number, x, y, z, w, p, r, ext1, ext2, ext3, speed, speed_type, term_type, term_value, uframe, utool, control)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
             params![
                 program_id, instruction.line_number,
                 instruction.x, instruction.y, instruction.z,
@@ -615,7 +738,7 @@ impl Database {
                 instruction.ext1, instruction.ext2, instruction.ext3,
                 instruction.speed, instruction.speed_type, instruction.term_type,
                 instruction.term_value.map(|v| v as i32),
-                instruction.uframe, instruction.utool
+                instruction.uframe, instruction.utool, instruction.control
             ],
         )?;
         Ok(self.conn.last_insert_rowid())
@@ -624,7 +747,7 @@ impl Database {
     /// Get all instructions for a program, ordered by line number.
     pub fn get_instructions(&self, program_id: i64) -> Result<Vec<ProgramInstruction>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, program_id, line_number, x, y, z, w, p, r, ext1, ext2, ext3, speed, speed_type, term_type, term_value, uframe, utool
+            "SELECT id, program_id, line_number, x, y, z, w, p, r, ext1, ext2, ext3, speed, speed_type, term_type, term_value, uframe, utool, control
              FROM program_instructions WHERE program_id = ?1 ORDER BY line_number"
         )?;
 
@@ -648,12 +771,107 @@ impl Database {
                 term_value: row.get::<_, Option<i32>>(15)?.map(|v| v as u8),
                 uframe: row.get(16)?,
                 utool: row.get(17)?,
+                control: row.get(18)?,
             })
         })?;
 
         rows.collect()
     }
 
+    /// Insert an instruction at its line number, moving the lines from
+    /// there on down by one.
+    pub fn insert_instruction(&self, program_id: i64, instruction: &ProgramInstruction) -> Result<i64> {
+        let tx = self.conn.unchecked_transaction()?;
+        self.conn.execute(
+            "UPDATE program_instructions SET line_number = line_number + 1
+             WHERE program_id = ?1 AND line_number >= ?2",
+            params![program_id, instruction.line_number],
+        )?;
+        let id = self.add_instruction(program_id, instruction)?;
+        self.touch_program(program_id)?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// Replace the instruction at `instruction.line_number`. Returns whether
+    /// the line existed.
+    pub fn update_instruction(&self, program_id: i64, instruction: &ProgramInstruction) -> Result<bool> {
+        let tx = self.conn.unchecked_transaction()?;
+        let updated = self.conn.execute(
+            "UPDATE program_instructions SET
+                x = ?3, y = ?4, z = ?5, w = ?6, p = ?7, r = ?8, ext1 = ?9, ext2 = ?10, ext3 = ?11,
+                speed = ?12, speed_type = ?13, term_type = ?14, term_value = ?15,
+                uframe = ?16, utool = ?17, control = ?18
+             WHERE program_id = ?1 AND line_number = ?2",
+            params![
+                program_id, instruction.line_number,
+                instruction.x, instruction.y, instruction.z,
+                instruction.w, instruction.p, instruction.r,
+                instruction.ext1, instruction.ext2, instruction.ext3,
+                instruction.speed, instruction.speed_type, instruction.term_type,
+                instruction.term_value.map(|v| v as i32),
+                instruction.uframe, instruction.utool, instruction.control
+            ],
+        )?;
+        if updated > 0 {
+            self.touch_program(program_id)?;
+        }
+        tx.commit()?;
+        Ok(updated > 0)
+    }
+
+    /// Delete the instruction at `line_number`, moving the lines after it
+    /// up by one. Returns whether the line existed.
+    pub fn delete_instruction(&self, program_id: i64, line_number: i32) -> Result<bool> {
+        let tx = self.conn.unchecked_transaction()?;
+        let deleted = self.conn.execute(
+            "DELETE FROM program_instructions WHERE program_id = ?1 AND line_number = ?2",
+            params![program_id, line_number],
+        )?;
+        if deleted > 0 {
+            self.conn.execute(
+                "UPDATE program_instructions SET line_number = line_number - 1
+                 WHERE program_id = ?1 AND line_number > ?2",
+                params![program_id, line_number],
+            )?;
+            self.touch_program(program_id)?;
+        }
+        tx.commit()?;
+        Ok(deleted > 0)
+    }
+
+    /// Renumber a program's instructions: the instruction with id `ids[i]`
+    /// becomes line `i + 1`.
+    pub fn renumber_instructions(&self, program_id: i64, ids: &[i64]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for (index, id) in ids.iter().enumerate() {
+            self.conn.execute(
+                "UPDATE program_instructions SET line_number = ?1 WHERE program_id = ?2 AND id = ?3",
+                params![index as i32 + 1, program_id, id],
+            )?;
+        }
+        self.touch_program(program_id)?;
+        tx.commit()
+    }
+
+    /// Count a completed run of a program, stamping it as the last run.
+    pub fn record_program_run(&self, program_id: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE programs SET run_count = run_count + 1, last_run_at = CURRENT_TIMESTAMP WHERE id = ?1",
+            params![program_id],
+        )?;
+        Ok(())
+    }
+
+    /// Mark a program as modified now.
+    fn touch_program(&self, program_id: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE programs SET updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+            params![program_id],
+        )?;
+        Ok(())
+    }
+
     /// Clear all instructions for a program.
     pub fn clear_instructions(&self, program_id: i64) -> Result<()> {
         self.conn.execute("DELETE FROM program_instructions WHERE program_id = ?1", params![program_id])?;
@@ -764,6 +982,7 @@ impl Database {
                     COALESCE(default_joint_jog_step, 0.25),
                     COALESCE(default_rotation_jog_speed, 5.0),
                     COALESCE(default_rotation_jog_step, 1.0),
+                    COALESCE(coordinate_unit, 'mm'),
                     created_at, updated_at
              FROM robot_connections WHERE id = ?1"
         )?;
@@ -788,8 +1007,9 @@ impl Database {
                 default_joint_jog_step: row.get(14)?,
                 default_rotation_jog_speed: row.get(15)?,
                 default_rotation_jog_step: row.get(16)?,
-                created_at: row.get(17)?,
-                updated_at: row.get(18)?,
+                coordinate_unit: row.get(17)?,
+                created_at: row.get(18)?,
+                updated_at: row.get(19)?,
             }))
         } else {
             Ok(None)
@@ -813,6 +1033,7 @@ impl Database {
                     COALESCE(default_joint_jog_step, 0.25),
                     COALESCE(default_rotation_jog_speed, 5.0),
                     COALESCE(default_rotation_jog_step, 1.0),
+                    COALESCE(coordinate_unit, 'mm'),
                     created_at, updated_at
              FROM robot_connections ORDER BY name"
         )?;
@@ -836,8 +1057,9 @@ impl Database {
                 default_joint_jog_step: row.get(14)?,
                 default_rotation_jog_speed: row.get(15)?,
                 default_rotation_jog_step: row.get(16)?,
-                created_at: row.get(17)?,
-                updated_at: row.get(18)?,
+                coordinate_unit: row.get(17)?,
+                created_at: row.get(18)?,
+                updated_at: row.get(19)?,
             })
         })?;
 
@@ -905,6 +1127,15 @@ impl Database {
         Ok(())
     }
 
+    /// Update the length unit of a robot connection ("mm" or "inch").
+    pub fn update_robot_connection_unit(&self, id: i64, coordinate_unit: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE robot_connections SET coordinate_unit = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = ?2",
+            params![coordinate_unit, id],
+        )?;
+        Ok(())
+    }
+
     /// Delete a robot connection.
     pub fn delete_robot_connection(&self, id: i64) -> Result<()> {
         self.conn.execute("DELETE FROM robot_connections WHERE id = ?1", params![id])?;
@@ -916,7 +1147,8 @@ impl Database {
     /// Get I/O display config for a robot.
     pub fn get_io_display_config(&self, robot_connection_id: i64) -> Result<Vec<IoDisplayConfig>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, robot_connection_id, io_type, io_index, display_name, is_visible, display_order
+            "SELECT id, robot_connection_id, io_type, io_index, display_name, is_visible, display_order,
+                    warning_threshold, alarm_threshold
              FROM io_display_config WHERE robot_connection_id = ?1 ORDER BY io_type, display_order, io_index"
         )?;
 
@@ -929,6 +1161,8 @@ impl Database {
                 display_name: row.get(4)?,
                 is_visible: row.get::<_, i64>(5)? != 0,
                 display_order: row.get(6)?,
+                warning_threshold: row.get(7)?,
+                alarm_threshold: row.get(8)?,
             })
         })?;
 
@@ -936,6 +1170,7 @@ impl Database {
     }
 
     /// Upsert I/O display config.
+    #[allow(clippy::too_many_arguments)]
     pub fn upsert_io_display_config(
         &self,
         robot_connection_id: i64,
@@ -944,15 +1179,89 @@ impl Database {
         display_name: Option<&str>,
         is_visible: bool,
         display_order: Option<i32>,
+        warning_threshold: Option<f64>,
+        alarm_threshold: Option<f64>,
     ) -> Result<()> {
         self.conn.execute(
-            "INSERT INTO io_display_config (robot_connection_id, io_type, io_index, display_name, is_visible, display_order)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "INSERT INTO io_display_config (robot_connection_id, io_type, io_index, display_name, is_visible, display_order,
+                                            warning_threshold, alarm_threshold)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
              ON CONFLICT(robot_connection_id, io_type, io_index) DO UPDATE SET
                 display_name = excluded.display_name,
                 is_visible = excluded.is_visible,
-                display_order = excluded.display_order",
-            params![robot_connection_id, io_type, io_index, display_name, is_visible as i64, display_order],
+                display_order = excluded.display_order,
+                warning_threshold = excluded.warning_threshold,
+                alarm_threshold = excluded.alarm_threshold",
+            params![
+                robot_connection_id,
+                io_type,
+                io_index,
+                display_name,
+                is_visible as i64,
+                display_order,
+                warning_threshold,
+                alarm_threshold
+            ],
+        )?;
+        Ok(())
+    }
+
+    // ========== Safety Limits Operations ==========
+
+    /// Get the safety limits for a robot. A robot without saved limits has
+    /// none enforced.
+    pub fn get_safety_limits(&self, robot_connection_id: i64) -> Result<SafetyLimits> {
+        let mut stmt = self.conn.prepare(
+            "SELECT x_min, x_max, y_min, y_max, z_min, z_max, max_speed, speed_limit_action
+             FROM safety_limits WHERE robot_connection_id = ?1"
+        )?;
+
+        let mut rows = stmt.query(params![robot_connection_id])?;
+        if let Some(row) = rows.next()? {
+            Ok(SafetyLimits {
+                x_min: row.get(0)?,
+                x_max: row.get(1)?,
+                y_min: row.get(2)?,
+                y_max: row.get(3)?,
+                z_min: row.get(4)?,
+                z_max: row.get(5)?,
+                max_speed: row.get(6)?,
+                speed_limit_action: row.get(7)?,
+            })
+        } else {
+            Ok(SafetyLimits {
+                speed_limit_action: "Reject".to_string(),
+                ..Default::default()
+            })
+        }
+    }
+
+    /// Save the safety limits for a robot, replacing any existing ones.
+    pub fn upsert_safety_limits(&self, robot_connection_id: i64, limits: &SafetyLimits) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO safety_limits (robot_connection_id, x_min, x_max, y_min, y_max, z_min, z_max,
+                                        max_speed, speed_limit_action)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+             ON CONFLICT(robot_connection_id) DO UPDATE SET
+                x_min = excluded.x_min,
+                x_max = excluded.x_max,
+                y_min = excluded.y_min,
+                y_max = excluded.y_max,
+                z_min = excluded.z_min,
+                z_max = excluded.z_max,
+                max_speed = excluded.max_speed,
+                speed_limit_action = excluded.speed_limit_action",
+            params![
+                robot_connection_id,
+                limits.x_min,
+                limits.x_max,
+                limits.y_min,
+                limits.y_max,
+                limits.z_min,
+                limits.z_max,
+                limits.max_speed,
+                limits.speed_limit_action
+            ],
         )?;
         Ok(())
     }
@@ -1001,6 +1310,39 @@ impl Database {
         rows.collect()
     }
 
+    // ========== Event Log Operations ==========
+
+    /// Record an event, returning its id.
+    pub fn add_event(&self, kind: &str, client_id: Option<&str>, message: &str) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO event_log (kind, client_id, message) VALUES (?1, ?2, ?3)",
+            params![kind, client_id, message],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// The newest `limit` events with an id greater than `since`, oldest first.
+    pub fn get_events(&self, since: Option<i64>, limit: u32) -> Result<Vec<EventLogEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, timestamp, kind, client_id, message FROM event_log
+             WHERE id > ?1 ORDER BY id DESC LIMIT ?2"
+        )?;
+
+        let rows = stmt.query_map(params![since.unwrap_or(0), limit], |row| {
+            Ok(EventLogEntry {
+                id: row.get(0)?,
+                timestamp: row.get(1)?,
+                kind: row.get(2)?,
+                client_id: row.get(3)?,
+                message: row.get(4)?,
+            })
+        })?;
+
+        let mut events = rows.collect::<Result<Vec<_>>>()?;
+        events.reverse();
+        Ok(events)
+    }
+
     // ========== Robot Configuration Operations ==========
 
     /// Create a new robot configuration.
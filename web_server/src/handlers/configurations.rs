@@ -8,6 +8,7 @@ use crate::session::ClientManager;
 use crate::RobotConnection;
 use fanuc_rmi::commands::FrcSetUFrameUTool;
 use fanuc_rmi::packets::{Command, CommandResponse, PacketPriority, ResponsePacket, SendPacket};
+use fanuc_rmi::ArmConfig;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{Mutex, RwLock};
@@ -93,6 +94,11 @@ pub async fn create_robot_configuration(
     turn5: i32,
     turn6: i32,
 ) -> ServerResponse {
+    if let Err(e) = ArmConfig::try_from_raw(front, up, left, flip, turn4, turn5, turn6) {
+        return ServerResponse::Error {
+            message: format!("Invalid arm configuration: {}", e),
+        };
+    }
     let db = db.lock().await;
     match db.create_robot_configuration(
         robot_connection_id,
@@ -142,6 +148,11 @@ pub async fn update_robot_configuration(
     turn5: i32,
     turn6: i32,
 ) -> ServerResponse {
+    if let Err(e) = ArmConfig::try_from_raw(front, up, left, flip, turn4, turn5, turn6) {
+        return ServerResponse::Error {
+            message: format!("Invalid arm configuration: {}", e),
+        };
+    }
     let db = db.lock().await;
     match db.update_robot_configuration(
         id, &name, is_default, u_frame_number, u_tool_number,
@@ -218,6 +229,88 @@ pub async fn get_active_configuration(
     }
 }
 
+/// Compare the active configuration, field by field, with the saved
+/// configuration it was loaded from as currently stored in the database.
+/// Jog defaults are compared with the robot connection's saved defaults.
+pub async fn get_configuration_diff(
+    db: Arc<Mutex<Database>>,
+    robot_connection: Option<Arc<RwLock<RobotConnection>>>,
+) -> ServerResponse {
+    let Some(conn) = robot_connection else {
+        return ServerResponse::Error {
+            message: "Not connected to robot".to_string(),
+        };
+    };
+
+    let active = conn.read().await.active_configuration.clone();
+    let Some(configuration_id) = active.loaded_from_id else {
+        return ServerResponse::Error {
+            message: "No saved configuration loaded".to_string(),
+        };
+    };
+
+    let db = db.lock().await;
+    let saved = match db.get_robot_configuration(configuration_id) {
+        Ok(Some(c)) => c,
+        Ok(None) => {
+            return ServerResponse::Error {
+                message: "Configuration not found".to_string(),
+            }
+        }
+        Err(e) => {
+            return ServerResponse::Error {
+                message: format!("Failed to get configuration: {}", e),
+            }
+        }
+    };
+    let saved_connection = match db.get_robot_connection(saved.robot_connection_id) {
+        Ok(Some(c)) => c,
+        Ok(None) => {
+            return ServerResponse::Error {
+                message: "Robot connection not found".to_string(),
+            }
+        }
+        Err(e) => {
+            return ServerResponse::Error {
+                message: format!("Failed to get robot connection: {}", e),
+            }
+        }
+    };
+
+    let fields = [
+        ("UFrame", saved.u_frame_number.to_string(), active.u_frame_number.to_string()),
+        ("UTool", saved.u_tool_number.to_string(), active.u_tool_number.to_string()),
+        ("Front", saved.front.to_string(), active.front.to_string()),
+        ("Up", saved.up.to_string(), active.up.to_string()),
+        ("Left", saved.left.to_string(), active.left.to_string()),
+        ("Flip", saved.flip.to_string(), active.flip.to_string()),
+        ("Turn4", saved.turn4.to_string(), active.turn4.to_string()),
+        ("Turn5", saved.turn5.to_string(), active.turn5.to_string()),
+        ("Turn6", saved.turn6.to_string(), active.turn6.to_string()),
+        ("Cartesian Jog Speed", saved_connection.default_cartesian_jog_speed.to_string(), active.default_cartesian_jog_speed.to_string()),
+        ("Cartesian Jog Step", saved_connection.default_cartesian_jog_step.to_string(), active.default_cartesian_jog_step.to_string()),
+        ("Joint Jog Speed", saved_connection.default_joint_jog_speed.to_string(), active.default_joint_jog_speed.to_string()),
+        ("Joint Jog Step", saved_connection.default_joint_jog_step.to_string(), active.default_joint_jog_step.to_string()),
+        ("Rotation Jog Speed", saved_connection.default_rotation_jog_speed.to_string(), active.default_rotation_jog_speed.to_string()),
+        ("Rotation Jog Step", saved_connection.default_rotation_jog_step.to_string(), active.default_rotation_jog_step.to_string()),
+    ];
+    let differences = fields
+        .into_iter()
+        .filter(|(_, old_value, new_value)| old_value != new_value)
+        .map(|(name, old_value, new_value)| ChangeLogEntryDto {
+            field_name: name.to_string(),
+            old_value,
+            new_value,
+        })
+        .collect();
+
+    ServerResponse::ConfigurationDiff {
+        configuration_id,
+        configuration_name: saved.name,
+        differences,
+    }
+}
+
 /// Load a saved configuration as the active configuration.
 /// This also sends FrcSetUFrameUTool to the robot and broadcasts to all clients.
 pub async fn load_configuration(
@@ -540,3 +633,58 @@ pub async fn save_current_configuration(
         message: format!("Configuration '{}' saved successfully", config_name),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Load a saved configuration, change the active UFrame, and diff.
+    #[tokio::test]
+    async fn test_configuration_diff_reports_changed_uframe() {
+        let db = Database::new(":memory:").expect("in-memory database");
+        let robot_id = db
+            .create_robot_connection(
+                "test", None, "127.0.0.1", 16001, 100.0, "mmSec", "CNT", 0.0, 0.0, 0.0, 10.0, 1.0,
+                0.1, 0.25, 5.0, 1.0,
+            )
+            .unwrap();
+        let config_id = db
+            .create_robot_configuration(robot_id, "Cell A", true, 1, 1, 1, 1, 1, 0, 0, 0, 0)
+            .unwrap();
+        let db = Arc::new(Mutex::new(db));
+
+        let mut conn = RobotConnection::new("127.0.0.1".to_string(), 16001);
+        conn.saved_connection = db.lock().await.get_robot_connection(robot_id).unwrap();
+        let conn = Arc::new(RwLock::new(conn));
+
+        let loaded = load_configuration(Arc::clone(&db), Some(Arc::clone(&conn)), None, config_id).await;
+        assert!(matches!(loaded, ServerResponse::ActiveConfigurationResponse { .. }), "{:?}", loaded);
+
+        let unchanged = get_configuration_diff(Arc::clone(&db), Some(Arc::clone(&conn))).await;
+        assert!(
+            matches!(&unchanged, ServerResponse::ConfigurationDiff { differences, .. } if differences.is_empty()),
+            "{:?}",
+            unchanged
+        );
+
+        conn.write().await.active_configuration.u_frame_number = 3;
+        match get_configuration_diff(db, Some(conn)).await {
+            ServerResponse::ConfigurationDiff { configuration_id, configuration_name, differences } => {
+                assert_eq!((configuration_id, configuration_name.as_str()), (config_id, "Cell A"));
+                assert_eq!(differences.len(), 1, "{:?}", differences);
+                assert_eq!(differences[0].field_name, "UFrame");
+                assert_eq!((differences[0].old_value.as_str(), differences[0].new_value.as_str()), ("1", "3"));
+            }
+            other => panic!("expected configuration_diff, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_configuration_diff_requires_loaded_configuration() {
+        let db = Arc::new(Mutex::new(Database::new(":memory:").expect("in-memory database")));
+        let conn = Arc::new(RwLock::new(RobotConnection::new("127.0.0.1".to_string(), 16001)));
+
+        let response = get_configuration_diff(db, Some(conn)).await;
+        assert!(matches!(response, ServerResponse::Error { .. }), "{:?}", response);
+    }
+}
@@ -62,9 +62,11 @@ pub async fn connect_robot(
         match conn.connect().await {
             Ok(()) => {
                 info!("Successfully connected to robot at {}:{}", robot_addr, robot_port);
-                ServerResponse::Success {
-                    message: format!("Connected to robot at {}:{}", robot_addr, robot_port)
-                }
+                let message = match conn.startup_report {
+                    Some(report) => format!("Connected to robot at {}:{} ({})", robot_addr, robot_port, report),
+                    None => format!("Connected to robot at {}:{}", robot_addr, robot_port),
+                };
+                ServerResponse::Success { message }
             }
             Err(e) => {
                 warn!("Failed to connect to robot: {}", e);
@@ -159,10 +161,18 @@ pub async fn connect_to_saved_robot(
 
             // Initialize active jog settings from saved connection defaults
             // These are the "active jog controls" that can be changed independently from the defaults
-            conn_guard.active_cartesian_jog_speed = saved_conn.default_cartesian_jog_speed;
-            conn_guard.active_cartesian_jog_step = saved_conn.default_cartesian_jog_step;
-            conn_guard.active_joint_jog_speed = saved_conn.default_joint_jog_speed;
-            conn_guard.active_joint_jog_step = saved_conn.default_joint_jog_step;
+            let jog = conn_guard.jog_limits.clamp(crate::jog::JogSettings {
+                cartesian_speed: saved_conn.default_cartesian_jog_speed,
+                cartesian_step: saved_conn.default_cartesian_jog_step,
+                joint_speed: saved_conn.default_joint_jog_speed,
+                joint_step: saved_conn.default_joint_jog_step,
+                rotation_speed: conn_guard.active_rotation_jog_speed,
+                rotation_step: conn_guard.active_rotation_jog_step,
+            });
+            conn_guard.active_cartesian_jog_speed = jog.cartesian_speed;
+            conn_guard.active_cartesian_jog_step = jog.cartesian_step;
+            conn_guard.active_joint_jog_speed = jog.joint_speed;
+            conn_guard.active_joint_jog_step = jog.joint_step;
             info!("Loaded jog defaults: cart_speed={}, cart_step={}, joint_speed={}, joint_step={}",
                 conn_guard.active_cartesian_jog_speed, conn_guard.active_cartesian_jog_step,
                 conn_guard.active_joint_jog_speed, conn_guard.active_joint_jog_step);
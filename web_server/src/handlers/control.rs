@@ -1,15 +1,24 @@
 //! Control lock handlers.
 //!
 //! Manages which client has control of the robot. Only one client
-//! can control the robot at a time; others can observe.
+//! can control the robot at a time; others can observe. The client
+//! manager's control policy decides whether a request may take control
+//! from the current holder.
 
-use crate::api_types::ServerResponse;
-use crate::session::{ClientManager, ControlError};
+use super::{execution, jog};
+use crate::api_types::{AbortReason, ServerResponse};
+use crate::program_executor::ProgramExecutor;
+use crate::session::{ClientManager, ControlError, HandoffError};
+use crate::RobotConnection;
 use std::sync::Arc;
-use tracing::info;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{info, warn};
 use uuid::Uuid;
 
 /// Request control of the robot.
+///
+/// Under role priority a client takes control from a lower-role holder,
+/// which is sent `ControlLost`.
 pub async fn request_control(
     client_manager: Option<Arc<ClientManager>>,
     client_id: Option<Uuid>,
@@ -28,8 +37,20 @@ pub async fn request_control(
         },
     };
 
-    // Try to acquire control
-    let result = client_manager.try_acquire_control(client_id).await;
+    // Try to acquire control, taking it from a lower-role holder if the
+    // policy allows
+    let result = match client_manager.try_acquire_control(client_id).await {
+        Err(ControlError::AlreadyControlled { holder, .. })
+            if client_manager.preempt_control(client_id, holder).await =>
+        {
+            let lost_response = ServerResponse::ControlLost {
+                reason: "Control taken by a higher-priority client".to_string(),
+            };
+            client_manager.send_to_client(holder, &lost_response).await;
+            Ok(None)
+        }
+        result => result,
+    };
 
     match result {
         Ok(previous_holder) => {
@@ -56,6 +77,10 @@ pub async fn request_control(
                 reason: "Another client already has control".to_string(),
             }
         }
+        Err(ControlError::RoleNotPermitted { role, holder }) => ServerResponse::ControlDenied {
+            holder_id: holder.map(|h| h.to_string()).unwrap_or_default(),
+            reason: format!("{:?} clients may not take control", role),
+        },
         Err(ControlError::TimedOut { previous_holder }) => {
             // This shouldn't happen normally as try_acquire handles timeout internally
             info!("Control timed out from {}", previous_holder);
@@ -99,6 +124,220 @@ pub async fn release_control(
     }
 }
 
+/// Ask the current holder to hand control over.
+///
+/// The holder is sent `HandoffRequested`. If it neither grants nor denies
+/// within the client manager's handoff timeout, the request lapses, control
+/// stays put and the requester is sent `ControlDenied`.
+pub async fn request_control_handoff(
+    client_manager: Option<Arc<ClientManager>>,
+    client_id: Option<Uuid>,
+) -> ServerResponse {
+    let client_manager = match client_manager {
+        Some(cm) => cm,
+        None => return ServerResponse::Error {
+            message: "Client manager not available".to_string()
+        },
+    };
+
+    let client_id = match client_id {
+        Some(id) => id,
+        None => return ServerResponse::Error {
+            message: "Client ID not available".to_string()
+        },
+    };
+
+    let request = match client_manager.request_handoff(client_id).await {
+        Ok(request) => request,
+        Err(HandoffError::NoHolder) => {
+            return ServerResponse::Error {
+                message: "No client has control; request control instead".to_string(),
+            };
+        }
+        Err(HandoffError::AlreadyHolder) => {
+            return ServerResponse::Error {
+                message: "You already have control".to_string(),
+            };
+        }
+        Err(HandoffError::AlreadyPending { holder }) => {
+            return ServerResponse::ControlDenied {
+                holder_id: holder.to_string(),
+                reason: "Another handoff request is already pending".to_string(),
+            };
+        }
+        Err(HandoffError::RoleNotPermitted { holder }) => {
+            return ServerResponse::ControlDenied {
+                holder_id: holder.to_string(),
+                reason: "Your role may not take control".to_string(),
+            };
+        }
+    };
+
+    let requested = ServerResponse::HandoffRequested {
+        from: client_id.to_string(),
+    };
+    client_manager.send_to_client(request.holder, &requested).await;
+
+    let cm = Arc::clone(&client_manager);
+    tokio::spawn(async move {
+        tokio::time::sleep(cm.handoff_timeout()).await;
+        if cm.expire_handoff(request).await {
+            let denied = ServerResponse::ControlDenied {
+                holder_id: request.holder.to_string(),
+                reason: "Control holder did not answer the handoff request".to_string(),
+            };
+            cm.send_to_client(request.from, &denied).await;
+        }
+    });
+
+    info!("Client {} requested a handoff from {}", client_id, request.holder);
+    ServerResponse::HandoffPending {
+        holder_id: request.holder.to_string(),
+    }
+}
+
+/// Hand control to the client whose handoff request is pending.
+pub async fn grant_control(
+    client_manager: Option<Arc<ClientManager>>,
+    client_id: Option<Uuid>,
+) -> ServerResponse {
+    let client_manager = match client_manager {
+        Some(cm) => cm,
+        None => return ServerResponse::Error {
+            message: "Client manager not available".to_string()
+        },
+    };
+
+    let client_id = match client_id {
+        Some(id) => id,
+        None => return ServerResponse::Error {
+            message: "Client ID not available".to_string()
+        },
+    };
+
+    match client_manager.grant_handoff(client_id).await {
+        Some(new_holder) => {
+            client_manager.send_to_client(new_holder, &ServerResponse::ControlAcquired).await;
+
+            // Broadcast control change to all clients
+            let changed_response = ServerResponse::ControlChanged {
+                holder_id: Some(new_holder.to_string()),
+            };
+            client_manager.broadcast_all(&changed_response).await;
+
+            info!("Client {} handed control to {}", client_id, new_holder);
+            ServerResponse::ControlReleased
+        }
+        None => ServerResponse::Error {
+            message: "No handoff request to grant".to_string(),
+        },
+    }
+}
+
+/// Refuse the pending handoff request and keep control.
+pub async fn deny_control(
+    client_manager: Option<Arc<ClientManager>>,
+    client_id: Option<Uuid>,
+) -> ServerResponse {
+    let client_manager = match client_manager {
+        Some(cm) => cm,
+        None => return ServerResponse::Error {
+            message: "Client manager not available".to_string()
+        },
+    };
+
+    let client_id = match client_id {
+        Some(id) => id,
+        None => return ServerResponse::Error {
+            message: "Client ID not available".to_string()
+        },
+    };
+
+    match client_manager.deny_handoff(client_id).await {
+        Some(requester) => {
+            let denied = ServerResponse::ControlDenied {
+                holder_id: client_id.to_string(),
+                reason: "Control holder denied the handoff request".to_string(),
+            };
+            client_manager.send_to_client(requester, &denied).await;
+            ServerResponse::Success {
+                message: "Handoff request denied".to_string(),
+            }
+        }
+        None => ServerResponse::Error {
+            message: "No handoff request to deny".to_string(),
+        },
+    }
+}
+
+/// Record a heartbeat from the control holder.
+pub async fn heartbeat(
+    client_manager: Option<Arc<ClientManager>>,
+    client_id: Option<Uuid>,
+) -> ServerResponse {
+    let client_manager = match client_manager {
+        Some(cm) => cm,
+        None => return ServerResponse::Error {
+            message: "Client manager not available".to_string()
+        },
+    };
+
+    let client_id = match client_id {
+        Some(id) => id,
+        None => return ServerResponse::Error {
+            message: "Client ID not available".to_string()
+        },
+    };
+
+    ServerResponse::HeartbeatAck {
+        has_control: client_manager.heartbeat(client_id).await,
+    }
+}
+
+/// Abort motion if the control holder has stopped sending heartbeats.
+///
+/// Called periodically. Only acts while a program is running or a jog is
+/// active: the jog is stopped, the program is stopped with `FRC_Abort`,
+/// control is released and the holder is sent `ControlLost`. Returns the
+/// silent holder if motion was aborted.
+pub async fn check_deadman(
+    client_manager: Arc<ClientManager>,
+    executor: Arc<Mutex<ProgramExecutor>>,
+    robot_connection: Arc<RwLock<RobotConnection>>,
+) -> Option<Uuid> {
+    let holder = client_manager.silent_holder().await?;
+    let running = executor.lock().await.is_running();
+    let (jogging, driver) = {
+        let conn = robot_connection.read().await;
+        (conn.jog.is_some(), conn.driver.clone())
+    };
+    if !running && !jogging {
+        return None;
+    }
+    // Another check, or the holder itself, may have released control meanwhile
+    if !client_manager.release_control(holder).await {
+        return None;
+    }
+
+    warn!("Client {} stopped sending heartbeats during motion, aborting", holder);
+    jog::jog_stop(Some(Arc::clone(&robot_connection))).await;
+    execution::stop_program(
+        driver,
+        Some(executor),
+        Some(robot_connection),
+        Some(Arc::clone(&client_manager)),
+        AbortReason::HeartbeatLost,
+    ).await;
+
+    let lost_response = ServerResponse::ControlLost {
+        reason: "Motion aborted: no heartbeat from the control holder".to_string(),
+    };
+    client_manager.send_to_client(holder, &lost_response).await;
+    let changed_response = ServerResponse::ControlChanged { holder_id: None };
+    client_manager.broadcast_all(&changed_response).await;
+    Some(holder)
+}
+
 /// Get current control status.
 pub async fn get_control_status(
     client_manager: Option<Arc<ClientManager>>,
@@ -120,3 +359,250 @@ pub async fn get_control_status(
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_types::{JogAxis, JogDirection};
+    use crate::session::test_support::{connect_client, connect_client_as, pushed, ClientSocket};
+    use crate::session::{AcceptOrder, ClientRole, ControlPolicy};
+    use crate::test_support::{command_reply, connect_driver, instruction_reply, start_fake_controller};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// A manager where `holder` has control and `requester` has asked for it.
+    async fn pending_handoff(
+        timeout: Duration,
+    ) -> (Arc<ClientManager>, (Uuid, ClientSocket), (Uuid, ClientSocket)) {
+        let cm = Arc::new(ClientManager::with_handoff_timeout(timeout));
+        let (holder, mut holder_socket) = connect_client(&cm).await;
+        let (requester, requester_socket) = connect_client(&cm).await;
+        cm.try_acquire_control(holder).await.expect("acquire control");
+
+        let response = request_control_handoff(Some(Arc::clone(&cm)), Some(requester)).await;
+        assert!(
+            matches!(&response, ServerResponse::HandoffPending { holder_id } if *holder_id == holder.to_string()),
+            "{:?}",
+            response
+        );
+        let to_holder = pushed(&mut holder_socket, Duration::from_millis(100)).await;
+        assert!(
+            matches!(to_holder.as_slice(), [ServerResponse::HandoffRequested { from }] if *from == requester.to_string()),
+            "{:?}",
+            to_holder
+        );
+
+        // Only one request waits at a time
+        let (other, _other_socket) = connect_client(&cm).await;
+        let response = request_control_handoff(Some(Arc::clone(&cm)), Some(other)).await;
+        assert!(matches!(response, ServerResponse::ControlDenied { .. }), "{:?}", response);
+
+        (cm, (holder, holder_socket), (requester, requester_socket))
+    }
+
+    #[tokio::test]
+    async fn test_granted_handoff_moves_control() {
+        let (cm, (holder, _holder_socket), (requester, mut requester_socket)) =
+            pending_handoff(Duration::from_secs(30)).await;
+
+        let response = grant_control(Some(Arc::clone(&cm)), Some(holder)).await;
+        assert!(matches!(response, ServerResponse::ControlReleased), "{:?}", response);
+        assert_eq!(cm.get_control_holder().await, Some(requester));
+
+        let to_requester = pushed(&mut requester_socket, Duration::from_millis(100)).await;
+        assert!(matches!(to_requester.first(), Some(ServerResponse::ControlAcquired)), "{:?}", to_requester);
+
+        // The request is used up
+        let response = grant_control(Some(Arc::clone(&cm)), Some(requester)).await;
+        assert!(matches!(response, ServerResponse::Error { .. }), "{:?}", response);
+    }
+
+    #[tokio::test]
+    async fn test_denied_handoff_keeps_control() {
+        let (cm, (holder, _holder_socket), (_, mut requester_socket)) =
+            pending_handoff(Duration::from_secs(30)).await;
+
+        let response = deny_control(Some(Arc::clone(&cm)), Some(holder)).await;
+        assert!(matches!(response, ServerResponse::Success { .. }), "{:?}", response);
+        assert_eq!(cm.get_control_holder().await, Some(holder));
+
+        let to_requester = pushed(&mut requester_socket, Duration::from_millis(100)).await;
+        assert!(matches!(to_requester.as_slice(), [ServerResponse::ControlDenied { .. }]), "{:?}", to_requester);
+
+        // Nothing left to grant
+        let response = grant_control(Some(Arc::clone(&cm)), Some(holder)).await;
+        assert!(matches!(response, ServerResponse::Error { .. }), "{:?}", response);
+        assert_eq!(cm.get_control_holder().await, Some(holder));
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_handoff_times_out() {
+        let (cm, (holder, _holder_socket), (requester, mut requester_socket)) =
+            pending_handoff(Duration::from_millis(200)).await;
+
+        let to_requester = pushed(&mut requester_socket, Duration::from_secs(2)).await;
+        assert!(
+            matches!(to_requester.as_slice(), [ServerResponse::ControlDenied { reason, .. }] if reason.contains("did not answer")),
+            "{:?}",
+            to_requester
+        );
+        assert_eq!(cm.get_control_holder().await, Some(holder));
+
+        // A late grant has nothing to act on
+        let response = grant_control(Some(Arc::clone(&cm)), Some(holder)).await;
+        assert!(matches!(response, ServerResponse::Error { .. }), "{:?}", response);
+        assert_ne!(cm.get_control_holder().await, Some(requester));
+    }
+
+    fn role_priority(viewers_may_control: bool) -> ControlPolicy {
+        ControlPolicy { accept_order: AcceptOrder::RolePriority, viewers_may_control }
+    }
+
+    #[tokio::test]
+    async fn test_higher_role_preempts_holder() {
+        let cm = Arc::new(ClientManager::new().with_control_policy(role_priority(true)));
+        let (viewer, mut viewer_socket) = connect_client_as(&cm, ClientRole::Viewer).await;
+        let (operator, _operator_socket) = connect_client_as(&cm, ClientRole::Operator).await;
+        let (other_viewer, _other_socket) = connect_client_as(&cm, ClientRole::Viewer).await;
+
+        let response = request_control(Some(Arc::clone(&cm)), Some(viewer)).await;
+        assert!(matches!(response, ServerResponse::ControlAcquired), "{:?}", response);
+        pushed(&mut viewer_socket, Duration::from_millis(50)).await;
+
+        // Equal roles still wait their turn
+        let response = request_control(Some(Arc::clone(&cm)), Some(other_viewer)).await;
+        assert!(matches!(response, ServerResponse::ControlDenied { .. }), "{:?}", response);
+        assert_eq!(cm.get_control_holder().await, Some(viewer));
+
+        let response = request_control(Some(Arc::clone(&cm)), Some(operator)).await;
+        assert!(matches!(response, ServerResponse::ControlAcquired), "{:?}", response);
+        assert_eq!(cm.get_control_holder().await, Some(operator));
+        let to_viewer = pushed(&mut viewer_socket, Duration::from_millis(100)).await;
+        assert!(
+            to_viewer.iter().any(|r| matches!(r, ServerResponse::ControlLost { .. })),
+            "{:?}",
+            to_viewer
+        );
+
+        // A lower role can't take it back
+        let response = request_control(Some(Arc::clone(&cm)), Some(viewer)).await;
+        assert!(matches!(response, ServerResponse::ControlDenied { .. }), "{:?}", response);
+        assert_eq!(cm.get_control_holder().await, Some(operator));
+    }
+
+    #[tokio::test]
+    async fn test_first_come_ignores_roles() {
+        let cm = Arc::new(ClientManager::new());
+        let (viewer, _viewer_socket) = connect_client_as(&cm, ClientRole::Viewer).await;
+        let (supervisor, _supervisor_socket) = connect_client_as(&cm, ClientRole::Supervisor).await;
+
+        request_control(Some(Arc::clone(&cm)), Some(viewer)).await;
+        let response = request_control(Some(Arc::clone(&cm)), Some(supervisor)).await;
+        assert!(matches!(response, ServerResponse::ControlDenied { .. }), "{:?}", response);
+        assert_eq!(cm.get_control_holder().await, Some(viewer));
+    }
+
+    #[tokio::test]
+    async fn test_barred_viewer_is_denied_control() {
+        let cm = Arc::new(ClientManager::new().with_control_policy(role_priority(false)));
+        let (viewer, _viewer_socket) = connect_client_as(&cm, ClientRole::Viewer).await;
+
+        let response = request_control(Some(Arc::clone(&cm)), Some(viewer)).await;
+        assert!(matches!(response, ServerResponse::ControlDenied { .. }), "{:?}", response);
+        assert_eq!(cm.get_control_holder().await, None);
+
+        // Nor can it ask a holder to hand control over
+        let (operator, _operator_socket) = connect_client(&cm).await;
+        request_control(Some(Arc::clone(&cm)), Some(operator)).await;
+        let response = request_control_handoff(Some(Arc::clone(&cm)), Some(viewer)).await;
+        assert!(matches!(response, ServerResponse::ControlDenied { .. }), "{:?}", response);
+        assert_eq!(cm.get_control_holder().await, Some(operator));
+    }
+
+    /// Fake controller that accepts one connection and completes every
+    /// instruction and command straight away. Returns the connect port and
+    /// a count of `FRC_Abort`s received.
+    async fn start_abort_counting_controller() -> (u32, Arc<AtomicUsize>) {
+        let aborts = Arc::new(AtomicUsize::new(0));
+        let count = Arc::clone(&aborts);
+        let port = start_fake_controller(move |packet| {
+            if packet["Command"] == "FRC_Abort" {
+                count.fetch_add(1, Ordering::SeqCst);
+            }
+            instruction_reply(&packet).or_else(|| command_reply(&packet))
+        })
+        .await;
+        (port, aborts)
+    }
+
+    #[tokio::test]
+    async fn test_silent_holder_aborts_motion() {
+        let (port, aborts) = start_abort_counting_controller().await;
+        let driver = connect_driver(port).await;
+        let mut conn = RobotConnection::new("127.0.0.1".to_string(), port);
+        conn.driver = Some(driver);
+        conn.connected = true;
+        let conn = Arc::new(RwLock::new(conn));
+        let executor = Arc::new(Mutex::new(ProgramExecutor::new()));
+
+        let deadman_timeout = Duration::from_millis(300);
+        let cm = Arc::new(ClientManager::new().with_deadman_timeout(Some(deadman_timeout)));
+        let (holder, mut holder_socket) = connect_client(&cm).await;
+        let (_observer, mut observer_socket) = connect_client(&cm).await;
+        cm.try_acquire_control(holder).await.expect("acquire control");
+        let check = || check_deadman(Arc::clone(&cm), Arc::clone(&executor), Arc::clone(&conn));
+
+        // A quiet holder keeps control while the robot is idle
+        tokio::time::sleep(deadman_timeout * 2).await;
+        assert_eq!(check().await, None);
+        assert_eq!(cm.get_control_holder().await, Some(holder));
+
+        let response = heartbeat(Some(Arc::clone(&cm)), Some(holder)).await;
+        assert!(matches!(response, ServerResponse::HeartbeatAck { has_control: true }), "{:?}", response);
+        let response = jog::jog_continuous(
+            Some(Arc::clone(&executor)),
+            Some(Arc::clone(&conn)),
+            Some(Arc::clone(&cm)),
+            Some(holder),
+            JogAxis::X,
+            JogDirection::Positive,
+            50.0,
+        )
+        .await;
+        assert!(matches!(response, ServerResponse::Success { .. }), "{:?}", response);
+
+        // Heartbeats keep the motion going
+        for _ in 0..4 {
+            tokio::time::sleep(deadman_timeout / 2).await;
+            heartbeat(Some(Arc::clone(&cm)), Some(holder)).await;
+            assert_eq!(check().await, None);
+        }
+        assert_eq!(aborts.load(Ordering::SeqCst), 0);
+        pushed(&mut holder_socket, Duration::from_millis(50)).await;
+        pushed(&mut observer_socket, Duration::from_millis(50)).await;
+
+        // Then the holder goes quiet mid-jog
+        tokio::time::sleep(deadman_timeout * 2).await;
+        assert_eq!(check().await, Some(holder));
+        assert!(aborts.load(Ordering::SeqCst) > 0, "FRC_Abort should be dispatched");
+        assert!(conn.read().await.jog.is_none(), "the jog should be stopped");
+        assert_eq!(cm.get_control_holder().await, None);
+
+        let to_holder = pushed(&mut holder_socket, Duration::from_millis(100)).await;
+        assert!(
+            to_holder.iter().any(|r| matches!(r, ServerResponse::ControlLost { .. })),
+            "{:?}",
+            to_holder
+        );
+        let to_observer = pushed(&mut observer_socket, Duration::from_millis(100)).await;
+        assert!(
+            to_observer.iter().any(|r| matches!(r, ServerResponse::ControlChanged { holder_id: None })),
+            "{:?}",
+            to_observer
+        );
+
+        // A late heartbeat learns that control is gone
+        let response = heartbeat(Some(Arc::clone(&cm)), Some(holder)).await;
+        assert!(matches!(response, ServerResponse::HeartbeatAck { has_control: false }), "{:?}", response);
+    }
+}
@@ -0,0 +1,149 @@
+//! Event log handlers.
+//!
+//! Operator actions (connecting, aborting, control changes, program runs)
+//! are recorded in the database's `event_log` table as an audit trail, and
+//! read back with `GetEventLog`. A failed action is recorded as an
+//! [`EventKind::Error`].
+
+use crate::api_types::*;
+use crate::database::Database;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Events returned by `GetEventLog` without a `limit`.
+pub const DEFAULT_LIMIT: u32 = 100;
+
+/// The event `request` is recorded as and a description of it, if it is an
+/// action the event log covers.
+pub fn audited(request: &ClientRequest) -> Option<(EventKind, String)> {
+    let event = match request {
+        ClientRequest::ConnectRobot { robot_addr, robot_port } => {
+            (EventKind::Connect, format!("Connect to {}:{}", robot_addr, robot_port))
+        }
+        ClientRequest::ConnectToSavedRobot { connection_id } => {
+            (EventKind::Connect, format!("Connect to saved robot {}", connection_id))
+        }
+        ClientRequest::DisconnectRobot => (EventKind::Disconnect, "Disconnect".to_string()),
+        ClientRequest::RobotAbort => (EventKind::Abort, "Abort motion".to_string()),
+        ClientRequest::RequestControl => (EventKind::ControlAcquired, "Request control".to_string()),
+        ClientRequest::ReleaseControl => (EventKind::ControlReleased, "Release control".to_string()),
+        ClientRequest::GrantControl => (EventKind::ControlReleased, "Grant control".to_string()),
+        ClientRequest::StartProgram { program_id } => {
+            (EventKind::ProgramStarted, format!("Start program {}", program_id))
+        }
+        ClientRequest::StopProgram => (EventKind::ProgramStopped, "Stop program".to_string()),
+        _ => return None,
+    };
+    Some(event)
+}
+
+/// Why `response` reports a failure, if it does.
+fn failure(response: &ServerResponse) -> Option<&str> {
+    match response {
+        ServerResponse::Error { message } | ServerResponse::ValidationError { message } => Some(message),
+        ServerResponse::ControlDenied { reason, .. } => Some(reason),
+        ServerResponse::RobotCommandResult { success: false, message, .. } => {
+            Some(message.as_deref().unwrap_or("rejected by the robot"))
+        }
+        _ => None,
+    }
+}
+
+/// Record an event. A database failure is logged, not returned, so that it
+/// never fails the action being recorded.
+pub async fn log_event(db: &Arc<Mutex<Database>>, kind: EventKind, client_id: Option<Uuid>, message: &str) {
+    info!("Event {}: {}", kind.as_str(), message);
+    let client_id = client_id.map(|id| id.to_string());
+    if let Err(e) = db.lock().await.add_event(kind.as_str(), client_id.as_deref(), message) {
+        warn!("Failed to record {} event: {}", kind.as_str(), e);
+    }
+}
+
+/// Record the outcome of an [`audited`] request: its own event if
+/// `response` reports success, an [`EventKind::Error`] otherwise.
+pub async fn log_outcome(
+    db: &Arc<Mutex<Database>>,
+    (kind, description): (EventKind, String),
+    client_id: Option<Uuid>,
+    response: &ServerResponse,
+) {
+    match failure(response) {
+        None => log_event(db, kind, client_id, &description).await,
+        Some(reason) => {
+            log_event(db, EventKind::Error, client_id, &format!("{} failed: {}", description, reason)).await
+        }
+    }
+}
+
+/// Recorded events with an id greater than `since`, oldest first.
+pub async fn get_event_log(db: Arc<Mutex<Database>>, since: Option<i64>, limit: Option<u32>) -> ServerResponse {
+    let db = db.lock().await;
+    match db.get_events(since, limit.unwrap_or(DEFAULT_LIMIT)) {
+        Ok(entries) => ServerResponse::EventLog {
+            events: entries
+                .into_iter()
+                .filter_map(|entry| {
+                    Some(EventLogEntryDto {
+                        id: entry.id,
+                        timestamp: entry.timestamp,
+                        kind: EventKind::from_db(&entry.kind)?,
+                        client_id: entry.client_id,
+                        message: entry.message,
+                    })
+                })
+                .collect(),
+        },
+        Err(e) => ServerResponse::Error { message: format!("Failed to read event log: {}", e) },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::handle_request;
+    use crate::test_support::{command_reply, connect_driver, start_fake_controller};
+
+
+    #[tokio::test]
+    async fn test_abort_is_recorded_in_event_log() {
+        let db = Arc::new(Mutex::new(Database::new(":memory:").expect("in-memory database")));
+        let port = start_fake_controller(|packet| command_reply(&packet)).await;
+        let driver = connect_driver(port).await;
+        let client_id = Uuid::new_v4();
+        let request = |request| {
+            handle_request(request, Arc::clone(&db), Some(Arc::clone(&driver)), None, None, None, Some(client_id))
+        };
+
+        let response = request(ClientRequest::RobotAbort).await;
+        assert!(matches!(response, ServerResponse::RobotCommandResult { success: true, .. }), "{:?}", response);
+
+        let events = match request(ClientRequest::GetEventLog { since: None, limit: None }).await {
+            ServerResponse::EventLog { events } => events,
+            other => panic!("expected EventLog, got {:?}", other),
+        };
+        assert_eq!(events.len(), 1, "{:?}", events);
+        assert_eq!(events[0].kind, EventKind::Abort);
+        assert_eq!(events[0].client_id, Some(client_id.to_string()));
+        assert!(!events[0].timestamp.is_empty());
+
+        // Reading the log is not itself recorded
+        match request(ClientRequest::GetEventLog { since: Some(events[0].id), limit: None }).await {
+            ServerResponse::EventLog { events } => assert!(events.is_empty(), "{:?}", events),
+            other => panic!("expected EventLog, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_action_is_recorded_as_error() {
+        let db = Arc::new(Mutex::new(Database::new(":memory:").expect("in-memory database")));
+        let response = handle_request(ClientRequest::RobotAbort, Arc::clone(&db), None, None, None, None, None).await;
+        assert!(matches!(response, ServerResponse::RobotCommandResult { success: false, .. }), "{:?}", response);
+
+        let events = db.lock().await.get_events(None, DEFAULT_LIMIT).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, "error");
+        assert_eq!(events[0].message, "Abort motion failed: Not connected to robot");
+    }
+}
@@ -2,13 +2,14 @@
 //!
 //! Handles starting, pausing, resuming, and stopping program execution.
 
-use crate::api_types::ServerResponse;
+use crate::api_types::{AbortReason, ServerResponse};
 use crate::database::Database;
-use crate::program_executor::ProgramExecutor;
-use crate::session::{ClientManager, execution_state_to_response};
+use crate::program_executor::{ExecutionState, LineOutcome, Origin, ProgramExecutor};
+use crate::session::{ClientManager, execution_state_changed, execution_state_to_response};
 use crate::RobotConnection;
 use fanuc_rmi::drivers::FanucDriver;
-use fanuc_rmi::packets::{PacketPriority, SendPacket, DriverCommand, SentInstructionInfo, ResponsePacket, Command};
+use fanuc_rmi::commands::FrcReadDIN;
+use fanuc_rmi::packets::{PacketPriority, SendPacket, DriverCommand, SentInstructionInfo, ResponsePacket, Command, CommandResponse};
 use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 use tracing::{info, error, warn, debug};
@@ -64,7 +65,8 @@ pub async fn pause_program(
 /// Resume program execution.
 ///
 /// This:
-/// 1. Resumes the executor (allows sending more instructions from the buffer)
+/// 1. Resumes the executor (allows sending more instructions from the buffer),
+///    waking the buffered executor task in case nothing is left in flight
 /// 2. Unpauses the driver's packet queue
 /// 3. Sends FRC_Continue to the robot controller (resumes motion)
 /// 4. Broadcasts state change to all connected clients
@@ -78,6 +80,9 @@ pub async fn resume_program(
         let state_response = if let Some(ref executor) = executor {
             let mut exec_guard = executor.lock().await;
             exec_guard.resume();
+            // A program paused at a breakpoint may have nothing in flight
+            // whose completion would send the next batch
+            exec_guard.dispatch_wakeup().notify_one();
             info!("Executor resumed");
             Some(execution_state_to_response(&exec_guard.get_state()))
         } else {
@@ -113,15 +118,16 @@ pub async fn resume_program(
 ///
 /// This:
 /// 1. Stops the executor (clears pending queue)
-/// 2. Sends FRC_Abort to the robot controller (aborts current motion)
+/// 2. Flushes the driver's queue and sends FRC_Abort to the robot controller (aborts current motion)
 /// 3. Clears in-flight tracking
 /// 4. Auto-reinitializes the TP program (allows immediate motion commands)
-/// 5. Broadcasts state change to all connected clients
+/// 5. Broadcasts `MotionAborted` with `reason` and the state change to all connected clients
 pub async fn stop_program(
     driver: Option<Arc<FanucDriver>>,
     executor: Option<Arc<Mutex<ProgramExecutor>>>,
     robot_connection: Option<Arc<RwLock<RobotConnection>>>,
     client_manager: Option<Arc<ClientManager>>,
+    reason: AbortReason,
 ) -> ServerResponse {
     if let Some(driver) = driver {
         // Stop the executor (clears pending queue)
@@ -131,9 +137,16 @@ pub async fn stop_program(
             info!("Executor stopped, pending queue cleared");
         }
 
-        // Send FRC_Abort to the robot
+        // Drop motions still queued in the driver, then send FRC_Abort to the robot
+        if let Err(e) = driver.flush_pending() {
+            warn!("Failed to flush driver queue: {}", e);
+        }
         match driver.abort().await {
-            Ok(_) => {
+            Ok(response) => {
+                if let (Some(client_manager), 0) = (&client_manager, response.error_id) {
+                    client_manager.broadcast_all(&ServerResponse::MotionAborted { reason }).await;
+                }
+
                 // Clear in-flight tracking after abort completes
                 let state_response = if let Some(ref executor) = executor {
                     let mut exec_guard = executor.lock().await;
@@ -209,14 +222,66 @@ pub async fn get_execution_state(
         execution_state_to_response(&exec_guard.get_state())
     } else {
         // No executor means idle state
-        ServerResponse::ExecutionStateChanged {
-            state: "idle".to_string(),
-            program_id: None,
-            current_line: None,
-            total_lines: None,
-            message: None,
+        execution_state_changed(web_common::ExecutionState::Idle, None, None, None, None)
+    }
+}
+
+/// Pause a stored program before `line` each time it runs.
+///
+/// The executor stops sending lines when it reaches the breakpoint and
+/// broadcasts the `Paused` state; lines already sent run to completion, so
+/// the robot stops before the breakpoint line. `resume_program` continues
+/// from it.
+pub async fn set_breakpoint(
+    db: Arc<Mutex<Database>>,
+    executor: Option<Arc<Mutex<ProgramExecutor>>>,
+    program_id: i64,
+    line: usize,
+) -> ServerResponse {
+    let Some(executor) = executor else {
+        return ServerResponse::Error { message: "Executor not available".to_string() };
+    };
+    let line_count = {
+        let db = db.lock().await;
+        match db.get_program(program_id) {
+            Ok(Some(_)) => {}
+            Ok(None) => return ServerResponse::Error { message: format!("Program {} not found", program_id) },
+            Err(e) => return ServerResponse::Error { message: format!("Database error: {}", e) },
+        }
+        match db.get_instructions(program_id) {
+            Ok(instructions) => instructions.len(),
+            Err(e) => return ServerResponse::Error { message: format!("Failed to load instructions: {}", e) },
         }
+    };
+    if !(1..=line_count).contains(&line) {
+        return ServerResponse::Error {
+            message: format!("Program {} has no line {} (lines 1 to {})", program_id, line, line_count),
+        };
+    }
+
+    let mut exec_guard = executor.lock().await;
+    exec_guard.set_breakpoint(program_id, line);
+    info!("Breakpoint set before line {} of program {}", line, program_id);
+    ServerResponse::Breakpoints { program_id, lines: exec_guard.breakpoints(program_id) }
+}
+
+/// Remove a breakpoint set with [`set_breakpoint`].
+pub async fn clear_breakpoint(
+    executor: Option<Arc<Mutex<ProgramExecutor>>>,
+    program_id: i64,
+    line: usize,
+) -> ServerResponse {
+    let Some(executor) = executor else {
+        return ServerResponse::Error { message: "Executor not available".to_string() };
+    };
+    let mut exec_guard = executor.lock().await;
+    if !exec_guard.clear_breakpoint(program_id, line) {
+        return ServerResponse::Error {
+            message: format!("Program {} has no breakpoint at line {}", program_id, line),
+        };
     }
+    info!("Breakpoint before line {} of program {} cleared", line, program_id);
+    ServerResponse::Breakpoints { program_id, lines: exec_guard.breakpoints(program_id) }
 }
 
 /// Load a program into the executor without starting execution.
@@ -224,6 +289,9 @@ pub async fn get_execution_state(
 /// Loads the program from the database into the executor's pending queue.
 /// The program is ready to run but won't start until start_program is called.
 /// Broadcasts the "loaded" state to all connected clients.
+///
+/// Replies with `ConfigurationWarnings` instead of `Success` when program
+/// lines set a frame or tool number other than the active configuration's.
 pub async fn load_program(
     db: Arc<Mutex<Database>>,
     executor: Option<Arc<Mutex<ProgramExecutor>>>,
@@ -248,13 +316,16 @@ pub async fn load_program(
     };
 
     // Load program into executor
-    let state_response = {
+    let (state_response, warnings) = {
         let db_guard = db.lock().await;
         let mut exec_guard = executor.lock().await;
         if let Err(e) = exec_guard.load_program(&db_guard, program_id, active_config.as_ref(), &default_speed_type) {
             return ServerResponse::Error { message: format!("Failed to load program: {}", e) };
         }
-        execution_state_to_response(&exec_guard.get_state())
+        let warnings = active_config.as_ref()
+            .map(|config| exec_guard.configuration_warnings(config))
+            .unwrap_or_default();
+        (execution_state_to_response(&exec_guard.get_state()), warnings)
     };
 
     info!("Loaded program {} into executor", program_id);
@@ -264,6 +335,11 @@ pub async fn load_program(
         client_manager.broadcast_all(&state_response).await;
     }
 
+    if !warnings.is_empty() {
+        warn!("Program {} has {} frame/tool mismatches with the active configuration", program_id, warnings.len());
+        return ServerResponse::ConfigurationWarnings { program_id, warnings };
+    }
+
     ServerResponse::Success { message: format!("Program {} loaded", program_id) }
 }
 
@@ -365,20 +441,20 @@ pub async fn start_program(
     let response_rx = driver.response_tx.subscribe();
 
     // Send initial batch
-    let initial_batch = {
+    let (initial_batch, breakpoint_pause) = {
         let mut exec_guard = executor.lock().await;
-        exec_guard.get_next_batch()
+        take_dispatch(&mut exec_guard)
     };
 
-    for (line_number, packet) in initial_batch {
+    for (origin, packet) in initial_batch {
         match driver.send_packet(packet, PacketPriority::Standard) {
             Ok(request_id) => {
                 let mut exec_guard = executor.lock().await;
-                exec_guard.record_sent(request_id, line_number);
-                info!("Sent instruction {} (request_id: {})", line_number, request_id);
+                exec_guard.record_dispatched(request_id, origin);
+                info!("Sent instruction {:?} (request_id: {})", origin, request_id);
             }
             Err(e) => {
-                error!("Failed to send instruction {}: {}", line_number, e);
+                error!("Failed to send instruction {:?}: {}", origin, e);
                 let mut exec_guard = executor.lock().await;
                 exec_guard.reset();
                 return ServerResponse::Error { message: format!("Failed to send instruction: {}", e) };
@@ -386,6 +462,20 @@ pub async fn start_program(
         }
     }
 
+    // The first lines may already have reached a conditional jump (or a runaway loop)
+    let din_request = {
+        let mut exec_guard = executor.lock().await;
+        if let ExecutionState::Error { message } = exec_guard.get_state() {
+            let message = message.clone();
+            exec_guard.reset();
+            return ServerResponse::Error { message };
+        }
+        exec_guard.take_din_request()
+    };
+    if let Some(port) = din_request {
+        request_din(&driver, port);
+    }
+
     // Send first InstructionSent to all clients
     if let Some(ref client_manager) = client_manager {
         let sent_msg = ServerResponse::InstructionSent {
@@ -393,12 +483,15 @@ pub async fn start_program(
             total_lines: total_instructions,
         };
         client_manager.broadcast_all(&sent_msg).await;
+        if let Some(state_response) = breakpoint_pause {
+            client_manager.broadcast_all(&state_response).await;
+        }
     }
 
     // Spawn buffered execution task (broadcasts progress to all clients)
     if let Some(client_manager) = client_manager {
         spawn_buffered_executor(
-            driver, executor, sent_rx, response_rx, client_manager,
+            db, driver, executor, sent_rx, response_rx, client_manager,
             total_instructions, program_id,
         );
     }
@@ -414,9 +507,12 @@ pub async fn start_program(
 /// This task:
 /// 1. Maps request_ids to sequence_ids when SentInstructionInfo arrives
 /// 2. Handles instruction completions and sends more instructions
-/// 3. Broadcasts progress updates to all connected clients
-/// 4. Handles completion/error states
+/// 3. Sends instructions queued in execution contexts (such as jogs)
+/// 4. Broadcasts progress updates to all connected clients
+/// 5. Handles completion/error states, counting successful runs in `db`
+#[allow(clippy::too_many_arguments)]
 fn spawn_buffered_executor(
+    db: Arc<Mutex<Database>>,
     driver: Arc<FanucDriver>,
     executor: Arc<Mutex<ProgramExecutor>>,
     mut sent_rx: tokio::sync::broadcast::Receiver<SentInstructionInfo>,
@@ -427,16 +523,32 @@ fn spawn_buffered_executor(
 ) {
     tokio::spawn(async move {
         info!("Buffered executor started for program {}", program_id);
+        let dispatch_wakeup = executor.lock().await.dispatch_wakeup();
 
         loop {
             tokio::select! {
+                // An execution context queued an instruction
+                _ = dispatch_wakeup.notified() => {
+                    if !send_next_batch(&driver, &executor, &client_manager, program_id).await {
+                        return;
+                    }
+                }
+
                 // Handle SentInstructionInfo (map request_id -> sequence_id)
                 sent_result = sent_rx.recv() => {
                     match sent_result {
                         Ok(sent_info) => {
-                            let mut exec_guard = executor.lock().await;
-                            exec_guard.map_sequence(sent_info.request_id, sent_info.sequence_id);
+                            // The instruction's response may already have arrived
+                            let outcome = {
+                                let mut exec_guard = executor.lock().await;
+                                exec_guard.map_sequence(sent_info.request_id, sent_info.sequence_id)
+                            };
                             debug!("Mapped request {} -> sequence {}", sent_info.request_id, sent_info.sequence_id);
+                            if let Some(outcome) = outcome {
+                                if !handle_outcome(&db, &driver, &executor, &client_manager, outcome, sent_info.sequence_id, total_instructions, program_id).await {
+                                    return;
+                                }
+                            }
                         }
                         Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
                             warn!("Sent notification channel lagged by {} messages", n);
@@ -453,60 +565,28 @@ fn spawn_buffered_executor(
                     match response_result {
                         Ok(ResponsePacket::InstructionResponse(resp)) => {
                             let seq_id = resp.get_sequence_id();
-                            let error_id = resp.get_error_id();
-
-                            let (completed_line, is_complete, is_running) = {
+                            let outcome = {
                                 let mut exec_guard = executor.lock().await;
-                                let line = exec_guard.handle_completion(seq_id);
-                                (line, exec_guard.is_complete(), exec_guard.is_running())
+                                exec_guard.handle_response(seq_id, resp.get_error_id())
                             };
-
-                            if let Some(line) = completed_line {
-                                info!("📍 Line {} completed (seq_id {})", line, seq_id);
-
-                                // Broadcast progress update to all clients
-                                broadcast_progress_update(&client_manager, line, total_instructions).await;
-
-                                // Check for error
-                                if error_id != 0 {
-                                    error!("Instruction {} failed with error {}", line, error_id);
-                                    let mut exec_guard = executor.lock().await;
-                                    exec_guard.reset();
-                                    broadcast_error_completion(&client_manager, program_id, line, error_id).await;
+                            if let Some(outcome) = outcome {
+                                if !handle_outcome(&db, &driver, &executor, &client_manager, outcome, seq_id, total_instructions, program_id).await {
                                     return;
                                 }
-
-                                // Check for completion
-                                if is_complete {
-                                    info!("Program {} completed successfully", program_id);
-                                    broadcast_success_completion(&client_manager, program_id, total_instructions).await;
+                            }
+                        }
+                        Ok(ResponsePacket::CommandResponse(CommandResponse::FrcReadDIN(resp))) => {
+                            // Resolves a conditional jump the program is waiting on
+                            let resumed = {
+                                let mut exec_guard = executor.lock().await;
+                                exec_guard.is_running() && resp.error_id == 0
+                                    && exec_guard.provide_din(resp.port_number, resp.port_value != 0)
+                            };
+                            if resumed {
+                                info!("DIN[{}] = {} resolved conditional jump", resp.port_number, resp.port_value);
+                                if !send_next_batch(&driver, &executor, &client_manager, program_id).await {
                                     return;
                                 }
-
-                                // Send more instructions if running
-                                if is_running {
-                                    let next_batch = {
-                                        let mut exec_guard = executor.lock().await;
-                                        exec_guard.get_next_batch()
-                                    };
-
-                                    for (line_number, packet) in next_batch {
-                                        match driver.send_packet(packet, PacketPriority::Standard) {
-                                            Ok(request_id) => {
-                                                let mut exec_guard = executor.lock().await;
-                                                exec_guard.record_sent(request_id, line_number);
-                                                info!("Sent instruction {} (request_id: {})", line_number, request_id);
-                                            }
-                                            Err(e) => {
-                                                error!("Failed to send instruction {}: {}", line_number, e);
-                                                let mut exec_guard = executor.lock().await;
-                                                exec_guard.reset();
-                                                broadcast_error_completion(&client_manager, program_id, line_number, 999).await;
-                                                return;
-                                            }
-                                        }
-                                    }
-                                }
                             }
                         }
                         Ok(_) => {
@@ -526,7 +606,7 @@ fn spawn_buffered_executor(
             // Check if executor was stopped externally
             {
                 let exec_guard = executor.lock().await;
-                if matches!(exec_guard.get_state(), crate::program_executor::ExecutionState::Idle | crate::program_executor::ExecutionState::Stopping) {
+                if matches!(exec_guard.get_state(), ExecutionState::Idle | ExecutionState::Stopping | ExecutionState::Aborted { .. }) {
                     info!("Executor stopped, exiting buffered executor task");
                     break;
                 }
@@ -534,6 +614,161 @@ fn spawn_buffered_executor(
         }
     });
 }
+/// Act on the outcome of one program line: report progress, then finish the
+/// program, halt it on an error, or send more instructions.
+///
+/// Returns `false` once execution is over.
+#[allow(clippy::too_many_arguments)]
+async fn handle_outcome(
+    db: &Mutex<Database>,
+    driver: &FanucDriver,
+    executor: &Mutex<ProgramExecutor>,
+    client_manager: &ClientManager,
+    outcome: LineOutcome,
+    seq_id: u32,
+    total_instructions: usize,
+    program_id: i64,
+) -> bool {
+    match outcome {
+        LineOutcome::Failed { line, error_id } => {
+            // The executor has halted on the failing line; report it
+            let state_response = {
+                let exec_guard = executor.lock().await;
+                execution_state_to_response(exec_guard.get_state())
+            };
+            client_manager.broadcast_all(&state_response).await;
+            broadcast_error_completion(client_manager, program_id, line, error_id).await;
+            false
+        }
+        LineOutcome::Completed(line) => {
+            let (completed_line, is_complete, is_running) = {
+                let exec_guard = executor.lock().await;
+                (exec_guard.completed_line(), exec_guard.is_complete(), exec_guard.is_running())
+            };
+            info!("📍 Line {} completed (seq_id {})", line, seq_id);
+
+            // Broadcast progress update to all clients
+            broadcast_progress_update(client_manager, completed_line, total_instructions).await;
+
+            // Check for completion
+            if is_complete {
+                info!("Program {} completed successfully", program_id);
+                if let Err(e) = db.lock().await.record_program_run(program_id) {
+                    warn!("Failed to record run of program {}: {}", program_id, e);
+                }
+                broadcast_success_completion(client_manager, program_id, total_instructions).await;
+                return false;
+            }
+
+            // Send more instructions if running
+            !is_running || send_next_batch(driver, executor, client_manager, program_id).await
+        }
+        LineOutcome::Context { context, error_id } => {
+            if error_id != 0 {
+                // Only the context stops; the program keeps running
+                warn!("{} instruction (seq_id {}) failed with error {}", context, seq_id, error_id);
+                let error = ServerResponse::Error {
+                    message: format!("{} instruction failed with error {}", context, error_id),
+                };
+                client_manager.broadcast_all(&error).await;
+            }
+            send_next_batch(driver, executor, client_manager, program_id).await
+        }
+    }
+}
+
+/// Send the next batch of program and execution context instructions, then
+/// issue the DIN read for any conditional jump the program has reached.
+///
+/// Returns `false` if execution was aborted (send failure or loop guard).
+async fn send_next_batch(
+    driver: &FanucDriver,
+    executor: &Mutex<ProgramExecutor>,
+    client_manager: &ClientManager,
+    program_id: i64,
+) -> bool {
+    let (next_batch, breakpoint_pause) = {
+        let mut exec_guard = executor.lock().await;
+        take_dispatch(&mut exec_guard)
+    };
+
+    for (origin, packet) in next_batch {
+        let line_number = match origin {
+            Origin::Line(line_number) => line_number,
+            Origin::Context(context) => {
+                match driver.send_packet(packet, PacketPriority::Standard) {
+                    Ok(request_id) => executor.lock().await.record_dispatched(request_id, origin),
+                    Err(e) => error!("Failed to send {} instruction: {}", context, e),
+                }
+                continue;
+            }
+        };
+        match driver.send_packet(packet, PacketPriority::Standard) {
+            Ok(request_id) => {
+                let mut exec_guard = executor.lock().await;
+                exec_guard.record_sent(request_id, line_number);
+                info!("Sent instruction {} (request_id: {})", line_number, request_id);
+            }
+            Err(e) => {
+                error!("Failed to send instruction {}: {}", line_number, e);
+                let mut exec_guard = executor.lock().await;
+                exec_guard.reset();
+                broadcast_error_completion(client_manager, program_id, line_number, 999).await;
+                return false;
+            }
+        }
+    }
+
+    let (din_request, failed) = {
+        let mut exec_guard = executor.lock().await;
+        let failed = match exec_guard.get_state() {
+            ExecutionState::Error { message } => Some((message.clone(), execution_state_to_response(exec_guard.get_state()))),
+            _ => None,
+        };
+        (exec_guard.take_din_request(), failed)
+    };
+
+    if let Some((message, state_response)) = failed {
+        error!("Program {} aborted: {}", program_id, message);
+        client_manager.broadcast_all(&state_response).await;
+        let response = ServerResponse::ProgramComplete {
+            program_id,
+            success: false,
+            message: Some(message),
+        };
+        client_manager.broadcast_all(&response).await;
+        return false;
+    }
+
+    if let Some(port) = din_request {
+        request_din(driver, port);
+    }
+    if let Some(state_response) = breakpoint_pause {
+        client_manager.broadcast_all(&state_response).await;
+    }
+    true
+}
+
+/// Take the executor's next dispatch, along with the state to broadcast if
+/// the program paused at a breakpoint while filling it.
+fn take_dispatch(exec_guard: &mut ProgramExecutor) -> (Vec<(Origin, SendPacket)>, Option<ServerResponse>) {
+    let was_running = exec_guard.is_running();
+    let dispatch = exec_guard.next_dispatch();
+    let paused = was_running
+        && matches!(exec_guard.get_state(), ExecutionState::Paused { breakpoint: Some(_), .. });
+    (dispatch, paused.then(|| execution_state_to_response(exec_guard.get_state())))
+}
+
+/// Ask the controller for a DIN value needed by a conditional jump. The
+/// response is picked up by the buffered executor task.
+fn request_din(driver: &FanucDriver, port_number: u16) {
+    info!("Reading DIN[{}] for conditional jump", port_number);
+    let packet = SendPacket::Command(Command::FrcReadDIN(FrcReadDIN { port_number }));
+    if let Err(e) = driver.send_packet(packet, PacketPriority::Standard) {
+        error!("Failed to read DIN[{}]: {}", port_number, e);
+    }
+}
+
 /// Broadcast a progress update to all connected clients.
 async fn broadcast_progress_update(client_manager: &ClientManager, current_line: usize, total_lines: usize) {
     let progress = ServerResponse::InstructionProgress {
@@ -575,3 +810,403 @@ async fn broadcast_success_completion(client_manager: &ClientManager, program_id
     client_manager.broadcast_all(&response).await;
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_types::{JogAxis, JogDirection};
+    use crate::database::ProgramInstruction;
+    use crate::jog::spawn_jog;
+    use crate::program_executor::MAX_BUFFER;
+    use crate::session::test_support::{connect_client, pushed};
+    use crate::test_support::{connect_driver, connect_robot, start_fake_controller};
+    use std::time::Duration;
+
+    /// Fake controller for a five-line program whose line N moves to
+    /// X = N * 100. Once all five instructions are in, it answers line 2,
+    /// then line 1, then rejects line 3 with RMIT-036. Returns the connect port.
+    async fn start_program_controller() -> u32 {
+        // program line -> sequence ID
+        let mut sequence_ids = std::collections::HashMap::new();
+        start_fake_controller(move |packet| {
+            if packet["Instruction"] != "FRC_LinearMotion" {
+                return Vec::new();
+            }
+            let program_line = (packet["Position"]["X"].as_f64().unwrap() / 100.0).round() as u32;
+            sequence_ids.insert(program_line, packet["SequenceID"].as_u64().unwrap());
+            if sequence_ids.len() < 5 {
+                return Vec::new();
+            }
+            [(2, 0), (1, 0), (3, 2556964)]
+                .into_iter()
+                .map(|(program_line, error_id)| {
+                    format!(
+                        "{{\"Instruction\":\"FRC_LinearMotion\",\"ErrorID\":{},\"SequenceID\":{}}}\r\n",
+                        error_id, sequence_ids[&program_line]
+                    )
+                })
+                .collect()
+        })
+        .await
+    }
+
+    /// Fake controller that completes every instruction as soon as it
+    /// arrives. Returns the connect port.
+    async fn start_completing_controller() -> u32 {
+        start_fake_controller(|packet| match packet["Instruction"].as_str() {
+            Some(instruction) => vec![format!(
+                "{{\"Instruction\":\"{}\",\"ErrorID\":0,\"SequenceID\":{}}}\r\n",
+                instruction, packet["SequenceID"]
+            )],
+            None => Vec::new(),
+        })
+        .await
+    }
+
+    /// A five-line program whose line N moves to X = N * 100.
+    fn create_five_line_program(db: &Database, name: &str) -> i64 {
+        create_line_program(db, name, 5)
+    }
+
+    /// A program of `lines` lines whose line N moves to X = N * 100.
+    fn create_line_program(db: &Database, name: &str, lines: i32) -> i64 {
+        let program_id = db.create_program(name, None).expect("create program");
+        for line_number in 1..=lines {
+            let instruction = ProgramInstruction {
+                id: 0,
+                program_id,
+                line_number,
+                x: f64::from(line_number) * 100.0,
+                y: 0.0,
+                z: 300.0,
+                w: None,
+                p: None,
+                r: None,
+                ext1: None,
+                ext2: None,
+                ext3: None,
+                speed: Some(100.0),
+                speed_type: None,
+                term_type: None,
+                term_value: None,
+                uframe: None,
+                utool: None,
+                control: None,
+            };
+            db.add_instruction(program_id, &instruction).expect("add instruction");
+        }
+        program_id
+    }
+
+    #[tokio::test]
+    async fn test_instruction_error_reports_failing_line() {
+        let db = Database::new(":memory:").expect("in-memory database");
+        let program_id = create_five_line_program(&db, "fails on line 3");
+        let db = Arc::new(Mutex::new(db));
+
+        let driver = connect_driver(start_program_controller().await).await;
+        let executor = Arc::new(Mutex::new(ProgramExecutor::new()));
+        let client_manager = Arc::new(ClientManager::new());
+        let (_client_id, mut socket) = connect_client(&client_manager).await;
+
+        let response = start_program(
+            db,
+            Some(driver),
+            Some(Arc::clone(&executor)),
+            program_id,
+            None,
+            Some(Arc::clone(&client_manager)),
+        )
+        .await;
+        assert!(matches!(response, ServerResponse::ExecutionStarted { .. }), "{:?}", response);
+
+        let pushed = pushed(&mut socket, Duration::from_millis(500)).await;
+        let failed_line = pushed.iter().find_map(|response| match response {
+            ServerResponse::ExecutionStateChanged {
+                execution_state: web_common::ExecutionState::Error,
+                current_line,
+                ..
+            } => Some(*current_line),
+            _ => None,
+        });
+        assert_eq!(failed_line, Some(Some(3)), "{:?}", pushed);
+        assert!(
+            pushed.iter().any(|response| matches!(
+                response,
+                ServerResponse::ProgramComplete { success: false, message: Some(message), .. }
+                    if message.starts_with("Error at line 3:")
+            )),
+            "{:?}",
+            pushed
+        );
+        // Lines 1 and 2 completed before the failure, even though they answered out of order
+        assert!(
+            pushed.iter().any(|response| matches!(response, ServerResponse::InstructionProgress { current_line: 2, .. })),
+            "{:?}",
+            pushed
+        );
+        assert!(matches!(
+            executor.lock().await.get_state(),
+            ExecutionState::Failed { line: 3, error_id: 2556964, .. }
+        ));
+    }
+    #[tokio::test]
+    async fn test_completed_runs_are_counted() {
+        let db = Database::new(":memory:").expect("in-memory database");
+        let program_id = create_five_line_program(&db, "runs twice");
+        let db = Arc::new(Mutex::new(db));
+
+        let driver = connect_driver(start_completing_controller().await).await;
+        let executor = Arc::new(Mutex::new(ProgramExecutor::new()));
+        let client_manager = Arc::new(ClientManager::new());
+        let (_client_id, mut socket) = connect_client(&client_manager).await;
+
+        let program = db.lock().await.get_program(program_id).unwrap().unwrap();
+        assert_eq!((program.run_count, program.last_run_at), (0, None));
+
+        for _ in 0..2 {
+            let response = start_program(
+                Arc::clone(&db),
+                Some(Arc::clone(&driver)),
+                Some(Arc::clone(&executor)),
+                program_id,
+                None,
+                Some(Arc::clone(&client_manager)),
+            )
+            .await;
+            assert!(matches!(response, ServerResponse::ExecutionStarted { .. }), "{:?}", response);
+
+            let pushed = pushed(&mut socket, Duration::from_millis(500)).await;
+            assert!(
+                pushed.iter().any(|response| matches!(response, ServerResponse::ProgramComplete { success: true, .. })),
+                "{:?}",
+                pushed
+            );
+        }
+
+        let program = db.lock().await.get_program(program_id).unwrap().unwrap();
+        assert_eq!(program.run_count, 2);
+        assert!(program.last_run_at.is_some());
+        match crate::handlers::programs::get_program(db, program_id).await {
+            ServerResponse::Program { program } => assert_eq!(program.run_count, 2),
+            other => panic!("unexpected response {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_breakpoint_pauses_before_its_line() {
+        let db = Database::new(":memory:").expect("in-memory database");
+        let program_id = create_five_line_program(&db, "breaks on line 3");
+        let db = Arc::new(Mutex::new(db));
+
+        // Completes every instruction at once, recording the program line
+        let executed = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let port = {
+            let executed = Arc::clone(&executed);
+            start_fake_controller(move |packet| match packet["Instruction"].as_str() {
+                Some(instruction) => {
+                    executed.lock().unwrap().push((packet["Position"]["X"].as_f64().unwrap() / 100.0).round() as usize);
+                    vec![format!(
+                        "{{\"Instruction\":\"{}\",\"ErrorID\":0,\"SequenceID\":{}}}\r\n",
+                        instruction, packet["SequenceID"]
+                    )]
+                }
+                None => Vec::new(),
+            })
+            .await
+        };
+        let driver = connect_driver(port).await;
+        let executor = Arc::new(Mutex::new(ProgramExecutor::new()));
+        let client_manager = Arc::new(ClientManager::new());
+        let (_client_id, mut socket) = connect_client(&client_manager).await;
+
+        match set_breakpoint(Arc::clone(&db), Some(Arc::clone(&executor)), program_id, 3).await {
+            ServerResponse::Breakpoints { lines, .. } => assert_eq!(lines, vec![3]),
+            other => panic!("expected Breakpoints, got {:?}", other),
+        }
+        let response = start_program(
+            db,
+            Some(Arc::clone(&driver)),
+            Some(Arc::clone(&executor)),
+            program_id,
+            None,
+            Some(Arc::clone(&client_manager)),
+        )
+        .await;
+        assert!(matches!(response, ServerResponse::ExecutionStarted { .. }), "{:?}", response);
+
+        let pushed_while_paused = pushed(&mut socket, Duration::from_millis(500)).await;
+        assert!(
+            pushed_while_paused.iter().any(|response| matches!(
+                response,
+                ServerResponse::ExecutionStateChanged {
+                    execution_state: web_common::ExecutionState::Paused,
+                    message: Some(message),
+                    ..
+                } if message == "Breakpoint before line 3"
+            )),
+            "{:?}",
+            pushed_while_paused
+        );
+        assert_eq!(*executed.lock().unwrap(), vec![1, 2]);
+        assert!(matches!(
+            executor.lock().await.get_state(),
+            ExecutionState::Paused { last_completed: 2, breakpoint: Some(3), .. }
+        ));
+
+        let response = resume_program(Some(driver), Some(Arc::clone(&executor)), Some(Arc::clone(&client_manager))).await;
+        assert!(matches!(response, ServerResponse::Success { .. }), "{:?}", response);
+        let pushed = pushed(&mut socket, Duration::from_millis(500)).await;
+        assert!(
+            pushed.iter().any(|response| matches!(response, ServerResponse::ProgramComplete { success: true, .. })),
+            "{:?}",
+            pushed
+        );
+        assert_eq!(*executed.lock().unwrap(), vec![1, 2, 3, 4, 5]);
+    }
+
+    /// An instruction received by the fake controller.
+    #[derive(Debug, Clone, PartialEq)]
+    struct Received {
+        instruction: String,
+        sequence_id: u64,
+        x: f64,
+        term_type: String,
+    }
+
+    /// Everything the fake controller has received, and the most
+    /// instructions it had outstanding at once.
+    #[derive(Default)]
+    struct Interleaving {
+        received: Vec<Received>,
+        outstanding: std::collections::VecDeque<(String, u64)>,
+        max_outstanding: usize,
+    }
+
+    /// Fake controller that holds every instruction until the test sends
+    /// `FRC_GetStatus`, then completes the oldest one. Returns the connect
+    /// port.
+    async fn start_stepping_controller(log: Arc<std::sync::Mutex<Interleaving>>) -> u32 {
+        start_fake_controller(move |packet| {
+            let mut log = log.lock().unwrap();
+            if let Some(instruction) = packet["Instruction"].as_str() {
+                let sequence_id = packet["SequenceID"].as_u64().unwrap();
+                let x = packet["Position"]["X"].as_f64().unwrap_or_default();
+                let term_type = packet["TermType"].as_str().unwrap_or_default().to_string();
+                log.received.push(Received { instruction: instruction.to_string(), sequence_id, x, term_type });
+                log.outstanding.push_back((instruction.to_string(), sequence_id));
+                log.max_outstanding = log.max_outstanding.max(log.outstanding.len());
+                return Vec::new();
+            }
+            match log.outstanding.pop_front() {
+                Some((instruction, sequence_id)) if packet["Command"] == "FRC_GetStatus" => vec![format!(
+                    "{{\"Instruction\":\"{}\",\"ErrorID\":0,\"SequenceID\":{}}}\r\n",
+                    instruction, sequence_id
+                )],
+                _ => Vec::new(),
+            }
+        })
+        .await
+    }
+
+    /// Wait until the fake controller has received `count` instructions.
+    async fn wait_for_received(log: &std::sync::Mutex<Interleaving>, count: usize) {
+        for _ in 0..200 {
+            if log.lock().unwrap().received.len() >= count {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("controller received {:?}, expected {} instructions", log.lock().unwrap().received, count);
+    }
+
+    #[tokio::test]
+    async fn test_jog_interleaves_with_running_program() {
+        let db = Database::new(":memory:").expect("in-memory database");
+        let program_id = create_line_program(&db, "jogged", 7);
+        let db = Arc::new(Mutex::new(db));
+
+        let log = Arc::new(std::sync::Mutex::new(Interleaving::default()));
+        let conn = connect_robot(start_stepping_controller(Arc::clone(&log)).await).await;
+        let driver = conn.driver.clone().unwrap();
+        let robot_connection = Arc::new(RwLock::new(conn));
+        let executor = Arc::new(Mutex::new(ProgramExecutor::new()));
+        let client_manager = Arc::new(ClientManager::new());
+        let (_client_id, mut socket) = connect_client(&client_manager).await;
+
+        let response = start_program(
+            db,
+            Some(Arc::clone(&driver)),
+            Some(Arc::clone(&executor)),
+            program_id,
+            None,
+            Some(Arc::clone(&client_manager)),
+        )
+        .await;
+        assert!(matches!(response, ServerResponse::ExecutionStarted { .. }), "{:?}", response);
+        wait_for_received(&log, MAX_BUFFER).await;
+
+        // The buffer is full, so the jog's moves wait for the first free slot
+        let jog = spawn_jog(
+            Arc::clone(&driver),
+            robot_connection,
+            Some(Arc::clone(&executor)),
+            None,
+            None,
+            JogAxis::J1,
+            JogDirection::Positive,
+            10.0,
+            Default::default(),
+        );
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(log.lock().unwrap().received.len(), MAX_BUFFER);
+        driver.send_packet(SendPacket::Command(Command::FrcGetStatus), PacketPriority::Standard).unwrap();
+        wait_for_received(&log, MAX_BUFFER + 1).await;
+
+        // Stopping drops the queued jog moves and queues the stop move
+        drop(jog);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // Complete one instruction at a time until the program finishes
+        for completed in 1..=8 {
+            driver.send_packet(SendPacket::Command(Command::FrcGetStatus), PacketPriority::Standard).unwrap();
+            wait_for_received(&log, (MAX_BUFFER + 1 + completed).min(9)).await;
+        }
+        let pushed = pushed(&mut socket, Duration::from_millis(500)).await;
+        assert!(
+            pushed.iter().any(|response| matches!(response, ServerResponse::ProgramComplete { success: true, .. })),
+            "{:?}",
+            pushed
+        );
+
+        let log = log.lock().unwrap();
+        assert!(log.max_outstanding <= MAX_BUFFER, "{} instructions outstanding", log.max_outstanding);
+        // One sequence counter numbers program lines and jog moves alike
+        let sequence_ids: Vec<u64> = log.received.iter().map(|received| received.sequence_id).collect();
+        assert_eq!(sequence_ids, (1..=9).collect::<Vec<u64>>());
+        // Each jog move takes the first free slot, ahead of the remaining
+        // lines, and the jog ends with its FINE stop move
+        let order: Vec<String> = log
+            .received
+            .iter()
+            .map(|received| match received.instruction.as_str() {
+                "FRC_LinearMotion" => format!("line {}", (received.x / 100.0).round()),
+                other => format!("{} {}", other, received.term_type),
+            })
+            .collect();
+        assert_eq!(
+            order,
+            [
+                "line 1",
+                "line 2",
+                "line 3",
+                "line 4",
+                "line 5",
+                "FRC_JointRelativeJRep CNT",
+                "FRC_JointRelativeJRep FINE",
+                "line 6",
+                "line 7",
+            ]
+        );
+    }
+}
@@ -71,6 +71,19 @@ pub async fn set_active_frame_tool(
         };
     };
 
+    if uframe > conn.model.uframe_count() || utool > conn.model.utool_count() {
+        return ServerResponse::Error {
+            message: format!(
+                "UFrame {} / UTool {} out of range: the {} has {} user frames and {} tools",
+                uframe,
+                utool,
+                conn.model,
+                conn.model.uframe_count(),
+                conn.model.utool_count()
+            ),
+        };
+    }
+
     // Send FrcSetUFrameUTool command
     let cmd = FrcSetUFrameUTool::new(None, utool, uframe);
     let packet = SendPacket::Command(Command::FrcSetUFrameUTool(cmd));
@@ -321,11 +334,20 @@ pub async fn read_tool_data(
 }
 
 /// Write UFrame data for a specific frame number.
+///
+/// Frames that fail [`FrameData::validate`] are answered with
+/// `ValidationError` and never sent.
 pub async fn write_frame_data(
     robot_connection: Option<Arc<RwLock<RobotConnection>>>,
     frame_number: u8,
     frame: FrameData,
 ) -> ServerResponse {
+    if let Err(reason) = frame.validate() {
+        return ServerResponse::ValidationError {
+            message: format!("Invalid UFrame {} data: {}", frame_number, reason),
+        };
+    }
+
     let Some(conn) = robot_connection else {
         return ServerResponse::Error {
             message: "Not connected to robot".to_string(),
@@ -383,11 +405,20 @@ pub async fn write_frame_data(
 }
 
 /// Write UTool data for a specific tool number.
+///
+/// Tools that fail [`FrameData::validate`] are answered with
+/// `ValidationError` and never sent.
 pub async fn write_tool_data(
     robot_connection: Option<Arc<RwLock<RobotConnection>>>,
     tool_number: u8,
     frame: FrameData,
 ) -> ServerResponse {
+    if let Err(reason) = frame.validate() {
+        return ServerResponse::ValidationError {
+            message: format!("Invalid UTool {} data: {}", tool_number, reason),
+        };
+    }
+
     let Some(conn) = robot_connection else {
         return ServerResponse::Error {
             message: "Not connected to robot".to_string(),
@@ -440,3 +471,83 @@ pub async fn write_tool_data(
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{connect_robot, start_fake_controller};
+    use tokio::sync::mpsc;
+
+    /// Fake controller that acknowledges every `FRC_WriteUFrameData` and
+    /// forwards the request line to the returned receiver. Returns the
+    /// connect port.
+    async fn start_frame_controller() -> (u32, mpsc::UnboundedReceiver<String>) {
+        let (writes_tx, writes_rx) = mpsc::unbounded_channel();
+        let port = start_fake_controller(move |packet| {
+            (packet["Command"] == "FRC_WriteUFrameData").then(|| {
+                let _ = writes_tx.send(packet.to_string());
+                "{\"Command\":\"FRC_WriteUFrameData\",\"ErrorID\":0,\"Group\":1}\r\n"
+            })
+        })
+        .await;
+        (port, writes_rx)
+    }
+
+    /// Connect a driver to the fake controller and wrap it in a robot
+    /// connection.
+    async fn connect_to_fake_controller() -> (Arc<RwLock<RobotConnection>>, mpsc::UnboundedReceiver<String>) {
+        let (port, writes) = start_frame_controller().await;
+        (Arc::new(RwLock::new(connect_robot(port).await)), writes)
+    }
+
+    #[tokio::test]
+    async fn test_frame_with_nan_z_is_rejected_before_sending() {
+        let (conn, mut writes) = connect_to_fake_controller().await;
+        let frame = FrameData { x: 100.0, y: 0.0, z: f64::NAN, w: 0.0, p: 0.0, r: 0.0 };
+
+        match write_frame_data(Some(conn), 2, frame).await {
+            ServerResponse::ValidationError { message } => {
+                assert_eq!(message, "Invalid UFrame 2 data: Z must be a finite number, got NaN");
+            }
+            other => panic!("expected ValidationError, got {:?}", other),
+        }
+        let sent = tokio::time::timeout(Duration::from_millis(200), writes.recv()).await;
+        assert!(sent.is_err(), "invalid frame reached the controller: {:?}", sent);
+    }
+
+    #[tokio::test]
+    async fn test_valid_frame_is_written() {
+        let (conn, mut writes) = connect_to_fake_controller().await;
+        let frame = FrameData { x: 100.0, y: -50.0, z: 25.0, w: -180.0, p: 0.0, r: 360.0 };
+
+        let response = write_frame_data(Some(conn), 2, frame).await;
+        assert!(matches!(response, ServerResponse::Success { .. }), "{:?}", response);
+        let sent = writes.recv().await.expect("write reached the controller");
+        assert!(sent.contains("\"FrameNumber\":2"), "{}", sent);
+    }
+
+    #[tokio::test]
+    async fn test_frame_past_the_models_count_is_rejected() {
+        let (conn, _writes) = connect_to_fake_controller().await;
+
+        match set_active_frame_tool(Some(Arc::clone(&conn)), None, 10, 1).await {
+            ServerResponse::Error { message } => {
+                assert_eq!(message, "UFrame 10 / UTool 1 out of range: the CRX-10iA has 9 user frames and 10 tools");
+            }
+            other => panic!("expected Error, got {:?}", other),
+        }
+        assert_eq!(conn.read().await.active_configuration.u_frame_number, 1);
+    }
+
+    #[tokio::test]
+    async fn test_tool_rotation_beyond_360_degrees_is_rejected() {
+        let tool = FrameData { x: 0.0, y: 0.0, z: 150.0, w: 0.0, p: 400.0, r: 0.0 };
+
+        match write_tool_data(None, 1, tool).await {
+            ServerResponse::ValidationError { message } => {
+                assert_eq!(message, "Invalid UTool 1 data: P must be between -360 and 360 degrees, got 400");
+            }
+            other => panic!("expected ValidationError, got {:?}", other),
+        }
+    }
+}
@@ -1,15 +1,197 @@
 //! I/O handlers for reading/writing digital, analog, and group I/O.
 
-use crate::api_types::ServerResponse;
+use crate::api_types::{AlarmState, IoPoint, IoPortRange, IoType, ServerResponse};
+use crate::session::ClientManager;
 use crate::RobotConnection;
 use fanuc_rmi::commands::{
     FrcReadAIN, FrcReadDIN, FrcReadGIN, FrcWriteAOUT, FrcWriteDOUT, FrcWriteGOUT,
 };
 use fanuc_rmi::packets::{Command, CommandResponse, PacketPriority, ResponsePacket, SendPacket};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
 use tracing::{info, warn, error};
+use uuid::Uuid;
+
+/// Last known value of every I/O port the server has read or written on a
+/// robot connection. Answers [`read_io_snapshot`] without touching the robot.
+#[derive(Debug, Default)]
+pub struct IoCache {
+    din: HashMap<u16, bool>,
+    dout: HashMap<u16, bool>,
+    ain: HashMap<u16, f64>,
+    aout: HashMap<u16, f64>,
+    gin: HashMap<u16, u32>,
+    gout: HashMap<u16, u32>,
+    /// Points whose value changed since the last [`IoCache::take_changes`].
+    changes: Vec<(IoPoint, f64)>,
+}
+
+/// `(port, value)` for every port in `range`, defaulting ports not in `values`.
+fn snapshot_range<T: Copy + Default>(values: &HashMap<u16, T>, range: Option<IoPortRange>) -> Vec<(u16, T)> {
+    range
+        .into_iter()
+        .flat_map(IoPortRange::ports)
+        .map(|port| (port, values.get(&port).copied().unwrap_or_default()))
+        .collect()
+}
+
+/// Store `value` for `port`, noting a change if it differs from the last
+/// known value (a port seen for the first time counts as changed).
+fn update<T: Copy + PartialEq + Into<f64>>(
+    values: &mut HashMap<u16, T>,
+    changes: &mut Vec<(IoPoint, f64)>,
+    io_type: IoType,
+    port: u16,
+    value: T,
+) {
+    if values.insert(port, value) != Some(value) {
+        changes.push((IoPoint { io_type, port, robot_id: None }, value.into()));
+    }
+}
+
+impl IoCache {
+    /// Record the value of every I/O response passing through the server.
+    pub fn record(&mut self, response: &ServerResponse) {
+        let changes = &mut self.changes;
+        match *response {
+            ServerResponse::DinValue { port_number, port_value } => {
+                update(&mut self.din, changes, IoType::Din, port_number, port_value);
+            }
+            ServerResponse::DinBatch { ref values } => {
+                for &(port, value) in values {
+                    update(&mut self.din, changes, IoType::Din, port, value);
+                }
+            }
+            ServerResponse::DoutValue { port_number, port_value, .. } => {
+                update(&mut self.dout, changes, IoType::Dout, port_number, port_value);
+            }
+            ServerResponse::AinValue { port_number, port_value, .. } => {
+                update(&mut self.ain, changes, IoType::Ain, port_number, port_value);
+            }
+            ServerResponse::AoutValue { port_number, port_value, .. } => {
+                update(&mut self.aout, changes, IoType::Aout, port_number, port_value);
+            }
+            ServerResponse::GinValue { port_number, port_value } => {
+                update(&mut self.gin, changes, IoType::Gin, port_number, port_value);
+            }
+            ServerResponse::GoutValue { port_number, port_value, .. } => {
+                update(&mut self.gout, changes, IoType::Gout, port_number, port_value);
+            }
+            _ => {}
+        }
+    }
+
+    /// Every known value, in type then port order.
+    pub fn values(&self) -> Vec<(IoPoint, f64)> {
+        fn points<T: Copy + Into<f64>>(values: &HashMap<u16, T>, io_type: IoType) -> Vec<(IoPoint, f64)> {
+            let mut points: Vec<_> = values
+                .iter()
+                .map(|(&port, &value)| (IoPoint { io_type, port, robot_id: None }, value.into()))
+                .collect();
+            points.sort_by_key(|(point, _)| point.port);
+            points
+        }
+        let mut values = points(&self.din, IoType::Din);
+        values.extend(points(&self.dout, IoType::Dout));
+        values.extend(points(&self.ain, IoType::Ain));
+        values.extend(points(&self.aout, IoType::Aout));
+        values.extend(points(&self.gin, IoType::Gin));
+        values.extend(points(&self.gout, IoType::Gout));
+        values
+    }
+
+    /// Drain the changes recorded since the last call, oldest first.
+    pub fn take_changes(&mut self) -> Vec<(IoPoint, f64)> {
+        std::mem::take(&mut self.changes)
+    }
+}
+
+/// Push the I/O changes recorded on `robot_connection` to the clients
+/// subscribed to them, as points of the connection's robot.
+pub async fn push_io_changes(
+    robot_connection: &Option<Arc<RwLock<RobotConnection>>>,
+    client_manager: &Option<Arc<ClientManager>>,
+) {
+    let Some(conn) = robot_connection else {
+        return;
+    };
+    let (robot_id, changes) = {
+        let conn = conn.read().await;
+        let changes = conn.io_cache.lock().unwrap().take_changes();
+        (conn.robot_id, changes)
+    };
+    if let Some(cm) = client_manager {
+        for (point, value) in changes {
+            cm.notify_io_changed(IoPoint { robot_id, ..point }, value).await;
+        }
+    }
+}
+
+/// Add `points` to the client's I/O subscriptions.
+pub async fn subscribe_io(
+    client_manager: Option<Arc<ClientManager>>,
+    client_id: Option<Uuid>,
+    points: Vec<IoPoint>,
+) -> ServerResponse {
+    let (Some(cm), Some(client_id)) = (client_manager, client_id) else {
+        return ServerResponse::Error {
+            message: "I/O subscriptions need a client connection".to_string(),
+        };
+    };
+    let count = cm.subscribe_io(client_id, &points).await;
+    ServerResponse::Success {
+        message: format!("Subscribed to {} I/O points", count),
+    }
+}
+
+/// Remove `points` from the client's I/O subscriptions.
+pub async fn unsubscribe_io(
+    client_manager: Option<Arc<ClientManager>>,
+    client_id: Option<Uuid>,
+    points: Vec<IoPoint>,
+) -> ServerResponse {
+    let (Some(cm), Some(client_id)) = (client_manager, client_id) else {
+        return ServerResponse::Error {
+            message: "I/O subscriptions need a client connection".to_string(),
+        };
+    };
+    let count = cm.unsubscribe_io(client_id, &points).await;
+    ServerResponse::Success {
+        message: format!("Unsubscribed; {} I/O points remain", count),
+    }
+}
+
+/// Report every port in the requested ranges from the connection's
+/// [`IoCache`]. Ports never read or written report `false` / `0`.
+pub async fn read_io_snapshot(
+    robot_connection: Option<Arc<RwLock<RobotConnection>>>,
+    din: Option<IoPortRange>,
+    dout: Option<IoPortRange>,
+    ain: Option<IoPortRange>,
+    aout: Option<IoPortRange>,
+    gin: Option<IoPortRange>,
+    gout: Option<IoPortRange>,
+) -> ServerResponse {
+    let Some(conn) = robot_connection else {
+        return ServerResponse::Error {
+            message: "Not connected to robot".to_string(),
+        };
+    };
+
+    let conn = conn.read().await;
+    let cache = conn.io_cache.lock().unwrap();
+    ServerResponse::IoSnapshot {
+        din: snapshot_range(&cache.din, din),
+        dout: snapshot_range(&cache.dout, dout),
+        ain: snapshot_range(&cache.ain, ain),
+        aout: snapshot_range(&cache.aout, aout),
+        gin: snapshot_range(&cache.gin, gin),
+        gout: snapshot_range(&cache.gout, gout),
+    }
+}
 
 /// Read a digital input port.
 pub async fn read_din(
@@ -61,10 +243,12 @@ pub async fn read_din(
                     message: format!("Robot error: {}", resp.error_id),
                 };
             }
-            ServerResponse::DinValue {
+            let response = ServerResponse::DinValue {
                 port_number: resp.port_number,
                 port_value: resp.port_value != 0,
-            }
+            };
+            conn.io_cache.lock().unwrap().record(&response);
+            response
         }
         Ok(None) => {
             error!("No response received for FRC_ReadDIN (port {})", port_number);
@@ -131,7 +315,9 @@ pub async fn write_dout(
             }
             info!("DOUT[{}] set to {} successfully", port_number, if port_value { "ON" } else { "OFF" });
             // Return the new value - this will be broadcast to all clients
-            ServerResponse::DoutValue { port_number, port_value }
+            let response = ServerResponse::DoutValue { port_number, port_value, robot_id: conn.robot_id };
+            conn.io_cache.lock().unwrap().record(&response);
+            response
         }
         Ok(None) => ServerResponse::Error {
             message: "No response received".to_string(),
@@ -142,6 +328,65 @@ pub async fn write_dout(
     }
 }
 
+/// The scheduled turn-off of a [`pulse_dout`]. Dropping it cancels the
+/// turn-off.
+pub struct DoutPulse {
+    task: JoinHandle<()>,
+}
+
+impl Drop for DoutPulse {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Turn a digital output on, then off again after `duration_ms`.
+///
+/// Returns the on edge like [`write_dout`]; the off edge is broadcast to
+/// all clients when it happens. Pulsing a port whose previous pulse hasn't
+/// ended restarts its timer rather than queueing a second pulse.
+pub async fn pulse_dout(
+    robot_connection: Option<Arc<RwLock<RobotConnection>>>,
+    client_manager: Option<Arc<ClientManager>>,
+    port_number: u16,
+    duration_ms: u32,
+) -> ServerResponse {
+    let Some(ref conn) = robot_connection else {
+        return ServerResponse::Error {
+            message: "Not connected to robot".to_string(),
+        };
+    };
+
+    // Cancel the pending turn-off before the on write, so an old turn-off
+    // can't land after it
+    let previous = conn.read().await.dout_pulses.lock().unwrap().remove(&port_number);
+    drop(previous);
+
+    let response = write_dout(robot_connection.clone(), port_number, true).await;
+    if !matches!(response, ServerResponse::DoutValue { .. }) {
+        return response;
+    }
+
+    let pulse_conn = Arc::clone(conn);
+    let task = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(duration_ms.into())).await;
+        let robot_connection = Some(pulse_conn);
+        let response = write_dout(robot_connection.clone(), port_number, false).await;
+        match response {
+            ServerResponse::DoutValue { .. } => {
+                if let Some(ref cm) = client_manager {
+                    cm.broadcast_all(&response).await;
+                }
+            }
+            _ => warn!("DOUT[{}] pulse failed to turn off: {:?}", port_number, response),
+        }
+        push_io_changes(&robot_connection, &client_manager).await;
+    });
+    conn.read().await.dout_pulses.lock().unwrap().insert(port_number, DoutPulse { task });
+
+    response
+}
+
 /// Read multiple digital inputs (batch operation).
 pub async fn read_din_batch(
     robot_connection: Option<Arc<RwLock<RobotConnection>>>,
@@ -187,7 +432,9 @@ pub async fn read_din_batch(
         }
     }
 
-    ServerResponse::DinBatch { values: results }
+    let response = ServerResponse::DinBatch { values: results };
+    conn.io_cache.lock().unwrap().record(&response);
+    response
 }
 
 // ========== Analog I/O ==========
@@ -236,10 +483,13 @@ pub async fn read_ain(
                     message: format!("Robot error: {}", resp.error_id),
                 };
             }
-            ServerResponse::AinValue {
+            let response = ServerResponse::AinValue {
                 port_number: resp.port_number,
                 port_value: resp.port_value,
-            }
+                alarm_state: AlarmState::Normal,
+            };
+            conn.io_cache.lock().unwrap().record(&response);
+            response
         }
         Ok(None) => ServerResponse::Error {
             message: "No response received".to_string(),
@@ -300,7 +550,14 @@ pub async fn write_aout(
             }
             info!("AOUT[{}] set to {:.2} successfully", port_number, port_value);
             // Return the new value - this will be broadcast to all clients
-            ServerResponse::AoutValue { port_number, port_value }
+            let response = ServerResponse::AoutValue {
+                port_number,
+                port_value,
+                alarm_state: AlarmState::Normal,
+                robot_id: conn.robot_id,
+            };
+            conn.io_cache.lock().unwrap().record(&response);
+            response
         }
         Ok(None) => ServerResponse::Error {
             message: "No response received".to_string(),
@@ -357,10 +614,12 @@ pub async fn read_gin(
                     message: format!("Robot error: {}", resp.error_id),
                 };
             }
-            ServerResponse::GinValue {
+            let response = ServerResponse::GinValue {
                 port_number: resp.port_number,
                 port_value: resp.port_value,
-            }
+            };
+            conn.io_cache.lock().unwrap().record(&response);
+            response
         }
         Ok(None) => ServerResponse::Error {
             message: "No response received".to_string(),
@@ -421,7 +680,9 @@ pub async fn write_gout(
             }
             info!("GOUT[{}] set to {} successfully", port_number, port_value);
             // Return the new value - this will be broadcast to all clients
-            ServerResponse::GoutValue { port_number, port_value }
+            let response = ServerResponse::GoutValue { port_number, port_value, robot_id: conn.robot_id };
+            conn.io_cache.lock().unwrap().record(&response);
+            response
         }
         Ok(None) => ServerResponse::Error {
             message: "No response received".to_string(),
@@ -431,3 +692,182 @@ pub async fn write_gout(
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_types::ClientRequest;
+    use crate::database::Database;
+    use crate::test_support::{connect_robot, start_fake_controller};
+    use tokio::sync::Mutex;
+
+    /// Fake controller that acknowledges every `FRC_WriteDOUT` and answers
+    /// `FRC_ReadAIN` with 4.5. Returns the connect port.
+    async fn start_io_controller() -> u32 {
+        start_fake_controller(|packet| match packet["Command"].as_str() {
+            Some("FRC_WriteDOUT") => Some("{\"Command\":\"FRC_WriteDOUT\",\"ErrorID\":0}\r\n"),
+            Some("FRC_ReadAIN") => Some("{\"Command\":\"FRC_ReadAIN\",\"ErrorID\":0,\"PortNumber\":2,\"PortValue\":4.5}\r\n"),
+            _ => None,
+        })
+        .await
+    }
+
+    /// Connect a driver to the fake controller and wrap it in a robot
+    /// connection.
+    async fn connect_to_fake_controller() -> Arc<RwLock<RobotConnection>> {
+        let port = start_io_controller().await;
+        Arc::new(RwLock::new(connect_robot(port).await))
+    }
+
+    #[tokio::test]
+    async fn test_io_snapshot_reports_every_requested_port() {
+        let db = Arc::new(Mutex::new(Database::new(":memory:").expect("in-memory database")));
+        let conn = Some(connect_to_fake_controller().await);
+
+        let response = write_dout(conn.clone(), 3, true).await;
+        assert!(matches!(response, ServerResponse::DoutValue { .. }), "{:?}", response);
+        let response = read_ain(conn.clone(), 2).await;
+        assert!(matches!(response, ServerResponse::AinValue { .. }), "{:?}", response);
+
+        let response = crate::handlers::handle_request(
+            ClientRequest::ReadIoSnapshot {
+                din: Some(IoPortRange { start: 1, count: 2 }),
+                dout: Some(IoPortRange { start: 2, count: 3 }),
+                ain: Some(IoPortRange { start: 2, count: 1 }),
+                aout: None,
+                gin: None,
+                gout: Some(IoPortRange { start: 7, count: 1 }),
+            },
+            db,
+            None,
+            None,
+            conn,
+            None,
+            None,
+        )
+        .await;
+        match response {
+            ServerResponse::IoSnapshot { din, dout, ain, aout, gin, gout } => {
+                assert_eq!(din, vec![(1, false), (2, false)]);
+                assert_eq!(dout, vec![(2, false), (3, true), (4, false)]);
+                assert_eq!(ain, vec![(2, 4.5)]);
+                assert!(aout.is_empty());
+                assert!(gin.is_empty());
+                assert_eq!(gout, vec![(7, 0)]);
+            }
+            other => panic!("expected IoSnapshot, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscribed_dout_change_is_pushed_once() {
+        use crate::session::test_support::{connect_client, pushed};
+
+        let db = Arc::new(Mutex::new(Database::new(":memory:").expect("in-memory database")));
+        let conn = connect_to_fake_controller().await;
+
+        let client_manager = Arc::new(ClientManager::new());
+        let (client_id, mut socket) = connect_client(&client_manager).await;
+        client_manager.try_acquire_control(client_id).await.expect("acquire control");
+
+        let request = |request| {
+            crate::handlers::handle_request(
+                request,
+                Arc::clone(&db),
+                None,
+                None,
+                Some(Arc::clone(&conn)),
+                Some(Arc::clone(&client_manager)),
+                Some(client_id),
+            )
+        };
+
+        let dout3 = IoPoint { io_type: IoType::Dout, port: 3, robot_id: None };
+        let response = request(ClientRequest::SubscribeIo { points: vec![dout3] }).await;
+        assert!(matches!(response, ServerResponse::Success { .. }), "{:?}", response);
+
+        for (port_number, port_value) in [(3, true), (3, true), (4, true)] {
+            let response = request(ClientRequest::WriteDout { port_number, port_value }).await;
+            assert!(matches!(response, ServerResponse::DoutValue { .. }), "{:?}", response);
+        }
+
+        // Collect everything pushed to the client until it goes quiet
+        let changes: Vec<_> = pushed(&mut socket, Duration::from_millis(200))
+            .await
+            .into_iter()
+            .filter_map(|response| match response {
+                ServerResponse::IoChanged { point, value } => Some((point, value)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(changes, vec![(dout3, 1.0)]);
+
+        // Subscriptions go away with the client
+        client_manager.unregister(client_id).await;
+        assert_eq!(client_manager.subscribe_io(client_id, &[dout3]).await, 0);
+    }
+
+    /// The DOUT values broadcast to a client until it goes quiet for `quiet`.
+    async fn broadcast_douts(socket: &mut crate::session::test_support::ClientSocket, quiet: Duration) -> Vec<(u16, bool)> {
+        crate::session::test_support::pushed(socket, quiet)
+            .await
+            .into_iter()
+            .filter_map(|response| match response {
+                ServerResponse::DoutValue { port_number, port_value, .. } => Some((port_number, port_value)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_pulse_dout_broadcasts_on_then_off() {
+        use crate::session::test_support::connect_client;
+
+        let db = Arc::new(Mutex::new(Database::new(":memory:").expect("in-memory database")));
+        let conn = connect_to_fake_controller().await;
+        let client_manager = Arc::new(ClientManager::new());
+        let (client_id, mut socket) = connect_client(&client_manager).await;
+        client_manager.try_acquire_control(client_id).await.expect("acquire control");
+
+        let response = crate::handlers::handle_request(
+            ClientRequest::PulseDout { port_number: 5, duration_ms: 100 },
+            db,
+            None,
+            None,
+            Some(Arc::clone(&conn)),
+            Some(Arc::clone(&client_manager)),
+            Some(client_id),
+        )
+        .await;
+        assert!(
+            matches!(response, ServerResponse::DoutValue { port_number: 5, port_value: true, robot_id: None }),
+            "{:?}",
+            response
+        );
+
+        assert_eq!(broadcast_douts(&mut socket, Duration::from_millis(400)).await, vec![(5, true), (5, false)]);
+        let values = conn.read().await.io_cache.lock().unwrap().values();
+        assert_eq!(values, vec![(IoPoint { io_type: IoType::Dout, port: 5, robot_id: None }, 0.0)]);
+    }
+
+    #[tokio::test]
+    async fn test_second_pulse_restarts_the_timer() {
+        use crate::session::test_support::connect_client;
+
+        let conn = connect_to_fake_controller().await;
+        let client_manager = Arc::new(ClientManager::new());
+        let (_, mut socket) = connect_client(&client_manager).await;
+        let pulse = || pulse_dout(Some(Arc::clone(&conn)), Some(Arc::clone(&client_manager)), 5, 200);
+
+        assert!(matches!(pulse().await, ServerResponse::DoutValue { .. }));
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        assert!(matches!(pulse().await, ServerResponse::DoutValue { .. }));
+
+        // Past the first pulse's end, the output is still on
+        tokio::time::sleep(Duration::from_millis(140)).await;
+        assert_eq!(broadcast_douts(&mut socket, Duration::from_millis(10)).await, vec![]);
+
+        // The second pulse turns it off, once
+        assert_eq!(broadcast_douts(&mut socket, Duration::from_millis(300)).await, vec![(5, false)]);
+    }
+}
@@ -1,9 +1,11 @@
 //! I/O configuration handlers.
 
-use crate::api_types::{IoDisplayConfigDto, ServerResponse};
+use crate::api_types::{AlarmState, IoDisplayConfigDto, ServerResponse};
 use crate::database::Database;
+use crate::RobotConnection;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, RwLock};
+use tracing::warn;
 
 /// Get I/O display configuration for a robot.
 pub async fn get_io_config(
@@ -21,6 +23,8 @@ pub async fn get_io_config(
                     display_name: c.display_name,
                     is_visible: c.is_visible,
                     display_order: c.display_order,
+                    warning_threshold: c.warning_threshold,
+                    alarm_threshold: c.alarm_threshold,
                 })
                 .collect();
             ServerResponse::IoConfig { configs: dtos }
@@ -32,6 +36,7 @@ pub async fn get_io_config(
 }
 
 /// Update I/O display configuration.
+#[allow(clippy::too_many_arguments)]
 pub async fn update_io_config(
     db: Arc<Mutex<Database>>,
     robot_connection_id: i64,
@@ -40,6 +45,8 @@ pub async fn update_io_config(
     display_name: Option<String>,
     is_visible: bool,
     display_order: Option<i32>,
+    warning_threshold: Option<f64>,
+    alarm_threshold: Option<f64>,
 ) -> ServerResponse {
     let db = db.lock().await;
     match db.upsert_io_display_config(
@@ -49,6 +56,8 @@ pub async fn update_io_config(
         display_name.as_deref(),
         is_visible,
         display_order,
+        warning_threshold,
+        alarm_threshold,
     ) {
         Ok(()) => ServerResponse::Success {
             message: format!("Updated {}[{}] config", io_type, io_index),
@@ -59,3 +68,133 @@ pub async fn update_io_config(
     }
 }
 
+
+/// Fill in the alarm state of an `AinValue`/`AoutValue` response from the
+/// thresholds configured for the connected robot.
+///
+/// Other responses are returned unchanged. Without a saved robot connection
+/// or configured thresholds the state stays `Normal`.
+pub async fn apply_alarm_state(
+    db: &Arc<Mutex<Database>>,
+    robot_connection: &Option<Arc<RwLock<RobotConnection>>>,
+    mut response: ServerResponse,
+) -> ServerResponse {
+    let (io_type, port, value, state) = match &mut response {
+        ServerResponse::AinValue { port_number, port_value, alarm_state } => {
+            ("AIN", *port_number, *port_value, alarm_state)
+        }
+        ServerResponse::AoutValue { port_number, port_value, alarm_state, .. } => {
+            ("AOUT", *port_number, *port_value, alarm_state)
+        }
+        _ => return response,
+    };
+
+    let robot_connection_id = match robot_connection {
+        Some(conn) => conn.read().await.saved_connection.as_ref().map(|c| c.id),
+        None => None,
+    };
+    let Some(robot_connection_id) = robot_connection_id else {
+        return response;
+    };
+
+    let configs = match db.lock().await.get_io_display_config(robot_connection_id) {
+        Ok(configs) => configs,
+        Err(e) => {
+            warn!("Failed to load I/O thresholds: {}", e);
+            return response;
+        }
+    };
+    if let Some(config) = configs
+        .iter()
+        .find(|c| c.io_type == io_type && c.io_index == port as i32)
+    {
+        *state = AlarmState::evaluate(value, config.warning_threshold, config.alarm_threshold);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_types::ClientRequest;
+    use crate::test_support::{connect_robot, start_fake_controller};
+
+    /// Fake controller that accepts one connection and acknowledges every
+    /// `FRC_WriteAOUT`. Returns the connect port.
+    async fn start_aout_controller() -> u32 {
+        start_fake_controller(|packet| {
+            (packet["Command"] == "FRC_WriteAOUT").then_some("{\"Command\":\"FRC_WriteAOUT\",\"ErrorID\":0}\r\n")
+        })
+        .await
+    }
+
+    #[test]
+    fn test_missing_thresholds_are_normal() {
+        assert_eq!(AlarmState::evaluate(1e9, None, None), AlarmState::Normal);
+        assert_eq!(AlarmState::evaluate(6.0, Some(5.0), None), AlarmState::Warning);
+        assert_eq!(AlarmState::evaluate(6.0, None, Some(8.0)), AlarmState::Normal);
+        assert_eq!(AlarmState::evaluate(8.0, Some(5.0), Some(8.0)), AlarmState::Alarm);
+    }
+
+    #[tokio::test]
+    async fn test_aout_above_alarm_threshold_broadcasts_alarm() {
+        let db = Database::new(":memory:").expect("in-memory database");
+        let port = start_aout_controller().await;
+        let robot_id = db
+            .create_robot_connection(
+                "test", None, "127.0.0.1", port, 100.0, "mmSec", "CNT", 0.0, 0.0, 0.0, 10.0, 1.0,
+                0.1, 0.25, 5.0, 1.0,
+            )
+            .unwrap();
+        let saved = db.get_robot_connection(robot_id).unwrap();
+        let db = Arc::new(Mutex::new(db));
+
+        let response = update_io_config(
+            Arc::clone(&db),
+            robot_id,
+            "AOUT".to_string(),
+            1,
+            None,
+            true,
+            None,
+            Some(5.0),
+            Some(8.0),
+        )
+        .await;
+        assert!(matches!(response, ServerResponse::Success { .. }), "{:?}", response);
+
+        let mut conn = connect_robot(port).await;
+        conn.saved_connection = saved;
+        let conn = Arc::new(RwLock::new(conn));
+
+        // Single-client mode (no client manager): the returned response is
+        // exactly what would be broadcast.
+        let response = crate::handlers::handle_request(
+            ClientRequest::WriteAout { port_number: 1, port_value: 9.5 },
+            Arc::clone(&db),
+            None,
+            None,
+            Some(Arc::clone(&conn)),
+            None,
+            None,
+        )
+        .await;
+        match response {
+            ServerResponse::AoutValue { port_number, port_value, alarm_state, .. } => {
+                assert_eq!(port_number, 1);
+                assert_eq!(port_value, 9.5);
+                assert_eq!(alarm_state, AlarmState::Alarm);
+            }
+            other => panic!("expected AoutValue, got {:?}", other),
+        }
+
+        // A port without thresholds stays Normal.
+        let response = apply_alarm_state(
+            &db,
+            &Some(conn),
+            ServerResponse::AoutValue { port_number: 2, port_value: 9.5, alarm_state: AlarmState::Normal, robot_id: None },
+        )
+        .await;
+        assert!(matches!(response, ServerResponse::AoutValue { alarm_state: AlarmState::Normal, .. }));
+    }
+}
@@ -0,0 +1,163 @@
+//! Continuous jog handlers.
+
+use crate::api_types::{JogAxis, JogDirection, ServerResponse};
+use crate::jog::spawn_jog;
+use crate::program_executor::ProgramExecutor;
+use crate::session::ClientManager;
+use crate::RobotConnection;
+use fanuc_rmi::ArmConfig;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+use tracing::info;
+use uuid::Uuid;
+
+/// Start a server-paced jog, replacing any jog already running.
+///
+/// While a program runs, the jog's moves interleave with the program's
+/// through the executor's jog context.
+#[allow(clippy::too_many_arguments)]
+pub async fn jog_continuous(
+    executor: Option<Arc<Mutex<ProgramExecutor>>>,
+    robot_connection: Option<Arc<RwLock<RobotConnection>>>,
+    client_manager: Option<Arc<ClientManager>>,
+    client_id: Option<Uuid>,
+    axis: JogAxis,
+    direction: JogDirection,
+    speed: f64,
+) -> ServerResponse {
+    if !(speed.is_finite() && speed > 0.0) {
+        return ServerResponse::Error {
+            message: format!("Invalid jog speed: {}", speed),
+        };
+    }
+
+    let Some(robot_connection) = robot_connection else {
+        return ServerResponse::Error { message: "Not connected to robot".to_string() };
+    };
+
+    let mut conn = robot_connection.write().await;
+    let driver = match (&conn.driver, conn.connected) {
+        (Some(driver), true) => Arc::clone(driver),
+        _ => return ServerResponse::Error { message: "Not connected to robot".to_string() },
+    };
+    let configuration = match ArmConfig::try_from(&conn.active_configuration) {
+        Ok(arm) => arm.to_configuration(conn.active_utool() as i8, conn.active_uframe() as i8),
+        Err(e) => {
+            return ServerResponse::Error {
+                message: format!("Invalid arm configuration: {}", e),
+            };
+        }
+    };
+
+    // Replacing the handle stops the previous jog first
+    conn.jog = None;
+    conn.jog = Some(spawn_jog(
+        driver,
+        Arc::clone(&robot_connection),
+        executor,
+        client_manager,
+        client_id,
+        axis,
+        direction,
+        speed,
+        configuration,
+    ));
+
+    ServerResponse::Success {
+        message: format!("Jogging {:?} {:?}", axis, direction),
+    }
+}
+
+/// Stop the running jog, if any.
+pub async fn jog_stop(robot_connection: Option<Arc<RwLock<RobotConnection>>>) -> ServerResponse {
+    if let Some(robot_connection) = robot_connection {
+        if robot_connection.write().await.jog.take().is_some() {
+            info!("Jog stopped");
+        }
+    }
+    ServerResponse::Success { message: "Jog stopped".to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_types::ClientRequest;
+    use crate::database::Database;
+    use crate::test_support::{connect_driver, instruction_reply, start_fake_controller};
+    use std::sync::Mutex as StdMutex;
+    use std::time::Duration;
+
+    /// Long enough for several jog ticks.
+    const JOG_SETTLE: Duration = Duration::from_millis(400);
+
+    /// Fake controller that accepts one connection and completes every
+    /// instruction straight away. Returns the connect port and the
+    /// `TermType` of every instruction received, in order.
+    async fn start_instruction_recording_controller() -> (u32, Arc<StdMutex<Vec<String>>>) {
+        let instructions = Arc::new(StdMutex::new(Vec::new()));
+        let received = Arc::clone(&instructions);
+        let port = start_fake_controller(move |packet| {
+            let reply = instruction_reply(&packet)?;
+            let term_type = packet["TermType"].as_str().unwrap_or_default().to_string();
+            received.lock().unwrap().push(term_type);
+            Some(reply)
+        })
+        .await;
+        (port, instructions)
+    }
+
+    #[tokio::test]
+    async fn test_jog_stops_on_release_control() {
+        let db = Arc::new(Mutex::new(Database::new(":memory:").expect("in-memory database")));
+        let (port, instructions) = start_instruction_recording_controller().await;
+
+        let driver = connect_driver(port).await;
+        let mut conn = RobotConnection::new("127.0.0.1".to_string(), port);
+        conn.driver = Some(Arc::clone(&driver));
+        conn.connected = true;
+        let conn = Arc::new(RwLock::new(conn));
+
+        let client_manager = Arc::new(ClientManager::new());
+        let client_id = Uuid::new_v4();
+        client_manager.try_acquire_control(client_id).await.expect("acquire control");
+
+        let request = |request| {
+            crate::handlers::handle_request(
+                request,
+                Arc::clone(&db),
+                Some(Arc::clone(&driver)),
+                None,
+                Some(Arc::clone(&conn)),
+                Some(Arc::clone(&client_manager)),
+                Some(client_id),
+            )
+        };
+
+        let response = request(ClientRequest::JogContinuous {
+            axis: JogAxis::X,
+            direction: JogDirection::Positive,
+            speed: 50.0,
+        })
+        .await;
+        assert!(matches!(response, ServerResponse::Success { .. }), "{:?}", response);
+
+        tokio::time::sleep(JOG_SETTLE).await;
+        assert!(!instructions.lock().unwrap().is_empty(), "jog should stream moves");
+
+        let response = request(ClientRequest::ReleaseControl).await;
+        assert!(matches!(response, ServerResponse::ControlReleased), "{:?}", response);
+        assert!(conn.read().await.jog.is_none(), "releasing control must stop the jog");
+
+        // Let any move already on the wire arrive, then check nothing follows
+        tokio::time::sleep(JOG_SETTLE).await;
+        let sent = instructions.lock().unwrap().clone();
+        tokio::time::sleep(JOG_SETTLE).await;
+        assert_eq!(*instructions.lock().unwrap(), sent, "no moves after release");
+
+        // The jog ends on a FINE move, with every move before it blended
+        let (last, moves) = sent.split_last().expect("jog moves");
+        assert_eq!(last, "FINE", "the jog must end with a stop move");
+        assert!(moves.iter().all(|term_type| term_type == "CNT"), "{:?}", sent);
+        assert_eq!(driver.outstanding_instruction_count(), 0, "nothing left queued in the driver");
+    }
+}
@@ -0,0 +1,112 @@
+//! Metrics handler.
+//!
+//! Reports traffic and usage counters for monitoring. Packet counts come
+//! from the robot's drivers; the rest from the server's own state. Reading
+//! them does not require control.
+
+use crate::api_types::ServerResponse;
+use crate::program_executor::ProgramExecutor;
+use crate::session::ClientManager;
+use crate::RobotConnection;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+
+/// Collect the counters for `robot_connection`.
+///
+/// Program runs are counted on the active robot only, so they read 0 when
+/// `executor` is `None`.
+pub async fn get_metrics(
+    robot_connection: Option<Arc<RwLock<RobotConnection>>>,
+    executor: Option<Arc<Mutex<ProgramExecutor>>>,
+    client_manager: Option<Arc<ClientManager>>,
+) -> ServerResponse {
+    let (traffic, reconnects) = match robot_connection {
+        Some(conn) => {
+            let conn = conn.read().await;
+            (conn.traffic(), conn.reconnects())
+        }
+        None => Default::default(),
+    };
+    let (active_clients, control_holder) = match client_manager {
+        Some(client_manager) => (
+            client_manager.client_count().await,
+            client_manager.get_control_holder().await.map(|holder| holder.to_string()),
+        ),
+        None => (0, None),
+    };
+    let program_runs = match executor {
+        Some(executor) => executor.lock().await.runs_started(),
+        None => 0,
+    };
+
+    ServerResponse::Metrics {
+        packets_sent: traffic.packets_sent,
+        packets_received: traffic.packets_received,
+        protocol_errors: traffic.protocol_errors,
+        reconnects,
+        active_clients,
+        control_holder,
+        program_runs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_support::connect_client;
+    use crate::test_support::{connect_driver, start_silent_controller};
+    use fanuc_rmi::packets::{Command, PacketPriority, SendPacket};
+    use std::time::Duration;
+
+
+    async fn packets_sent(robot_connection: &Arc<RwLock<RobotConnection>>) -> u64 {
+        match get_metrics(Some(Arc::clone(robot_connection)), None, None).await {
+            ServerResponse::Metrics { packets_sent, .. } => packets_sent,
+            other => panic!("expected Metrics, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_packets_sent_counts_issued_commands() {
+        let port = start_silent_controller().await;
+        let driver = connect_driver(port).await;
+        let mut conn = RobotConnection::new("127.0.0.1".to_string(), port);
+        conn.driver = Some(Arc::clone(&driver));
+        conn.connected = true;
+        let conn = Arc::new(RwLock::new(conn));
+
+        let before = packets_sent(&conn).await;
+        for _ in 0..3 {
+            driver
+                .send_packet(SendPacket::Command(Command::FrcGetStatus), PacketPriority::Standard)
+                .expect("send FRC_GetStatus");
+        }
+        // Commands are written by a spawned task
+        let mut sent = before;
+        for _ in 0..50 {
+            sent = packets_sent(&conn).await;
+            if sent >= before + 3 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(sent, before + 3);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_report_clients_and_control_without_a_robot() {
+        let client_manager = Arc::new(ClientManager::new());
+        let (client_id, _socket) = connect_client(&client_manager).await;
+        client_manager.try_acquire_control(client_id).await.expect("take control");
+
+        match get_metrics(None, Some(Arc::new(Mutex::new(ProgramExecutor::new()))), Some(client_manager)).await {
+            ServerResponse::Metrics { packets_sent, active_clients, control_holder, program_runs, .. } => {
+                assert_eq!(packets_sent, 0);
+                assert_eq!(active_clients, 1);
+                assert_eq!(control_holder, Some(client_id.to_string()));
+                assert_eq!(program_runs, 0);
+            }
+            other => panic!("expected Metrics, got {:?}", other),
+        }
+    }
+}
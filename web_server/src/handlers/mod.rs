@@ -4,6 +4,7 @@
 //! - `configurations`: Robot configuration management (named configs per robot)
 //! - `connection`: Robot connection management (connect/disconnect/status)
 //! - `control`: Control locking (request/release control)
+//! - `event_log`: Audit trail of operator actions
 //! - `execution`: Program execution (start/pause/resume/stop)
 //! - `programs`: Program CRUD operations
 //! - `settings`: Robot settings management
@@ -11,23 +12,31 @@
 //! - `frame_tool`: Frame and tool data management
 //! - `io`: Digital I/O management (DIN/DOUT/AIN/AOUT/GIN/GOUT)
 //! - `io_config`: I/O display configuration management
+//! - `jog`: Server-paced continuous jogging
+//! - `metrics`: Traffic and usage counters for monitoring
 //! - `robot_control`: Robot control commands (abort/reset/initialize)
+//! - `safety_limits`: Per-robot motion safety limits
 
 pub mod configurations;
 pub mod connection;
 pub mod control;
+pub mod event_log;
 pub mod execution;
 pub mod frame_tool;
 pub mod io;
 pub mod io_config;
+pub mod jog;
+pub mod metrics;
 pub mod programs;
 pub mod robot_connections;
 pub mod robot_control;
+pub mod safety_limits;
 pub mod settings;
 
 use crate::api_types::*;
 use crate::database::Database;
 use crate::program_executor::ProgramExecutor;
+use crate::robots::RobotRegistry;
 use crate::session::ClientManager;
 use crate::RobotConnection;
 use fanuc_rmi::drivers::FanucDriver;
@@ -68,7 +77,123 @@ async fn require_control(
     }
 }
 
+/// Handle a client API request addressed to `robot_id`.
+///
+/// Requests for the active robot (`robot_id` of `None` or the active robot's
+/// connection id) are handled exactly as [`handle_request`]. Any other id
+/// names an additional robot in `registry`:
+/// - `connect_to_saved_robot` (whose `connection_id` must equal `robot_id`)
+///   adds and connects it, without the UI broadcasts of the active robot;
+/// - `disconnect_robot` disconnects and removes it;
+/// - programs only run on the active robot;
+/// - everything else is handled against that robot's connection and driver.
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_routed_request(
+    robot_id: Option<i64>,
+    request: ClientRequest,
+    registry: &RobotRegistry,
+    db: Arc<Mutex<Database>>,
+    executor: Option<Arc<Mutex<ProgramExecutor>>>,
+    client_manager: Option<Arc<ClientManager>>,
+    client_id: Option<Uuid>,
+) -> ServerResponse {
+    let robot_id = match robot_id {
+        Some(id) if !registry.is_active(Some(id)).await => id,
+        _ => {
+            let robot_connection = registry.active();
+            let driver = robot_connection.read().await.driver.clone();
+            return handle_request(
+                request, db, driver, executor, Some(robot_connection), client_manager, client_id,
+            )
+            .await;
+        }
+    };
+
+    // Connecting and disconnecting an additional robot bypass `handle_request`
+    let event = match request {
+        ClientRequest::ConnectToSavedRobot { .. } | ClientRequest::DisconnectRobot => event_log::audited(&request),
+        _ => None,
+    };
+    let response = route_to_robot(robot_id, request, registry, db.clone(), client_manager, client_id).await;
+    if let Some(event) = event {
+        event_log::log_outcome(&db, event, client_id, &response).await;
+    }
+    response
+}
+
+/// [`handle_routed_request`] for additional robot `robot_id`.
+async fn route_to_robot(
+    robot_id: i64,
+    request: ClientRequest,
+    registry: &RobotRegistry,
+    db: Arc<Mutex<Database>>,
+    client_manager: Option<Arc<ClientManager>>,
+    client_id: Option<Uuid>,
+) -> ServerResponse {
+    match request {
+        ClientRequest::ConnectToSavedRobot { connection_id } => {
+            if let Err(e) = require_control(&client_manager, client_id).await {
+                return e;
+            }
+            if connection_id != robot_id {
+                return ServerResponse::Error {
+                    message: format!(
+                        "robot_id {} does not match connection_id {}",
+                        robot_id, connection_id
+                    ),
+                };
+            }
+            let robot_connection = registry.get_or_insert(robot_id).await;
+            let response =
+                connection::connect_to_saved_robot(db, Some(robot_connection), None, connection_id).await;
+            if matches!(response, ServerResponse::Error { .. }) {
+                registry.remove(robot_id).await;
+            }
+            response
+        }
+        ClientRequest::ConnectRobot { .. } => ServerResponse::Error {
+            message: "Additional robots connect by saved connection (connect_to_saved_robot)".to_string(),
+        },
+        ClientRequest::DisconnectRobot => {
+            if let Err(e) = require_control(&client_manager, client_id).await {
+                return e;
+            }
+            match registry.remove(robot_id).await {
+                Some(robot_connection) => connection::disconnect_robot(Some(robot_connection)).await,
+                None => ServerResponse::Error {
+                    message: format!("Robot {} is not connected", robot_id),
+                },
+            }
+        }
+        ClientRequest::LoadProgram { .. }
+        | ClientRequest::UnloadProgram
+        | ClientRequest::StartProgram { .. }
+        | ClientRequest::PauseProgram
+        | ClientRequest::ResumeProgram
+        | ClientRequest::StopProgram
+        | ClientRequest::GetExecutionState
+        | ClientRequest::SetBreakpoint { .. }
+        | ClientRequest::ClearBreakpoint { .. } => ServerResponse::Error {
+            message: "Programs run on the active robot only".to_string(),
+        },
+        request => {
+            let Some(robot_connection) = registry.resolve(Some(robot_id)).await else {
+                return ServerResponse::Error {
+                    message: format!("Robot {} is not connected", robot_id),
+                };
+            };
+            let driver = robot_connection.read().await.driver.clone();
+            handle_request(
+                request, db, driver, None, Some(robot_connection), client_manager, client_id,
+            )
+            .await
+        }
+    }
+}
+
 /// Handle a client API request and return a response.
+///
+/// Operator actions are recorded in the event log along with their outcome.
 pub async fn handle_request(
     request: ClientRequest,
     db: Arc<Mutex<Database>>,
@@ -77,17 +202,58 @@ pub async fn handle_request(
     robot_connection: Option<Arc<RwLock<RobotConnection>>>,
     client_manager: Option<Arc<ClientManager>>,
     client_id: Option<uuid::Uuid>,
+) -> ServerResponse {
+    let event = event_log::audited(&request);
+    let response = dispatch_request(
+        request, Arc::clone(&db), driver, executor, robot_connection, client_manager, client_id,
+    )
+    .await;
+    if let Some(event) = event {
+        event_log::log_outcome(&db, event, client_id, &response).await;
+    }
+    response
+}
+
+async fn dispatch_request(
+    request: ClientRequest,
+    db: Arc<Mutex<Database>>,
+    driver: Option<Arc<FanucDriver>>,
+    executor: Option<Arc<Mutex<ProgramExecutor>>>,
+    robot_connection: Option<Arc<RwLock<RobotConnection>>>,
+    client_manager: Option<Arc<ClientManager>>,
+    client_id: Option<uuid::Uuid>,
 ) -> ServerResponse {
     match request {
         // Program management
         ClientRequest::ListPrograms => programs::list_programs(db).await,
         ClientRequest::GetProgram { id } => programs::get_program(db, id).await,
+        ClientRequest::ExportProgramCsv { program_id } => {
+            let unit = programs::csv_unit(&robot_connection).await;
+            programs::export_program_csv(db, program_id, unit).await
+        }
         ClientRequest::CreateProgram { name, description } => {
             programs::create_program(db, &name, description.as_deref()).await
         }
         ClientRequest::DeleteProgram { id } => programs::delete_program(db, id).await,
+        ClientRequest::PreviewCsv { csv_content } => {
+            let unit = programs::csv_unit(&robot_connection).await;
+            programs::preview_csv(&csv_content, unit).await
+        }
         ClientRequest::UploadCsv { program_id, csv_content, start_position } => {
-            programs::upload_csv(db, program_id, &csv_content, start_position).await
+            let unit = programs::csv_unit(&robot_connection).await;
+            programs::upload_csv(db, program_id, &csv_content, start_position, unit).await
+        }
+        ClientRequest::InsertInstruction { program_id, instruction } => {
+            programs::insert_instruction(db, program_id, instruction).await
+        }
+        ClientRequest::UpdateInstruction { program_id, instruction } => {
+            programs::update_instruction(db, program_id, instruction).await
+        }
+        ClientRequest::DeleteInstruction { program_id, line_number } => {
+            programs::delete_instruction(db, program_id, line_number).await
+        }
+        ClientRequest::ReorderInstructions { program_id, order } => {
+            programs::reorder_instructions(db, program_id, &order).await
         }
         ClientRequest::UpdateProgramSettings {
             program_id, start_x, start_y, start_z, start_w, start_p, start_r,
@@ -116,6 +282,7 @@ pub async fn handle_request(
             ).await
         }
         ClientRequest::ResetDatabase => settings::reset_database(db).await,
+        ClientRequest::GetEventLog { since, limit } => event_log::get_event_log(db, since, limit).await,
 
         // Program execution (requires control)
         ClientRequest::LoadProgram { program_id } => {
@@ -124,6 +291,9 @@ pub async fn handle_request(
             }
             execution::load_program(db, executor, program_id, robot_connection, client_manager).await
         }
+        ClientRequest::EstimateProgram { program_id } => {
+            programs::estimate_program(db, program_id, robot_connection).await
+        }
         ClientRequest::UnloadProgram => {
             if let Err(e) = require_control(&client_manager, client_id).await {
                 return e;
@@ -152,9 +322,21 @@ pub async fn handle_request(
             if let Err(e) = require_control(&client_manager, client_id).await {
                 return e;
             }
-            execution::stop_program(driver, executor, robot_connection, client_manager).await
+            execution::stop_program(driver, executor, robot_connection, client_manager, AbortReason::UserRequested).await
         }
         ClientRequest::GetExecutionState => execution::get_execution_state(executor).await,
+        ClientRequest::SetBreakpoint { program_id, line } => {
+            if let Err(e) = require_control(&client_manager, client_id).await {
+                return e;
+            }
+            execution::set_breakpoint(db, executor, program_id, line).await
+        }
+        ClientRequest::ClearBreakpoint { program_id, line } => {
+            if let Err(e) = require_control(&client_manager, client_id).await {
+                return e;
+            }
+            execution::clear_breakpoint(executor, program_id, line).await
+        }
 
         // Robot control commands (requires control)
         ClientRequest::RobotAbort => {
@@ -175,11 +357,13 @@ pub async fn handle_request(
             }
             robot_control::robot_initialize(driver, robot_connection, client_manager, group_mask.unwrap_or(1)).await
         }
+        ClientRequest::ReadControllerError => robot_control::read_controller_error(driver).await,
 
         // Robot connection management
         ClientRequest::GetConnectionStatus => {
             connection::get_connection_status(robot_connection).await
         }
+        ClientRequest::GetMetrics => metrics::get_metrics(robot_connection, executor, client_manager).await,
         ClientRequest::ConnectRobot { robot_addr, robot_port } => {
             // Requires control - changes which robot the server is connected to
             if let Err(e) = require_control(&client_manager, client_id).await {
@@ -259,6 +443,9 @@ pub async fn handle_request(
         ClientRequest::UpdateRobotJogDefaults { id, cartesian_jog_speed, cartesian_jog_step, joint_jog_speed, joint_jog_step, rotation_jog_speed, rotation_jog_step } => {
             robot_connections::update_robot_jog_defaults(db, id, cartesian_jog_speed, cartesian_jog_step, joint_jog_speed, joint_jog_step, rotation_jog_speed, rotation_jog_step).await
         }
+        ClientRequest::UpdateRobotConnectionUnit { id, coordinate_unit } => {
+            robot_connections::update_robot_connection_unit(db, robot_connection, id, coordinate_unit).await
+        }
         ClientRequest::UpdateJogControls { cartesian_jog_speed, cartesian_jog_step, joint_jog_speed, joint_jog_step, rotation_jog_speed, rotation_jog_step } => {
             // Requires control - changes active jog controls (from Control panel)
             if let Err(e) = require_control(&client_manager, client_id).await {
@@ -307,75 +494,153 @@ pub async fn handle_request(
         }
 
         // I/O management - Digital
+        // Every read or write also pushes value changes to I/O subscribers.
         ClientRequest::ReadDin { port_number } => {
-            io::read_din(robot_connection, port_number).await
+            let response = io::read_din(robot_connection.clone(), port_number).await;
+            io::push_io_changes(&robot_connection, &client_manager).await;
+            response
         }
         ClientRequest::WriteDout { port_number, port_value } => {
             // Requires control - modifies robot outputs
             if let Err(e) = require_control(&client_manager, client_id).await {
                 return e;
             }
-            let response = io::write_dout(robot_connection, port_number, port_value).await;
+            let response = io::write_dout(robot_connection.clone(), port_number, port_value).await;
             // Broadcast successful I/O changes to all clients
             if matches!(response, ServerResponse::DoutValue { .. }) {
                 if let Some(ref cm) = client_manager {
                     cm.broadcast_all(&response).await;
                 }
             }
+            io::push_io_changes(&robot_connection, &client_manager).await;
+            response
+        }
+        ClientRequest::PulseDout { port_number, duration_ms } => {
+            // Requires control - modifies robot outputs
+            if let Err(e) = require_control(&client_manager, client_id).await {
+                return e;
+            }
+            let response = io::pulse_dout(robot_connection.clone(), client_manager.clone(), port_number, duration_ms).await;
+            // Broadcast the on edge; the off edge is broadcast when it happens
+            if matches!(response, ServerResponse::DoutValue { .. }) {
+                if let Some(ref cm) = client_manager {
+                    cm.broadcast_all(&response).await;
+                }
+            }
+            io::push_io_changes(&robot_connection, &client_manager).await;
             response
         }
         ClientRequest::ReadDinBatch { port_numbers } => {
-            io::read_din_batch(robot_connection, port_numbers).await
+            let response = io::read_din_batch(robot_connection.clone(), port_numbers).await;
+            io::push_io_changes(&robot_connection, &client_manager).await;
+            response
         }
 
         // I/O management - Analog
         ClientRequest::ReadAin { port_number } => {
-            io::read_ain(robot_connection, port_number).await
+            let response = io::read_ain(robot_connection.clone(), port_number).await;
+            io::push_io_changes(&robot_connection, &client_manager).await;
+            io_config::apply_alarm_state(&db, &robot_connection, response).await
         }
         ClientRequest::WriteAout { port_number, port_value } => {
             // Requires control - modifies robot outputs
             if let Err(e) = require_control(&client_manager, client_id).await {
                 return e;
             }
-            let response = io::write_aout(robot_connection, port_number, port_value).await;
+            let response = io::write_aout(robot_connection.clone(), port_number, port_value).await;
+            let response = io_config::apply_alarm_state(&db, &robot_connection, response).await;
             // Broadcast successful I/O changes to all clients
             if matches!(response, ServerResponse::AoutValue { .. }) {
                 if let Some(ref cm) = client_manager {
                     cm.broadcast_all(&response).await;
                 }
             }
+            io::push_io_changes(&robot_connection, &client_manager).await;
             response
         }
 
         // I/O management - Group
         ClientRequest::ReadGin { port_number } => {
-            io::read_gin(robot_connection, port_number).await
+            let response = io::read_gin(robot_connection.clone(), port_number).await;
+            io::push_io_changes(&robot_connection, &client_manager).await;
+            response
         }
         ClientRequest::WriteGout { port_number, port_value } => {
             // Requires control - modifies robot outputs
             if let Err(e) = require_control(&client_manager, client_id).await {
                 return e;
             }
-            let response = io::write_gout(robot_connection, port_number, port_value).await;
+            let response = io::write_gout(robot_connection.clone(), port_number, port_value).await;
             // Broadcast successful I/O changes to all clients
             if matches!(response, ServerResponse::GoutValue { .. }) {
                 if let Some(ref cm) = client_manager {
                     cm.broadcast_all(&response).await;
                 }
             }
+            io::push_io_changes(&robot_connection, &client_manager).await;
             response
         }
 
+        // I/O management - Snapshot
+        ClientRequest::ReadIoSnapshot { din, dout, ain, aout, gin, gout } => {
+            io::read_io_snapshot(robot_connection, din, dout, ain, aout, gin, gout).await
+        }
+
+        // I/O management - Subscriptions
+        ClientRequest::SubscribeIo { points } => {
+            io::subscribe_io(client_manager, client_id, points).await
+        }
+        ClientRequest::UnsubscribeIo { points } => {
+            io::unsubscribe_io(client_manager, client_id, points).await
+        }
+
         // Control locking
         ClientRequest::RequestControl => {
             control::request_control(client_manager, client_id).await
         }
         ClientRequest::ReleaseControl => {
-            control::release_control(client_manager, client_id).await
+            let response = control::release_control(client_manager, client_id).await;
+            // A jog must not outlive the control it was started under
+            if matches!(response, ServerResponse::ControlReleased) {
+                jog::jog_stop(robot_connection).await;
+            }
+            response
         }
         ClientRequest::GetControlStatus => {
             control::get_control_status(client_manager, client_id).await
         }
+        ClientRequest::RequestControlHandoff => {
+            control::request_control_handoff(client_manager, client_id).await
+        }
+        ClientRequest::GrantControl => {
+            let response = control::grant_control(client_manager, client_id).await;
+            // A jog must not outlive the control it was started under
+            if matches!(response, ServerResponse::ControlReleased) {
+                jog::jog_stop(robot_connection).await;
+            }
+            response
+        }
+        ClientRequest::DenyControl => {
+            control::deny_control(client_manager, client_id).await
+        }
+        ClientRequest::Heartbeat => {
+            control::heartbeat(client_manager, client_id).await
+        }
+
+        // Jogging (requires control)
+        ClientRequest::JogContinuous { axis, direction, speed } => {
+            if let Err(e) = require_control(&client_manager, client_id).await {
+                return e;
+            }
+            jog::jog_continuous(executor, robot_connection, client_manager, client_id, axis, direction, speed).await
+        }
+        ClientRequest::JogStop => {
+            // Stopping is always allowed, even without control
+            jog::jog_stop(robot_connection).await
+        }
+
+        // Protocol handshake
+        ClientRequest::Hello { protocol_version } => negotiate_protocol(protocol_version),
 
         // I/O Configuration
         ClientRequest::GetIoConfig { robot_connection_id } => {
@@ -388,6 +653,8 @@ pub async fn handle_request(
             display_name,
             is_visible,
             display_order,
+            warning_threshold,
+            alarm_threshold,
         } => {
             io_config::update_io_config(
                 db,
@@ -397,9 +664,23 @@ pub async fn handle_request(
                 display_name,
                 is_visible,
                 display_order,
+                warning_threshold,
+                alarm_threshold,
             ).await
         }
 
+        // Safety Limits
+        ClientRequest::GetSafetyLimits { robot_connection_id } => {
+            safety_limits::get_safety_limits(db, robot_connection_id).await
+        }
+        ClientRequest::UpdateSafetyLimits { robot_connection_id, limits } => {
+            // Changing the guardrails requires control
+            if let Err(e) = require_control(&client_manager, client_id).await {
+                return e;
+            }
+            safety_limits::update_safety_limits(db, robot_connection_id, limits).await
+        }
+
         // Robot Configurations
         ClientRequest::ListRobotConfigurations { robot_connection_id } => {
             configurations::list_robot_configurations(db, robot_connection_id).await
@@ -479,6 +760,9 @@ pub async fn handle_request(
         ClientRequest::LoadConfiguration { configuration_id } => {
             configurations::load_configuration(db, robot_connection, client_manager, configuration_id).await
         }
+        ClientRequest::GetConfigurationDiff => {
+            configurations::get_configuration_diff(db, robot_connection).await
+        }
         ClientRequest::SaveCurrentConfiguration { configuration_name } => {
             // Requires control - saves configuration to database
             if let Err(e) = require_control(&client_manager, client_id).await {
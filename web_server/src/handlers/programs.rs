@@ -1,14 +1,26 @@
 //! Program management handlers.
 //!
-//! Handles CRUD operations for programs and CSV upload.
+//! Handles CRUD operations for programs and CSV upload and export.
 
 use crate::api_types::*;
 use crate::database::{Database, ProgramInstruction};
-use crate::program_parser::{parse_csv_string, ProgramDefaults};
+use crate::program_executor::ProgramExecutor;
+use crate::program_parser::{parse_csv_string, parse_csv_with_errors, write_csv_string, ProgramDefaults};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tracing::{info, warn};
 
+/// The length unit CSV files are read and written in: that of the active
+/// robot connection, millimetres without a saved connection.
+pub async fn csv_unit(robot_connection: &Option<Arc<tokio::sync::RwLock<crate::RobotConnection>>>) -> LengthUnit {
+    match robot_connection {
+        Some(conn) => conn.read().await.saved_connection.as_ref()
+            .map(|c| LengthUnit::from_db(&c.coordinate_unit))
+            .unwrap_or_default(),
+        None => LengthUnit::default(),
+    }
+}
+
 /// List all programs.
 pub async fn list_programs(db: Arc<Mutex<Database>>) -> ServerResponse {
     let db = db.lock().await;
@@ -23,6 +35,8 @@ pub async fn list_programs(db: Arc<Mutex<Database>>) -> ServerResponse {
                     instruction_count: count,
                     created_at: p.created_at.clone(),
                     updated_at: p.updated_at.clone(),
+                    run_count: p.run_count,
+                    last_run_at: p.last_run_at.clone(),
                 }
             }).collect();
             ServerResponse::Programs { programs: program_infos }
@@ -76,6 +90,8 @@ pub async fn get_program(db: Arc<Mutex<Database>>, id: i64) -> ServerResponse {
                     move_speed: program.move_speed,
                     created_at: program.created_at,
                     updated_at: program.updated_at,
+                    run_count: program.run_count,
+                    last_run_at: program.last_run_at,
                 }
             }
         }
@@ -84,6 +100,80 @@ pub async fn get_program(db: Arc<Mutex<Database>>, id: i64) -> ServerResponse {
     }
 }
 
+/// Export a program's instructions as CSV in the upload format.
+///
+/// Control lines (labels, jumps, loops) have no CSV representation, so
+/// programs containing them are refused rather than exported without them.
+/// X/Y/Z are written in `unit`.
+pub async fn export_program_csv(db: Arc<Mutex<Database>>, program_id: i64, unit: LengthUnit) -> ServerResponse {
+    let db = db.lock().await;
+    let program = match db.get_program(program_id) {
+        Ok(Some(program)) => program,
+        Ok(None) => return ServerResponse::Error { message: "Program not found".to_string() },
+        Err(e) => return ServerResponse::Error { message: format!("Failed to get program: {}", e) },
+    };
+    let instructions = match db.get_instructions(program_id) {
+        Ok(instructions) => instructions,
+        Err(e) => return ServerResponse::Error { message: format!("Failed to get instructions: {}", e) },
+    };
+
+    if let Some(control) = instructions.iter().find(|i| i.control.is_some()) {
+        return ServerResponse::Error {
+            message: format!(
+                "Cannot export to CSV: line {} is a control instruction",
+                control.line_number
+            ),
+        };
+    }
+
+    let instructions: Vec<ProgramInstruction> = instructions.into_iter()
+        .map(|i| ProgramInstruction { x: unit.from_mm(i.x), y: unit.from_mm(i.y), z: unit.from_mm(i.z), ..i })
+        .collect();
+
+    match write_csv_string(&instructions) {
+        Ok(content) => {
+            let stem: String = program.name.chars()
+                .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+                .collect();
+            ServerResponse::ProgramCsv { filename: format!("{}.csv", stem), content }
+        }
+        Err(e) => ServerResponse::Error { message: format!("Failed to export CSV: {}", e) },
+    }
+}
+
+/// Estimate a program's run time.
+///
+/// Builds the same motion sequence `load_program` would (approach/retreat moves,
+/// defaults, speed type) in a scratch executor, so the active executor is untouched.
+pub async fn estimate_program(
+    db: Arc<Mutex<Database>>,
+    program_id: i64,
+    robot_connection: Option<Arc<tokio::sync::RwLock<crate::RobotConnection>>>,
+) -> ServerResponse {
+    let (active_config, default_speed_type) = if let Some(ref conn) = robot_connection {
+        let conn_guard = conn.read().await;
+        let speed_type = conn_guard.saved_connection.as_ref()
+            .map(|sc| sc.default_speed_type.clone())
+            .unwrap_or_else(|| "mmSec".to_string());
+        (Some(conn_guard.active_configuration.clone()), speed_type)
+    } else {
+        (None, "mmSec".to_string())
+    };
+
+    let db = db.lock().await;
+    let mut executor = ProgramExecutor::new();
+    if let Err(e) = executor.load_program(&db, program_id, active_config.as_ref(), &default_speed_type) {
+        return ServerResponse::Error { message: format!("Failed to estimate program: {}", e) };
+    }
+
+    let (duration, segment_count) = executor.estimate();
+    ServerResponse::ProgramEstimate {
+        program_id,
+        seconds: duration.as_secs_f64(),
+        segment_count,
+    }
+}
+
 /// Create a new program.
 pub async fn create_program(db: Arc<Mutex<Database>>, name: &str, description: Option<&str>) -> ServerResponse {
     let db = db.lock().await;
@@ -108,23 +198,12 @@ pub async fn delete_program(db: Arc<Mutex<Database>>, id: i64) -> ServerResponse
     }
 }
 
-/// Upload CSV content to a program.
+/// Defaults for CSV parsing, shared by upload and preview.
 ///
-/// CSV contains generic waypoints (X, Y, Z, optional W, P, R, speed, term_type).
-/// Robot-specific configuration (UFrame, UTool, arm config) is NOT stored in the program -
-/// it is applied at execution time from the active robot configuration.
-pub async fn upload_csv(
-    db: Arc<Mutex<Database>>,
-    program_id: i64,
-    csv_content: &str,
-    start_position: Option<StartPosition>,
-) -> ServerResponse {
-    let db = db.lock().await;
-
-    // Use sensible defaults for CSV parsing
-    // Robot-specific config (uframe, utool, arm config) is NULL in stored instructions
-    // and will be applied from active configuration at execution time
-    let defaults = ProgramDefaults {
+/// Robot-specific config (uframe, utool, arm config) is NULL in stored instructions
+/// and will be applied from active configuration at execution time.
+fn csv_defaults() -> ProgramDefaults {
+    ProgramDefaults {
         w: 0.0,           // Default rotation if not specified in CSV
         p: 0.0,
         r: 0.0,
@@ -145,8 +224,60 @@ pub async fn upload_csv(
         turn4: None,
         turn5: None,
         turn6: None,
+    }
+}
+
+/// Parse CSV content as [`upload_csv`] would, without touching the database.
+///
+/// Rows that parsed are returned with X/Y/Z converted from `unit` to
+/// millimetres, alongside the warnings and the validation errors that
+/// would make the upload fail.
+pub async fn preview_csv(csv_content: &str, unit: LengthUnit) -> ServerResponse {
+    let (parse_result, errors) = match parse_csv_with_errors(csv_content.as_bytes(), &csv_defaults()) {
+        Ok(parsed) => parsed,
+        Err(e) => return ServerResponse::Error {
+            message: format!("Failed to parse CSV: {}", e)
+        }
     };
 
+    let instructions = parse_result.instructions.iter().map(|i| InstructionDto {
+        line_number: i.line_number,
+        x: unit.to_mm(i.x),
+        y: unit.to_mm(i.y),
+        z: unit.to_mm(i.z),
+        w: i.w,
+        p: i.p,
+        r: i.r,
+        speed: i.speed,
+        term_type: i.term_type.clone(),
+        term_value: i.term_value,
+        uframe: i.uframe,
+        utool: i.utool,
+    }).collect();
+
+    ServerResponse::CsvPreview {
+        instructions,
+        warnings: parse_result.warnings.iter().map(ToString::to_string).collect(),
+        errors: errors.iter().map(ToString::to_string).collect(),
+    }
+}
+
+/// Upload CSV content to a program.
+///
+/// CSV contains generic waypoints (X, Y, Z, optional W, P, R, speed, term_type).
+/// Robot-specific configuration (UFrame, UTool, arm config) is NOT stored in the program -
+/// it is applied at execution time from the active robot configuration.
+/// X/Y/Z are read in `unit` and stored in millimetres.
+pub async fn upload_csv(
+    db: Arc<Mutex<Database>>,
+    program_id: i64,
+    csv_content: &str,
+    start_position: Option<StartPosition>,
+    unit: LengthUnit,
+) -> ServerResponse {
+    let db = db.lock().await;
+    let defaults = csv_defaults();
+
     // Parse CSV with full validation
     let parse_result = match parse_csv_string(csv_content, &defaults) {
         Ok(result) => result,
@@ -155,7 +286,13 @@ pub async fn upload_csv(
         }
     };
 
-    let instructions = parse_result.instructions;
+    // Store canonical millimetres whatever unit the file is in
+    let mut instructions = parse_result.instructions;
+    for instr in &mut instructions {
+        instr.x = unit.to_mm(instr.x);
+        instr.y = unit.to_mm(instr.y);
+        instr.z = unit.to_mm(instr.z);
+    }
 
     // Log any warnings
     for warning in &parse_result.warnings {
@@ -190,6 +327,7 @@ pub async fn upload_csv(
             term_value: instr.term_value,
             uframe: instr.uframe,
             utool: instr.utool,
+            control: None,
         };
         if let Err(e) = db.add_instruction(program_id, &db_instr) {
             return ServerResponse::Error {
@@ -270,6 +408,157 @@ pub async fn upload_csv(
     }
 }
 
+/// The error to answer with if a program does not exist.
+fn missing_program(db: &Database, program_id: i64) -> Option<ServerResponse> {
+    match db.get_program(program_id) {
+        Ok(Some(_)) => None,
+        Ok(None) => Some(ServerResponse::Error { message: "Program not found".to_string() }),
+        Err(e) => Some(ServerResponse::Error { message: format!("Failed to get program: {}", e) }),
+    }
+}
+
+/// The stored motion line for an edited instruction. External axes and
+/// speed type, which the DTO does not carry, are kept from `existing`.
+fn motion_line(program_id: i64, instruction: &InstructionDto, existing: Option<&ProgramInstruction>) -> ProgramInstruction {
+    ProgramInstruction {
+        id: existing.map_or(0, |e| e.id),
+        program_id,
+        line_number: instruction.line_number,
+        x: instruction.x,
+        y: instruction.y,
+        z: instruction.z,
+        w: instruction.w,
+        p: instruction.p,
+        r: instruction.r,
+        ext1: existing.and_then(|e| e.ext1),
+        ext2: existing.and_then(|e| e.ext2),
+        ext3: existing.and_then(|e| e.ext3),
+        speed: instruction.speed,
+        speed_type: existing.and_then(|e| e.speed_type.clone()),
+        term_type: instruction.term_type.clone(),
+        term_value: instruction.term_value,
+        uframe: instruction.uframe,
+        utool: instruction.utool,
+        control: None,
+    }
+}
+
+/// Insert an instruction so it becomes line `instruction.line_number`,
+/// moving the lines from there on down. A line number past the end appends.
+///
+/// Edits hold the database lock from validation to write, so concurrent
+/// edits apply one after the other and line numbers stay contiguous.
+pub async fn insert_instruction(
+    db: Arc<Mutex<Database>>,
+    program_id: i64,
+    instruction: InstructionDto,
+) -> ServerResponse {
+    {
+        let db = db.lock().await;
+        if let Some(response) = missing_program(&db, program_id) {
+            return response;
+        }
+        if instruction.line_number < 1 {
+            return ServerResponse::Error { message: "Line numbers start at 1".to_string() };
+        }
+        let count = match db.instruction_count(program_id) {
+            Ok(count) => count,
+            Err(e) => return ServerResponse::Error { message: format!("Failed to get instructions: {}", e) },
+        };
+        let mut line = motion_line(program_id, &instruction, None);
+        line.line_number = line.line_number.min(count as i32 + 1);
+        if let Err(e) = db.insert_instruction(program_id, &line) {
+            return ServerResponse::Error { message: format!("Failed to insert instruction: {}", e) };
+        }
+        info!("Inserted line {} into program {}", line.line_number, program_id);
+    }
+    get_program(db, program_id).await
+}
+
+/// Replace line `instruction.line_number` of a program. Control lines
+/// cannot be replaced this way.
+pub async fn update_instruction(
+    db: Arc<Mutex<Database>>,
+    program_id: i64,
+    instruction: InstructionDto,
+) -> ServerResponse {
+    {
+        let db = db.lock().await;
+        if let Some(response) = missing_program(&db, program_id) {
+            return response;
+        }
+        let instructions = match db.get_instructions(program_id) {
+            Ok(instructions) => instructions,
+            Err(e) => return ServerResponse::Error { message: format!("Failed to get instructions: {}", e) },
+        };
+        let Some(existing) = instructions.iter().find(|i| i.line_number == instruction.line_number) else {
+            return ServerResponse::Error { message: format!("Line {} not found", instruction.line_number) };
+        };
+        if existing.control.is_some() {
+            return ServerResponse::Error {
+                message: format!("Line {} is a control instruction", instruction.line_number),
+            };
+        }
+        if let Err(e) = db.update_instruction(program_id, &motion_line(program_id, &instruction, Some(existing))) {
+            return ServerResponse::Error { message: format!("Failed to update instruction: {}", e) };
+        }
+        info!("Updated line {} of program {}", instruction.line_number, program_id);
+    }
+    get_program(db, program_id).await
+}
+
+/// Delete a line of a program, moving the lines after it up.
+pub async fn delete_instruction(db: Arc<Mutex<Database>>, program_id: i64, line_number: i32) -> ServerResponse {
+    {
+        let db = db.lock().await;
+        if let Some(response) = missing_program(&db, program_id) {
+            return response;
+        }
+        match db.delete_instruction(program_id, line_number) {
+            Ok(true) => info!("Deleted line {} of program {}", line_number, program_id),
+            Ok(false) => return ServerResponse::Error { message: format!("Line {} not found", line_number) },
+            Err(e) => return ServerResponse::Error { message: format!("Failed to delete instruction: {}", e) },
+        }
+    }
+    get_program(db, program_id).await
+}
+
+/// Put a program's lines in a new order. `order` must list every current
+/// line number exactly once.
+pub async fn reorder_instructions(db: Arc<Mutex<Database>>, program_id: i64, order: &[i32]) -> ServerResponse {
+    {
+        let db = db.lock().await;
+        if let Some(response) = missing_program(&db, program_id) {
+            return response;
+        }
+        let instructions = match db.get_instructions(program_id) {
+            Ok(instructions) => instructions,
+            Err(e) => return ServerResponse::Error { message: format!("Failed to get instructions: {}", e) },
+        };
+        if order.len() != instructions.len() {
+            return ServerResponse::Error {
+                message: format!("Order lists {} lines but the program has {}", order.len(), instructions.len()),
+            };
+        }
+        let mut seen = std::collections::HashSet::new();
+        let mut ids = Vec::with_capacity(order.len());
+        for &line_number in order {
+            if !seen.insert(line_number) {
+                return ServerResponse::Error { message: format!("Line {} is listed twice", line_number) };
+            }
+            match instructions.iter().find(|i| i.line_number == line_number) {
+                Some(instruction) => ids.push(instruction.id),
+                None => return ServerResponse::Error { message: format!("Line {} not found", line_number) },
+            }
+        }
+        if let Err(e) = db.renumber_instructions(program_id, &ids) {
+            return ServerResponse::Error { message: format!("Failed to reorder instructions: {}", e) };
+        }
+        info!("Reordered {} lines of program {}", ids.len(), program_id);
+    }
+    get_program(db, program_id).await
+}
+
 /// Update program settings (start/end positions with orientation, move speed, termination defaults).
 #[allow(clippy::too_many_arguments)]
 pub async fn update_program_settings(
@@ -341,3 +630,188 @@ pub async fn update_program_settings(
         message: "Program settings updated".to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_export_reimports_to_identical_instructions() {
+        let db = Arc::new(Mutex::new(Database::new(":memory:").expect("in-memory database")));
+        let program_id = db.lock().await.create_program("Deburr pass #2", None).unwrap();
+
+        let csv = "x,y,z,w,p,r,ext1,speed,speed_type,term_type,term_value,uframe,utool\n\
+                   400,-25.5,300,180,0,90,10,80,mmSec,CNT,50,2,1\n\
+                   410.125,-25.5,299.75,180,0.5,90,12.5,80,mmSec,FINE,0,2,1";
+        let response = upload_csv(Arc::clone(&db), program_id, csv, None, LengthUnit::Millimeters).await;
+        assert!(!matches!(response, ServerResponse::Error { .. }), "{:?}", response);
+        let imported = db.lock().await.get_instructions(program_id).unwrap();
+
+        let (filename, content) = match export_program_csv(Arc::clone(&db), program_id, LengthUnit::Millimeters).await {
+            ServerResponse::ProgramCsv { filename, content } => (filename, content),
+            other => panic!("expected ProgramCsv, got {:?}", other),
+        };
+        assert_eq!(filename, "Deburr_pass__2.csv");
+
+        let response = upload_csv(Arc::clone(&db), program_id, &content, None, LengthUnit::Millimeters).await;
+        assert!(!matches!(response, ServerResponse::Error { .. }), "{:?}", response);
+        let reimported = db.lock().await.get_instructions(program_id).unwrap();
+
+        let rows = |instructions: &[ProgramInstruction]| -> Vec<ProgramInstruction> {
+            instructions.iter().map(|i| ProgramInstruction { id: 0, ..i.clone() }).collect()
+        };
+        assert_eq!(rows(&reimported), rows(&imported));
+    }
+
+    #[tokio::test]
+    async fn test_inch_csv_is_stored_in_mm_and_exported_in_inches() {
+        let db = Arc::new(Mutex::new(Database::new(":memory:").expect("in-memory database")));
+        let program_id = db.lock().await.create_program("Inch part", None).unwrap();
+
+        let csv = "x,y,z,w,p,r,speed\n1,2.5,-10,180,0,90,50";
+        let response = upload_csv(Arc::clone(&db), program_id, csv, None, LengthUnit::Inches).await;
+        assert!(!matches!(response, ServerResponse::Error { .. }), "{:?}", response);
+
+        let stored = db.lock().await.get_instructions(program_id).unwrap();
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+        assert!(close(stored[0].x, 25.4) && close(stored[0].y, 63.5) && close(stored[0].z, -254.0), "{:?}", stored[0]);
+        // Angles and speeds are not lengths
+        assert_eq!((stored[0].w, stored[0].r, stored[0].speed), (Some(180.0), Some(90.0), Some(50.0)));
+
+        let content = match export_program_csv(Arc::clone(&db), program_id, LengthUnit::Inches).await {
+            ServerResponse::ProgramCsv { content, .. } => content,
+            other => panic!("expected ProgramCsv, got {:?}", other),
+        };
+        let parsed = parse_csv_string(&content, &ProgramDefaults::default()).unwrap().instructions;
+        assert!(close(parsed[0].x, 1.0) && close(parsed[0].y, 2.5) && close(parsed[0].z, -10.0), "{}", content);
+    }
+
+    #[tokio::test]
+    async fn test_preview_reports_bad_row_without_saving() {
+        let db = Arc::new(Mutex::new(Database::new(":memory:").expect("in-memory database")));
+        let program_id = program_with_lines(&db, 2).await;
+        let before = db.lock().await.get_instructions(program_id).unwrap();
+
+        let csv = "x,y,z,speed\n400,0,300,80\n410,abc,300,80\n420,0,300,80";
+        match preview_csv(csv, LengthUnit::Millimeters).await {
+            ServerResponse::CsvPreview { instructions, errors, .. } => {
+                let xs: Vec<f64> = instructions.iter().map(|i| i.x).collect();
+                assert_eq!(xs, vec![400.0, 420.0]);
+                assert!(!errors.is_empty());
+                assert!(errors.iter().all(|e| e.starts_with("Line 3, column 'y'")), "{:?}", errors);
+            }
+            other => panic!("expected CsvPreview, got {:?}", other),
+        }
+
+        assert_eq!(db.lock().await.get_instructions(program_id).unwrap(), before);
+    }
+
+    /// A program with lines at x = 10, 20, 30, ...
+    async fn program_with_lines(db: &Arc<Mutex<Database>>, count: usize) -> i64 {
+        let program_id = db.lock().await.create_program("Edited", None).unwrap();
+        let csv: String = std::iter::once("x,y,z,speed".to_string())
+            .chain((1..=count).map(|i| format!("{},0,100,50", i * 10)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let response = upload_csv(Arc::clone(db), program_id, &csv, None, LengthUnit::Millimeters).await;
+        assert!(!matches!(response, ServerResponse::Error { .. }), "{:?}", response);
+        program_id
+    }
+
+    /// Line numbers and x of the program's lines, in line order.
+    fn lines(response: &ServerResponse) -> Vec<(i32, f64)> {
+        match response {
+            ServerResponse::Program { program } => {
+                program.instructions.iter().map(|i| (i.line_number, i.x)).collect()
+            }
+            other => panic!("expected Program, got {:?}", other),
+        }
+    }
+
+    fn instruction(line_number: i32, x: f64) -> InstructionDto {
+        InstructionDto {
+            line_number,
+            x,
+            y: 0.0,
+            z: 100.0,
+            w: None,
+            p: None,
+            r: None,
+            speed: None,
+            term_type: None,
+            term_value: None,
+            uframe: None,
+            utool: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_insert_in_middle_renumbers_following_lines() {
+        let db = Arc::new(Mutex::new(Database::new(":memory:").expect("in-memory database")));
+        let program_id = program_with_lines(&db, 3).await;
+
+        let response = insert_instruction(Arc::clone(&db), program_id, instruction(2, 15.0)).await;
+        assert_eq!(lines(&response), vec![(1, 10.0), (2, 15.0), (3, 20.0), (4, 30.0)]);
+
+        // Past the end appends
+        let response = insert_instruction(Arc::clone(&db), program_id, instruction(99, 40.0)).await;
+        assert_eq!(lines(&response).last(), Some(&(5, 40.0)));
+
+        let response = update_instruction(Arc::clone(&db), program_id, instruction(3, 25.0)).await;
+        assert_eq!(lines(&response), vec![(1, 10.0), (2, 15.0), (3, 25.0), (4, 30.0), (5, 40.0)]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_then_read_closes_the_gap() {
+        let db = Arc::new(Mutex::new(Database::new(":memory:").expect("in-memory database")));
+        let program_id = program_with_lines(&db, 4).await;
+
+        let response = delete_instruction(Arc::clone(&db), program_id, 2).await;
+        assert_eq!(lines(&response), vec![(1, 10.0), (2, 30.0), (3, 40.0)]);
+
+        let response = get_program(Arc::clone(&db), program_id).await;
+        assert_eq!(lines(&response), vec![(1, 10.0), (2, 30.0), (3, 40.0)]);
+
+        let response = delete_instruction(Arc::clone(&db), program_id, 4).await;
+        assert!(matches!(response, ServerResponse::Error { .. }), "{:?}", response);
+    }
+
+    #[tokio::test]
+    async fn test_reorder_requires_every_line_once() {
+        let db = Arc::new(Mutex::new(Database::new(":memory:").expect("in-memory database")));
+        let program_id = program_with_lines(&db, 3).await;
+
+        let response = reorder_instructions(Arc::clone(&db), program_id, &[3, 3, 1]).await;
+        assert!(matches!(response, ServerResponse::Error { .. }), "{:?}", response);
+        let response = reorder_instructions(Arc::clone(&db), program_id, &[3, 1]).await;
+        assert!(matches!(response, ServerResponse::Error { .. }), "{:?}", response);
+
+        let response = reorder_instructions(Arc::clone(&db), program_id, &[3, 1, 2]).await;
+        assert_eq!(lines(&response), vec![(1, 30.0), (2, 10.0), (3, 20.0)]);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_edits_keep_line_numbers_contiguous() {
+        let db = Arc::new(Mutex::new(Database::new(":memory:").expect("in-memory database")));
+        let program_id = program_with_lines(&db, 3).await;
+
+        let edits = (0..8).map(|i| {
+            let db = Arc::clone(&db);
+            tokio::spawn(async move {
+                if i % 2 == 0 {
+                    insert_instruction(db, program_id, instruction(2, 100.0 + i as f64)).await
+                } else {
+                    delete_instruction(db, program_id, 1).await
+                }
+            })
+        });
+        for edit in futures_util::future::join_all(edits).await {
+            edit.unwrap();
+        }
+
+        let response = get_program(Arc::clone(&db), program_id).await;
+        let numbers: Vec<i32> = lines(&response).iter().map(|(line, _)| *line).collect();
+        assert_eq!(numbers, (1..=numbers.len() as i32).collect::<Vec<_>>());
+        assert_eq!(numbers.len(), 3);
+    }
+}
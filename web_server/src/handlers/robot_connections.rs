@@ -4,9 +4,10 @@
 
 use crate::api_types::*;
 use crate::database::Database;
+use crate::jog::JogSettings;
 use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
-use tracing::info;
+use tracing::{info, warn};
 
 /// List all saved robot connections.
 pub async fn list_robot_connections(db: Arc<Mutex<Database>>) -> ServerResponse {
@@ -31,6 +32,7 @@ pub async fn list_robot_connections(db: Arc<Mutex<Database>>) -> ServerResponse
                 default_joint_jog_step: c.default_joint_jog_step,
                 default_rotation_jog_speed: c.default_rotation_jog_speed,
                 default_rotation_jog_step: c.default_rotation_jog_step,
+                coordinate_unit: LengthUnit::from_db(&c.coordinate_unit),
             }).collect();
             ServerResponse::RobotConnections { connections }
         }
@@ -62,6 +64,7 @@ pub async fn get_robot_connection(db: Arc<Mutex<Database>>, id: i64) -> ServerRe
                     default_joint_jog_step: c.default_joint_jog_step,
                     default_rotation_jog_speed: c.default_rotation_jog_speed,
                     default_rotation_jog_step: c.default_rotation_jog_step,
+                    coordinate_unit: LengthUnit::from_db(&c.coordinate_unit),
                 }
             }
         }
@@ -179,8 +182,31 @@ pub async fn update_robot_jog_defaults(
     }
 }
 
+/// Set the length unit of a saved robot connection, including the active
+/// connection's copy if it is the one changed.
+pub async fn update_robot_connection_unit(
+    db: Arc<Mutex<Database>>,
+    robot_connection: Option<Arc<RwLock<crate::RobotConnection>>>,
+    id: i64,
+    coordinate_unit: LengthUnit,
+) -> ServerResponse {
+    if let Err(e) = db.lock().await.update_robot_connection_unit(id, coordinate_unit.as_str()) {
+        return ServerResponse::Error { message: format!("Failed to update coordinate unit: {}", e) };
+    }
+    if let Some(conn) = robot_connection {
+        let mut conn = conn.write().await;
+        if let Some(saved_conn) = conn.saved_connection.as_mut().filter(|c| c.id == id) {
+            saved_conn.coordinate_unit = coordinate_unit.as_str().to_string();
+        }
+    }
+    info!("Set coordinate unit of robot connection {} to {}", id, coordinate_unit.as_str());
+    ServerResponse::Success { message: "Coordinate unit updated".to_string() }
+}
+
 /// Update jog controls (from Control panel - updates active jog controls only, does NOT update defaults or increment changes_count).
 /// This is called when the user changes jog settings from the jog controls in the Control tab.
+/// Values are clamped to the robot's [`JogLimits`](crate::jog::JogLimits); the
+/// broadcast `ActiveJogSettings` carries the clamped values.
 pub async fn update_jog_controls(
     robot_connection: Option<Arc<RwLock<crate::RobotConnection>>>,
     client_manager: Option<Arc<crate::session::ClientManager>>,
@@ -198,6 +224,26 @@ pub async fn update_jog_controls(
     };
 
     let mut conn = conn.write().await;
+    let requested = JogSettings {
+        cartesian_speed: cartesian_jog_speed,
+        cartesian_step: cartesian_jog_step,
+        joint_speed: joint_jog_speed,
+        joint_step: joint_jog_step,
+        rotation_speed: rotation_jog_speed,
+        rotation_step: rotation_jog_step,
+    };
+    let clamped = conn.jog_limits.clamp(requested);
+    if clamped != requested {
+        warn!("Clamped jog settings {:?} to {:?}", requested, clamped);
+    }
+    let JogSettings {
+        cartesian_speed: cartesian_jog_speed,
+        cartesian_step: cartesian_jog_step,
+        joint_speed: joint_jog_speed,
+        joint_step: joint_jog_step,
+        rotation_speed: rotation_jog_speed,
+        rotation_step: rotation_jog_step,
+    } = clamped;
 
     // Update active jog controls (NOT the defaults)
     conn.active_cartesian_jog_speed = cartesian_jog_speed;
@@ -231,6 +277,7 @@ pub async fn update_jog_controls(
 /// Apply jog defaults (from Configuration panel - updates active defaults AND active jog controls, increments changes_count).
 /// This is called when the user clicks "Apply" in the Jog Defaults panel in the Configuration tab.
 /// Does NOT save to database - use SaveCurrentConfiguration to persist changes.
+/// Values are clamped like [`update_jog_controls`].
 pub async fn apply_jog_settings(
     robot_connection: Option<Arc<RwLock<crate::RobotConnection>>>,
     client_manager: Option<Arc<crate::session::ClientManager>>,
@@ -248,6 +295,26 @@ pub async fn apply_jog_settings(
     };
 
     let mut conn = conn.write().await;
+    let requested = JogSettings {
+        cartesian_speed: cartesian_jog_speed,
+        cartesian_step: cartesian_jog_step,
+        joint_speed: joint_jog_speed,
+        joint_step: joint_jog_step,
+        rotation_speed: rotation_jog_speed,
+        rotation_step: rotation_jog_step,
+    };
+    let clamped = conn.jog_limits.clamp(requested);
+    if clamped != requested {
+        warn!("Clamped jog settings {:?} to {:?}", requested, clamped);
+    }
+    let JogSettings {
+        cartesian_speed: cartesian_jog_speed,
+        cartesian_step: cartesian_jog_step,
+        joint_speed: joint_jog_speed,
+        joint_step: joint_jog_step,
+        rotation_speed: rotation_jog_speed,
+        rotation_step: rotation_jog_step,
+    } = clamped;
 
     // Capture old values from active defaults before updating
     let old_cart_speed = conn.active_configuration.default_cartesian_jog_speed;
@@ -534,7 +601,77 @@ pub async fn create_robot_with_configurations(
             default_joint_jog_step: connection.default_joint_jog_step,
             default_rotation_jog_speed: connection.default_rotation_jog_speed,
             default_rotation_jog_step: connection.default_rotation_jog_step,
+            coordinate_unit: LengthUnit::from_db(&connection.coordinate_unit),
         },
         configurations,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jog::JogLimits;
+    use crate::session::test_support::{connect_client, pushed};
+    use crate::session::ClientManager;
+    use crate::RobotConnection;
+    use std::time::Duration;
+
+    /// The `ActiveJogSettings` broadcast to `socket`, as
+    /// (cartesian speed, cartesian step, joint speed, joint step, rotation speed, rotation step).
+    async fn broadcast_jog_settings(socket: &mut crate::session::test_support::ClientSocket) -> (f64, f64, f64, f64, f64, f64) {
+        pushed(socket, Duration::from_millis(300))
+            .await
+            .into_iter()
+            .find_map(|response| match response {
+                ServerResponse::ActiveJogSettings {
+                    cartesian_jog_speed,
+                    cartesian_jog_step,
+                    joint_jog_speed,
+                    joint_jog_step,
+                    rotation_jog_speed,
+                    rotation_jog_step,
+                } => Some((cartesian_jog_speed, cartesian_jog_step, joint_jog_speed, joint_jog_step, rotation_jog_speed, rotation_jog_step)),
+                _ => None,
+            })
+            .expect("ActiveJogSettings broadcast")
+    }
+
+    #[tokio::test]
+    async fn test_over_range_jog_step_is_clamped() {
+        let limits = JogLimits::default();
+        let conn = Arc::new(RwLock::new(RobotConnection::new("127.0.0.1".to_string(), 16001)));
+        let client_manager = Arc::new(ClientManager::new());
+        let (_client_id, mut socket) = connect_client(&client_manager).await;
+
+        // A 10 m step
+        let response = update_jog_controls(
+            Some(Arc::clone(&conn)), Some(Arc::clone(&client_manager)), 50.0, 10_000.0, 10.0, 1.0, 5.0, 1.0,
+        )
+        .await;
+        assert!(matches!(response, ServerResponse::Success { .. }), "{:?}", response);
+
+        let expected = (50.0, limits.cartesian_step.max, 10.0, 1.0, 5.0, 1.0);
+        assert_eq!(broadcast_jog_settings(&mut socket).await, expected);
+        assert_eq!(conn.read().await.active_cartesian_jog_step, limits.cartesian_step.max);
+    }
+
+    #[tokio::test]
+    async fn test_negative_jog_speed_is_clamped_to_minimum() {
+        let limits = JogLimits::default();
+        let conn = Arc::new(RwLock::new(RobotConnection::new("127.0.0.1".to_string(), 16001)));
+        let client_manager = Arc::new(ClientManager::new());
+        let (_client_id, mut socket) = connect_client(&client_manager).await;
+
+        let response = apply_jog_settings(
+            Some(Arc::clone(&conn)), Some(Arc::clone(&client_manager)), -20.0, 1.0, f64::NAN, 1.0, 5.0, 1.0,
+        )
+        .await;
+        assert!(matches!(response, ServerResponse::Success { .. }), "{:?}", response);
+
+        let expected = (limits.cartesian_speed.min, 1.0, limits.joint_speed.min, 1.0, 5.0, 1.0);
+        assert_eq!(broadcast_jog_settings(&mut socket).await, expected);
+        let conn = conn.read().await;
+        assert_eq!(conn.active_cartesian_jog_speed, limits.cartesian_speed.min);
+        assert_eq!(conn.active_configuration.default_cartesian_jog_speed, limits.cartesian_speed.min);
+    }
+}
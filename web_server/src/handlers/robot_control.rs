@@ -1,11 +1,11 @@
-//! Robot control handlers (abort, reset, initialize).
+//! Robot control handlers (abort, reset, initialize, read error).
 
 use std::sync::Arc;
-use tracing::{info, error};
+use tracing::{info, error, warn};
 use fanuc_rmi::drivers::FanucDriver;
 use tokio::sync::{Mutex, RwLock};
 
-use crate::api_types::ServerResponse;
+use crate::api_types::{AbortReason, ServerResponse};
 use crate::program_executor::ProgramExecutor;
 use crate::session::{ClientManager, execution_state_to_response};
 use crate::RobotConnection;
@@ -23,6 +23,18 @@ pub async fn robot_abort(
     executor: Option<Arc<Mutex<ProgramExecutor>>>,
     robot_connection: Option<Arc<RwLock<RobotConnection>>>,
     client_manager: Option<Arc<ClientManager>>,
+) -> ServerResponse {
+    abort_motion(driver, executor, robot_connection, client_manager, AbortReason::UserRequested).await
+}
+
+/// [`robot_abort`] for `reason`, which is broadcast to all clients as
+/// `MotionAborted` once the robot confirms the abort.
+pub async fn abort_motion(
+    driver: Option<Arc<FanucDriver>>,
+    executor: Option<Arc<Mutex<ProgramExecutor>>>,
+    robot_connection: Option<Arc<RwLock<RobotConnection>>>,
+    client_manager: Option<Arc<ClientManager>>,
+    reason: AbortReason,
 ) -> ServerResponse {
     let Some(driver) = driver else {
         return ServerResponse::RobotCommandResult {
@@ -41,16 +53,22 @@ pub async fn robot_abort(
         info!("Executor stopped and in-flight cleared for abort");
     }
 
-    // Send abort and wait for response
+    // Drop motions still queued in the driver, then abort and wait for response
+    if let Err(e) = driver.flush_pending() {
+        warn!("Failed to flush driver queue: {}", e);
+    }
     match driver.abort().await {
         Ok(response) => {
             let error_id = response.error_id as i32;
             let success = error_id == 0;
 
-            info!("Robot abort completed: error_id={}", error_id);
+            info!("Robot abort completed: error_id={} ({:?})", error_id, reason);
 
-            // Broadcast execution state change
+            // Broadcast why the robot stopped and the execution state change
             if let Some(ref cm) = client_manager {
+                if success {
+                    cm.broadcast_all(&ServerResponse::MotionAborted { reason }).await;
+                }
                 if let Some(ref executor) = executor {
                     let exec_guard = executor.lock().await;
                     let state = exec_guard.get_state();
@@ -247,3 +265,109 @@ pub async fn robot_initialize(
     }
 }
 
+
+/// Read the controller's most recent error.
+///
+/// This sends FRC_ReadError and decodes the error id with the RMI error
+/// table. Ids the table does not know keep the controller's error text.
+pub async fn read_controller_error(driver: Option<Arc<FanucDriver>>) -> ServerResponse {
+    let Some(driver) = driver else {
+        return ServerResponse::Error {
+            message: "Not connected to robot".to_string(),
+        };
+    };
+
+    match driver.read_error().await {
+        Ok(response) => {
+            info!("Controller error read: error_id={}", response.error_id);
+            controller_error(response.error_id, response.error_data)
+        }
+        Err(e) => {
+            error!("Reading controller error failed: {:?}", e);
+            ServerResponse::Error {
+                message: format!("Read error failed: {}", e),
+            }
+        }
+    }
+}
+
+fn controller_error(error_id: u32, error_data: String) -> ServerResponse {
+    let (message, cause_code) = match fanuc_rmi::decode_error_id(error_id) {
+        Some(info) => (info.message.to_string(), Some(info.code.to_string())),
+        None if !error_data.is_empty() => (error_data, None),
+        None if error_id == 0 => ("No error".to_string(), None),
+        None => (fanuc_rmi::format_error_id(error_id), None),
+    };
+    ServerResponse::ControllerError { error_id, message, cause_code }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_types::ClientRequest;
+    use crate::database::Database;
+    use crate::test_support::{connect_driver, start_fake_controller};
+
+    /// Fake controller that rejects `FRC_Reset` with RMIT-009 and answers
+    /// `FRC_ReadError` with the last error it sent. Returns the connect port.
+    async fn start_reset_controller() -> u32 {
+        let mut last_error = 0;
+        start_fake_controller(move |packet| match packet["Command"].as_str() {
+            Some("FRC_Reset") => {
+                last_error = 2556937;
+                Some(format!("{{\"Command\":\"FRC_Reset\",\"ErrorID\":{}}}\r\n", last_error))
+            }
+            Some("FRC_ReadError") => {
+                Some(format!("{{\"Command\":\"FRC_ReadError\",\"ErrorID\":{},\"Count\":1}}\r\n", last_error))
+            }
+            _ => None,
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_read_controller_error_decodes_recorded_error() {
+        let db = Arc::new(Mutex::new(Database::new(":memory:").expect("in-memory database")));
+        let port = start_reset_controller().await;
+        let driver = connect_driver(port).await;
+        let request = |request| {
+            crate::handlers::handle_request(request, Arc::clone(&db), Some(Arc::clone(&driver)), None, None, None, None)
+        };
+
+        match request(ClientRequest::ReadControllerError).await {
+            ServerResponse::ControllerError { error_id, message, cause_code } => {
+                assert_eq!(error_id, 0);
+                assert_eq!(message, "No error");
+                assert_eq!(cause_code, None);
+            }
+            other => panic!("expected ControllerError, got {:?}", other),
+        }
+
+        let response = request(ClientRequest::RobotReset).await;
+        assert!(matches!(response, ServerResponse::RobotCommandResult { success: false, .. }), "{:?}", response);
+
+        match request(ClientRequest::ReadControllerError).await {
+            ServerResponse::ControllerError { error_id, message, cause_code } => {
+                assert_eq!(error_id, 2556937);
+                assert_eq!(message, "RMI is Not Running");
+                assert_eq!(cause_code.as_deref(), Some("RMIT-009"));
+            }
+            other => panic!("expected ControllerError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unknown_error_id_keeps_controller_text() {
+        let response = controller_error(4242, "SRVO-007 External emergency stops".to_string());
+        assert!(matches!(
+            response,
+            ServerResponse::ControllerError { error_id: 4242, ref message, cause_code: None }
+                if message == "SRVO-007 External emergency stops"
+        ));
+        let response = controller_error(4242, String::new());
+        assert!(matches!(
+            response,
+            ServerResponse::ControllerError { ref message, .. } if message == "ErrorID 4242 (unknown)"
+        ));
+    }
+}
@@ -0,0 +1,251 @@
+//! Safety limit handlers.
+
+use super::robot_control;
+use crate::api_types::{AbortReason, SafetyLimitsDto, ServerResponse, SpeedLimitAction};
+use crate::database::{Database, SafetyLimits};
+use crate::program_executor::ProgramExecutor;
+use crate::safety;
+use crate::session::ClientManager;
+use crate::RobotConnection;
+use fanuc_rmi::dto::SendPacket;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+use tracing::warn;
+
+fn to_dto(limits: SafetyLimits) -> SafetyLimitsDto {
+    SafetyLimitsDto {
+        x_min: limits.x_min,
+        x_max: limits.x_max,
+        y_min: limits.y_min,
+        y_max: limits.y_max,
+        z_min: limits.z_min,
+        z_max: limits.z_max,
+        max_speed: limits.max_speed,
+        speed_limit_action: match limits.speed_limit_action.as_str() {
+            "Clamp" => SpeedLimitAction::Clamp,
+            _ => SpeedLimitAction::Reject,
+        },
+    }
+}
+
+/// Get the safety limits for a robot.
+pub async fn get_safety_limits(
+    db: Arc<Mutex<Database>>,
+    robot_connection_id: i64,
+) -> ServerResponse {
+    let db = db.lock().await;
+    match db.get_safety_limits(robot_connection_id) {
+        Ok(limits) => ServerResponse::SafetyLimits {
+            robot_connection_id,
+            limits: to_dto(limits),
+        },
+        Err(e) => ServerResponse::Error {
+            message: format!("Failed to get safety limits: {}", e),
+        },
+    }
+}
+
+/// Save the safety limits for a robot. They apply to the next motion sent.
+pub async fn update_safety_limits(
+    db: Arc<Mutex<Database>>,
+    robot_connection_id: i64,
+    limits: SafetyLimitsDto,
+) -> ServerResponse {
+    let bounds = [
+        ("X", limits.x_min, limits.x_max),
+        ("Y", limits.y_min, limits.y_max),
+        ("Z", limits.z_min, limits.z_max),
+    ];
+    for (axis, min, max) in bounds {
+        if let (Some(min), Some(max)) = (min, max) {
+            if min > max {
+                return ServerResponse::Error {
+                    message: format!("{} minimum {} is above maximum {}", axis, min, max),
+                };
+            }
+        }
+    }
+    if limits.max_speed.is_some_and(|speed| !(speed.is_finite() && speed > 0.0)) {
+        return ServerResponse::Error {
+            message: format!("Invalid maximum speed: {:?}", limits.max_speed),
+        };
+    }
+
+    let row = SafetyLimits {
+        x_min: limits.x_min,
+        x_max: limits.x_max,
+        y_min: limits.y_min,
+        y_max: limits.y_max,
+        z_min: limits.z_min,
+        z_max: limits.z_max,
+        max_speed: limits.max_speed,
+        speed_limit_action: format!("{:?}", limits.speed_limit_action),
+    };
+    let db = db.lock().await;
+    match db.upsert_safety_limits(robot_connection_id, &row) {
+        Ok(()) => ServerResponse::SafetyLimits { robot_connection_id, limits },
+        Err(e) => ServerResponse::Error {
+            message: format!("Failed to update safety limits: {}", e),
+        },
+    }
+}
+
+/// Safety limits of the connected saved robot, or `None` when the robot was
+/// connected by address (no saved connection) or the limits can't be read.
+pub async fn active_safety_limits(
+    db: &Arc<Mutex<Database>>,
+    robot_connection: &Arc<RwLock<RobotConnection>>,
+) -> Option<SafetyLimitsDto> {
+    let robot_connection_id = robot_connection.read().await.saved_connection.as_ref()?.id;
+    match db.lock().await.get_safety_limits(robot_connection_id) {
+        Ok(limits) => Some(to_dto(limits)),
+        Err(e) => {
+            warn!("Failed to load safety limits: {}", e);
+            None
+        }
+    }
+}
+
+/// Check a motion packet from the binary channel against the safety limits
+/// of `robot_connection`, clamping its speed if configured to.
+///
+/// On a violation the packet must not be sent. Motion already under way is
+/// aborted with [`AbortReason::SafetyViolation`], and the `SafetyViolation`
+/// reply for the sender is returned as the error.
+pub async fn enforce_active_limits(
+    db: &Arc<Mutex<Database>>,
+    robot_connection: &Arc<RwLock<RobotConnection>>,
+    executor: Option<Arc<Mutex<ProgramExecutor>>>,
+    client_manager: Option<Arc<ClientManager>>,
+    packet: &mut SendPacket,
+) -> Result<(), ServerResponse> {
+    let Some(limits) = active_safety_limits(db, robot_connection).await else {
+        return Ok(());
+    };
+    let Err(message) = safety::enforce_limits(&limits, packet) else {
+        return Ok(());
+    };
+
+    let driver = robot_connection.read().await.driver.clone();
+    robot_control::abort_motion(
+        driver,
+        executor,
+        Some(Arc::clone(robot_connection)),
+        client_manager,
+        AbortReason::SafetyViolation,
+    )
+    .await;
+    Err(ServerResponse::SafetyViolation { message })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_support::{connect_client, pushed};
+    use crate::test_support::{connect_driver, start_fake_controller};
+    use fanuc_rmi::instructions::FrcLinearMotion;
+    use fanuc_rmi::packets::{Instruction, SendPacket as RmiSendPacket};
+    use fanuc_rmi::{Configuration, Position, SpeedType, TermType};
+    use std::time::Duration;
+
+    /// Fake controller that acknowledges `FRC_Abort` and `FRC_Initialize`.
+    /// Returns the connect port.
+    async fn start_abort_controller() -> u32 {
+        start_fake_controller(|packet| match packet["Command"].as_str() {
+            Some("FRC_Abort") => Some("{\"Command\":\"FRC_Abort\",\"ErrorID\":0}\r\n"),
+            Some("FRC_Initialize") => Some("{\"Command\":\"FRC_Initialize\",\"ErrorID\":0,\"GroupMask\":1}\r\n"),
+            _ => None,
+        })
+        .await
+    }
+
+    /// The reasons in every `MotionAborted` pushed to `socket`.
+    async fn abort_reasons(socket: &mut crate::session::test_support::ClientSocket) -> Vec<AbortReason> {
+        pushed(socket, Duration::from_millis(300))
+            .await
+            .into_iter()
+            .filter_map(|response| match response {
+                ServerResponse::MotionAborted { reason } => Some(reason),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_abort_reason_is_broadcast_for_violation_and_user_abort() {
+        let db = Database::new(":memory:").expect("in-memory database");
+        let port = start_abort_controller().await;
+        let robot_id = db
+            .create_robot_connection(
+                "test", None, "127.0.0.1", port, 100.0, "mmSec", "CNT", 0.0, 0.0, 0.0, 10.0, 1.0,
+                0.1, 0.25, 5.0, 1.0,
+            )
+            .unwrap();
+        let saved = db.get_robot_connection(robot_id).unwrap();
+        let db = Arc::new(Mutex::new(db));
+        let limits = SafetyLimitsDto { z_min: Some(-100.0), ..Default::default() };
+        let response = update_safety_limits(Arc::clone(&db), robot_id, limits).await;
+        assert!(matches!(response, ServerResponse::SafetyLimits { .. }), "{:?}", response);
+
+        let driver = connect_driver(port).await;
+        let mut conn = RobotConnection::new("127.0.0.1".to_string(), port);
+        conn.saved_connection = saved;
+        conn.driver = Some(Arc::clone(&driver));
+        conn.connected = true;
+        let conn = Arc::new(RwLock::new(conn));
+        let client_manager = Arc::new(ClientManager::new());
+        let (_client_id, mut socket) = connect_client(&client_manager).await;
+
+        let below_floor = Position { x: 400.0, z: -150.0, ..Default::default() };
+        let motion = FrcLinearMotion::new(0, Configuration::default(), below_floor, SpeedType::MMSec, 100.0, TermType::FINE, 0);
+        let mut packet: SendPacket = RmiSendPacket::Instruction(Instruction::FrcLinearMotion(motion)).into();
+        let violation = enforce_active_limits(&db, &conn, None, Some(Arc::clone(&client_manager)), &mut packet).await;
+        assert!(matches!(violation, Err(ServerResponse::SafetyViolation { .. })), "{:?}", violation);
+        assert_eq!(abort_reasons(&mut socket).await, vec![AbortReason::SafetyViolation]);
+
+        let response = robot_control::robot_abort(Some(driver), None, Some(conn), Some(client_manager)).await;
+        assert!(matches!(response, ServerResponse::RobotCommandResult { success: true, .. }), "{:?}", response);
+        assert_eq!(abort_reasons(&mut socket).await, vec![AbortReason::UserRequested]);
+    }
+
+    #[tokio::test]
+    async fn test_limits_round_trip_and_apply_to_saved_robot() {
+        let db = Database::new(":memory:").expect("in-memory database");
+        let robot_id = db
+            .create_robot_connection(
+                "test", None, "127.0.0.1", 16001, 100.0, "mmSec", "CNT", 0.0, 0.0, 0.0, 10.0, 1.0,
+                0.1, 0.25, 5.0, 1.0,
+            )
+            .unwrap();
+        let saved = db.get_robot_connection(robot_id).unwrap();
+        let db = Arc::new(Mutex::new(db));
+
+        let mut conn = RobotConnection::new("127.0.0.1".to_string(), 16001);
+        let unsaved = Arc::new(RwLock::new(RobotConnection::new("127.0.0.1".to_string(), 16001)));
+        conn.saved_connection = saved;
+        let conn = Arc::new(RwLock::new(conn));
+
+        // Nothing saved yet: no limits are enforced
+        assert_eq!(active_safety_limits(&db, &conn).await, Some(SafetyLimitsDto::default()));
+
+        let limits = SafetyLimitsDto {
+            z_min: Some(-100.0),
+            max_speed: Some(250.0),
+            speed_limit_action: SpeedLimitAction::Clamp,
+            ..Default::default()
+        };
+        let response = update_safety_limits(Arc::clone(&db), robot_id, limits.clone()).await;
+        assert!(matches!(response, ServerResponse::SafetyLimits { .. }), "{:?}", response);
+
+        match get_safety_limits(Arc::clone(&db), robot_id).await {
+            ServerResponse::SafetyLimits { limits: stored, .. } => assert_eq!(stored, limits),
+            other => panic!("expected SafetyLimits, got {:?}", other),
+        }
+        assert_eq!(active_safety_limits(&db, &conn).await, Some(limits));
+        assert_eq!(active_safety_limits(&db, &unsaved).await, None);
+
+        let inverted = SafetyLimitsDto { z_min: Some(10.0), z_max: Some(-10.0), ..Default::default() };
+        let response = update_safety_limits(Arc::clone(&db), robot_id, inverted).await;
+        assert!(matches!(response, ServerResponse::Error { .. }), "{:?}", response);
+    }
+}
@@ -0,0 +1,374 @@
+//! Server-paced continuous jogging.
+//!
+//! A continuous jog streams small relative moves to the robot at a fixed rate
+//! instead of relying on the client to pace its own jog packets. At most
+//! [`JOG_MAX_IN_FLIGHT`] jog moves are queued on the controller at a time, so
+//! a jog never fills the 8-slot instruction buffer and the robot coasts for
+//! at most a few ticks after the jog stops. However the jog ends, it sends a
+//! final zero-length FINE move so the robot decelerates to a stop instead of
+//! blending into a motion that never comes.
+//!
+//! While a program runs, jog moves are queued in the executor's
+//! [`JOG_CONTEXT`] instead of sent directly, so they interleave with the
+//! program's lines without overrunning the buffer.
+
+use crate::api_types::{JogAxis, JogDirection, ServerResponse};
+use crate::program_executor::ProgramExecutor;
+use crate::session::ClientManager;
+use crate::RobotConnection;
+use fanuc_rmi::drivers::FanucDriver;
+use fanuc_rmi::packets::{Instruction, PacketPriority, ResponsePacket, SendPacket};
+use fanuc_rmi::instructions::{FrcJointRelativeJRep, FrcLinearRelative};
+use fanuc_rmi::{Configuration, JointAngles, Position, SpeedType, TermType};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, Mutex, Notify, RwLock};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Interval between jog moves. Each move covers `speed * JOG_INTERVAL`.
+pub const JOG_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Jog moves allowed on the controller at once.
+pub const JOG_MAX_IN_FLIGHT: usize = 3;
+
+/// Execution context jog moves go through while a program runs, added to the
+/// executor by the first jog that needs it.
+pub const JOG_CONTEXT: &str = "jog";
+
+/// Priority of the jog context: above the program, so a jog interleaves
+/// ahead of the program's next lines.
+pub const JOG_PRIORITY: u8 = 200;
+
+/// Allowed range of one jog speed or step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JogRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl JogRange {
+    /// `value` limited to the range; NaN becomes the minimum.
+    pub fn clamp(&self, value: f64) -> f64 {
+        if value.is_nan() {
+            self.min
+        } else {
+            value.clamp(self.min, self.max)
+        }
+    }
+}
+
+/// The six jog speeds and steps a client sets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JogSettings {
+    /// mm/s
+    pub cartesian_speed: f64,
+    /// mm
+    pub cartesian_step: f64,
+    /// °/s
+    pub joint_speed: f64,
+    /// °
+    pub joint_step: f64,
+    /// °/s
+    pub rotation_speed: f64,
+    /// °
+    pub rotation_step: f64,
+}
+
+/// Safe ranges for a robot's jog settings. Client requests are clamped to
+/// them, so no client can jog faster or farther than the robot allows.
+///
+/// The defaults match the ranges the jog panels accept.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JogLimits {
+    pub cartesian_speed: JogRange,
+    pub cartesian_step: JogRange,
+    pub joint_speed: JogRange,
+    pub joint_step: JogRange,
+    pub rotation_speed: JogRange,
+    pub rotation_step: JogRange,
+}
+
+impl Default for JogLimits {
+    fn default() -> Self {
+        Self {
+            cartesian_speed: JogRange { min: 0.1, max: 1000.0 },
+            cartesian_step: JogRange { min: 0.1, max: 100.0 },
+            joint_speed: JogRange { min: 0.01, max: 100.0 },
+            joint_step: JogRange { min: 0.01, max: 90.0 },
+            rotation_speed: JogRange { min: 0.1, max: 180.0 },
+            rotation_step: JogRange { min: 0.1, max: 90.0 },
+        }
+    }
+}
+
+impl JogLimits {
+    /// `settings` with every value clamped to its range.
+    pub fn clamp(&self, settings: JogSettings) -> JogSettings {
+        JogSettings {
+            cartesian_speed: self.cartesian_speed.clamp(settings.cartesian_speed),
+            cartesian_step: self.cartesian_step.clamp(settings.cartesian_step),
+            joint_speed: self.joint_speed.clamp(settings.joint_speed),
+            joint_step: self.joint_step.clamp(settings.joint_step),
+            rotation_speed: self.rotation_speed.clamp(settings.rotation_speed),
+            rotation_step: self.rotation_step.clamp(settings.rotation_step),
+        }
+    }
+}
+
+/// A running continuous jog. Dropping the handle stops the jog.
+pub struct JogHandle {
+    stop: Arc<Notify>,
+}
+
+impl Drop for JogHandle {
+    fn drop(&mut self) {
+        self.stop.notify_one();
+    }
+}
+
+/// Build the relative move for one jog tick.
+pub fn jog_step(
+    axis: JogAxis,
+    direction: JogDirection,
+    speed: f64,
+    configuration: Configuration,
+) -> SendPacket {
+    let step = direction.sign() * speed * JOG_INTERVAL.as_secs_f64();
+    // CNT100 blends consecutive ticks into one continuous motion.
+    jog_move(axis, step, speed, TermType::CNT, 100, configuration)
+}
+
+/// Build the zero-length FINE move that ends a jog on `axis`.
+pub fn jog_stop_step(axis: JogAxis, speed: f64, configuration: Configuration) -> SendPacket {
+    jog_move(axis, 0.0, speed, TermType::FINE, 0, configuration)
+}
+
+fn jog_move(
+    axis: JogAxis,
+    step: f64,
+    speed: f64,
+    term_type: TermType,
+    term_value: u8,
+    configuration: Configuration,
+) -> SendPacket {
+    let mut position = Position { x: 0.0, y: 0.0, z: 0.0, w: 0.0, p: 0.0, r: 0.0, ext1: 0.0, ext2: 0.0, ext3: 0.0 };
+    let mut angles = JointAngles { j1: 0.0, j2: 0.0, j3: 0.0, j4: 0.0, j5: 0.0, j6: 0.0, j7: 0.0, j8: 0.0, j9: 0.0 };
+    match axis {
+        JogAxis::X => position.x = step,
+        JogAxis::Y => position.y = step,
+        JogAxis::Z => position.z = step,
+        JogAxis::W => position.w = step,
+        JogAxis::P => position.p = step,
+        JogAxis::R => position.r = step,
+        JogAxis::J1 => angles.j1 = step as f32,
+        JogAxis::J2 => angles.j2 = step as f32,
+        JogAxis::J3 => angles.j3 = step as f32,
+        JogAxis::J4 => angles.j4 = step as f32,
+        JogAxis::J5 => angles.j5 = step as f32,
+        JogAxis::J6 => angles.j6 = step as f32,
+    }
+
+    let joint = matches!(
+        axis,
+        JogAxis::J1 | JogAxis::J2 | JogAxis::J3 | JogAxis::J4 | JogAxis::J5 | JogAxis::J6
+    );
+    if joint {
+        // Joint moves are timed like the joint jog panel's: each tick takes
+        // one interval (in 0.1 s units), so it covers `speed` °/s.
+        let duration = JOG_INTERVAL.as_secs_f64() * 10.0;
+        SendPacket::Instruction(Instruction::FrcJointRelativeJRep(FrcJointRelativeJRep::new(
+            0, angles, SpeedType::Time, duration, term_type, term_value,
+        )))
+    } else {
+        SendPacket::Instruction(Instruction::FrcLinearRelative(FrcLinearRelative::new(
+            0, configuration, position, SpeedType::MMSec, speed, term_type, term_value,
+        )))
+    }
+}
+
+/// Spawn the task that streams jog moves for `client_id`.
+///
+/// The task stops on its own once the client loses control (when a client
+/// manager is present), the robot disconnects, or the robot rejects a move.
+/// Moves go through `executor`'s jog context while it runs a program.
+///
+/// On stopping, moves still waiting in the jog context are dropped and the
+/// stop move is sent, unless the robot is gone.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_jog(
+    driver: Arc<FanucDriver>,
+    robot_connection: Arc<RwLock<RobotConnection>>,
+    executor: Option<Arc<Mutex<ProgramExecutor>>>,
+    client_manager: Option<Arc<ClientManager>>,
+    client_id: Option<Uuid>,
+    axis: JogAxis,
+    direction: JogDirection,
+    speed: f64,
+    configuration: Configuration,
+) -> JogHandle {
+    // Subscribe before the first move is sent so no notification is missed
+    let mut sent_rx = driver.sent_instruction_tx.subscribe();
+    let mut response_rx = driver.response_tx.subscribe();
+    let stop = Arc::new(Notify::new());
+    let stop_requested = Arc::clone(&stop);
+
+    tokio::spawn(async move {
+        info!("Jog started: {:?} {:?} at {}", axis, direction, speed);
+        let mut interval = tokio::time::interval(JOG_INTERVAL);
+        // Sent but not yet assigned a sequence ID, and sent but not completed
+        let mut pending_requests: HashSet<u64> = HashSet::new();
+        let mut in_flight: HashSet<u32> = HashSet::new();
+
+        loop {
+            tokio::select! {
+                _ = stop_requested.notified() => {
+                    info!("Jog stopped");
+                    break;
+                }
+
+                _ = interval.tick() => {
+                    if let (Some(cm), Some(id)) = (&client_manager, client_id) {
+                        if !cm.has_control(id).await {
+                            info!("Jog stopped: client {} no longer has control", id);
+                            break;
+                        }
+                        cm.touch_control(id).await;
+                    }
+                    if !still_connected(&robot_connection, &driver).await {
+                        info!("Jog stopped: robot disconnected");
+                        break;
+                    }
+
+                    let mut streaming = match &executor {
+                        Some(executor) => Some(executor.lock().await),
+                        None => None,
+                    };
+                    if let Some(exec) = streaming.as_mut().filter(|exec| exec.is_streaming()) {
+                        if let Some(error_id) = exec.take_context_error(JOG_CONTEXT) {
+                            warn!("Jog stopped: robot rejected move with error {}", error_id);
+                            if let (Some(cm), Some(id)) = (&client_manager, client_id) {
+                                let error = ServerResponse::Error {
+                                    message: format!("Jog stopped: robot error {}", error_id),
+                                };
+                                cm.send_to_client(id, &error).await;
+                            }
+                            break;
+                        }
+                        if exec.context_backlog(JOG_CONTEXT) >= JOG_MAX_IN_FLIGHT {
+                            continue;
+                        }
+                        let packet = jog_step(axis, direction, speed, configuration.clone());
+                        if let Err(e) = queue_jog_move(exec, packet) {
+                            warn!("Jog stopped: failed to queue move: {}", e);
+                            break;
+                        }
+                        continue;
+                    }
+                    drop(streaming);
+
+                    if pending_requests.len() + in_flight.len() >= JOG_MAX_IN_FLIGHT {
+                        continue;
+                    }
+                    let packet = jog_step(axis, direction, speed, configuration.clone());
+                    match driver.send_packet(packet, PacketPriority::Standard) {
+                        Ok(request_id) => {
+                            pending_requests.insert(request_id);
+                        }
+                        Err(e) => {
+                            warn!("Jog stopped: failed to send move: {}", e);
+                            break;
+                        }
+                    }
+                }
+
+                sent_result = sent_rx.recv() => {
+                    match sent_result {
+                        Ok(sent_info) => {
+                            if pending_requests.remove(&sent_info.request_id) {
+                                in_flight.insert(sent_info.sequence_id);
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                            warn!("Jog sent notifications lagged by {} messages", n);
+                        }
+                        Err(_) => break,
+                    }
+                }
+
+                response_result = response_rx.recv() => {
+                    match response_result {
+                        Ok(ResponsePacket::InstructionResponse(resp)) => {
+                            if !in_flight.remove(&resp.get_sequence_id()) {
+                                continue;
+                            }
+                            if resp.get_error_id() != 0 {
+                                warn!("Jog stopped: robot rejected move with error {}", resp.get_error_id());
+                                if let (Some(cm), Some(id)) = (&client_manager, client_id) {
+                                    let error = ServerResponse::Error {
+                                        message: format!("Jog stopped: robot error {}", resp.get_error_id()),
+                                    };
+                                    cm.send_to_client(id, &error).await;
+                                }
+                                break;
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                            warn!("Jog response channel lagged by {} messages", n);
+                        }
+                        Err(_) => break,
+                    }
+                }
+            }
+        }
+
+        if still_connected(&robot_connection, &driver).await {
+            finish_jog(&driver, executor.as_ref(), axis, speed, configuration).await;
+        }
+    });
+
+    JogHandle { stop }
+}
+
+/// Whether `driver` is still the connected robot's driver.
+async fn still_connected(robot_connection: &RwLock<RobotConnection>, driver: &Arc<FanucDriver>) -> bool {
+    let conn = robot_connection.read().await;
+    conn.connected && conn.driver.as_ref().is_some_and(|d| Arc::ptr_eq(d, driver))
+}
+
+/// Queue a jog move in `exec`'s jog context, adding the context on first use.
+fn queue_jog_move(exec: &mut ProgramExecutor, packet: SendPacket) -> Result<(), String> {
+    if !exec.has_context(JOG_CONTEXT) {
+        exec.add_context(JOG_CONTEXT, JOG_PRIORITY)?;
+    }
+    exec.queue_in_context(JOG_CONTEXT, packet)
+}
+
+/// Drop the jog's queued moves and send the stop move after the rest.
+///
+/// The stop move goes through the jog context while a program runs, and is
+/// sent directly if the context refuses it (after a rejected move).
+async fn finish_jog(
+    driver: &FanucDriver,
+    executor: Option<&Arc<Mutex<ProgramExecutor>>>,
+    axis: JogAxis,
+    speed: f64,
+    configuration: Configuration,
+) {
+    let packet = jog_stop_step(axis, speed, configuration);
+    if let Some(executor) = executor {
+        let mut exec = executor.lock().await;
+        if exec.is_streaming() {
+            exec.clear_context(JOG_CONTEXT);
+            match queue_jog_move(&mut exec, packet.clone()) {
+                Ok(()) => return,
+                Err(e) => warn!("Failed to queue jog stop move, sending it directly: {}", e),
+            }
+        }
+    }
+    if let Err(e) = driver.send_packet(packet, PacketPriority::Standard) {
+        warn!("Failed to send jog stop move: {}", e);
+    }
+}
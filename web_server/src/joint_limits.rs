@@ -0,0 +1,110 @@
+//! Joint limit proximity warnings.
+//!
+//! The polled `FRC_ReadJointAngles` answers are compared with the robot's
+//! joint limits, and a [`ServerResponse::JointLimitWarning`] is broadcast
+//! when a joint comes within the warning margin of one of them.
+
+use crate::api_types::ServerResponse;
+
+/// Warning margin used unless `JOINT_LIMIT_WARNING_MARGIN_DEG` is set.
+pub const DEFAULT_WARNING_MARGIN_DEG: f64 = 10.0;
+
+/// Travel range of one joint, in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl JointRange {
+    /// Degrees from `angle` to the nearer end of the range; negative past
+    /// either end.
+    pub fn remaining(&self, angle: f64) -> f64 {
+        (angle - self.min).min(self.max - angle)
+    }
+}
+
+/// Joint limits of the connected robot and how close to them a joint may
+/// come before it is warned about.
+///
+/// The default ranges are those of the simulator's default robot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointLimits {
+    /// J1 to J6
+    pub joints: [JointRange; 6],
+    /// Degrees from a limit at which a joint is warned about.
+    pub warning_margin_deg: f64,
+}
+
+impl Default for JointLimits {
+    fn default() -> Self {
+        Self {
+            joints: [180.0, 180.0, 270.0, 190.0, 180.0, 225.0].map(|range| JointRange { min: -range, max: range }),
+            warning_margin_deg: DEFAULT_WARNING_MARGIN_DEG,
+        }
+    }
+}
+
+/// Tracks which joints are within the warning margin, so each approach to
+/// a limit is warned about once rather than on every poll.
+#[derive(Debug, Default)]
+pub struct JointLimitMonitor {
+    within_margin: [bool; 6],
+}
+
+impl JointLimitMonitor {
+    /// Check polled J1 to J6 `angles` against `limits`, returning a warning
+    /// for each joint that has entered the margin since the last check.
+    pub fn check(&mut self, limits: &JointLimits, angles: [f64; 6]) -> Vec<ServerResponse> {
+        let mut warnings = Vec::new();
+        for (index, (range, angle)) in limits.joints.iter().zip(angles).enumerate() {
+            let remaining_deg = range.remaining(angle);
+            let within = remaining_deg <= limits.warning_margin_deg;
+            if within && !self.within_margin[index] {
+                warnings.push(ServerResponse::JointLimitWarning { joint: index as u8 + 1, remaining_deg });
+            }
+            self.within_margin[index] = within;
+        }
+        warnings
+    }
+
+    /// Forget which joints were warned about, e.g. for a new driver.
+    pub fn reset(&mut self) {
+        self.within_margin = [false; 6];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_joint_near_limit_warns_once_per_approach() {
+        let limits = JointLimits::default();
+        let mut monitor = JointLimitMonitor::default();
+        assert!(monitor.check(&limits, [0.0, 45.0, -90.0, 0.0, 0.0, 0.0]).is_empty());
+
+        // J2 moves to 4° short of its +180° limit
+        let near = [0.0, 176.0, -90.0, 0.0, 0.0, 0.0];
+        match monitor.check(&limits, near).as_slice() {
+            [ServerResponse::JointLimitWarning { joint, remaining_deg }] => {
+                assert_eq!(*joint, 2);
+                assert!((remaining_deg - 4.0).abs() < 1e-9, "{}", remaining_deg);
+            }
+            other => panic!("expected one J2 warning, got {:?}", other),
+        }
+        assert!(monitor.check(&limits, near).is_empty());
+
+        // Leaving the margin re-arms the warning
+        assert!(monitor.check(&limits, [0.0, 100.0, -90.0, 0.0, 0.0, 0.0]).is_empty());
+        assert_eq!(monitor.check(&limits, [0.0, -175.0, -90.0, 0.0, 0.0, 0.0]).len(), 1);
+    }
+
+    #[test]
+    fn test_margin_is_configurable() {
+        let limits = JointLimits { warning_margin_deg: 2.0, ..Default::default() };
+        let mut monitor = JointLimitMonitor::default();
+        assert!(monitor.check(&limits, [0.0, 176.0, 0.0, 0.0, 0.0, 0.0]).is_empty());
+        assert_eq!(monitor.check(&limits, [0.0, 0.0, 0.0, 0.0, 0.0, 224.0]).len(), 1);
+    }
+}
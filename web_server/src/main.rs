@@ -4,25 +4,44 @@
 mod api_types;
 mod database;
 mod handlers;
+mod jog;
+mod joint_limits;
+mod overspeed;
 mod program_executor;
 mod program_parser;
+mod robots;
+mod safety;
 mod session;
+mod state_cache;
+#[cfg(test)]
+mod test_support;
 
-use handlers::handle_request;
-use api_types::{ClientRequest, ServerResponse};
+use handlers::handle_routed_request;
+use api_types::{decode_robot_frame, encode_frame, negotiate_protocol, ClientRequest, FrameError, RoutedRequest, ServerResponse};
 use database::Database;
 use program_executor::ProgramExecutor;
-use session::ClientManager;
+use robots::RobotRegistry;
+use session::{ClientManager, ClientRole, ControlPolicy, RobotControlLock};
+use state_cache::StateCache;
 use fanuc_rmi::{
-    drivers::{FanucDriver, FanucDriverConfig, LogLevel},
+    drivers::{
+        default_command_timeouts, ConnectionHealth, DriverStats, FanucDriver, FanucDriverConfig, LogLevel,
+        StartupReport, DEFAULT_HOME_SPEED,
+    },
     dto,
     packets::PacketPriority,
+    ArmConfig, ArmConfigError,
 };
 use futures_util::{SinkExt, StreamExt};
 use std::sync::Arc;
 use tokio::sync::{broadcast, RwLock};
-use tokio_tungstenite::{accept_async, tungstenite::Message};
+use tokio_tungstenite::accept_hdr_async_with_config;
+use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::{CloseFrame, WebSocketConfig};
+use tokio_tungstenite::tungstenite::{self, Message};
 use tracing::{info, warn, error};
+use web_common::RobotModel;
 
 /// A single change entry in the changelog
 #[derive(Debug, Clone)]
@@ -75,33 +94,40 @@ pub struct ActiveConfiguration {
 
 impl Default for ActiveConfiguration {
     fn default() -> Self {
+        Self::for_model(RobotModel::default())
+    }
+}
+
+impl ActiveConfiguration {
+    /// Unsaved defaults for a robot of `model`, used until a saved
+    /// configuration is loaded.
+    pub fn for_model(model: RobotModel) -> Self {
+        let config = model.default_configuration();
         Self {
             loaded_from_id: None,
             loaded_from_name: None,
             changes_count: 0,
             change_log: Vec::new(),
             // FANUC uses 1-based indexing for frames and tools
-            u_frame_number: 1,
-            u_tool_number: 1,
-            front: 1,  // Front
-            up: 1,     // Up
-            left: 0,   // Right
-            flip: 0,   // NoFlip
-            turn4: 0,
-            turn5: 0,
-            turn6: 0,
+            u_frame_number: config.u_frame_number as i32,
+            u_tool_number: config.u_tool_number as i32,
+            front: config.front as i32,
+            up: config.up as i32,
+            left: config.left as i32,
+            flip: config.flip as i32,
+            turn4: config.turn4 as i32,
+            turn5: config.turn5 as i32,
+            turn6: config.turn6 as i32,
             // Default jog settings
             default_cartesian_jog_speed: 10.0,
             default_cartesian_jog_step: 1.0,
-            default_joint_jog_speed: 10.0,
+            default_joint_jog_speed: model.default_joint_jog_speed(),
             default_joint_jog_step: 1.0,
             default_rotation_jog_speed: 5.0,
             default_rotation_jog_step: 1.0,
         }
     }
-}
 
-impl ActiveConfiguration {
     /// Create from a saved RobotConfiguration and RobotConnection (for jog defaults)
     pub fn from_saved(config: &database::RobotConfiguration, connection: &database::RobotConnection) -> Self {
         Self {
@@ -127,8 +153,30 @@ impl ActiveConfiguration {
             default_rotation_jog_step: connection.default_rotation_jog_step,
         }
     }
+}
 
+impl TryFrom<&ActiveConfiguration> for ArmConfig {
+    type Error = ArmConfigError;
+
+    fn try_from(config: &ActiveConfiguration) -> Result<Self, Self::Error> {
+        ArmConfig::try_from_raw(
+            config.front,
+            config.up,
+            config.left,
+            config.flip,
+            config.turn4,
+            config.turn5,
+            config.turn6,
+        )
+    }
+}
 
+impl From<ArmConfig> for ActiveConfiguration {
+    /// Default settings with the arm configuration of `arm`.
+    fn from(arm: ArmConfig) -> Self {
+        let [front, up, left, flip, turn4, turn5, turn6] = arm.to_raw();
+        Self { front, up, left, flip, turn4, turn5, turn6, ..Self::default() }
+    }
 }
 
 /// Shared robot connection state
@@ -137,6 +185,9 @@ pub struct RobotConnection {
     pub connected: bool,
     pub robot_addr: String,
     pub robot_port: u32,
+    /// Robot model, which seeds the configuration defaults and bounds the
+    /// frame and tool numbers.
+    pub model: RobotModel,
     /// Saved robot connection configuration from database (for defaults)
     pub saved_connection: Option<database::RobotConnection>,
     /// Active configuration state (runtime, not persisted)
@@ -148,30 +199,91 @@ pub struct RobotConnection {
     pub active_joint_jog_step: f64,
     pub active_rotation_jog_speed: f64,
     pub active_rotation_jog_step: f64,
+    /// Safe ranges the jog settings above are clamped to.
+    pub jog_limits: jog::JogLimits,
+    /// Id of this robot in the [`RobotRegistry`] when it is an additional
+    /// robot; `None` for the active robot. Tags its I/O broadcasts.
+    pub robot_id: Option<i64>,
+    /// Joint limits polled joint angles are checked against.
+    pub joint_limits: joint_limits::JointLimits,
+    /// Cell TCP speed limit (mm/s) that reported `FRC_ReadTCPSpeed` speeds
+    /// are checked against; `None` disables the check.
+    pub tcp_speed_limit: Option<f64>,
+    /// Whether [`connect`](Self::connect) runs the startup sequence
+    /// (see [`FanucDriverConfig::auto_initialize`]).
+    pub auto_initialize: bool,
     /// Whether the TP program is initialized (FRC_Initialize was successful)
     /// This must be true to send motion commands. It becomes false after:
     /// - FRC_Abort is called
     /// - Robot disconnects
     /// - Stop program is called
     pub tp_program_initialized: bool,
+    /// What the startup sequence did on the last connect, if it succeeded.
+    pub startup_report: Option<StartupReport>,
+    /// Running continuous jog, if any. Dropping it stops the jog.
+    pub jog: Option<jog::JogHandle>,
+    /// Last known I/O values, for snapshot reads. Cleared on disconnect.
+    pub io_cache: std::sync::Mutex<handlers::io::IoCache>,
+    /// Pending pulse turn-offs by DOUT port. Dropping one cancels it.
+    pub dout_pulses: std::sync::Mutex<std::collections::HashMap<u16, handlers::io::DoutPulse>>,
+    /// Traffic of the drivers this connection has dropped.
+    retired_traffic: DriverStats,
+    /// Successful `connect` calls.
+    connect_count: u64,
 }
 
 impl RobotConnection {
     pub fn new(robot_addr: String, robot_port: u32) -> Self {
+        Self::for_model(robot_addr, robot_port, RobotModel::default())
+    }
+
+    /// A disconnected robot of `model`, with that model's defaults active.
+    pub fn for_model(robot_addr: String, robot_port: u32, model: RobotModel) -> Self {
         Self {
             driver: None,
             connected: false,
             robot_addr,
             robot_port,
+            model,
             saved_connection: None,
-            active_configuration: ActiveConfiguration::default(),
+            active_configuration: ActiveConfiguration::for_model(model),
             active_cartesian_jog_speed: 10.0,  // Default values
             active_cartesian_jog_step: 1.0,
-            active_joint_jog_speed: 10.0,
+            active_joint_jog_speed: model.default_joint_jog_speed(),
             active_joint_jog_step: 1.0,
             active_rotation_jog_speed: 5.0,  // Default: 5 deg/s
             active_rotation_jog_step: 1.0,   // Default: 1 degree
+            jog_limits: jog::JogLimits::default(),
+            robot_id: None,
+            joint_limits: joint_limits::JointLimits::default(),
+            tcp_speed_limit: None,
+            auto_initialize: true,
             tp_program_initialized: false,
+            startup_report: None,
+            jog: None,
+            io_cache: Default::default(),
+            dout_pulses: Default::default(),
+            retired_traffic: DriverStats::default(),
+            connect_count: 0,
+        }
+    }
+
+    /// Packets sent and received and protocol errors over every driver
+    /// this connection has had, the current one included.
+    pub fn traffic(&self) -> DriverStats {
+        let current = self.driver.as_ref().map(|driver| driver.stats()).unwrap_or_default();
+        self.retired_traffic + current
+    }
+
+    /// Times the robot was connected again after the first connect.
+    pub fn reconnects(&self) -> u64 {
+        self.connect_count.saturating_sub(1)
+    }
+
+    /// Drop the driver, keeping its traffic for [`traffic`](Self::traffic).
+    fn retire_driver(&mut self) {
+        if let Some(driver) = self.driver.take() {
+            self.retired_traffic = self.retired_traffic + driver.stats();
         }
     }
 
@@ -199,29 +311,57 @@ impl RobotConnection {
             port: self.robot_port,
             max_messages: 30,
             log_level: LogLevel::Debug,
+            heartbeat_interval_ms: Some(1000),
+            heartbeat_max_missed: 3,
+            record: None,
+            trajectory_capacity: None,
+            timeouts: default_command_timeouts(),
+            group_count: 1,
+            home: None,
+            home_speed: DEFAULT_HOME_SPEED,
+            tcp_nodelay: true,
+            auto_initialize: self.auto_initialize,
+            override_range: fanuc_rmi::drivers::DEFAULT_OVERRIDE_RANGE,
         };
 
         info!("Connecting to robot at {}:{}", driver_config.addr, driver_config.port);
+        let auto_initialize = driver_config.auto_initialize;
         match FanucDriver::connect(driver_config).await {
+            Ok(d) if !auto_initialize => {
+                info!("✓ Connected to robot (not initialized - auto-initialize is off)");
+                self.retire_driver();
+                self.connect_count += 1;
+                self.driver = Some(Arc::new(d));
+                self.connected = true;
+                self.tp_program_initialized = false;
+                self.startup_report = None;
+                Ok(())
+            }
             Ok(d) => {
                 info!("✓ Connected to robot");
                 tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
 
                 // Smart initialization - checks status first, only aborts if needed
                 match d.startup_sequence().await {
-                    Ok(()) => {
-                        info!("✓ Robot initialization complete");
+                    Ok(report) => {
+                        info!("✓ Robot initialization complete: {}", report);
+                        self.retire_driver();
+                        self.connect_count += 1;
                         self.driver = Some(Arc::new(d));
                         self.connected = true;
                         self.tp_program_initialized = true;
+                        self.startup_report = Some(report);
                         Ok(())
                     }
                     Err(e) => {
                         warn!("⚠ Robot initialization failed: {}", e);
                         // Still connect, but warn that initialization failed
+                        self.retire_driver();
+                        self.connect_count += 1;
                         self.driver = Some(Arc::new(d));
                         self.connected = true;
                         self.tp_program_initialized = false; // Not initialized - cannot send motions
+                        self.startup_report = None;
                         Ok(())
                     }
                 }
@@ -229,7 +369,7 @@ impl RobotConnection {
             Err(e) => {
                 error!("✗ Failed to connect: {}", e);
                 self.connected = false;
-                self.driver = None;
+                self.retire_driver();
                 Err(format!("Failed to connect: {}", e))
             }
         }
@@ -251,9 +391,13 @@ impl RobotConnection {
                 fanuc_rmi::packets::PacketPriority::Standard,
             );
         }
-        self.driver = None;
+        self.jog = None;
+        self.retire_driver();
         self.connected = false;
         self.tp_program_initialized = false;
+        self.startup_report = None;
+        *self.io_cache.get_mut().unwrap() = Default::default();
+        self.dout_pulses.get_mut().unwrap().clear();
     }
 
     /// Async disconnect from the robot.
@@ -283,9 +427,13 @@ impl RobotConnection {
                 }
             }
         }
-        self.driver = None;
+        self.jog = None;
+        self.retire_driver();
         self.connected = false;
         self.tp_program_initialized = false;
+        self.startup_report = None;
+        *self.io_cache.get_mut().unwrap() = Default::default();
+        self.dout_pulses.get_mut().unwrap().clear();
     }
 
     /// Re-initialize the TP program after an abort.
@@ -353,84 +501,140 @@ async fn main() {
         .ok()
         .and_then(|p| p.parse::<u16>().ok())
         .unwrap_or(9000);
+    let max_message_size = std::env::var("WEBSOCKET_MAX_MESSAGE_SIZE")
+        .ok()
+        .and_then(|s| s.parse::<usize>().ok())
+        .filter(|&size| size > 0)
+        .unwrap_or(DEFAULT_MAX_MESSAGE_SIZE);
+    let handoff_timeout = std::env::var("CONTROL_HANDOFF_TIMEOUT_SECS")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .filter(|&secs| secs > 0)
+        .map(std::time::Duration::from_secs);
+    let inactivity_timeout = std::env::var("CONTROL_INACTIVITY_TIMEOUT_SECS")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .filter(|&secs| secs > 0)
+        .map_or(RobotControlLock::INACTIVITY_TIMEOUT, std::time::Duration::from_secs);
+    // 0 disables the motion watchdog
+    let deadman_timeout = match std::env::var("CONTROL_DEADMAN_TIMEOUT_MS").ok().and_then(|s| s.parse::<u64>().ok()) {
+        Some(0) => None,
+        Some(ms) => Some(std::time::Duration::from_millis(ms)),
+        None => Some(ClientManager::DEFAULT_DEADMAN_TIMEOUT),
+    };
+    let control_policy = ControlPolicy {
+        accept_order: std::env::var("CONTROL_ACCEPT_ORDER")
+            .ok()
+            .and_then(|s| s.parse().map_err(|e| warn!("{}, using first come", e)).ok())
+            .unwrap_or_default(),
+        viewers_may_control: std::env::var("CONTROL_VIEWERS_MAY_CONTROL")
+            .map_or(true, |s| s != "0" && !s.eq_ignore_ascii_case("false")),
+    };
 
     // Create robot connection in disconnected state
     // Users must explicitly connect via the UI by selecting a saved robot connection
-    let robot_connection = Arc::new(RwLock::new(RobotConnection::new(robot_addr.clone(), robot_port)));
+    let robot_model = std::env::var("ROBOT_MODEL")
+        .ok()
+        .and_then(|s| s.parse::<RobotModel>().map_err(|e| warn!("{}, using {}", e, RobotModel::default())).ok())
+        .unwrap_or_default();
+    let mut robot_connection = RobotConnection::for_model(robot_addr.clone(), robot_port, robot_model);
+    robot_connection.auto_initialize = std::env::var("FANUC_AUTO_INITIALIZE")
+        .map_or(true, |s| s != "0" && !s.eq_ignore_ascii_case("false"));
+    if let Some(margin) = std::env::var("JOINT_LIMIT_WARNING_MARGIN_DEG")
+        .ok()
+        .and_then(|s| s.parse::<f64>().ok())
+        .filter(|margin| margin.is_finite() && *margin >= 0.0)
+    {
+        robot_connection.joint_limits.warning_margin_deg = margin;
+    }
+    robot_connection.tcp_speed_limit = std::env::var("TCP_SPEED_LIMIT_MM_SEC")
+        .ok()
+        .and_then(|s| s.parse::<f64>().ok())
+        .filter(|limit| limit.is_finite() && *limit > 0.0);
+    let robot_connection = Arc::new(RwLock::new(robot_connection));
     info!("Robot connection initialized (not connected - use UI to connect)");
 
     let executor = Arc::new(tokio::sync::Mutex::new(ProgramExecutor::new()));
-    let client_manager = Arc::new(ClientManager::new());
+    let client_manager = Arc::new(
+        handoff_timeout
+            .map_or_else(ClientManager::new, ClientManager::with_handoff_timeout)
+            .with_inactivity_timeout(inactivity_timeout)
+            .with_deadman_timeout(deadman_timeout)
+            .with_control_policy(control_policy),
+    );
     let (broadcast_tx, _) = broadcast::channel::<Vec<u8>>(100);
     let broadcast_tx = Arc::new(broadcast_tx);
+    let registry = Arc::new(RobotRegistry::new(Arc::clone(&robot_connection), Arc::clone(&broadcast_tx)));
+    let state_cache = Arc::new(std::sync::Mutex::new(StateCache::default()));
 
     // Start response broadcast task - forwards robot responses to all WebSocket clients
-    let robot_connection_clone = Arc::clone(&robot_connection);
-    let broadcast_tx_clone = Arc::clone(&broadcast_tx);
-    let client_manager_broadcast = Arc::clone(&client_manager);
-    let executor_broadcast = Arc::clone(&executor);
+    tokio::spawn(forward_responses(
+        Arc::clone(&robot_connection),
+        Arc::clone(&broadcast_tx),
+        Arc::clone(&client_manager),
+        Arc::clone(&executor),
+        Arc::clone(&state_cache),
+    ));
+
+    // Start error broadcast task - forwards protocol errors to all WebSocket clients
+    tokio::spawn(forward_protocol_errors(Arc::clone(&robot_connection), Arc::clone(&client_manager)));
+
+    // Start status broadcast task - forwards robot status changes to all WebSocket clients
+    let robot_connection_status = Arc::clone(&robot_connection);
+    let client_manager_status = Arc::clone(&client_manager);
+    let state_cache_status = Arc::clone(&state_cache);
     tokio::spawn(async move {
-        // Track which driver we're currently subscribed to (by its channel address)
         let mut current_driver_id: Option<usize> = None;
 
         loop {
-            // Get current driver
             let driver_opt = {
-                let conn = robot_connection_clone.read().await;
+                let conn = robot_connection_status.read().await;
                 conn.driver.clone()
             };
 
             if let Some(driver) = driver_opt {
-                // Check if this is a different driver than we were subscribed to
                 let driver_id = Arc::as_ptr(&driver) as usize;
 
                 if current_driver_id != Some(driver_id) {
-                    // New driver - subscribe to its response channel
-                    info!("Subscribing to new robot driver response channel");
+                    info!("Subscribing to new robot driver status channel");
                     current_driver_id = Some(driver_id);
                 }
 
-                let mut response_rx = driver.response_tx.subscribe();
+                let mut status_rx = driver.status_tx.subscribe();
 
-                // Broadcast responses, but periodically check if driver changed
                 loop {
-                    // Use select to either receive a message or timeout to check for driver change
                     tokio::select! {
-                        result = response_rx.recv() => {
+                        result = status_rx.recv() => {
                             match result {
-                                Ok(response) => {
-                                    let dto_response: dto::ResponsePacket = response.into();
-                                    if let Ok(binary) = bincode::serialize(&dto_response) {
-                                        let _ = broadcast_tx_clone.send(binary);
-                                    }
+                                Ok(status) => {
+                                    let status: dto::RobotStatus = status.into();
+                                    state_cache_status.lock().unwrap().record_status(driver_id, status.clone());
+                                    let response = ServerResponse::StatusChanged { status };
+                                    client_manager_status.broadcast_all(&response).await;
                                 }
                                 Err(broadcast::error::RecvError::Closed) => {
-                                    warn!("Driver response channel closed - robot disconnected");
                                     current_driver_id = None;
                                     break;
                                 }
                                 Err(broadcast::error::RecvError::Lagged(n)) => {
-                                    warn!("Lagged {} messages", n);
+                                    warn!("Status channel lagged {} messages", n);
                                 }
                             }
                         }
                         _ = tokio::time::sleep(tokio::time::Duration::from_millis(500)) => {
-                            // Periodically check if the driver has changed
+                            // Check if driver changed
                             let new_driver_opt = {
-                                let conn = robot_connection_clone.read().await;
+                                let conn = robot_connection_status.read().await;
                                 conn.driver.clone()
                             };
-
                             match new_driver_opt {
                                 Some(new_driver) => {
                                     let new_id = Arc::as_ptr(&new_driver) as usize;
                                     if Some(new_id) != current_driver_id {
-                                        info!("Robot driver changed - resubscribing to new channel");
-                                        break; // Exit inner loop to resubscribe
+                                        break;
                                     }
                                 }
                                 None => {
-                                    info!("Robot driver disconnected");
                                     current_driver_id = None;
                                     break;
                                 }
@@ -438,58 +642,25 @@ async fn main() {
                         }
                     }
                 }
-
-                // Mark as disconnected if driver channel closed (not just switched)
-                if current_driver_id.is_none() {
-                    let mut conn = robot_connection_clone.write().await;
-                    conn.connected = false;
-
-                    // Unload any running program - it's no longer valid
-                    {
-                        let mut exec = executor_broadcast.lock().await;
-                        if exec.is_running() {
-                            exec.stop();
-                            warn!("Stopped running program due to robot disconnect");
-                        }
-                        exec.reset();
-                        warn!("Reset executor due to robot disconnect");
-                    }
-
-                    // Broadcast robot disconnected to all clients
-                    let disconnect_response = ServerResponse::RobotDisconnected {
-                        reason: "Robot connection lost".to_string(),
-                    };
-                    client_manager_broadcast.broadcast_all(&disconnect_response).await;
-
-                    // Broadcast execution state change (program unloaded)
-                    let state_response = ServerResponse::ExecutionStateChanged {
-                        state: "idle".to_string(),
-                        program_id: None,
-                        current_line: None,
-                        total_lines: None,
-                        message: Some("Program unloaded due to robot disconnect".to_string()),
-                    };
-                    client_manager_broadcast.broadcast_all(&state_response).await;
-                    warn!("Broadcasted RobotDisconnected and ExecutionStateChanged to all clients");
-                }
             } else {
                 current_driver_id = None;
             }
 
-            // Wait before trying again
             tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
         }
     });
 
-    // Start error broadcast task - forwards protocol errors to all WebSocket clients
-    let robot_connection_error = Arc::clone(&robot_connection);
-    let client_manager_error = Arc::clone(&client_manager);
+    // Start health watch task - reports a dead link as soon as the driver heartbeat
+    // gives up, instead of waiting for the response channel to close
+    let robot_connection_health = Arc::clone(&robot_connection);
+    let client_manager_health = Arc::clone(&client_manager);
+    let executor_health = Arc::clone(&executor);
     tokio::spawn(async move {
         let mut current_driver_id: Option<usize> = None;
 
         loop {
             let driver_opt = {
-                let conn = robot_connection_error.read().await;
+                let conn = robot_connection_health.read().await;
                 conn.driver.clone()
             };
 
@@ -497,42 +668,45 @@ async fn main() {
                 let driver_id = Arc::as_ptr(&driver) as usize;
 
                 if current_driver_id != Some(driver_id) {
-                    info!("Subscribing to new robot driver error channel");
+                    info!("Subscribing to new robot driver health channel");
                     current_driver_id = Some(driver_id);
                 }
 
-                let mut error_rx = driver.error_tx.subscribe();
+                let mut health_rx = driver.subscribe_health();
 
                 loop {
                     tokio::select! {
-                        result = error_rx.recv() => {
-                            match result {
-                                Ok(protocol_error) => {
-                                    warn!("Protocol error: {} - {}", protocol_error.error_type, protocol_error.message);
-                                    if let Some(ref raw) = protocol_error.raw_data {
-                                        warn!("Raw data that failed to parse: {}", raw);
-                                    }
-                                    let response = ServerResponse::RobotError {
-                                        error_type: protocol_error.error_type,
-                                        message: protocol_error.message,
-                                        error_id: None,
-                                        raw_data: protocol_error.raw_data,
-                                    };
-                                    client_manager_error.broadcast_all(&response).await;
-                                }
-                                Err(broadcast::error::RecvError::Closed) => {
-                                    current_driver_id = None;
-                                    break;
-                                }
-                                Err(broadcast::error::RecvError::Lagged(n)) => {
-                                    warn!("Error channel lagged {} messages", n);
+                        result = health_rx.changed() => {
+                            if result.is_err() {
+                                current_driver_id = None;
+                                break;
+                            }
+                            if *health_rx.borrow_and_update() != ConnectionHealth::Unhealthy {
+                                continue;
+                            }
+
+                            warn!("Robot heartbeat lost - marking connection as disconnected");
+                            {
+                                let mut conn = robot_connection_health.write().await;
+                                conn.connected = false;
+                                conn.jog = None;
+                            }
+                            {
+                                let mut exec = executor_health.lock().await;
+                                if exec.is_running() {
+                                    exec.stop();
+                                    warn!("Stopped running program due to lost heartbeat");
                                 }
                             }
+                            let response = ServerResponse::RobotDisconnected {
+                                reason: "Robot stopped responding to heartbeat".to_string(),
+                            };
+                            client_manager_health.broadcast_all(&response).await;
                         }
                         _ = tokio::time::sleep(tokio::time::Duration::from_millis(500)) => {
                             // Check if driver changed
                             let new_driver_opt = {
-                                let conn = robot_connection_error.read().await;
+                                let conn = robot_connection_health.read().await;
                                 conn.driver.clone()
                             };
                             match new_driver_opt {
@@ -599,7 +773,7 @@ async fn main() {
                 info!("Control lock timed out for client {}", timed_out_client);
                 // Notify the timed-out client that they lost control
                 let response = ServerResponse::ControlLost {
-                    reason: "Control released due to inactivity timeout (10 minutes)".to_string(),
+                    reason: "Control released due to inactivity timeout".to_string(),
                 };
                 client_manager_timeout.send_to_client(timed_out_client, &response).await;
                 // Notify all clients that control changed
@@ -609,34 +783,336 @@ async fn main() {
         }
     });
 
+    // Motion watchdog - aborts motion when the control holder stops sending heartbeats
+    let client_manager_deadman = Arc::clone(&client_manager);
+    let executor_deadman = Arc::clone(&executor);
+    let robot_connection_deadman = Arc::clone(&robot_connection);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(250));
+        loop {
+            interval.tick().await;
+            handlers::control::check_deadman(
+                Arc::clone(&client_manager_deadman),
+                Arc::clone(&executor_deadman),
+                Arc::clone(&robot_connection_deadman),
+            ).await;
+        }
+    });
+
     // Start WebSocket server
     let websocket_addr = format!("0.0.0.0:{}", websocket_port);
     let ws_listener = tokio::net::TcpListener::bind(&websocket_addr).await.unwrap();
     info!("🚀 WebSocket server listening on ws://{}", websocket_addr);
     info!("   No robot connected - use UI to connect to a saved robot connection");
-    info!("   Environment: WEBSOCKET_PORT={}", websocket_port);
+    info!("   Environment: WEBSOCKET_PORT={}, WEBSOCKET_MAX_MESSAGE_SIZE={}", websocket_port, max_message_size);
 
     while let Ok((stream, addr)) = ws_listener.accept().await {
         info!("New WebSocket connection from {}", addr);
-        let robot_connection = Arc::clone(&robot_connection);
+        let registry = Arc::clone(&registry);
         let db = Arc::clone(&db);
         let executor = Arc::clone(&executor);
         let client_manager = Arc::clone(&client_manager);
         let broadcast_rx = broadcast_tx.subscribe();
 
-        tokio::spawn(handle_connection(stream, robot_connection, db, executor, client_manager, broadcast_rx));
+        tokio::spawn(handle_connection(stream, registry, db, executor, client_manager, broadcast_rx, max_message_size));
+    }
+}
+
+/// Broadcast the active robot's protocol errors to every client as
+/// `RobotError`, with the packet's `ErrorID` when it had one.
+///
+/// Follows driver changes, resubscribing whenever the driver is replaced.
+async fn forward_protocol_errors(
+    robot_connection: Arc<RwLock<RobotConnection>>,
+    client_manager: Arc<ClientManager>,
+) {
+    let mut current_driver_id: Option<usize> = None;
+
+    loop {
+        let driver_opt = {
+            let conn = robot_connection.read().await;
+            conn.driver.clone()
+        };
+
+        if let Some(driver) = driver_opt {
+            let driver_id = Arc::as_ptr(&driver) as usize;
+
+            if current_driver_id != Some(driver_id) {
+                info!("Subscribing to new robot driver error channel");
+                current_driver_id = Some(driver_id);
+            }
+
+            let mut error_rx = driver.error_tx.subscribe();
+
+            loop {
+                tokio::select! {
+                    result = error_rx.recv() => {
+                        match result {
+                            Ok(protocol_error) => {
+                                warn!("Protocol error: {} - {}", protocol_error.error_type, protocol_error.message);
+                                if let Some(ref raw) = protocol_error.raw_data {
+                                    warn!("Raw data that failed to parse: {}", raw);
+                                }
+                                let response = ServerResponse::RobotError {
+                                    error_type: protocol_error.error_type,
+                                    message: protocol_error.message,
+                                    error_id: protocol_error.error_id.map(|id| id as i32),
+                                    raw_data: protocol_error.raw_data,
+                                };
+                                client_manager.broadcast_all(&response).await;
+                            }
+                            Err(broadcast::error::RecvError::Closed) => {
+                                current_driver_id = None;
+                                break;
+                            }
+                            Err(broadcast::error::RecvError::Lagged(n)) => {
+                                warn!("Error channel lagged {} messages", n);
+                            }
+                        }
+                    }
+                    _ = tokio::time::sleep(tokio::time::Duration::from_millis(500)) => {
+                        // Check if driver changed
+                        let new_driver_opt = {
+                            let conn = robot_connection.read().await;
+                            conn.driver.clone()
+                        };
+                        match new_driver_opt {
+                            Some(new_driver) => {
+                                let new_id = Arc::as_ptr(&new_driver) as usize;
+                                if Some(new_id) != current_driver_id {
+                                    break;
+                                }
+                            }
+                            None => {
+                                current_driver_id = None;
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        } else {
+            current_driver_id = None;
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
     }
 }
 
+/// Forward the active robot's responses to every client as binary frames.
+///
+/// Follows driver changes, replaying the cached state to all clients each
+/// time it subscribes to a driver. When the driver's response channel closes,
+/// the robot is marked disconnected and the loaded program unloaded.
+async fn forward_responses(
+    robot_connection: Arc<RwLock<RobotConnection>>,
+    broadcast_tx: Arc<broadcast::Sender<Vec<u8>>>,
+    client_manager: Arc<ClientManager>,
+    executor: Arc<tokio::sync::Mutex<ProgramExecutor>>,
+    state_cache: Arc<std::sync::Mutex<StateCache>>,
+) {
+    // Track which driver we're currently subscribed to (by its channel address)
+    let mut current_driver_id: Option<usize> = None;
+    // Driver the cached state was last replayed for; a closed channel is
+    // resubscribed to repeatedly and should not replay each time
+    let mut replayed_driver_id: Option<usize> = None;
+    let mut joint_limit_monitor = joint_limits::JointLimitMonitor::default();
+    let mut overspeed_monitor = overspeed::OverspeedMonitor::default();
+
+    loop {
+        // Get current driver
+        let driver_opt = {
+            let conn = robot_connection.read().await;
+            conn.driver.clone()
+        };
+
+        if let Some(driver) = driver_opt {
+            // Check if this is a different driver than we were subscribed to
+            let driver_id = Arc::as_ptr(&driver) as usize;
+
+            if current_driver_id != Some(driver_id) {
+                // New driver - subscribe to its response channel
+                info!("Subscribing to new robot driver response channel");
+                current_driver_id = Some(driver_id);
+                joint_limit_monitor.reset();
+                overspeed_monitor.reset();
+            }
+
+            let mut response_rx = driver.response_tx.subscribe();
+
+            // Clients may have missed state while the driver was switched
+            if replayed_driver_id != Some(driver_id) {
+                replayed_driver_id = Some(driver_id);
+                state_cache::replay_state(&state_cache, driver_id, &robot_connection, &broadcast_tx, &client_manager).await;
+            }
+
+            // Broadcast responses, but periodically check if driver changed
+            loop {
+                // Use select to either receive a message or timeout to check for driver change
+                tokio::select! {
+                    result = response_rx.recv() => {
+                        match result {
+                            Ok(response) => {
+                                let response_type = response.name();
+                                let dto_response: dto::ResponsePacket = response.into();
+                                match bincode::serialize(&dto_response) {
+                                    Ok(binary) => {
+                                        let frame = encode_frame(&binary);
+                                        state_cache.lock().unwrap().record_response(driver_id, &dto_response, &frame);
+                                        let _ = broadcast_tx.send(frame);
+                                        if let dto::ResponsePacket::CommandResponse(dto::CommandResponse::FrcReadJointAngles(r)) = &dto_response {
+                                            if r.error_id == 0 {
+                                                let a = &r.joint_angles;
+                                                let angles = [a.j1, a.j2, a.j3, a.j4, a.j5, a.j6].map(f64::from);
+                                                let limits = robot_connection.read().await.joint_limits;
+                                                for warning in joint_limit_monitor.check(&limits, angles) {
+                                                    client_manager.broadcast_all(&warning).await;
+                                                }
+                                            }
+                                        }
+                                        if let dto::ResponsePacket::CommandResponse(dto::CommandResponse::FrcReadTCPSpeed(r)) = &dto_response {
+                                            if r.error_id == 0 {
+                                                let limit = robot_connection.read().await.tcp_speed_limit;
+                                                if let Some(warning) = overspeed_monitor.check(limit, r.speed.into()) {
+                                                    client_manager.broadcast_all(&warning).await;
+                                                }
+                                            }
+                                        }
+                                    }
+                                    Err(e) => {
+                                        // Dropped responses would otherwise go unnoticed
+                                        let message = format!("Failed to serialize {} response: {}", response_type, e);
+                                        error!("{}", message);
+                                        client_manager.broadcast_all(&ServerResponse::Error { message }).await;
+                                    }
+                                }
+                            }
+                            Err(broadcast::error::RecvError::Closed) => {
+                                warn!("Driver response channel closed - robot disconnected");
+                                current_driver_id = None;
+                                break;
+                            }
+                            Err(broadcast::error::RecvError::Lagged(n)) => {
+                                warn!("Lagged {} messages", n);
+                            }
+                        }
+                    }
+                    _ = tokio::time::sleep(tokio::time::Duration::from_millis(500)) => {
+                        // Periodically check if the driver has changed
+                        let new_driver_opt = {
+                            let conn = robot_connection.read().await;
+                            conn.driver.clone()
+                        };
+
+                        match new_driver_opt {
+                            Some(new_driver) => {
+                                let new_id = Arc::as_ptr(&new_driver) as usize;
+                                if Some(new_id) != current_driver_id {
+                                    info!("Robot driver changed - resubscribing to new channel");
+                                    break; // Exit inner loop to resubscribe
+                                }
+                            }
+                            None => {
+                                info!("Robot driver disconnected");
+                                current_driver_id = None;
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Mark as disconnected if driver channel closed (not just switched)
+            if current_driver_id.is_none() {
+                // The lost robot's state must not be replayed as current
+                state_cache.lock().unwrap().clear();
+                let mut conn = robot_connection.write().await;
+                conn.connected = false;
+                conn.jog = None;
+
+                // Unload any running program - it's no longer valid
+                {
+                    let mut exec = executor.lock().await;
+                    if exec.is_running() {
+                        exec.stop();
+                        warn!("Stopped running program due to robot disconnect");
+                    }
+                    exec.reset();
+                    warn!("Reset executor due to robot disconnect");
+                }
+
+                // Broadcast robot disconnected to all clients
+                let disconnect_response = ServerResponse::RobotDisconnected {
+                    reason: "Robot connection lost".to_string(),
+                };
+                client_manager.broadcast_all(&disconnect_response).await;
+
+                // Broadcast execution state change (program unloaded)
+                let state_response = session::execution_state_changed(
+                    web_common::ExecutionState::Idle,
+                    None,
+                    None,
+                    None,
+                    Some("Program unloaded due to robot disconnect".to_string()),
+                );
+                client_manager.broadcast_all(&state_response).await;
+                warn!("Broadcasted RobotDisconnected and ExecutionStateChanged to all clients");
+            }
+        } else {
+            current_driver_id = None;
+        }
+
+        // Wait before trying again
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    }
+}
+
+/// Default limit on an incoming WebSocket message, in bytes. Override with
+/// `WEBSOCKET_MAX_MESSAGE_SIZE`.
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 4 * 1024 * 1024;
+
+/// WebSocket settings for client connections.
+///
+/// tungstenite checks the length in each frame header against the limits
+/// before reading the payload, so an oversized message is refused without
+/// being buffered.
+fn websocket_config(max_message_size: usize) -> WebSocketConfig {
+    WebSocketConfig {
+        max_message_size: Some(max_message_size),
+        max_frame_size: Some(max_message_size),
+        ..Default::default()
+    }
+}
+
+/// The role in a handshake query such as `role=viewer`. Clients that name
+/// none, or an unknown one, are operators.
+fn client_role(query: Option<&str>) -> ClientRole {
+    query
+        .into_iter()
+        .flat_map(|q| q.split('&'))
+        .find_map(|pair| pair.strip_prefix("role="))
+        .and_then(|role| role.parse().map_err(|e| warn!("{}, treating client as an operator", e)).ok())
+        .unwrap_or_default()
+}
+
 async fn handle_connection(
     stream: tokio::net::TcpStream,
-    robot_connection: Arc<RwLock<RobotConnection>>,
+    registry: Arc<RobotRegistry>,
     db: Arc<tokio::sync::Mutex<Database>>,
     executor: Arc<tokio::sync::Mutex<ProgramExecutor>>,
     client_manager: Arc<ClientManager>,
     mut broadcast_rx: broadcast::Receiver<Vec<u8>>,
+    max_message_size: usize,
 ) {
-    let ws_stream = match accept_async(stream).await {
+    // The client names its role in the handshake URL, e.g. `/?role=viewer`
+    let mut role = ClientRole::default();
+    // The error type is tungstenite's handshake callback signature
+    #[allow(clippy::result_large_err)]
+    let read_role = |request: &Request, response: Response| {
+        role = client_role(request.uri().query());
+        Ok(response)
+    };
+    let ws_stream = match accept_hdr_async_with_config(stream, read_role, Some(websocket_config(max_message_size))).await {
         Ok(ws) => ws,
         Err(e) => {
             error!("WebSocket handshake failed: {}", e);
@@ -644,11 +1120,12 @@ async fn handle_connection(
         }
     };
 
+    let robot_connection = registry.active();
     let (ws_sender, mut ws_receiver) = ws_stream.split();
     let ws_sender = Arc::new(tokio::sync::Mutex::new(ws_sender));
 
     // Register this client with the client manager
-    let client_id = client_manager.register(Arc::clone(&ws_sender)).await;
+    let client_id = client_manager.register(Arc::clone(&ws_sender), role).await;
     info!("Client {} connected", client_id);
 
     // Send initial state to the new client
@@ -704,7 +1181,6 @@ async fn handle_connection(
 
     // Task to handle incoming messages from client
     let ws_sender_clone = Arc::clone(&ws_sender);
-    let robot_connection_clone = Arc::clone(&robot_connection);
     let client_manager_clone = Arc::clone(&client_manager);
     let client_id_for_recv = client_id; // Copy for recv_task
     let recv_task = tokio::spawn(async move {
@@ -729,13 +1205,50 @@ async fn handle_connection(
                     // Touch activity to reset control timeout
                     client_manager_clone.touch_control(client_id_for_recv).await;
 
-                    if let Ok(dto_packet) = bincode::deserialize::<dto::SendPacket>(&data) {
+                    let (robot_id, payload) = match decode_robot_frame(&data) {
+                        Ok(frame) => frame,
+                        Err(FrameError::VersionMismatch { actual, .. }) => {
+                            warn!("Client {} sent a protocol v{} frame, closing", client_id_for_recv, actual);
+                            let mismatch_json = serde_json::to_string(&negotiate_protocol(actual)).unwrap_or_default();
+                            let mut sender = ws_sender_clone.lock().await;
+                            let _ = sender.send(Message::Text(mismatch_json)).await;
+                            break;
+                        }
+                        Err(e) => {
+                            warn!("Invalid binary frame from client {}: {}", client_id_for_recv, e);
+                            continue;
+                        }
+                    };
+
+                    // `payload` is within the message size limit, and bincode never
+                    // reads (or allocates) past the end of a slice.
+                    if let Ok(mut dto_packet) = bincode::deserialize::<dto::SendPacket>(payload) {
                         info!("Received robot command from client: {:?}", dto_packet);
-                        let driver_opt = {
-                            let conn = robot_connection_clone.read().await;
-                            conn.driver.clone()
+                        let target = registry.resolve(robot_id).await;
+                        let driver_opt = match &target {
+                            Some(conn) => conn.read().await.driver.clone(),
+                            None => None,
                         };
-                        if let Some(driver) = driver_opt {
+                        if let (Some(driver), Some(conn)) = (driver_opt, &target) {
+                            // Enforce safety limits on the DTO, before conversion.
+                            // The abort a violation triggers is broadcast after
+                            // the rejection reaches this client.
+                            client_manager_clone.hold_messages(client_id_for_recv).await;
+                            let checked = handlers::safety_limits::enforce_active_limits(
+                                &db,
+                                conn,
+                                Some(Arc::clone(&executor)),
+                                Some(Arc::clone(&client_manager_clone)),
+                                &mut dto_packet,
+                            ).await;
+                            if let Err(violation) = checked {
+                                warn!("Rejected robot command from client {}: {:?}", client_id_for_recv, violation);
+                                let violation_json = serde_json::to_string(&violation).unwrap_or_default();
+                                let _ = ws_sender_clone.lock().await.send(Message::Text(violation_json)).await;
+                                client_manager_clone.release_messages(client_id_for_recv).await;
+                                continue;
+                            }
+                            client_manager_clone.release_messages(client_id_for_recv).await;
                             let packet: fanuc_rmi::packets::SendPacket = dto_packet.into();
                             let _ = driver.send_packet(packet, PacketPriority::Standard);
                         } else {
@@ -754,28 +1267,33 @@ async fn handle_connection(
                 }
                 Ok(Message::Text(text)) => {
                     // Text = API request (JSON)
-                    match serde_json::from_str::<ClientRequest>(&text) {
-                        Ok(request) => {
-                            info!("Received API request: {:?}", request);
-                            // Get driver if connected
-                            let driver_opt = {
-                                let conn = robot_connection_clone.read().await;
-                                conn.driver.clone()
-                            };
-                            let response = handle_request(
+                    match serde_json::from_str::<RoutedRequest>(&text) {
+                        Ok(RoutedRequest { robot_id, request }) => {
+                            if !matches!(request, ClientRequest::Heartbeat) {
+                                info!("Received API request: {:?} (robot {:?})", request, robot_id);
+                            }
+                            // Broadcasts the request triggers reach this
+                            // client after its response
+                            client_manager_clone.hold_messages(client_id_for_recv).await;
+                            let response = handle_routed_request(
+                                robot_id,
                                 request,
+                                &registry,
                                 Arc::clone(&db),
-                                driver_opt,
                                 Some(Arc::clone(&executor)),
-                                Some(Arc::clone(&robot_connection_clone)),
                                 Some(Arc::clone(&client_manager_clone)),
                                 Some(client_id_for_recv),
                             ).await;
                             let response_json = serde_json::to_string(&response).unwrap_or_else(|e| {
                                 format!(r#"{{"type":"error","message":"Serialization error: {}"}}"#, e)
                             });
-                            let mut sender = ws_sender_clone.lock().await;
-                            if sender.send(Message::Text(response_json)).await.is_err() {
+                            let sent = ws_sender_clone.lock().await.send(Message::Text(response_json)).await;
+                            client_manager_clone.release_messages(client_id_for_recv).await;
+                            if sent.is_err() {
+                                break;
+                            }
+                            if matches!(response, ServerResponse::ProtocolMismatch { .. }) {
+                                warn!("Client {} speaks a different protocol version, closing", client_id_for_recv);
                                 break;
                             }
                         }
@@ -791,6 +1309,22 @@ async fn handle_connection(
                     }
                 }
                 Ok(Message::Close(_)) => break,
+                Err(tungstenite::Error::Capacity(e)) => {
+                    // The rest of the oversized message is never read, so the
+                    // stream cannot be resynchronized: report and close.
+                    warn!("Client {} sent an oversized message, closing: {}", client_id_for_recv, e);
+                    let error_response = ServerResponse::Error {
+                        message: format!("Message rejected: {} (limit {} bytes)", e, max_message_size),
+                    };
+                    let error_json = serde_json::to_string(&error_response).unwrap_or_default();
+                    let mut sender = ws_sender_clone.lock().await;
+                    let _ = sender.send(Message::Text(error_json)).await;
+                    let _ = sender.send(Message::Close(Some(CloseFrame {
+                        code: CloseCode::Size,
+                        reason: "message too large".into(),
+                    }))).await;
+                    break;
+                }
                 Err(e) => {
                     error!("WebSocket error: {}", e);
                     break;
@@ -811,3 +1345,291 @@ async fn handle_connection(
     info!("WebSocket connection closed for client {}", client_id);
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{command_reply, connect_driver, start_fake_controller};
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+
+    const TEST_MAX_MESSAGE_SIZE: usize = 1024;
+
+    #[test]
+    fn test_client_role_from_handshake_query() {
+        assert_eq!(client_role(Some("role=viewer")), ClientRole::Viewer);
+        assert_eq!(client_role(Some("token=abc&role=Supervisor")), ClientRole::Supervisor);
+        assert_eq!(client_role(Some("role=admin")), ClientRole::Operator);
+        assert_eq!(client_role(None), ClientRole::Operator);
+    }
+
+    #[test]
+    fn test_connections_seed_their_models_defaults() {
+        let small = RobotConnection::for_model("127.0.0.1".to_string(), 16001, RobotModel::CRX10iA);
+        let large = RobotConnection::for_model("127.0.0.1".to_string(), 16001, RobotModel::CRX30iA);
+
+        for conn in [&small, &large] {
+            let config = &conn.active_configuration;
+            assert_eq!((config.u_frame_number, config.u_tool_number), (1, 1));
+            assert_eq!((config.front, config.up, config.left, config.flip), (1, 1, 0, 0));
+            assert_eq!(config.default_joint_jog_speed, conn.model.default_joint_jog_speed());
+            assert_eq!(conn.active_joint_jog_speed, conn.model.default_joint_jog_speed());
+        }
+        assert_eq!(small.active_configuration.default_joint_jog_speed, 10.0);
+        assert_eq!(large.active_configuration.default_joint_jog_speed, 6.0);
+        assert_eq!(RobotConnection::new("127.0.0.1".to_string(), 16001).model, RobotModel::CRX10iA);
+    }
+
+    #[test]
+    fn test_arm_config_round_trips_through_active_configuration() {
+        let arm = ArmConfig { front: false, up: true, left: true, flip: true, turn4: -8, turn5: 0, turn6: 7 };
+        let active = ActiveConfiguration::from(arm);
+        assert_eq!((active.front, active.up, active.left, active.flip), (0, 1, 1, 1));
+        assert_eq!((active.turn4, active.turn5, active.turn6), (-8, 0, 7));
+        assert_eq!(ArmConfig::try_from(&active), Ok(arm));
+
+        // Everything but the arm configuration keeps its default
+        let default = ActiveConfiguration::default();
+        assert_eq!((active.u_frame_number, active.u_tool_number), (default.u_frame_number, default.u_tool_number));
+        assert_eq!(active.default_joint_jog_speed, default.default_joint_jog_speed);
+    }
+
+    #[test]
+    fn test_invalid_active_configuration_is_rejected() {
+        let active = ActiveConfiguration { flip: 2, ..Default::default() };
+        assert_eq!(
+            ArmConfig::try_from(&active),
+            Err(ArmConfigError::InvalidBit { field: "flip", value: 2 })
+        );
+        let active = ActiveConfiguration { turn5: 8, ..Default::default() };
+        assert_eq!(
+            ArmConfig::try_from(&active),
+            Err(ArmConfigError::TurnOutOfRange { field: "turn5", value: 8 })
+        );
+    }
+
+    /// Start a server on an ephemeral port that accepts one WebSocket client.
+    async fn start_server() -> std::net::SocketAddr {
+        start_server_for(Arc::new(RwLock::new(RobotConnection::new("127.0.0.1".to_string(), 16001)))).await
+    }
+
+    /// [`start_server`] with `connection` as the active robot.
+    async fn start_server_for(connection: Arc<RwLock<RobotConnection>>) -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (broadcast_tx, broadcast_rx) = broadcast::channel::<Vec<u8>>(16);
+        let registry = Arc::new(RobotRegistry::new(connection, Arc::new(broadcast_tx)));
+        let db = Arc::new(tokio::sync::Mutex::new(Database::new(":memory:").unwrap()));
+        let executor = Arc::new(tokio::sync::Mutex::new(ProgramExecutor::new()));
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            handle_connection(
+                stream,
+                registry,
+                db,
+                executor,
+                Arc::new(ClientManager::new()),
+                broadcast_rx,
+                TEST_MAX_MESSAGE_SIZE,
+            )
+            .await;
+        });
+        addr
+    }
+
+    /// Send the header of a 1 GiB frame with `opcode` plus a few payload bytes,
+    /// and return the server's error message. Only answering before the rest
+    /// of the payload arrives proves the server did not try to buffer it.
+    async fn send_oversized_frame(opcode: u8) -> String {
+        let addr = start_server().await;
+        let tcp = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let (mut ws, _) = tokio_tungstenite::client_async(format!("ws://{}", addr), tcp).await.unwrap();
+
+        let mut frame = vec![0x80 | opcode, 0x80 | 127];
+        frame.extend_from_slice(&(1u64 << 30).to_be_bytes());
+        frame.extend_from_slice(&[0x12, 0x34, 0x56, 0x78]);
+        frame.extend_from_slice(&[0u8; 16]);
+        ws.get_mut().write_all(&frame).await.unwrap();
+
+        let read_error = async {
+            let mut error = None;
+            while let Some(msg) = ws.next().await {
+                match msg.unwrap() {
+                    Message::Text(text) => {
+                        if let ServerResponse::Error { message } = serde_json::from_str(&text).unwrap() {
+                            error = Some(message);
+                        }
+                    }
+                    Message::Close(frame) => {
+                        assert_eq!(frame.map(|f| f.code), Some(CloseCode::Size));
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            error.expect("server reported the oversized message")
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(5), read_error)
+            .await
+            .expect("server answered without waiting for the payload")
+    }
+
+    #[tokio::test]
+    async fn test_oversized_text_frame_is_rejected() {
+        let message = send_oversized_frame(0x1).await;
+        assert!(message.starts_with("Message rejected"), "{}", message);
+        assert!(message.contains("limit 1024 bytes"), "{}", message);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_binary_frame_is_rejected() {
+        let message = send_oversized_frame(0x2).await;
+        assert!(message.starts_with("Message rejected"), "{}", message);
+    }
+
+    /// Fake controller that answers every command successfully, recording
+    /// each command name in `commands`. Returns the connect port.
+    async fn spawn_fake_controller(commands: Arc<std::sync::Mutex<Vec<String>>>) -> u32 {
+        start_fake_controller(move |packet| {
+            let reply = command_reply(&packet)?;
+            commands.lock().unwrap().push(packet["Command"].as_str()?.to_string());
+            Some(reply)
+        })
+        .await
+    }
+
+    /// A driver connected to a fresh [`spawn_fake_controller`].
+    async fn connect_fake_controller() -> Arc<FanucDriver> {
+        connect_driver(spawn_fake_controller(Default::default()).await).await
+    }
+
+    /// With auto-initialize off, connecting sends no `FRC_Initialize` and
+    /// leaves the TP program uninitialized.
+    #[tokio::test]
+    async fn test_connect_without_auto_initialize() {
+        let commands: Arc<std::sync::Mutex<Vec<String>>> = Default::default();
+        let port = spawn_fake_controller(Arc::clone(&commands)).await;
+        let mut connection = RobotConnection::new("127.0.0.1".to_string(), port);
+        connection.auto_initialize = false;
+
+        connection.connect().await.expect("connect to fake controller");
+        assert!(connection.connected);
+        assert!(!connection.tp_program_initialized);
+
+        // Give anything the connect might have queued time to arrive
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        let commands = commands.lock().unwrap();
+        assert!(!commands.iter().any(|c| c == "FRC_Initialize"), "{:?}", commands);
+    }
+
+    /// Switching drivers does not rebroadcast the previous robot's
+    /// position as the new robot's state.
+    #[tokio::test]
+    async fn test_driver_switch_does_not_replay_previous_robot_state() {
+        let connection = Arc::new(RwLock::new(RobotConnection::new("127.0.0.1".to_string(), 16001)));
+        let first_driver = connect_fake_controller().await;
+        connection.write().await.driver = Some(Arc::clone(&first_driver));
+
+        let (broadcast_tx, mut frames) = broadcast::channel::<Vec<u8>>(16);
+        tokio::spawn(forward_responses(
+            Arc::clone(&connection),
+            Arc::new(broadcast_tx),
+            Arc::new(ClientManager::new()),
+            Arc::new(tokio::sync::Mutex::new(ProgramExecutor::new())),
+            Arc::new(std::sync::Mutex::new(StateCache::default())),
+        ));
+        // Let the task subscribe before the robot answers
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let read_position: fanuc_rmi::packets::SendPacket = dto::SendPacket::Command(
+            dto::Command::FrcReadCartesianPosition(dto::FrcReadCartesianPosition { group: 1 }),
+        )
+        .into();
+        first_driver.send_packet(read_position, PacketPriority::High).unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(2), frames.recv())
+            .await
+            .expect("position frame")
+            .unwrap();
+
+        connection.write().await.driver = Some(connect_fake_controller().await);
+        // The task notices the switch within its 500ms driver check
+        let replayed = tokio::time::timeout(std::time::Duration::from_secs(1), frames.recv()).await;
+        assert!(replayed.is_err(), "stale state replayed: {:?}", replayed);
+    }
+
+    /// A protocol error whose packet names a controller error reaches
+    /// clients with that `ErrorID`.
+    #[tokio::test]
+    async fn test_protocol_error_broadcasts_its_error_id() {
+        use crate::session::test_support::{connect_client, pushed};
+        use fanuc_rmi::drivers::ProtocolError;
+
+        let connection = Arc::new(RwLock::new(RobotConnection::new("127.0.0.1".to_string(), 16001)));
+        let driver = connect_fake_controller().await;
+        connection.write().await.driver = Some(Arc::clone(&driver));
+        let client_manager = Arc::new(ClientManager::new());
+        let (_client_id, mut socket) = connect_client(&client_manager).await;
+        tokio::spawn(forward_protocol_errors(Arc::clone(&connection), Arc::clone(&client_manager)));
+        // Let the task subscribe before the error arrives
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let raw = r#"{"Command" : "FRC_ReadJointAngles", "ErrorID" : 2556955, "TimeTag" : }"#;
+        driver
+            .error_tx
+            .send(ProtocolError {
+                error_type: "protocol".to_string(),
+                message: "Failed to parse robot response".to_string(),
+                error_id: fanuc_rmi::extract_error_id(raw),
+                raw_data: Some(raw.to_string()),
+            })
+            .unwrap();
+
+        let pushed = pushed(&mut socket, std::time::Duration::from_millis(300)).await;
+        assert!(
+            pushed.iter().any(|response| matches!(
+                response,
+                ServerResponse::RobotError { error_id: Some(2556955), raw_data: Some(data), .. } if data == raw
+            )),
+            "{:?}",
+            pushed
+        );
+    }
+
+    /// A request that both answers and broadcasts: its client gets the
+    /// answer first, then the broadcast and the I/O push it triggered.
+    #[tokio::test]
+    async fn test_response_reaches_requester_before_its_broadcasts() {
+        use crate::api_types::{IoPoint, IoType};
+        use crate::session::test_support::pushed;
+
+        let connection = Arc::new(RwLock::new(RobotConnection::new("127.0.0.1".to_string(), 16001)));
+        {
+            let mut conn = connection.write().await;
+            conn.driver = Some(connect_fake_controller().await);
+            conn.connected = true;
+        }
+        let addr = start_server_for(connection).await;
+        let tcp = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let (mut ws, _) = tokio_tungstenite::client_async(format!("ws://{}", addr), tcp).await.unwrap();
+
+        let dout3 = IoPoint { io_type: IoType::Dout, port: 3, robot_id: None };
+        for request in [ClientRequest::RequestControl, ClientRequest::SubscribeIo { points: vec![dout3] }] {
+            ws.send(Message::Text(serde_json::to_string(&request).unwrap())).await.unwrap();
+        }
+        pushed(&mut ws, std::time::Duration::from_millis(200)).await;
+
+        let request = ClientRequest::WriteDout { port_number: 3, port_value: true };
+        ws.send(Message::Text(serde_json::to_string(&request).unwrap())).await.unwrap();
+        let received: Vec<_> = pushed(&mut ws, std::time::Duration::from_millis(300))
+            .await
+            .into_iter()
+            .filter_map(|response| match response {
+                ServerResponse::DoutValue { port_number: 3, port_value: true, robot_id: None } => Some("dout_value"),
+                ServerResponse::IoChanged { point, value } if point == dout3 && value == 1.0 => Some("io_changed"),
+                _ => None,
+            })
+            .collect();
+        // Response, then the DoutValue broadcast, then the push to subscribers
+        assert_eq!(received, vec!["dout_value", "dout_value", "io_changed"]);
+    }
+}
@@ -0,0 +1,66 @@
+//! TCP overspeed warnings.
+//!
+//! The `FRC_ReadTCPSpeed` answers are compared with the cell's TCP speed
+//! limit, and a [`ServerResponse::OverspeedWarning`] is broadcast when the
+//! reported speed exceeds it. This only watches what the controller reports;
+//! nothing is sent to the robot.
+
+use crate::api_types::ServerResponse;
+
+/// Tracks whether the TCP is over the limit, so each excursion above it is
+/// warned about once rather than on every reading.
+#[derive(Debug, Default)]
+pub struct OverspeedMonitor {
+    over_limit: bool,
+}
+
+impl OverspeedMonitor {
+    /// Check a reported TCP `speed` (mm/s) against `limit`, returning a
+    /// warning if the speed has gone over it since the last check. No limit
+    /// disables the check.
+    pub fn check(&mut self, limit: Option<f64>, speed: f64) -> Option<ServerResponse> {
+        let Some(limit) = limit else {
+            self.over_limit = false;
+            return None;
+        };
+        let over = speed > limit;
+        let newly_over = over && !self.over_limit;
+        self.over_limit = over;
+        newly_over.then_some(ServerResponse::OverspeedWarning { speed, limit })
+    }
+
+    /// Forget an excursion in progress, e.g. for a new driver.
+    pub fn reset(&mut self) {
+        self.over_limit = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overspeed_reading_warns() {
+        let mut monitor = OverspeedMonitor::default();
+        match monitor.check(Some(250.0), 300.0) {
+            Some(ServerResponse::OverspeedWarning { speed, limit }) => {
+                assert_eq!(speed, 300.0);
+                assert_eq!(limit, 250.0);
+            }
+            other => panic!("expected OverspeedWarning, got {:?}", other),
+        }
+        assert!(monitor.check(Some(250.0), 310.0).is_none());
+
+        // Dropping back under the limit re-arms the warning
+        assert!(monitor.check(Some(250.0), 100.0).is_none());
+        assert!(monitor.check(Some(250.0), 260.0).is_some());
+    }
+
+    #[test]
+    fn test_under_limit_reading_is_silent() {
+        let mut monitor = OverspeedMonitor::default();
+        assert!(monitor.check(Some(250.0), 249.9).is_none());
+        assert!(monitor.check(Some(250.0), 250.0).is_none());
+        assert!(monitor.check(None, 1000.0).is_none());
+    }
+}
@@ -5,18 +5,255 @@
 //! - CNT termination for all instructions except the last
 //! - FINE termination for the last instruction
 //! - Progress tracking and status updates
+//! - Control lines (`LBL`, `JMP`, `IF DIN[..]`, `REPEAT`), expanded lazily as the
+//!   buffer is filled so loops and conditional jumps follow the live robot state
+//! - `CALL` lines, which inline another stored program at load time
+//! - Named execution contexts (such as operator jogs) whose instructions
+//!   interleave with the program by priority, sharing its buffer slots
+//! - Breakpoints, which pause execution before a line is sent
 
+use crate::api_types::{ConfigurationField, ConfigurationWarning};
 use crate::database::{Database, Program, ProgramInstruction};
 use crate::program_parser::ProgramDefaults;
 use fanuc_rmi::packets::{SendPacket, Instruction};
 use fanuc_rmi::instructions::FrcLinearMotion;
 use fanuc_rmi::{TermType, SpeedType, Configuration, Position};
-use std::collections::{VecDeque, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
 use tracing::info;
 
 /// Maximum instructions to send ahead (conservative: use 5 of 8 available slots).
 pub const MAX_BUFFER: usize = 5;
 
+/// Backward jumps (loop iterations) allowed in one run before the program is
+/// stopped as a runaway loop.
+pub const MAX_LOOP_ITERATIONS: u32 = 10_000;
+
+/// Nesting depth of `CALL`s allowed before loading fails as runaway
+/// recursion.
+pub const MAX_CALL_DEPTH: usize = 8;
+
+/// Priority of the loaded program. Contexts above it are dispatched ahead of
+/// the program's next lines; contexts below it only fill slots the program
+/// leaves free.
+pub const PROGRAM_PRIORITY: u8 = 100;
+
+/// Execution contexts allowed besides the program.
+pub const MAX_CONTEXTS: usize = 4;
+
+/// Time a FINE termination spends decelerating and settling at its point.
+/// CNT blends scale this down by their term value (CNT100 never stops).
+pub const FINE_STOP_SECONDS: f64 = 0.25;
+
+/// Estimate how long a sequence of motion packets takes to run.
+///
+/// Each linear motion contributes its travel time plus its stop time:
+/// - `mmSec` / `InchMin`: distance from the previous motion's endpoint divided
+///   by the speed. The first motion starts from an unknown robot pose, so only
+///   its stop time counts.
+/// - `Time` (0.1 s units) / `mSec`: the declared time, regardless of distance.
+/// - Stop time is [`FINE_STOP_SECONDS`] for FINE, scaled by `1 - value/100`
+///   for CNT, and zero for CR.
+///
+/// Packets other than linear motions are ignored.
+pub fn estimate_duration(packets: &[SendPacket]) -> Duration {
+    let mut seconds = 0.0;
+    let mut previous: Option<&Position> = None;
+
+    for packet in packets {
+        let motion = match packet {
+            SendPacket::Instruction(Instruction::FrcLinearMotion(motion)) => motion,
+            _ => continue,
+        };
+
+        let distance = previous.map_or(0.0, |prev| motion.position.distance_to(prev));
+
+        let travel = match motion.speed_type {
+            SpeedType::MMSec if motion.speed > 0.0 => distance / motion.speed,
+            SpeedType::InchMin if motion.speed > 0.0 => distance / (motion.speed * 25.4 / 60.0),
+            SpeedType::Time => motion.speed * 0.1,
+            SpeedType::MilliSeconds => motion.speed / 1000.0,
+            _ => 0.0,
+        };
+
+        let stop = match motion.term_type {
+            TermType::FINE => FINE_STOP_SECONDS,
+            TermType::CNT => FINE_STOP_SECONDS * (1.0 - f64::from(motion.term_value.min(100)) / 100.0),
+            TermType::CR => 0.0,
+        };
+
+        seconds += travel + stop;
+        previous = Some(&motion.position);
+    }
+
+    Duration::from_secs_f64(seconds)
+}
+
+/// A control-flow instruction, parsed from a program line's `control` text.
+///
+/// Syntax follows the teach pendant:
+/// - `LBL[n]`
+/// - `JMP LBL[n]`
+/// - `IF DIN[port]=ON, JMP LBL[n]` (or `OFF`)
+/// - `REPEAT n`
+/// - `CALL name` or `CALL PROG[id]`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProgramControl {
+    /// Jump target.
+    Label(u32),
+    /// Unconditional jump to a label.
+    Jump(u32),
+    /// Jump to `label` if digital input `port` reads `value`.
+    JumpIfDin { port: u16, value: bool, label: u32 },
+    /// Run the lines since the preceding label (or the program start) `count`
+    /// times in total, then continue.
+    Repeat(u32),
+    /// Run another stored program, then continue.
+    Call(CallTarget),
+}
+
+/// The stored program a `CALL` line runs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CallTarget {
+    /// Program name, as stored (case-sensitive).
+    Name(String),
+    /// Program database ID, from `CALL PROG[id]`.
+    Id(i64),
+}
+
+impl ProgramControl {
+    /// Parse control text such as `JMP LBL[2]`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        // Program names are case-sensitive, so CALL is parsed before uppercasing.
+        let trimmed = text.trim();
+        if trimmed.get(..5).is_some_and(|keyword| keyword.eq_ignore_ascii_case("CALL ")) {
+            let target = trimmed[5..].trim();
+            let id = target
+                .get(..5)
+                .filter(|prefix| prefix.eq_ignore_ascii_case("PROG["))
+                .and_then(|_| target[5..].strip_suffix(']'))
+                .map(|id| id.trim().parse::<i64>());
+            return match id {
+                Some(Ok(id)) => Ok(ProgramControl::Call(CallTarget::Id(id))),
+                Some(Err(_)) => Err(format!("Invalid program ID in '{}'", text)),
+                None if !target.is_empty() && !target.contains(char::is_whitespace) => {
+                    Ok(ProgramControl::Call(CallTarget::Name(target.to_string())))
+                }
+                None => Err(format!("Invalid call '{}'", text)),
+            };
+        }
+
+        let compact: String = text.split_whitespace().collect::<Vec<_>>().join(" ").to_uppercase();
+
+        fn label(text: &str) -> Option<u32> {
+            text.trim().strip_prefix("LBL[")?.strip_suffix(']')?.trim().parse().ok()
+        }
+
+        if let Some(n) = label(&compact) {
+            return Ok(ProgramControl::Label(n));
+        }
+        if let Some(n) = compact.strip_prefix("JMP ").and_then(label) {
+            return Ok(ProgramControl::Jump(n));
+        }
+        if let Some(count) = compact.strip_prefix("REPEAT ") {
+            return match count.trim().parse::<u32>() {
+                Ok(count) if count > 0 => Ok(ProgramControl::Repeat(count)),
+                _ => Err(format!("Invalid repeat count in '{}'", text)),
+            };
+        }
+        if let Some(rest) = compact.strip_prefix("IF DIN[") {
+            let parsed = rest.split_once(']').and_then(|(port, rest)| {
+                let (value, jump) = rest.trim_start().strip_prefix('=')?.split_once(',')?;
+                let value = match value.trim() {
+                    "ON" => true,
+                    "OFF" => false,
+                    _ => return None,
+                };
+                let label = jump.trim().strip_prefix("JMP ").and_then(label)?;
+                Some(ProgramControl::JumpIfDin { port: port.trim().parse().ok()?, value, label })
+            });
+            return parsed.ok_or_else(|| format!("Invalid conditional jump '{}'", text));
+        }
+        Err(format!("Unknown control instruction '{}'", text))
+    }
+}
+
+/// One line of the loaded program, ready to execute.
+#[derive(Debug, Clone)]
+enum ProgramStep {
+    Motion(SendPacket),
+    Control(ProgramControl),
+}
+
+/// A step with the main-program line it reports progress on and the call
+/// it belongs to.
+#[derive(Debug, Clone)]
+struct Step {
+    /// Line of the main program; steps of a called program report the line
+    /// of the outermost `CALL`.
+    line: usize,
+    /// Index of the call that inlined this step (0 for the main program).
+    /// Labels and `REPEAT` blocks are local to it.
+    frame: usize,
+    kind: ProgramStep,
+}
+
+/// Cursor over the program steps.
+#[derive(Debug, Clone, Default)]
+struct ControlFlow {
+    /// Index of the next step to execute.
+    pc: usize,
+    /// Completed passes per `Repeat` step, keyed by step index.
+    repeats: HashMap<usize, u32>,
+    /// Backward jumps taken so far (bounded by [`MAX_LOOP_ITERATIONS`]).
+    iterations: u32,
+}
+
+/// Result of advancing the control flow to the next motion.
+enum FlowStep {
+    Motion(usize, SendPacket),
+    /// Blocked on a conditional jump until the DIN port value is known.
+    NeedDin(u16),
+    End,
+}
+
+/// How an instruction response resolved its program line, or the
+/// instruction of another execution context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineOutcome {
+    /// The line's instruction completed.
+    Completed(usize),
+    /// The controller rejected the line's instruction; execution halted.
+    Failed { line: usize, error_id: u32 },
+    /// An instruction of `context` finished; a non-zero `error_id` means it
+    /// was rejected and the rest of the context's queue dropped.
+    Context { context: &'static str, error_id: u32 },
+}
+
+/// What a dispatched instruction belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Origin {
+    /// A line of the loaded program.
+    Line(usize),
+    /// The named execution context.
+    Context(&'static str),
+}
+
+/// A named queue of instructions interleaved with the program, such as
+/// operator jogs.
+#[derive(Debug)]
+struct ExecutionContext {
+    name: &'static str,
+    priority: u8,
+    queue: VecDeque<SendPacket>,
+    /// Instructions sent and not yet answered.
+    in_flight: usize,
+    /// Error of the last rejected instruction, until taken.
+    error: Option<u32>,
+}
+
 /// Program execution state.
 #[derive(Debug, Clone, PartialEq)]
 pub enum ExecutionState {
@@ -32,17 +269,36 @@ pub enum ExecutionState {
         program_id: i64,
         total_lines: usize,
         last_completed: usize,
+        /// Program called from `last_completed`, if that line is a `CALL`.
+        subprogram: Option<String>,
     },
     /// Program is paused (no new instructions sent, waiting for in-flight to complete or resume).
     Paused {
         program_id: i64,
         total_lines: usize,
         last_completed: usize,
+        subprogram: Option<String>,
+        /// Breakpoint line the program paused before, if it was not paused
+        /// by request.
+        breakpoint: Option<usize>,
     },
     /// Stopping: draining in-flight before transitioning to Idle.
     Stopping,
     /// Completed successfully.
     Completed { program_id: i64, total_lines: usize },
+    /// Stopped with `FRC_Abort` before the last instruction completed.
+    Aborted {
+        program_id: i64,
+        total_lines: usize,
+        last_completed: usize,
+    },
+    /// Halted because the controller rejected the instruction for `line`.
+    Failed {
+        program_id: i64,
+        total_lines: usize,
+        line: usize,
+        error_id: u32,
+    },
     /// Error occurred.
     Error { message: String },
 }
@@ -58,15 +314,54 @@ pub struct ProgramExecutor {
 
     /// Current execution state.
     pub state: ExecutionState,
-    /// Instructions waiting to be sent (line_number, packet).
-    pending_queue: VecDeque<(usize, SendPacket)>,
+    /// Approach move, program lines (with called programs inlined) and
+    /// retreat move.
+    steps: Vec<Step>,
+    /// Index of the first step of each frame; frame 0 is the main program,
+    /// starting after the approach move.
+    frame_starts: Vec<usize>,
+    /// Program called from each main-program `CALL` line, by line number.
+    calls: HashMap<usize, String>,
+    /// Execution cursor over `steps`.
+    flow: ControlFlow,
+    /// DIN port the cursor is blocked on, if any.
+    awaiting_din: Option<u16>,
+    /// Whether the read for `awaiting_din` still has to be issued.
+    din_request_pending: bool,
+    /// Value supplied for `awaiting_din`, consumed by the next advance.
+    din_value: Option<bool>,
     /// Instructions sent but not yet completed: request_id -> line_number.
     /// Updated to sequence_id -> line_number when SentInstructionInfo arrives.
     in_flight_by_request: HashMap<u64, usize>,
     /// Sequence ID to line number mapping (populated when instruction is actually sent).
-    in_flight_by_sequence: HashMap<u32, usize>,
-    /// Highest completed line number.
+    in_flight_by_sequence: BTreeMap<u32, usize>,
+    /// Completed instructions still behind an earlier in-flight one, as
+    /// sequence_id -> line_number.
+    completed_ahead: BTreeMap<u32, usize>,
+    /// Responses that arrived before their sequence ID was mapped, as
+    /// sequence_id -> error_id.
+    early_responses: HashMap<u32, u32>,
+    /// Line of the latest instruction completed along with every instruction
+    /// sent before it.
     completed_line: usize,
+    /// Program interrupted by [`stop`](Self::stop) as (program_id, total_lines,
+    /// last_completed), reported as `Aborted` once the abort completes.
+    stopped_program: Option<(i64, usize, usize)>,
+    /// Programs started since the executor was created.
+    runs_started: u64,
+    /// Execution contexts besides the program, highest priority first.
+    contexts: Vec<ExecutionContext>,
+    /// Context instructions sent but not yet mapped: request_id -> context.
+    context_by_request: HashMap<u64, &'static str>,
+    /// Context instructions in flight: sequence_id -> context.
+    context_by_sequence: HashMap<u32, &'static str>,
+    /// Woken whenever a context gains an instruction to dispatch.
+    dispatch_wakeup: Arc<Notify>,
+    /// Lines to pause before, by program id. Kept across loads.
+    breakpoints: HashMap<i64, BTreeSet<usize>>,
+    /// Step the program paused before at a breakpoint; it is sent without
+    /// pausing again once execution resumes.
+    passed_breakpoint: Option<usize>,
 }
 
 impl ProgramExecutor {
@@ -77,13 +372,145 @@ impl ProgramExecutor {
             all_instructions: Vec::new(),
             defaults: ProgramDefaults::default(),
             state: ExecutionState::Idle,
-            pending_queue: VecDeque::new(),
+            steps: Vec::new(),
+            frame_starts: Vec::new(),
+            calls: HashMap::new(),
+            flow: ControlFlow::default(),
+            awaiting_din: None,
+            din_request_pending: false,
+            din_value: None,
             in_flight_by_request: HashMap::new(),
-            in_flight_by_sequence: HashMap::new(),
+            in_flight_by_sequence: BTreeMap::new(),
+            completed_ahead: BTreeMap::new(),
+            early_responses: HashMap::new(),
             completed_line: 0,
+            stopped_program: None,
+            runs_started: 0,
+            contexts: Vec::new(),
+            context_by_request: HashMap::new(),
+            context_by_sequence: HashMap::new(),
+            dispatch_wakeup: Arc::new(Notify::new()),
+            breakpoints: HashMap::new(),
+            passed_breakpoint: None,
+        }
+    }
+
+    /// Add an execution context whose instructions interleave with the
+    /// program's by `priority` (see [`PROGRAM_PRIORITY`]).
+    ///
+    /// Ties between contexts go to the one added first; ties with the
+    /// program go to the program.
+    pub fn add_context(&mut self, name: &'static str, priority: u8) -> Result<(), String> {
+        if self.has_context(name) {
+            return Err(format!("Execution context '{}' already exists", name));
+        }
+        if self.contexts.len() >= MAX_CONTEXTS {
+            return Err(format!("At most {} execution contexts are allowed", MAX_CONTEXTS));
+        }
+        let index = self.contexts.partition_point(|context| context.priority >= priority);
+        self.contexts.insert(
+            index,
+            ExecutionContext { name, priority, queue: VecDeque::new(), in_flight: 0, error: None },
+        );
+        Ok(())
+    }
+
+    /// Whether an execution context named `name` was added.
+    pub fn has_context(&self, name: &str) -> bool {
+        self.contexts.iter().any(|context| context.name == name)
+    }
+
+    /// Queue an instruction in `context`, to be dispatched by the next
+    /// [`next_dispatch`](Self::next_dispatch).
+    ///
+    /// Fails unless a program is running or paused (send the instruction
+    /// directly then), for an unknown context, for a packet that is not an
+    /// instruction, and for a context whose last instruction was rejected
+    /// and whose error has not been taken with
+    /// [`take_context_error`](Self::take_context_error).
+    pub fn queue_in_context(&mut self, context: &str, packet: SendPacket) -> Result<(), String> {
+        if !self.is_streaming() {
+            return Err("No program is running".to_string());
+        }
+        if !matches!(packet, SendPacket::Instruction(_)) {
+            return Err("Only instructions can be queued in an execution context".to_string());
+        }
+        let context = self.context_mut(context)?;
+        if let Some(error_id) = context.error {
+            return Err(format!("Execution context '{}' stopped on error {}", context.name, error_id));
+        }
+        context.queue.push_back(packet);
+        self.dispatch_wakeup.notify_one();
+        Ok(())
+    }
+
+    /// Instructions of `context` queued or in flight.
+    pub fn context_backlog(&self, context: &str) -> usize {
+        self.contexts
+            .iter()
+            .find(|c| c.name == context)
+            .map_or(0, |c| c.queue.len() + c.in_flight)
+    }
+
+    /// Drop the instructions of `context` not yet dispatched. Those already
+    /// sent still complete.
+    pub fn clear_context(&mut self, context: &str) {
+        if let Ok(context) = self.context_mut(context) {
+            context.queue.clear();
         }
     }
 
+    /// Error of the last rejected instruction of `context`, clearing it so
+    /// the context accepts instructions again.
+    pub fn take_context_error(&mut self, context: &str) -> Option<u32> {
+        self.context_mut(context).ok()?.error.take()
+    }
+
+    /// Notified whenever a context gains an instruction to dispatch.
+    pub fn dispatch_wakeup(&self) -> Arc<Notify> {
+        Arc::clone(&self.dispatch_wakeup)
+    }
+
+    /// Take up to `limit` queued context instructions, highest priority
+    /// first, from the contexts whose priority passes `include`.
+    fn take_from_contexts(&mut self, limit: usize, include: impl Fn(u8) -> bool) -> Vec<(Origin, SendPacket)> {
+        let mut taken = Vec::new();
+        for context in self.contexts.iter_mut().filter(|context| include(context.priority)) {
+            while taken.len() < limit {
+                let Some(packet) = context.queue.pop_front() else { break };
+                taken.push((Origin::Context(context.name), packet));
+            }
+        }
+        taken
+    }
+
+    /// Instruction slots left on the controller for the program and every
+    /// context together.
+    fn free_slots(&self) -> usize {
+        let contexts: usize = self.contexts.iter().map(|context| context.in_flight).sum();
+        MAX_BUFFER.saturating_sub(self.in_flight_by_sequence.len() + contexts)
+    }
+
+    /// Resolve a context instruction's response, dropping the rest of the
+    /// context's queue if it was rejected.
+    fn finish_context_instruction(&mut self, name: &'static str, error_id: u32) -> LineOutcome {
+        if let Ok(context) = self.context_mut(name) {
+            context.in_flight = context.in_flight.saturating_sub(1);
+            if error_id != 0 {
+                context.queue.clear();
+                context.error = Some(error_id);
+            }
+        }
+        LineOutcome::Context { context: name, error_id }
+    }
+
+    fn context_mut(&mut self, name: &str) -> Result<&mut ExecutionContext, String> {
+        self.contexts
+            .iter_mut()
+            .find(|context| context.name == name)
+            .ok_or_else(|| format!("Unknown execution context '{}'", name))
+    }
+
     /// Load a program from the database and prepare for execution.
     ///
     /// # Arguments
@@ -91,6 +518,11 @@ impl ProgramExecutor {
     /// * `program_id` - ID of the program to load
     /// * `active_config` - Optional active configuration for arm configuration (front, up, left, flip, turn4, turn5, turn6)
     /// * `default_speed_type` - Default speed type from robot connection (mmSec, InchMin, Time, mSec)
+    ///
+    /// `CALL` lines are resolved against the database and the called program's
+    /// lines inlined, up to [`MAX_CALL_DEPTH`] levels deep. Called programs run
+    /// with this program's defaults; their approach and retreat moves are not
+    /// used.
     pub fn load_program(
         &mut self,
         db: &Database,
@@ -141,9 +573,11 @@ impl ProgramExecutor {
             turn6: active_config.map(|c| c.turn6),
         };
 
-        // Build pending queue with all instructions
+        // Build the step list with all instructions
         let total = instructions.len();
-        self.pending_queue.clear();
+        self.steps.clear();
+        self.frame_starts.clear();
+        self.calls.clear();
 
         // Add approach move (start position) if defined
         // Line 0 is used for approach move so program instructions start at line 1
@@ -155,7 +589,7 @@ impl ProgramExecutor {
                 0, // Line 0 for approach
                 false, // Not last instruction - use CNT
             );
-            self.pending_queue.push_back((0, approach_packet));
+            self.steps.push(Step { line: 0, frame: 0, kind: ProgramStep::Motion(approach_packet) });
             info!("Added approach move to ({:.2}, {:.2}, {:.2}, {:.2}, {:.2}, {:.2}) at speed {:.0}",
                   start_x, start_y, start_z,
                   program.start_w.unwrap_or(program.default_w),
@@ -166,13 +600,13 @@ impl ProgramExecutor {
 
         // Add program instructions (lines 1 through N)
         let has_retreat = program.end_x.is_some() && program.end_y.is_some() && program.end_z.is_some();
-        for (i, instr) in instructions.iter().enumerate() {
-            let line_number = i + 1;
-            // If there's a retreat move, the last program instruction is NOT the last overall
-            let is_last_overall = !has_retreat && (i == total - 1);
-            let packet = self.build_motion_packet(instr, is_last_overall);
-            self.pending_queue.push_back((line_number, packet));
-        }
+        // If there's a retreat move, the last program instruction is NOT the last overall
+        let last_motion = instructions
+            .iter()
+            .rposition(|instr| instr.control.is_none())
+            .filter(|_| !has_retreat);
+        self.push_frame(db, &instructions, None, last_motion, &mut vec![program.name.clone()])?;
+        validate_labels(&self.steps)?;
 
         // Add retreat move (end position) if defined
         // Use line total+1 for retreat move
@@ -184,7 +618,7 @@ impl ProgramExecutor {
                 total + 1, // Line after last instruction
                 true, // Last instruction - use FINE
             );
-            self.pending_queue.push_back((total + 1, retreat_packet));
+            self.steps.push(Step { line: total + 1, frame: 0, kind: ProgramStep::Motion(retreat_packet) });
             info!("Added retreat move to ({:.2}, {:.2}, {:.2}, {:.2}, {:.2}, {:.2}) at speed {:.0}",
                   end_x, end_y, end_z,
                   program.end_w.unwrap_or(program.default_w),
@@ -203,10 +637,69 @@ impl ProgramExecutor {
             program_id,
             total_lines: total_with_extras,
         };
-        self.in_flight_by_request.clear();
-        self.in_flight_by_sequence.clear();
+        self.clear_tracking();
         self.completed_line = 0;
+        self.reset_flow();
+
+        Ok(())
+    }
+
+    /// Append `instructions` as a new frame, inlining the programs they call.
+    ///
+    /// `call_line` is the main-program line of the outermost `CALL` (`None`
+    /// for the main program itself) and `callers` the names of the programs
+    /// on the call stack, innermost last. `last_motion` is the index of the
+    /// instruction that ends the whole program with FINE, if it is in this
+    /// frame.
+    fn push_frame(
+        &mut self,
+        db: &Database,
+        instructions: &[ProgramInstruction],
+        call_line: Option<usize>,
+        last_motion: Option<usize>,
+        callers: &mut Vec<String>,
+    ) -> Result<(), String> {
+        let frame = self.frame_starts.len();
+        self.frame_starts.push(self.steps.len());
+        let at = |line_number: usize, callers: &[String]| match call_line {
+            None => format!("Line {}", line_number),
+            Some(_) => format!("{} line {}", callers.last().map(String::as_str).unwrap_or_default(), line_number),
+        };
+
+        for (i, instr) in instructions.iter().enumerate() {
+            let line_number = i + 1;
+            let line = call_line.unwrap_or(line_number);
+            let control = match &instr.control {
+                Some(text) => ProgramControl::parse(text).map_err(|e| format!("{}: {}", at(line_number, callers), e))?,
+                None => {
+                    let packet = self.build_motion_packet(instr, Some(i) == last_motion);
+                    self.steps.push(Step { line, frame, kind: ProgramStep::Motion(packet) });
+                    continue;
+                }
+            };
 
+            if let ProgramControl::Call(target) = &control {
+                let (name, called) = resolve_call(db, target).map_err(|e| format!("{}: {}", at(line_number, callers), e))?;
+                if callers.len() > MAX_CALL_DEPTH {
+                    return Err(format!(
+                        "{}: call depth limit of {} exceeded ({} > {})",
+                        at(line_number, callers),
+                        MAX_CALL_DEPTH,
+                        callers.join(" > "),
+                        name
+                    ));
+                }
+                if call_line.is_none() {
+                    self.calls.insert(line_number, name.clone());
+                }
+                self.steps.push(Step { line, frame, kind: ProgramStep::Control(control) });
+                callers.push(name);
+                self.push_frame(db, &called, Some(line), None, callers)?;
+                callers.pop();
+                continue;
+            }
+            self.steps.push(Step { line, frame, kind: ProgramStep::Control(control) });
+        }
         Ok(())
     }
 
@@ -214,11 +707,14 @@ impl ProgramExecutor {
     pub fn reset(&mut self) {
         self.loaded_program = None;
         self.all_instructions.clear();
-        self.pending_queue.clear();
-        self.in_flight_by_request.clear();
-        self.in_flight_by_sequence.clear();
+        self.steps.clear();
+        self.frame_starts.clear();
+        self.calls.clear();
+        self.reset_flow();
+        self.clear_tracking();
         self.state = ExecutionState::Idle;
         self.completed_line = 0;
+        self.stopped_program = None;
     }
 
     /// Get the current execution state.
@@ -231,6 +727,32 @@ impl ProgramExecutor {
         self.loaded_program.as_ref()
     }
 
+    /// Compare the loaded program's lines against `active`, returning a
+    /// warning for each explicit frame or tool number that differs.
+    ///
+    /// Lines without their own frame or tool inherit the defaults and are
+    /// not reported.
+    pub fn configuration_warnings(&self, active: &crate::ActiveConfiguration) -> Vec<ConfigurationWarning> {
+        let mut warnings = Vec::new();
+        for instr in &self.all_instructions {
+            let fields = [
+                (ConfigurationField::UFrame, instr.uframe, active.u_frame_number),
+                (ConfigurationField::UTool, instr.utool, active.u_tool_number),
+            ];
+            for (field, program_value, active_value) in fields {
+                if let Some(program_value) = program_value.filter(|&v| v != active_value) {
+                    warnings.push(ConfigurationWarning {
+                        line_number: instr.line_number,
+                        field,
+                        program_value,
+                        active_value,
+                    });
+                }
+            }
+        }
+        warnings
+    }
+
     /// Get the total number of instructions (including approach/retreat moves).
     pub fn total_instructions(&self) -> usize {
         match &self.state {
@@ -238,11 +760,28 @@ impl ProgramExecutor {
             ExecutionState::Running { total_lines, .. } => *total_lines,
             ExecutionState::Paused { total_lines, .. } => *total_lines,
             ExecutionState::Completed { total_lines, .. } => *total_lines,
+            ExecutionState::Aborted { total_lines, .. } => *total_lines,
+            ExecutionState::Failed { total_lines, .. } => *total_lines,
             _ => self.all_instructions.len(),
         }
     }
 
-    /// Get the highest completed line number.
+    /// Estimate the run time of the loaded program and count its motion segments
+    /// (including approach/retreat moves). See [`estimate_duration`].
+    ///
+    /// Loops are unrolled; the walk stops at the first conditional jump, since
+    /// its outcome depends on the robot's inputs at run time.
+    pub fn estimate(&self) -> (Duration, usize) {
+        let mut flow = self.flow.clone();
+        let mut packets = Vec::new();
+        while let Ok(FlowStep::Motion(_, packet)) = self.advance(&mut flow, &mut None) {
+            packets.push(packet);
+        }
+        (estimate_duration(&packets), packets.len())
+    }
+
+    /// Get the line of the latest instruction completed along with every
+    /// instruction sent before it.
     pub fn completed_line(&self) -> usize {
         self.completed_line
     }
@@ -254,103 +793,393 @@ impl ProgramExecutor {
 
     /// Check if there are more instructions to send.
     pub fn has_pending(&self) -> bool {
-        !self.pending_queue.is_empty()
+        self.awaiting_din.is_some()
+            || !matches!(self.advance(&mut self.flow.clone(), &mut None), Ok(FlowStep::End))
+    }
+
+    /// DIN port to read before execution can continue past a conditional jump.
+    ///
+    /// Returns the port once per jump; the caller reads it and passes the
+    /// value to [`ProgramExecutor::provide_din`].
+    pub fn take_din_request(&mut self) -> Option<u16> {
+        if self.din_request_pending {
+            self.din_request_pending = false;
+            self.awaiting_din
+        } else {
+            None
+        }
+    }
+
+    /// Supply the value of the DIN port the program is waiting on.
+    ///
+    /// Returns `false` (and ignores the value) if the program is not waiting
+    /// on `port`.
+    pub fn provide_din(&mut self, port: u16, value: bool) -> bool {
+        if self.awaiting_din != Some(port) {
+            return false;
+        }
+        self.awaiting_din = None;
+        self.din_value = Some(value);
+        true
     }
 
     /// Start execution (transition from Loaded to Running).
     pub fn start(&mut self) {
         if let ExecutionState::Loaded { program_id, total_lines } = self.state {
+            self.runs_started += 1;
+            // Context instructions left from the last run have no task
+            // tracking them any more.
+            self.clear_context_tracking();
             self.state = ExecutionState::Running {
                 program_id,
                 total_lines,
                 last_completed: 0,
+                subprogram: None,
             };
         }
     }
 
+    /// Programs started since the executor was created.
+    pub fn runs_started(&self) -> u64 {
+        self.runs_started
+    }
+
     /// Pause execution (stop sending new instructions).
     pub fn pause(&mut self) {
-        if let ExecutionState::Running { program_id, total_lines, last_completed } = self.state {
+        self.pause_before(None);
+    }
+
+    /// Pause execution, before `breakpoint` if the pause is a breakpoint's.
+    fn pause_before(&mut self, breakpoint: Option<usize>) {
+        if let ExecutionState::Running { program_id, total_lines, last_completed, ref mut subprogram } = self.state {
             self.state = ExecutionState::Paused {
                 program_id,
                 total_lines,
                 last_completed,
+                subprogram: subprogram.take(),
+                breakpoint,
             };
         }
     }
 
+    /// Pause the program `program_id` before sending `line`, whenever it
+    /// runs. Lines already sent are not affected.
+    pub fn set_breakpoint(&mut self, program_id: i64, line: usize) {
+        self.breakpoints.entry(program_id).or_default().insert(line);
+    }
+
+    /// Remove a breakpoint, returning whether it was set.
+    pub fn clear_breakpoint(&mut self, program_id: i64, line: usize) -> bool {
+        let Some(lines) = self.breakpoints.get_mut(&program_id) else {
+            return false;
+        };
+        let removed = lines.remove(&line);
+        if lines.is_empty() {
+            self.breakpoints.remove(&program_id);
+        }
+        removed
+    }
+
+    /// Breakpoint lines of `program_id`, in order.
+    pub fn breakpoints(&self, program_id: i64) -> Vec<usize> {
+        self.breakpoints.get(&program_id).map(|lines| lines.iter().copied().collect()).unwrap_or_default()
+    }
+
+    /// Whether the motion at `step` starts a breakpoint line of the running
+    /// program. A `CALL` line sends several motions and only pauses before
+    /// the first.
+    fn breaks_before(&self, step: usize) -> bool {
+        let ExecutionState::Running { program_id, .. } = self.state else {
+            return false;
+        };
+        let line = self.steps[step].line;
+        self.breakpoints.get(&program_id).is_some_and(|lines| lines.contains(&line))
+            && self.steps[..step]
+                .iter()
+                .rev()
+                .take_while(|earlier| earlier.line == line)
+                .all(|earlier| !matches!(earlier.kind, ProgramStep::Motion(_)))
+    }
+
     /// Resume execution (continue sending instructions).
     pub fn resume(&mut self) {
-        if let ExecutionState::Paused { program_id, total_lines, last_completed } = self.state {
+        if let ExecutionState::Paused { program_id, total_lines, last_completed, ref mut subprogram, .. } = self.state {
             self.state = ExecutionState::Running {
                 program_id,
                 total_lines,
                 last_completed,
+                subprogram: subprogram.take(),
             };
         }
     }
 
-    /// Stop execution (clear queues, transition to Stopping then Idle).
+    /// Stop execution (clear queues, transition to Stopping, then Aborted or
+    /// Idle once [`clear_in_flight`](Self::clear_in_flight) is called).
     pub fn stop(&mut self) {
-        self.pending_queue.clear();
+        self.flow.pc = self.steps.len();
+        self.awaiting_din = None;
+        self.din_request_pending = false;
+        if let ExecutionState::Running { program_id, total_lines, last_completed, .. }
+        | ExecutionState::Paused { program_id, total_lines, last_completed, .. } = self.state
+        {
+            self.stopped_program = Some((program_id, total_lines, last_completed));
+        }
         self.state = ExecutionState::Stopping;
     }
 
     /// Clear in-flight tracking (called after abort completes).
+    ///
+    /// A program that was running or paused when [`stop`](Self::stop) was
+    /// called ends `Aborted`; otherwise the executor returns to `Idle`.
     pub fn clear_in_flight(&mut self) {
-        self.in_flight_by_request.clear();
-        self.in_flight_by_sequence.clear();
-        self.state = ExecutionState::Idle;
+        self.clear_tracking();
+        self.state = match self.stopped_program.take() {
+            Some((program_id, total_lines, last_completed)) => {
+                ExecutionState::Aborted { program_id, total_lines, last_completed }
+            }
+            None => ExecutionState::Idle,
+        };
     }
 
-    /// Get the next batch of instructions to send (up to MAX_BUFFER - in_flight).
-    /// Returns Vec of (line_number, packet, request_id placeholder).
-    pub fn get_next_batch(&mut self) -> Vec<(usize, SendPacket)> {
-        let can_send = MAX_BUFFER.saturating_sub(self.in_flight_by_sequence.len());
+    /// Get the next instructions to send from the program and every
+    /// execution context, in priority order.
+    ///
+    /// Program lines and context instructions share the MAX_BUFFER slots, and
+    /// the driver numbers them in the order they are sent. Contexts above
+    /// [`PROGRAM_PRIORITY`] go ahead of the program's next lines; the rest
+    /// fill whatever the program leaves. Program lines are only taken while
+    /// running, context instructions also while paused.
+    pub fn next_dispatch(&mut self) -> Vec<(Origin, SendPacket)> {
+        if !self.is_streaming() {
+            return Vec::new();
+        }
+        let free = self.free_slots();
+        let mut dispatch = self.take_from_contexts(free, |priority| priority > PROGRAM_PRIORITY);
+        if self.is_running() {
+            let lines = self.next_lines(free - dispatch.len());
+            dispatch.extend(lines.into_iter().map(|(line, packet)| (Origin::Line(line), packet)));
+        }
+        let rest = free - dispatch.len();
+        dispatch.extend(self.take_from_contexts(rest, |priority| priority <= PROGRAM_PRIORITY));
+        dispatch
+    }
+
+    /// Get up to `can_send` program lines to send, as (line_number, packet).
+    ///
+    /// Control lines are resolved while filling the batch. The batch stops
+    /// early at a conditional jump whose DIN value is not yet known (see
+    /// [`ProgramExecutor::take_din_request`]), and at a breakpoint line,
+    /// where execution pauses. The executor moves to
+    /// [`ExecutionState::Error`] if the loop guard trips.
+    fn next_lines(&mut self, can_send: usize) -> Vec<(usize, SendPacket)> {
         let mut batch = Vec::new();
+        let mut flow = std::mem::take(&mut self.flow);
+        let mut din_value = self.din_value.take();
 
-        for _ in 0..can_send {
-            if let Some((line, packet)) = self.pending_queue.pop_front() {
-                batch.push((line, packet));
-            } else {
-                break;
+        while batch.len() < can_send && self.awaiting_din.is_none() {
+            let before = (flow.clone(), din_value);
+            match self.advance(&mut flow, &mut din_value) {
+                Ok(FlowStep::Motion(line, packet)) => {
+                    let step = flow.pc - 1;
+                    if self.passed_breakpoint.take() != Some(step) && self.breaks_before(step) {
+                        // Resuming advances to this motion again
+                        (flow, din_value) = before;
+                        self.passed_breakpoint = Some(step);
+                        self.pause_before(Some(line));
+                        info!("Paused at breakpoint before line {}", line);
+                        break;
+                    }
+                    batch.push((line, packet));
+                }
+                Ok(FlowStep::NeedDin(port)) => {
+                    self.awaiting_din = Some(port);
+                    self.din_request_pending = true;
+                }
+                Ok(FlowStep::End) => break,
+                Err(message) => {
+                    self.state = ExecutionState::Error { message };
+                    break;
+                }
             }
         }
 
+        self.flow = flow;
+        self.din_value = din_value;
         batch
     }
 
+    /// Advance `flow` to the next motion, resolving control lines on the way.
+    ///
+    /// `din` is the value for a pending conditional jump; it is consumed by
+    /// that jump.
+    fn advance(&self, flow: &mut ControlFlow, din: &mut Option<bool>) -> Result<FlowStep, String> {
+        loop {
+            let Some(Step { line, frame, kind }) = self.steps.get(flow.pc) else {
+                return Ok(FlowStep::End);
+            };
+            let control = match kind {
+                ProgramStep::Motion(packet) => {
+                    flow.pc += 1;
+                    return Ok(FlowStep::Motion(*line, packet.clone()));
+                }
+                ProgramStep::Control(control) => control,
+            };
+
+            match control {
+                // The called program's steps follow the CALL step.
+                ProgramControl::Label(_) | ProgramControl::Call(_) => flow.pc += 1,
+                ProgramControl::Jump(label) => self.jump(flow, *line, *label)?,
+                ProgramControl::JumpIfDin { port, value, label } => match din.take() {
+                    None => return Ok(FlowStep::NeedDin(*port)),
+                    Some(din) if din == *value => self.jump(flow, *line, *label)?,
+                    Some(_) => flow.pc += 1,
+                },
+                ProgramControl::Repeat(count) => {
+                    let passes = flow.repeats.entry(flow.pc).or_insert(1);
+                    if *passes < *count {
+                        *passes += 1;
+                        let start = self.steps[..flow.pc]
+                            .iter()
+                            .rposition(|step| {
+                                step.frame == *frame
+                                    && matches!(step.kind, ProgramStep::Control(ProgramControl::Label(_)))
+                            })
+                            .unwrap_or(self.frame_starts[*frame]);
+                        count_iteration(flow, *line)?;
+                        flow.pc = start;
+                    } else {
+                        // Reset so an enclosing loop can run this block again.
+                        flow.repeats.remove(&flow.pc);
+                        flow.pc += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Move `flow` to `label` in the current frame, counting backward jumps
+    /// against the loop guard.
+    fn jump(&self, flow: &mut ControlFlow, line: usize, label: u32) -> Result<(), String> {
+        let frame = self.steps[flow.pc].frame;
+        let target = self.steps
+            .iter()
+            .position(|step| {
+                step.frame == frame
+                    && matches!(step.kind, ProgramStep::Control(ProgramControl::Label(l)) if l == label)
+            })
+            .ok_or_else(|| format!("Line {}: unknown label LBL[{}]", line, label))?;
+        if target <= flow.pc {
+            count_iteration(flow, line)?;
+        }
+        flow.pc = target;
+        Ok(())
+    }
+
+    fn reset_flow(&mut self) {
+        self.flow = ControlFlow::default();
+        self.passed_breakpoint = None;
+        self.awaiting_din = None;
+        self.din_request_pending = false;
+        self.din_value = None;
+    }
+
     /// Record that an instruction was sent (by request_id).
     pub fn record_sent(&mut self, request_id: u64, line_number: usize) {
         self.in_flight_by_request.insert(request_id, line_number);
     }
 
+    /// Record that an instruction from [`next_dispatch`](Self::next_dispatch)
+    /// was sent (by request_id).
+    pub fn record_dispatched(&mut self, request_id: u64, origin: Origin) {
+        match origin {
+            Origin::Line(line_number) => self.record_sent(request_id, line_number),
+            Origin::Context(name) => {
+                if let Ok(context) = self.context_mut(name) {
+                    context.in_flight += 1;
+                    self.context_by_request.insert(request_id, name);
+                }
+            }
+        }
+    }
+
     /// Map request_id to sequence_id when SentInstructionInfo arrives.
-    pub fn map_sequence(&mut self, request_id: u64, sequence_id: u32) {
-        if let Some(line) = self.in_flight_by_request.remove(&request_id) {
+    ///
+    /// If the instruction's response already arrived, it is applied now and
+    /// its outcome returned.
+    pub fn map_sequence(&mut self, request_id: u64, sequence_id: u32) -> Option<LineOutcome> {
+        if let Some(context) = self.context_by_request.remove(&request_id) {
+            self.context_by_sequence.insert(sequence_id, context);
+        } else {
+            let line = self.in_flight_by_request.remove(&request_id)?;
             self.in_flight_by_sequence.insert(sequence_id, line);
         }
+        let error_id = self.early_responses.remove(&sequence_id)?;
+        self.handle_response(sequence_id, error_id)
+    }
+
+    /// Handle an instruction response by sequence_id.
+    ///
+    /// Returns the line the response belongs to and whether it completed or
+    /// failed. A failure halts the program (see [`ExecutionState::Failed`]);
+    /// a context instruction's failure only stops its context.
+    /// A response for an instruction whose sequence_id is not mapped yet is
+    /// held until [`map_sequence`](Self::map_sequence) maps it.
+    pub fn handle_response(&mut self, sequence_id: u32, error_id: u32) -> Option<LineOutcome> {
+        if let Some(context) = self.context_by_sequence.remove(&sequence_id) {
+            return Some(self.finish_context_instruction(context, error_id));
+        }
+        if !self.in_flight_by_sequence.contains_key(&sequence_id) {
+            if !self.in_flight_by_request.is_empty() || !self.context_by_request.is_empty() {
+                self.early_responses.insert(sequence_id, error_id);
+            }
+            return None;
+        }
+        if error_id == 0 {
+            return self.handle_completion(sequence_id).map(LineOutcome::Completed);
+        }
+
+        let line = self.in_flight_by_sequence.remove(&sequence_id)?;
+        if let ExecutionState::Running { program_id, total_lines, .. }
+        | ExecutionState::Paused { program_id, total_lines, .. } = self.state
+        {
+            self.state = ExecutionState::Failed { program_id, total_lines, line, error_id };
+        }
+        self.flow.pc = self.steps.len();
+        self.awaiting_din = None;
+        self.din_request_pending = false;
+        self.clear_tracking();
+        Some(LineOutcome::Failed { line, error_id })
     }
 
     /// Handle instruction completion by sequence_id.
     /// Returns the line number if found, and updates state.
     pub fn handle_completion(&mut self, sequence_id: u32) -> Option<usize> {
         if let Some(line) = self.in_flight_by_sequence.remove(&sequence_id) {
-            self.completed_line = self.completed_line.max(line);
+            // Responses can overtake each other, so progress only moves past
+            // instructions whose predecessors have all completed too. Loops
+            // revisit earlier lines, so this is the latest line rather than
+            // the highest.
+            self.completed_ahead.insert(sequence_id, line);
+            let oldest_in_flight = self.in_flight_by_sequence.keys().next().copied();
+            while let Some(entry) = self.completed_ahead.first_entry() {
+                if oldest_in_flight.is_some_and(|oldest| *entry.key() > oldest) {
+                    break;
+                }
+                self.completed_line = entry.remove();
+            }
 
             // Update state with new completed line
             match &mut self.state {
-                ExecutionState::Running { last_completed, .. } => {
-                    *last_completed = self.completed_line;
-                }
-                ExecutionState::Paused { last_completed, .. } => {
+                ExecutionState::Running { last_completed, subprogram, .. }
+                | ExecutionState::Paused { last_completed, subprogram, .. } => {
                     *last_completed = self.completed_line;
+                    *subprogram = self.calls.get(&self.completed_line).cloned();
                 }
                 _ => {}
             }
 
             // Check for completion
-            if self.pending_queue.is_empty() && self.in_flight_by_sequence.is_empty() {
+            if self.in_flight_by_sequence.is_empty() && !self.has_pending() {
                 if let ExecutionState::Running { program_id, total_lines, .. } = self.state {
                     self.state = ExecutionState::Completed { program_id, total_lines };
                 }
@@ -362,6 +1191,26 @@ impl ProgramExecutor {
         }
     }
 
+    /// Forget every sent instruction and held response.
+    fn clear_tracking(&mut self) {
+        self.in_flight_by_request.clear();
+        self.in_flight_by_sequence.clear();
+        self.completed_ahead.clear();
+        self.early_responses.clear();
+        self.clear_context_tracking();
+    }
+
+    /// Forget every queued and sent context instruction.
+    fn clear_context_tracking(&mut self) {
+        self.context_by_request.clear();
+        self.context_by_sequence.clear();
+        for context in &mut self.contexts {
+            context.queue.clear();
+            context.in_flight = 0;
+            context.error = None;
+        }
+    }
+
     /// Check if execution is complete.
     pub fn is_complete(&self) -> bool {
         matches!(self.state, ExecutionState::Completed { .. })
@@ -372,10 +1221,18 @@ impl ProgramExecutor {
         matches!(self.state, ExecutionState::Running { .. })
     }
 
+    /// Check if a program is running or paused, so execution contexts
+    /// dispatch through the executor.
+    pub fn is_streaming(&self) -> bool {
+        matches!(self.state, ExecutionState::Running { .. } | ExecutionState::Paused { .. })
+    }
+
     /// Get all motion packets for the loaded program (legacy method for compatibility).
     pub fn get_all_packets(&self) -> Vec<SendPacket> {
-        let total = self.all_instructions.len();
-        self.all_instructions.iter().enumerate().map(|(i, instr)| {
+        let motions: Vec<&ProgramInstruction> =
+            self.all_instructions.iter().filter(|instr| instr.control.is_none()).collect();
+        let total = motions.len();
+        motions.iter().enumerate().map(|(i, instr)| {
             self.build_motion_packet(instr, i == total - 1)
         }).collect()
     }
@@ -545,3 +1402,629 @@ impl ProgramExecutor {
     }
 }
 
+/// Count one backward jump, failing once [`MAX_LOOP_ITERATIONS`] is exceeded.
+fn count_iteration(flow: &mut ControlFlow, line: usize) -> Result<(), String> {
+    flow.iterations += 1;
+    if flow.iterations > MAX_LOOP_ITERATIONS {
+        return Err(format!(
+            "Line {}: loop limit of {} iterations exceeded",
+            line, MAX_LOOP_ITERATIONS
+        ));
+    }
+    Ok(())
+}
+
+/// Look up the program a `CALL` runs, returning its name and lines.
+fn resolve_call(db: &Database, target: &CallTarget) -> Result<(String, Vec<ProgramInstruction>), String> {
+    let program = match target {
+        CallTarget::Name(name) => db.get_program_by_name(name),
+        CallTarget::Id(id) => db.get_program(*id),
+    }
+    .map_err(|e| format!("Database error: {}", e))?
+    .ok_or_else(|| match target {
+        CallTarget::Name(name) => format!("called program '{}' not found", name),
+        CallTarget::Id(id) => format!("called program {} not found", id),
+    })?;
+    let instructions = db
+        .get_instructions(program.id)
+        .map_err(|e| format!("Failed to load instructions of '{}': {}", program.name, e))?;
+    Ok((program.name, instructions))
+}
+
+/// Reject duplicate labels and jumps to labels that do not exist, within
+/// each frame.
+fn validate_labels(steps: &[Step]) -> Result<(), String> {
+    let mut labels = HashSet::new();
+    for step in steps {
+        if let ProgramStep::Control(ProgramControl::Label(label)) = step.kind {
+            if !labels.insert((step.frame, label)) {
+                return Err(format!("Line {}: duplicate label LBL[{}]", step.line, label));
+            }
+        }
+    }
+    for step in steps {
+        if let ProgramStep::Control(ProgramControl::Jump(label) | ProgramControl::JumpIfDin { label, .. }) = step.kind {
+            if !labels.contains(&(step.frame, label)) {
+                return Err(format!("Line {}: unknown label LBL[{}]", step.line, label));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_types::ServerResponse;
+
+    fn motion(x: f64, speed_type: SpeedType, speed: f64, term_type: TermType, term_value: u8) -> SendPacket {
+        let configuration = Configuration {
+            u_tool_number: 1,
+            u_frame_number: 1,
+            front: 1,
+            up: 1,
+            left: 0,
+            flip: 0,
+            turn4: 0,
+            turn5: 0,
+            turn6: 0,
+        };
+        let position = Position { x, y: 0.0, z: 300.0, w: 0.0, p: 0.0, r: 0.0, ext1: 0.0, ext2: 0.0, ext3: 0.0 };
+        SendPacket::Instruction(Instruction::FrcLinearMotion(FrcLinearMotion::new(
+            0, configuration, position, speed_type, speed, term_type, term_value,
+        )))
+    }
+
+    /// Ten 100mm moves at 100mm/s: 9 travelling segments of 1s each.
+    fn path(term_type: TermType, term_value: u8) -> Vec<SendPacket> {
+        (0..10)
+            .map(|i| motion(i as f64 * 100.0, SpeedType::MMSec, 100.0, term_type.clone(), term_value))
+            .collect()
+    }
+
+    #[test]
+    fn test_estimate_fine_heavy_program() {
+        let estimate = estimate_duration(&path(TermType::FINE, 0));
+        let expected = 9.0 + 10.0 * FINE_STOP_SECONDS;
+        assert!((estimate.as_secs_f64() - expected).abs() < 1e-9, "got {:?}", estimate);
+    }
+
+    #[test]
+    fn test_estimate_cnt_heavy_program_is_faster() {
+        let fine = estimate_duration(&path(TermType::FINE, 0));
+        let cnt50 = estimate_duration(&path(TermType::CNT, 50));
+        let cnt100 = estimate_duration(&path(TermType::CNT, 100));
+
+        assert!(cnt50 < fine, "CNT blends must reduce stop time: {:?} vs {:?}", cnt50, fine);
+        assert!(cnt100 < cnt50);
+        // CNT100 never stops, leaving only travel time.
+        assert!((cnt100.as_secs_f64() - 9.0).abs() < 1e-9, "got {:?}", cnt100);
+    }
+
+    #[test]
+    fn test_estimate_time_based_segments_use_declared_time() {
+        let packets = vec![
+            motion(0.0, SpeedType::MMSec, 100.0, TermType::CNT, 100),
+            // 5000mm away but declared as 2.5s (Time is in 0.1s units)
+            motion(5000.0, SpeedType::Time, 25.0, TermType::CNT, 100),
+            motion(0.0, SpeedType::MilliSeconds, 750.0, TermType::CNT, 100),
+        ];
+        let estimate = estimate_duration(&packets);
+        assert!((estimate.as_secs_f64() - 3.25).abs() < 1e-9, "got {:?}", estimate);
+    }
+
+    fn motion_line(line_number: i32, x: f64) -> ProgramInstruction {
+        ProgramInstruction {
+            id: 0,
+            program_id: 0,
+            line_number,
+            x,
+            y: 0.0,
+            z: 300.0,
+            w: None,
+            p: None,
+            r: None,
+            ext1: None,
+            ext2: None,
+            ext3: None,
+            speed: Some(100.0),
+            speed_type: None,
+            term_type: None,
+            term_value: None,
+            uframe: None,
+            utool: None,
+            control: None,
+        }
+    }
+
+    fn control_line(line_number: i32, text: &str) -> ProgramInstruction {
+        ProgramInstruction {
+            control: Some(text.to_string()),
+            ..motion_line(line_number, 0.0)
+        }
+    }
+
+    fn load(lines: &[ProgramInstruction]) -> ProgramExecutor {
+        let db = Database::new(":memory:").expect("in-memory database");
+        let program_id = db.create_program("control flow", None).expect("create program");
+        for line in lines {
+            db.add_instruction(program_id, line).expect("add instruction");
+        }
+        let mut executor = ProgramExecutor::new();
+        executor.load_program(&db, program_id, None, "mmSec").expect("load program");
+        executor.start();
+        executor
+    }
+
+    /// Drive the executor like the buffered execution task: send each batch,
+    /// complete it in order, answer DIN reads from `din`. Returns the executed
+    /// line numbers.
+    fn run(executor: &mut ProgramExecutor, din: impl Fn(u16) -> bool) -> Vec<usize> {
+        let mut executed = Vec::new();
+        let mut next_id = 1;
+        while executor.is_running() {
+            let batch = executor.next_dispatch();
+            if let Some(port) = executor.take_din_request() {
+                assert!(executor.provide_din(port, din(port)));
+            }
+            for (origin, _) in batch {
+                let Origin::Line(line) = origin else { unreachable!("no contexts are added") };
+                executor.record_dispatched(next_id as u64, origin);
+                executor.map_sequence(next_id as u64, next_id);
+                assert_eq!(executor.handle_completion(next_id), Some(line));
+                executed.push(line);
+                next_id += 1;
+            }
+        }
+        executed
+    }
+
+    #[test]
+    fn test_parse_control_instructions() {
+        assert_eq!(ProgramControl::parse("LBL[3]"), Ok(ProgramControl::Label(3)));
+        assert_eq!(ProgramControl::parse("jmp lbl[3]"), Ok(ProgramControl::Jump(3)));
+        assert_eq!(ProgramControl::parse("REPEAT 4"), Ok(ProgramControl::Repeat(4)));
+        assert_eq!(
+            ProgramControl::parse("IF DIN[12]=OFF, JMP LBL[2]"),
+            Ok(ProgramControl::JumpIfDin { port: 12, value: false, label: 2 })
+        );
+        assert!(ProgramControl::parse("REPEAT 0").is_err());
+        assert!(ProgramControl::parse("IF DIN[1]=MAYBE, JMP LBL[2]").is_err());
+        assert!(ProgramControl::parse("RUN SUB").is_err());
+    }
+
+    #[test]
+    fn test_repeat_runs_block_three_times() {
+        let mut executor = load(&[
+            motion_line(1, 0.0),
+            control_line(2, "LBL[1]"),
+            motion_line(3, 100.0),
+            motion_line(4, 200.0),
+            control_line(5, "REPEAT 3"),
+            motion_line(6, 300.0),
+        ]);
+
+        // Unrolled: 1 + 3 * 2 + 1 motions.
+        assert_eq!(executor.estimate().1, 8);
+
+        let executed = run(&mut executor, |_| unreachable!("no DIN jumps"));
+        assert_eq!(executed, vec![1, 3, 4, 3, 4, 3, 4, 6]);
+        assert!(executor.is_complete());
+        assert_eq!(executor.completed_line(), 6);
+    }
+
+    #[test]
+    fn test_conditional_jump_skips_block_on_din() {
+        let program = [
+            motion_line(1, 0.0),
+            control_line(2, "IF DIN[5]=ON, JMP LBL[1]"),
+            motion_line(3, 100.0),
+            motion_line(4, 200.0),
+            control_line(5, "LBL[1]"),
+            motion_line(6, 300.0),
+        ];
+
+        let mut executor = load(&program);
+        assert_eq!(run(&mut executor, |port| { assert_eq!(port, 5); true }), vec![1, 6]);
+        assert!(executor.is_complete());
+
+        let mut executor = load(&program);
+        assert_eq!(run(&mut executor, |_| false), vec![1, 3, 4, 6]);
+        assert!(executor.is_complete());
+    }
+
+    #[test]
+    fn test_waiting_on_din_is_not_completion() {
+        let mut executor = load(&[
+            motion_line(1, 0.0),
+            control_line(2, "IF DIN[5]=ON, JMP LBL[1]"),
+            motion_line(3, 100.0),
+            control_line(4, "LBL[1]"),
+        ]);
+
+        let batch = executor.next_dispatch();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(executor.take_din_request(), Some(5));
+        assert_eq!(executor.take_din_request(), None, "read is requested once");
+        executor.record_dispatched(1, Origin::Line(1));
+        executor.map_sequence(1, 1);
+        executor.handle_completion(1);
+        assert!(executor.is_running(), "program must wait for the DIN value");
+
+        assert!(!executor.provide_din(6, true), "value for another port is ignored");
+        assert!(executor.provide_din(5, true));
+        assert!(executor.next_dispatch().is_empty());
+        assert!(!executor.has_pending());
+    }
+
+    #[test]
+    fn test_runaway_loop_is_stopped() {
+        let mut executor = load(&[
+            control_line(1, "LBL[1]"),
+            motion_line(2, 0.0),
+            control_line(3, "JMP LBL[1]"),
+        ]);
+
+        let executed = run(&mut executor, |_| unreachable!("no DIN jumps"));
+        assert_eq!(executed.len(), MAX_LOOP_ITERATIONS as usize + 1);
+        assert!(
+            matches!(executor.get_state(), ExecutionState::Error { message } if message.contains("loop limit")),
+            "got {:?}",
+            executor.get_state()
+        );
+    }
+
+    #[test]
+    fn test_unknown_label_is_rejected_at_load() {
+        let db = Database::new(":memory:").expect("in-memory database");
+        let program_id = db.create_program("bad jump", None).expect("create program");
+        db.add_instruction(program_id, &motion_line(1, 0.0)).unwrap();
+        db.add_instruction(program_id, &control_line(2, "JMP LBL[9]")).unwrap();
+
+        let err = ProgramExecutor::new()
+            .load_program(&db, program_id, None, "mmSec")
+            .unwrap_err();
+        assert_eq!(err, "Line 2: unknown label LBL[9]");
+    }
+
+    /// Only lines with their own frame or tool are compared; line 1
+    /// inherits the defaults.
+    #[test]
+    fn test_configuration_warnings_report_explicit_mismatches() {
+        let tool_3 = ProgramInstruction { utool: Some(3), ..motion_line(2, 10.0) };
+        let frame_1 = ProgramInstruction { uframe: Some(1), ..motion_line(3, 20.0) };
+        let executor = load(&[motion_line(1, 0.0), tool_3, frame_1]);
+
+        let active = crate::ActiveConfiguration::default();
+        assert_eq!(active.u_tool_number, 1);
+        assert_eq!(
+            executor.configuration_warnings(&active),
+            vec![ConfigurationWarning {
+                line_number: 2,
+                field: ConfigurationField::UTool,
+                program_value: 3,
+                active_value: 1,
+            }]
+        );
+    }
+
+    /// A database holding each `(name, lines)` program, for `CALL` tests.
+    fn programs(programs: &[(&str, Vec<ProgramInstruction>)]) -> Database {
+        let db = Database::new(":memory:").expect("in-memory database");
+        for (name, lines) in programs {
+            let program_id = db.create_program(name, None).expect("create program");
+            for line in lines {
+                db.add_instruction(program_id, line).expect("add instruction");
+            }
+        }
+        db
+    }
+
+    fn load_named(db: &Database, name: &str) -> Result<ProgramExecutor, String> {
+        let program = db.get_program_by_name(name).unwrap().expect("program exists");
+        let mut executor = ProgramExecutor::new();
+        executor.load_program(db, program.id, None, "mmSec")?;
+        executor.start();
+        Ok(executor)
+    }
+
+    fn subprogram(executor: &ProgramExecutor) -> Option<String> {
+        match crate::session::execution_state_to_response(executor.get_state()) {
+            ServerResponse::ExecutionStateChanged { subprogram, .. } => subprogram,
+            other => panic!("unexpected response {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_call() {
+        assert_eq!(
+            ProgramControl::parse("CALL PickPart"),
+            Ok(ProgramControl::Call(CallTarget::Name("PickPart".to_string())))
+        );
+        assert_eq!(ProgramControl::parse("call prog[7]"), Ok(ProgramControl::Call(CallTarget::Id(7))));
+        assert!(ProgramControl::parse("CALL").is_err());
+        assert!(ProgramControl::parse("CALL PROG[x]").is_err());
+    }
+
+    /// The called program runs in place of the CALL line, reports progress on
+    /// it, and keeps its labels to itself.
+    #[test]
+    fn test_call_runs_subprogram_on_its_line() {
+        let db = programs(&[
+            ("PICK", vec![control_line(1, "LBL[1]"), motion_line(2, 10.0), motion_line(3, 20.0)]),
+            (
+                "MAIN",
+                vec![control_line(1, "LBL[1]"), motion_line(2, 0.0), control_line(3, "CALL PICK"), motion_line(4, 30.0)],
+            ),
+        ]);
+        let mut executor = load_named(&db, "MAIN").expect("load program");
+
+        let batch = executor.next_dispatch();
+        assert_eq!(batch.iter().map(|(origin, _)| *origin).collect::<Vec<_>>(), [2, 3, 3, 4].map(Origin::Line));
+        for (id, (origin, _)) in batch.into_iter().enumerate() {
+            executor.record_dispatched(id as u64 + 1, origin);
+            executor.map_sequence(id as u64 + 1, id as u32 + 1);
+        }
+
+        executor.handle_completion(1);
+        assert_eq!(subprogram(&executor), None);
+        executor.handle_completion(2);
+        assert_eq!(subprogram(&executor), Some("PICK".to_string()));
+        executor.handle_completion(3);
+        assert_eq!(subprogram(&executor), Some("PICK".to_string()));
+        executor.handle_completion(4);
+        assert!(executor.is_complete());
+    }
+
+    #[test]
+    fn test_missing_call_target_is_rejected_at_load() {
+        let db = programs(&[("MAIN", vec![motion_line(1, 0.0), control_line(2, "CALL NOPE")])]);
+        let err = load_named(&db, "MAIN").err().expect("missing target must fail");
+        assert_eq!(err, "Line 2: called program 'NOPE' not found");
+
+        let db = programs(&[("MAIN", vec![motion_line(1, 0.0), control_line(2, "CALL PROG[99]")])]);
+        let err = load_named(&db, "MAIN").err().expect("missing target must fail");
+        assert_eq!(err, "Line 2: called program 99 not found");
+    }
+
+    #[test]
+    fn test_recursive_call_exceeds_depth_limit() {
+        let db = programs(&[
+            ("LOOPER", vec![motion_line(1, 0.0), control_line(2, "CALL LOOPER")]),
+            ("MAIN", vec![control_line(1, "CALL LOOPER")]),
+        ]);
+        let err = load_named(&db, "MAIN").err().expect("runaway recursion must fail");
+        assert!(
+            err.starts_with(&format!("LOOPER line 2: call depth limit of {} exceeded", MAX_CALL_DEPTH)),
+            "{}",
+            err
+        );
+    }
+
+    fn event(executor: &ProgramExecutor) -> web_common::ExecutionState {
+        match crate::session::execution_state_to_response(executor.get_state()) {
+            ServerResponse::ExecutionStateChanged { execution_state, state, .. } => {
+                assert_eq!(state, execution_state.as_str());
+                execution_state
+            }
+            other => panic!("unexpected response {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_two_line_program_reports_completed() {
+        let mut executor = load(&[motion_line(1, 0.0), motion_line(2, 100.0)]);
+        assert_eq!(event(&executor), web_common::ExecutionState::Running);
+
+        let batch = executor.next_dispatch();
+        assert_eq!(batch.iter().map(|(origin, _)| *origin).collect::<Vec<_>>(), [1, 2].map(Origin::Line));
+        for (id, (origin, _)) in batch.into_iter().enumerate() {
+            executor.record_dispatched(id as u64, origin);
+            executor.map_sequence(id as u64, id as u32);
+        }
+
+        executor.handle_completion(0);
+        assert_eq!(event(&executor), web_common::ExecutionState::Running, "line 2 is still in flight");
+        executor.handle_completion(1);
+        assert_eq!(event(&executor), web_common::ExecutionState::Completed);
+    }
+
+    #[test]
+    fn test_abort_reports_aborted() {
+        let mut executor = load(&[motion_line(1, 0.0), motion_line(2, 100.0)]);
+        for (id, (origin, _)) in executor.next_dispatch().into_iter().enumerate() {
+            executor.record_dispatched(id as u64, origin);
+            executor.map_sequence(id as u64, id as u32);
+        }
+        executor.handle_completion(0);
+
+        // stop_program: stop, send FRC_Abort, then clear in-flight tracking
+        executor.stop();
+        assert_eq!(event(&executor), web_common::ExecutionState::Stopping);
+        executor.clear_in_flight();
+        assert_eq!(event(&executor), web_common::ExecutionState::Aborted);
+        assert!(matches!(
+            executor.get_state(),
+            ExecutionState::Aborted { last_completed: 1, total_lines: 2, .. }
+        ));
+
+        // Stopping with nothing running is not an abort
+        executor.stop();
+        executor.clear_in_flight();
+        assert_eq!(event(&executor), web_common::ExecutionState::Idle);
+    }
+
+    /// Load a program, start it and send its first batch with sequence IDs
+    /// 1, 2, ... mapped as they were sent.
+    fn sent(lines: usize) -> ProgramExecutor {
+        let program: Vec<_> = (1..=lines).map(|line| motion_line(line as i32, line as f64 * 100.0)).collect();
+        let mut executor = load(&program);
+        for (id, (origin, _)) in executor.next_dispatch().into_iter().enumerate() {
+            executor.record_dispatched(id as u64 + 1, origin);
+            assert_eq!(executor.map_sequence(id as u64 + 1, id as u32 + 1), None);
+        }
+        executor
+    }
+
+    #[test]
+    fn test_out_of_order_responses_report_exact_progress() {
+        let mut executor = sent(3);
+
+        // Line 2 answers first; line 1 has not completed, so neither has the program up to 2
+        assert_eq!(executor.handle_response(2, 0), Some(LineOutcome::Completed(2)));
+        assert_eq!(executor.completed_line(), 0);
+        assert!(matches!(executor.get_state(), ExecutionState::Running { last_completed: 0, .. }));
+
+        assert_eq!(executor.handle_response(1, 0), Some(LineOutcome::Completed(1)));
+        assert_eq!(executor.completed_line(), 2);
+        assert!(matches!(executor.get_state(), ExecutionState::Running { last_completed: 2, .. }));
+
+        assert_eq!(executor.handle_response(3, 0), Some(LineOutcome::Completed(3)));
+        assert!(executor.is_complete());
+    }
+
+    #[test]
+    fn test_response_before_sequence_mapping_is_held() {
+        let mut executor = load(&[motion_line(1, 0.0), motion_line(2, 100.0)]);
+        let batch = executor.next_dispatch();
+        for (id, (origin, _)) in batch.iter().enumerate() {
+            executor.record_dispatched(id as u64 + 1, *origin);
+        }
+
+        // Line 1's response overtakes its SentInstructionInfo
+        assert_eq!(executor.handle_response(7, 0), None);
+        assert_eq!(executor.map_sequence(1, 7), Some(LineOutcome::Completed(1)));
+        assert_eq!(executor.map_sequence(2, 8), None);
+        assert_eq!(executor.handle_response(8, 0), Some(LineOutcome::Completed(2)));
+        assert!(executor.is_complete());
+    }
+
+    #[test]
+    fn test_instruction_error_halts_on_its_line() {
+        let mut executor = sent(5);
+        assert_eq!(executor.handle_response(1, 0), Some(LineOutcome::Completed(1)));
+        assert_eq!(
+            executor.handle_response(3, 2556964),
+            Some(LineOutcome::Failed { line: 3, error_id: 2556964 })
+        );
+        assert!(matches!(
+            executor.get_state(),
+            ExecutionState::Failed { line: 3, error_id: 2556964, total_lines: 5, .. }
+        ));
+        assert!(!executor.has_pending(), "nothing more is sent after a failure");
+        assert!(executor.next_dispatch().is_empty());
+        // Late responses for lines still in flight are ignored
+        assert_eq!(executor.handle_response(2, 0), None);
+
+        match crate::session::execution_state_to_response(executor.get_state()) {
+            ServerResponse::ExecutionStateChanged { execution_state, current_line, message, .. } => {
+                assert_eq!(execution_state, web_common::ExecutionState::Error);
+                assert_eq!(current_line, Some(3));
+                assert!(message.unwrap().contains("2556964"));
+            }
+            other => panic!("unexpected response {:?}", other),
+        }
+    }
+
+    /// A context like the jog module's, above the program.
+    const JOG_CONTEXT: &str = "jog";
+    const JOG_PRIORITY: u8 = 200;
+
+    fn jog_move() -> SendPacket {
+        motion(1.0, SpeedType::MMSec, 50.0, TermType::CNT, 100)
+    }
+
+    /// Send everything [`ProgramExecutor::next_dispatch`] returns with
+    /// sequence IDs from `next_id`, returning what was sent.
+    fn dispatch(executor: &mut ProgramExecutor, next_id: &mut u32) -> Vec<Origin> {
+        let mut sent = Vec::new();
+        for (origin, _) in executor.next_dispatch() {
+            executor.record_dispatched(*next_id as u64, origin);
+            assert_eq!(executor.map_sequence(*next_id as u64, *next_id), None);
+            sent.push(origin);
+            *next_id += 1;
+        }
+        sent
+    }
+
+    #[test]
+    fn test_jog_goes_ahead_of_program_lines_within_the_buffer() {
+        let program: Vec<_> = (1..=8).map(|line| motion_line(line, line as f64 * 100.0)).collect();
+        let mut executor = load(&program);
+        executor.add_context(JOG_CONTEXT, JOG_PRIORITY).unwrap();
+        let mut next_id = 1;
+        executor.queue_in_context(JOG_CONTEXT, jog_move()).unwrap();
+
+        let jog = Origin::Context(JOG_CONTEXT);
+        let lines: Vec<_> = (1..MAX_BUFFER).map(Origin::Line).collect();
+        assert_eq!(dispatch(&mut executor, &mut next_id), [vec![jog], lines].concat());
+
+        // The buffer is full, so the next jog waits for a free slot
+        executor.queue_in_context(JOG_CONTEXT, jog_move()).unwrap();
+        assert!(dispatch(&mut executor, &mut next_id).is_empty());
+        assert_eq!(executor.context_backlog(JOG_CONTEXT), 2);
+
+        // A completed program line frees a slot, which the jog takes first
+        assert_eq!(executor.handle_response(2, 0), Some(LineOutcome::Completed(1)));
+        assert_eq!(dispatch(&mut executor, &mut next_id), vec![jog]);
+        assert_eq!(
+            executor.handle_response(1, 0),
+            Some(LineOutcome::Context { context: JOG_CONTEXT, error_id: 0 })
+        );
+        assert_eq!(dispatch(&mut executor, &mut next_id), vec![Origin::Line(MAX_BUFFER)]);
+        assert_eq!(executor.context_backlog(JOG_CONTEXT), 1);
+    }
+
+    #[test]
+    fn test_rejected_context_instruction_only_stops_its_context() {
+        let mut executor = sent(3);
+        executor.add_context(JOG_CONTEXT, JOG_PRIORITY).unwrap();
+        let mut next_id = 4;
+        executor.queue_in_context(JOG_CONTEXT, jog_move()).unwrap();
+        assert_eq!(dispatch(&mut executor, &mut next_id), vec![Origin::Context(JOG_CONTEXT)]);
+        executor.queue_in_context(JOG_CONTEXT, jog_move()).unwrap();
+
+        assert_eq!(
+            executor.handle_response(4, 2556964),
+            Some(LineOutcome::Context { context: JOG_CONTEXT, error_id: 2556964 })
+        );
+        assert!(executor.is_running(), "the program keeps running");
+        assert_eq!(executor.context_backlog(JOG_CONTEXT), 0, "queued moves are dropped");
+        assert!(executor.queue_in_context(JOG_CONTEXT, jog_move()).is_err());
+        assert_eq!(executor.take_context_error(JOG_CONTEXT), Some(2556964));
+        assert!(executor.queue_in_context(JOG_CONTEXT, jog_move()).is_ok());
+
+        for sequence_id in 1..=3 {
+            assert_eq!(executor.handle_response(sequence_id, 0), Some(LineOutcome::Completed(sequence_id as usize)));
+        }
+        assert!(executor.is_complete());
+    }
+
+    #[test]
+    fn test_contexts_are_ordered_by_priority_and_limited() {
+        let mut executor = sent(1);
+        executor.add_context(JOG_CONTEXT, JOG_PRIORITY).unwrap();
+        assert!(executor.has_context(JOG_CONTEXT));
+        assert!(executor.add_context(JOG_CONTEXT, 10).is_err(), "names are unique");
+        executor.add_context("low", PROGRAM_PRIORITY - 1).unwrap();
+        executor.add_context("high", JOG_PRIORITY + 1).unwrap();
+        executor.add_context("tie", PROGRAM_PRIORITY).unwrap();
+        assert!(executor.add_context("extra", 1).is_err(), "at most {} contexts", MAX_CONTEXTS);
+        assert!(executor.queue_in_context("missing", jog_move()).is_err());
+        assert!(executor.queue_in_context("low", SendPacket::Command(fanuc_rmi::packets::Command::FrcGetStatus)).is_err());
+
+        for context in ["low", "tie", JOG_CONTEXT, "high"] {
+            executor.queue_in_context(context, jog_move()).unwrap();
+        }
+        let mut next_id = 2;
+        assert_eq!(
+            dispatch(&mut executor, &mut next_id),
+            vec![
+                Origin::Context("high"),
+                Origin::Context(JOG_CONTEXT),
+                Origin::Context("tie"),
+                Origin::Context("low"),
+            ]
+        );
+    }
+}
@@ -10,9 +10,12 @@
 //! - Range validation: speed > 0, uframe >= 0, utool >= 0
 //! - Valid speed_type values: mmSec, InchMin, Time, mSec (defaults to robot's default_speed_type if not specified)
 //! - Valid term_type values: FINE, CNT (also accepts CNT with value like CNT100, normalized to CNT)
+//!
+//! [`write_csv_string`] turns stored instructions back into this format, so an
+//! exported program re-imports to the same instruction rows.
 
 use crate::database::ProgramInstruction;
-use csv::ReaderBuilder;
+use csv::{ReaderBuilder, WriterBuilder};
 use std::collections::HashMap;
 use std::io::Read;
 
@@ -90,7 +93,7 @@ impl From<csv::Error> for ParseError {
 /// Result of parsing a CSV file.
 #[derive(Debug)]
 pub struct ParseResult {
-    /// Successfully parsed instructions (empty if errors occurred)
+    /// Instructions from the rows that parsed
     pub instructions: Vec<ProgramInstruction>,
     /// Warnings about potential issues (non-fatal)
     pub warnings: Vec<ParseWarning>,
@@ -255,7 +258,22 @@ impl ColumnConsistencyTracker {
 ///
 /// Returns a ParseResult with instructions, warnings, and metadata.
 /// Returns Err(ParseError) if validation fails.
-pub fn parse_csv<R: Read>(reader: R, _defaults: &ProgramDefaults) -> Result<ParseResult, ParseError> {
+pub fn parse_csv<R: Read>(reader: R, defaults: &ProgramDefaults) -> Result<ParseResult, ParseError> {
+    let (result, errors) = parse_csv_with_errors(reader, defaults)?;
+    if !errors.is_empty() {
+        return Err(ParseError::ValidationErrors(errors));
+    }
+    Ok(result)
+}
+
+/// Parse a CSV program like [`parse_csv`], but return the rows that parsed
+/// alongside the validation errors instead of failing on them.
+///
+/// Only a malformed header (or a missing required column) is an `Err`.
+pub fn parse_csv_with_errors<R: Read>(
+    reader: R,
+    _defaults: &ProgramDefaults,
+) -> Result<(ParseResult, Vec<ValidationError>), ParseError> {
     let mut csv_reader = ReaderBuilder::new()
         .has_headers(true)
         .flexible(true)
@@ -559,6 +577,7 @@ pub fn parse_csv<R: Read>(reader: R, _defaults: &ProgramDefaults) -> Result<Pars
                 term_value,
                 uframe,
                 utool,
+                control: None,
             });
         }
 
@@ -577,17 +596,13 @@ pub fn parse_csv<R: Read>(reader: R, _defaults: &ProgramDefaults) -> Result<Pars
         });
     }
 
-    // Return error if any validation errors occurred
-    if !errors.is_empty() {
-        return Err(ParseError::ValidationErrors(errors));
-    }
-
-    Ok(ParseResult {
+    let result = ParseResult {
         instructions,
         warnings,
         row_count: line_number - 1,
         columns_present,
-    })
+    };
+    Ok((result, errors))
 }
 
 /// Parse CSV from a string.
@@ -595,6 +610,67 @@ pub fn parse_csv_string(csv_content: &str, defaults: &ProgramDefaults) -> Result
     parse_csv(csv_content.as_bytes(), defaults)
 }
 
+/// Columns written by [`write_csv_string`], in order.
+const EXPORT_COLUMNS: &[&str] = &[
+    "x", "y", "z", "w", "p", "r", "ext1", "ext2", "ext3",
+    "speed", "speed_type", "term_type", "term_value", "uframe", "utool",
+];
+
+/// Write instructions as CSV in the format accepted by [`parse_csv`].
+///
+/// The required columns are always written. An optional column is written
+/// only when some instruction has a value for it, so a program imported from
+/// a minimal CSV exports as a minimal CSV. Rows are written in slice order.
+///
+/// Importing `FINE` and `CNT100` rows leaves `term_value` set on the CNT
+/// rows only. A partly filled `term_value` column would fail the consistency
+/// check on re-import, so in that case CNT values are folded back into the
+/// term type (`CNT100`) instead.
+pub fn write_csv_string(instructions: &[ProgramInstruction]) -> Result<String, csv::Error> {
+    let fold_term_value = !instructions.iter().all(|instr| instr.term_value.is_some());
+    let cell = |instr: &ProgramInstruction, column: &str| -> Option<String> {
+        match column {
+            "x" => Some(instr.x.to_string()),
+            "y" => Some(instr.y.to_string()),
+            "z" => Some(instr.z.to_string()),
+            "w" => instr.w.map(|v| v.to_string()),
+            "p" => instr.p.map(|v| v.to_string()),
+            "r" => instr.r.map(|v| v.to_string()),
+            "ext1" => instr.ext1.map(|v| v.to_string()),
+            "ext2" => instr.ext2.map(|v| v.to_string()),
+            "ext3" => instr.ext3.map(|v| v.to_string()),
+            "speed" => instr.speed.map(|v| v.to_string()),
+            "speed_type" => instr.speed_type.clone(),
+            "term_type" => match (&instr.term_type, instr.term_value) {
+                (Some(tt), Some(tv)) if fold_term_value && tt == "CNT" => Some(format!("CNT{}", tv)),
+                (tt, _) => tt.clone(),
+            },
+            "term_value" if fold_term_value => None,
+            "term_value" => instr.term_value.map(|v| v.to_string()),
+            "uframe" => instr.uframe.map(|v| v.to_string()),
+            "utool" => instr.utool.map(|v| v.to_string()),
+            _ => None,
+        }
+    };
+
+    let columns: Vec<&str> = EXPORT_COLUMNS
+        .iter()
+        .copied()
+        .filter(|column| {
+            ["x", "y", "z", "speed"].contains(column)
+                || instructions.iter().any(|instr| cell(instr, column).is_some())
+        })
+        .collect();
+
+    let mut writer = WriterBuilder::new().from_writer(Vec::new());
+    writer.write_record(&columns)?;
+    for instr in instructions {
+        writer.write_record(columns.iter().map(|column| cell(instr, column).unwrap_or_default()))?;
+    }
+    let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+    Ok(String::from_utf8(bytes).expect("CSV built from UTF-8 strings"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -753,5 +829,33 @@ mod tests {
         assert_eq!(result.instructions[1].term_type, Some("CNT".to_string()));
         assert_eq!(result.instructions[2].term_type, Some("CNT".to_string()));
     }
-}
 
+    #[test]
+    fn test_export_round_trip_is_stable() {
+        let csv = "x,y,z,w,p,r,speed,speed_type,term_type,uframe,utool\n\
+                   100.5,200,300.25,180,0,-90,50,mmSec,CNT100,3,1\n\
+                   150,250,350,179.999,0.5,-90,100,mmSec,FINE,3,1\n\
+                   -12.125,0.001,1e3,180,0,-90,25.5,mmSec,cnt50,3,1";
+        let defaults = ProgramDefaults::default();
+        let imported = parse_csv_string(csv, &defaults).unwrap().instructions;
+
+        let exported = write_csv_string(&imported).unwrap();
+        let reimported = parse_csv_string(&exported, &defaults).unwrap().instructions;
+        assert_eq!(reimported, imported, "exported CSV:\n{}", exported);
+        assert!(exported.starts_with(
+            "x,y,z,w,p,r,speed,speed_type,term_type,uframe,utool\n"
+        ));
+
+        // Exporting again yields the same text
+        assert_eq!(write_csv_string(&reimported).unwrap(), exported);
+    }
+
+    #[test]
+    fn test_export_minimal_program_keeps_required_columns_only() {
+        let csv = "x,y,z,speed\n100.0,200.0,300.0,50";
+        let defaults = ProgramDefaults::default();
+        let imported = parse_csv_string(csv, &defaults).unwrap().instructions;
+
+        assert_eq!(write_csv_string(&imported).unwrap(), "x,y,z,speed\n100,200,300,50\n");
+    }
+}
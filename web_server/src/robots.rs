@@ -0,0 +1,380 @@
+//! Connections to more than one robot.
+//!
+//! The server always has one *active* robot: the one the UI connects with
+//! `connect_to_saved_robot`, whose responses go out as untagged binary frames
+//! and which runs programs. A [`RobotRegistry`] adds any number of further
+//! robots keyed by saved connection id. Requests name them with
+//! [`RoutedRequest::robot_id`](crate::api_types::RoutedRequest), and their
+//! robot responses are broadcast as frames tagged with that id
+//! ([`encode_robot_frame`]).
+
+use crate::api_types::encode_robot_frame;
+use crate::RobotConnection;
+use fanuc_rmi::dto;
+use fanuc_rmi::packets::{PacketPriority, ResponsePacket};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// Interval between position/status polls of an additional robot.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An additional robot and the task forwarding its responses.
+struct RegisteredRobot {
+    connection: Arc<RwLock<RobotConnection>>,
+    forwarder: JoinHandle<()>,
+}
+
+impl Drop for RegisteredRobot {
+    fn drop(&mut self) {
+        self.forwarder.abort();
+    }
+}
+
+/// The active robot plus any additional robots, keyed by connection id.
+pub struct RobotRegistry {
+    active: Arc<RwLock<RobotConnection>>,
+    robots: RwLock<HashMap<i64, RegisteredRobot>>,
+    broadcast_tx: Arc<broadcast::Sender<Vec<u8>>>,
+}
+
+impl RobotRegistry {
+    /// A registry around the server's active robot. Responses of additional
+    /// robots are sent on `broadcast_tx`.
+    pub fn new(
+        active: Arc<RwLock<RobotConnection>>,
+        broadcast_tx: Arc<broadcast::Sender<Vec<u8>>>,
+    ) -> Self {
+        Self {
+            active,
+            robots: RwLock::new(HashMap::new()),
+            broadcast_tx,
+        }
+    }
+
+    /// The active robot.
+    pub fn active(&self) -> Arc<RwLock<RobotConnection>> {
+        Arc::clone(&self.active)
+    }
+
+    /// Whether `robot_id` names the active robot: `None`, or the id of the
+    /// saved connection the active robot was connected from.
+    pub async fn is_active(&self, robot_id: Option<i64>) -> bool {
+        match robot_id {
+            None => true,
+            Some(id) => {
+                let conn = self.active.read().await;
+                conn.saved_connection.as_ref().is_some_and(|saved| saved.id == id)
+            }
+        }
+    }
+
+    /// The robot `robot_id` refers to, if it is the active robot or has been
+    /// added to the registry.
+    pub async fn resolve(&self, robot_id: Option<i64>) -> Option<Arc<RwLock<RobotConnection>>> {
+        if self.is_active(robot_id).await {
+            return Some(self.active());
+        }
+        let id = robot_id?;
+        self.robots.read().await.get(&id).map(|robot| Arc::clone(&robot.connection))
+    }
+
+    /// The additional robot `robot_id`, added disconnected if not yet known.
+    /// A new robot initializes on connect if the active robot does, and
+    /// starts with the active robot's model.
+    pub async fn get_or_insert(&self, robot_id: i64) -> Arc<RwLock<RobotConnection>> {
+        let (auto_initialize, model) = {
+            let active = self.active.read().await;
+            (active.auto_initialize, active.model)
+        };
+        let mut robots = self.robots.write().await;
+        let robot = robots.entry(robot_id).or_insert_with(|| {
+            info!("Adding robot {} to the registry", robot_id);
+            let mut connection = RobotConnection::for_model(String::new(), 0, model);
+            connection.robot_id = Some(robot_id);
+            connection.auto_initialize = auto_initialize;
+            let connection = Arc::new(RwLock::new(connection));
+            let forwarder = tokio::spawn(forward_robot(
+                robot_id,
+                Arc::clone(&connection),
+                Arc::clone(&self.broadcast_tx),
+            ));
+            RegisteredRobot { connection, forwarder }
+        });
+        Arc::clone(&robot.connection)
+    }
+
+    /// Remove the additional robot `robot_id`, stopping its forwarder.
+    pub async fn remove(&self, robot_id: i64) -> Option<Arc<RwLock<RobotConnection>>> {
+        let robot = self.robots.write().await.remove(&robot_id)?;
+        info!("Removed robot {} from the registry", robot_id);
+        Some(Arc::clone(&robot.connection))
+    }
+}
+
+/// Poll an additional robot's position and status and broadcast every robot
+/// response as a frame tagged with `robot_id`.
+///
+/// Follows driver changes like the active robot's broadcast tasks do, and
+/// marks the robot disconnected when its driver's response channel closes.
+async fn forward_robot(
+    robot_id: i64,
+    connection: Arc<RwLock<RobotConnection>>,
+    broadcast_tx: Arc<broadcast::Sender<Vec<u8>>>,
+) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    // Driver identity and its response subscription
+    let mut subscription: Option<(usize, broadcast::Receiver<ResponsePacket>)> = None;
+    // Driver whose response channel closed; it is not polled again
+    let mut closed_driver: Option<usize> = None;
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                let Some(driver) = connection.read().await.driver.clone() else {
+                    subscription = None;
+                    continue;
+                };
+                let driver_id = Arc::as_ptr(&driver) as usize;
+                if closed_driver == Some(driver_id) {
+                    continue;
+                }
+                if subscription.as_ref().map(|(id, _)| *id) != Some(driver_id) {
+                    info!("Forwarding responses of robot {}", robot_id);
+                    subscription = Some((driver_id, driver.response_tx.subscribe()));
+                }
+
+                for command in [
+                    dto::Command::FrcReadCartesianPosition(dto::FrcReadCartesianPosition { group: 1 }),
+                    dto::Command::FrcReadJointAngles(dto::FrcReadJointAngles { group: 1 }),
+                    dto::Command::FrcGetStatus,
+                ] {
+                    let _ = driver.send_packet(dto::SendPacket::Command(command).into(), PacketPriority::High);
+                }
+            }
+
+            result = async {
+                match subscription.as_mut() {
+                    Some((_, response_rx)) => response_rx.recv().await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                match result {
+                    Ok(response) => {
+                        let response_type = response.name();
+                        let dto_response: dto::ResponsePacket = response.into();
+                        match bincode::serialize(&dto_response) {
+                            Ok(binary) => {
+                                let _ = broadcast_tx.send(encode_robot_frame(robot_id, &binary));
+                            }
+                            Err(e) => error!("Failed to serialize robot {} {} response: {}", robot_id, response_type, e),
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("Robot {} response channel lagged by {} messages", robot_id, n);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        warn!("Robot {} response channel closed - robot disconnected", robot_id);
+                        closed_driver = subscription.take().map(|(id, _)| id);
+                        let mut conn = connection.write().await;
+                        conn.connected = false;
+                        conn.jog = None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_types::{ClientRequest, JogAxis, JogDirection, ServerResponse};
+    use crate::database::Database;
+    use crate::handlers::handle_routed_request;
+    use crate::test_support::{command_reply, connect_driver, instruction_reply, start_fake_controller};
+    use std::sync::Mutex as StdMutex;
+    use tokio::sync::Mutex;
+
+    /// Fake controller that accepts one connection, answers every command
+    /// successfully and reports `din_value` for any digital input. Every
+    /// instruction completes straight away. Returns the connect port and the
+    /// names of the instructions received.
+    async fn start_robot_controller(din_value: u8) -> (u32, Arc<StdMutex<Vec<String>>>) {
+        let instructions = Arc::new(StdMutex::new(Vec::new()));
+        let received = Arc::clone(&instructions);
+        let port = start_fake_controller(move |packet| {
+            if let Some(reply) = instruction_reply(&packet) {
+                received.lock().unwrap().push(packet["Instruction"].as_str().unwrap_or_default().to_string());
+                Some(reply)
+            } else if packet["Command"] == "FRC_ReadDIN" {
+                Some(format!(
+                    "{{\"Command\":\"FRC_ReadDIN\",\"ErrorID\":0,\"PortNumber\":{},\"PortValue\":{}}}\r\n",
+                    packet["PortNumber"], din_value
+                ))
+            } else {
+                command_reply(&packet)
+            }
+        })
+        .await;
+        (port, instructions)
+    }
+
+    /// A connection to the fake controller on `port`, as saved robot `id`.
+    async fn connect_fake(
+        conn: &mut RobotConnection,
+        db: &Arc<Mutex<Database>>,
+        port: u32,
+        name: &str,
+    ) -> i64 {
+        let db = db.lock().await;
+        let id = db
+            .create_robot_connection(
+                name, None, "127.0.0.1", port, 50.0, "mmSec", "CNT", 0.0, 0.0, 0.0, 10.0, 1.0, 10.0,
+                1.0, 10.0, 1.0,
+            )
+            .expect("save robot connection");
+        conn.driver = Some(connect_driver(port).await);
+        conn.connected = true;
+        conn.saved_connection = db.get_robot_connection(id).expect("load robot connection");
+        id
+    }
+
+    #[tokio::test]
+    async fn test_requests_route_to_each_robot() {
+        let db = Arc::new(Mutex::new(Database::new(":memory:").expect("in-memory database")));
+        let (port_a, instructions_a) = start_robot_controller(1).await;
+        let (port_b, instructions_b) = start_robot_controller(0).await;
+
+        let mut active = RobotConnection::new("127.0.0.1".to_string(), port_a);
+        let id_a = connect_fake(&mut active, &db, port_a, "Robot A").await;
+        let (broadcast_tx, mut frames) = broadcast::channel(256);
+        let registry = RobotRegistry::new(Arc::new(RwLock::new(active)), Arc::new(broadcast_tx));
+
+        let id_b = {
+            let robot_b = registry.get_or_insert(2).await;
+            let mut conn = robot_b.write().await;
+            connect_fake(&mut conn, &db, port_b, "Robot B").await
+        };
+        assert_eq!(id_b, 2, "second saved connection gets id 2");
+        assert_ne!(id_a, id_b);
+
+        let request = |robot_id, request| {
+            handle_routed_request(robot_id, request, &registry, Arc::clone(&db), None, None, None)
+        };
+        let read_din = |robot_id| request(robot_id, ClientRequest::ReadDin { port_number: 7 });
+        for (robot_id, expected) in [(None, true), (Some(id_a), true), (Some(id_b), false)] {
+            match read_din(robot_id).await {
+                ServerResponse::DinValue { port_number, port_value } => {
+                    assert_eq!(port_number, 7);
+                    assert_eq!(port_value, expected, "DIN value for robot {:?}", robot_id);
+                }
+                other => panic!("unexpected response for robot {:?}: {:?}", robot_id, other),
+            }
+        }
+
+        match read_din(Some(99)).await {
+            ServerResponse::Error { .. } => {}
+            other => panic!("unknown robot should be an error, got {:?}", other),
+        }
+
+        // Motion goes to the addressed robot only
+        let jog = ClientRequest::JogContinuous { axis: JogAxis::X, direction: JogDirection::Positive, speed: 50.0 };
+        let response = request(Some(id_b), jog).await;
+        assert!(matches!(response, ServerResponse::Success { .. }), "{:?}", response);
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let response = request(Some(id_b), ClientRequest::JogStop).await;
+        assert!(matches!(response, ServerResponse::Success { .. }), "{:?}", response);
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let moves_b = instructions_b.lock().unwrap().clone();
+        assert!(!moves_b.is_empty(), "robot B should receive the jog");
+        assert!(moves_b.iter().all(|name| name == "FRC_LinearRelative"), "{:?}", moves_b);
+        assert!(instructions_a.lock().unwrap().is_empty(), "robot A must not move");
+
+        // Robot B's polled responses are broadcast tagged with its id
+        let frame = tokio::time::timeout(Duration::from_secs(2), frames.recv())
+            .await
+            .expect("tagged frame")
+            .expect("open channel");
+        let (robot_id, _) = crate::api_types::decode_robot_frame(&frame).expect("valid frame");
+        assert_eq!(robot_id, Some(id_b));
+    }
+
+    /// (kind, robot) of every DOUT 3 broadcast or change pushed to `socket`
+    /// until it goes quiet.
+    async fn pushed_dout3(socket: &mut crate::session::test_support::ClientSocket) -> Vec<(&'static str, Option<i64>)> {
+        crate::session::test_support::pushed(socket, Duration::from_millis(200))
+            .await
+            .into_iter()
+            .filter_map(|response| match response {
+                ServerResponse::DoutValue { port_number: 3, port_value: true, robot_id } => Some(("dout_value", robot_id)),
+                ServerResponse::IoChanged { point, value } if point.port == 3 && value == 1.0 => {
+                    Some(("io_changed", point.robot_id))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// A DOUT written on one robot is broadcast tagged with that robot, and
+    /// only reaches subscribers of that robot's port.
+    #[tokio::test]
+    async fn test_dout_write_is_scoped_to_its_robot() {
+        use crate::api_types::{IoPoint, IoType};
+        use crate::session::test_support::connect_client;
+        use crate::session::ClientManager;
+
+        let db = Arc::new(Mutex::new(Database::new(":memory:").expect("in-memory database")));
+        let (port_a, _) = start_robot_controller(0).await;
+        let (port_b, _) = start_robot_controller(0).await;
+        let mut active = RobotConnection::new("127.0.0.1".to_string(), port_a);
+        connect_fake(&mut active, &db, port_a, "Robot A").await;
+        let registry = RobotRegistry::new(Arc::new(RwLock::new(active)), Arc::new(broadcast::channel(256).0));
+        let id_b = {
+            let robot_b = registry.get_or_insert(2).await;
+            let mut conn = robot_b.write().await;
+            connect_fake(&mut conn, &db, port_b, "Robot B").await
+        };
+
+        let client_manager = Arc::new(ClientManager::new());
+        let (client_id, mut socket) = connect_client(&client_manager).await;
+        client_manager.try_acquire_control(client_id).await.expect("acquire control");
+        let request = |robot_id, request| {
+            handle_routed_request(
+                robot_id,
+                request,
+                &registry,
+                Arc::clone(&db),
+                None,
+                Some(Arc::clone(&client_manager)),
+                Some(client_id),
+            )
+        };
+
+        let dout3_a = IoPoint { io_type: IoType::Dout, port: 3, robot_id: None };
+        let dout3_b = IoPoint { robot_id: Some(id_b), ..dout3_a };
+        let response = request(None, ClientRequest::SubscribeIo { points: vec![dout3_a, dout3_b] }).await;
+        assert!(matches!(response, ServerResponse::Success { .. }), "{:?}", response);
+
+        let response = request(Some(id_b), ClientRequest::WriteDout { port_number: 3, port_value: true }).await;
+        assert!(matches!(response, ServerResponse::DoutValue { robot_id: Some(id), .. } if id == id_b), "{:?}", response);
+        assert_eq!(pushed_dout3(&mut socket).await, vec![("dout_value", Some(id_b)), ("io_changed", Some(id_b))]);
+
+        // Robot A's port is untouched, and its writes carry no robot id
+        let response = request(None, ClientRequest::ReadIoSnapshot {
+            din: None,
+            dout: Some(crate::api_types::IoPortRange { start: 3, count: 1 }),
+            ain: None,
+            aout: None,
+            gin: None,
+            gout: None,
+        })
+        .await;
+        assert!(matches!(&response, ServerResponse::IoSnapshot { dout, .. } if dout == &vec![(3, false)]), "{:?}", response);
+        request(None, ClientRequest::WriteDout { port_number: 3, port_value: true }).await;
+        assert_eq!(pushed_dout3(&mut socket).await, vec![("dout_value", None), ("io_changed", None)]);
+    }
+}
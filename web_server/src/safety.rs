@@ -0,0 +1,197 @@
+//! Server-side motion safety limits.
+//!
+//! Motion packets from the binary channel are checked against the connected
+//! robot's [`SafetyLimitsDto`] before they are converted to protocol packets,
+//! independent of any limits configured on the controller itself.
+//!
+//! Position bounds apply to absolute Cartesian targets (including circular
+//! via points); relative and joint-representation moves have no absolute
+//! Cartesian target to check here. The speed cap applies to every motion
+//! whose speed is a velocity (`mmSec` or `InchMin`); time-based speeds are
+//! left alone.
+
+use crate::api_types::{SafetyLimitsDto, SpeedLimitAction};
+use fanuc_rmi::dto::{Instruction, Position, SendPacket};
+use fanuc_rmi::SpeedType;
+
+/// Millimetres per second in one inch per minute.
+const MM_SEC_PER_INCH_MIN: f64 = 25.4 / 60.0;
+
+/// Check `packet` against `limits`, clamping its speed if configured to.
+///
+/// Returns the reason the packet must not be sent. Non-motion packets always
+/// pass.
+pub fn enforce_limits(limits: &SafetyLimitsDto, packet: &mut SendPacket) -> Result<(), String> {
+    let SendPacket::Instruction(instruction) = packet else {
+        return Ok(());
+    };
+
+    let (targets, speed_type, speed): (Vec<&Position>, &SpeedType, &mut f64) = match instruction {
+        Instruction::FrcLinearMotion(i) => (vec![&i.position], &i.speed_type, &mut i.speed),
+        Instruction::FrcJointMotion(i) => (vec![&i.position], &i.speed_type, &mut i.speed),
+        Instruction::FrcCircularMotion(i) => {
+            (vec![&i.via_position, &i.position], &i.speed_type, &mut i.speed)
+        }
+        Instruction::FrcLinearRelative(i) => (vec![], &i.speed_type, &mut i.speed),
+        Instruction::FrcJointRelative(i) => (vec![], &i.speed_type, &mut i.speed),
+        Instruction::FrcCircularRelative(i) => (vec![], &i.speed_type, &mut i.speed),
+        Instruction::FrcLinearMotionJRep(i) => (vec![], &i.speed_type, &mut i.speed),
+        Instruction::FrcLinearRelativeJRep(i) => (vec![], &i.speed_type, &mut i.speed),
+        Instruction::FrcJointMotionJRep(i) => (vec![], &i.speed_type, &mut i.speed),
+        Instruction::FrcJointRelativeJRep(i) => (vec![], &i.speed_type, &mut i.speed),
+        // The controller resolves the register, so only the speed is known here
+        Instruction::FrcLinearMotionPR(i) => (vec![], &i.speed_type, &mut i.speed),
+        Instruction::FrcJointMotionPR(i) => (vec![], &i.speed_type, &mut i.speed),
+        Instruction::FrcWaitDIN(_)
+        | Instruction::FrcSetUFrame(_)
+        | Instruction::FrcSetUTool(_)
+        | Instruction::FrcWaitTime(_)
+        | Instruction::FrcSetPayLoad(_)
+        | Instruction::FrcCall(_) => return Ok(()),
+    };
+
+    for target in targets {
+        check_position(limits, target)?;
+    }
+    check_speed(limits, speed_type, speed)
+}
+
+fn check_position(limits: &SafetyLimitsDto, position: &Position) -> Result<(), String> {
+    let axes = [
+        ("X", position.x, limits.x_min, limits.x_max),
+        ("Y", position.y, limits.y_min, limits.y_max),
+        ("Z", position.z, limits.z_min, limits.z_max),
+    ];
+    for (axis, value, min, max) in axes {
+        if let Some(min) = min.filter(|min| value < *min) {
+            return Err(format!("{} {:.1} mm is below the limit of {:.1} mm", axis, value, min));
+        }
+        if let Some(max) = max.filter(|max| value > *max) {
+            return Err(format!("{} {:.1} mm is above the limit of {:.1} mm", axis, value, max));
+        }
+    }
+    Ok(())
+}
+
+fn check_speed(limits: &SafetyLimitsDto, speed_type: &SpeedType, speed: &mut f64) -> Result<(), String> {
+    let Some(max_speed) = limits.max_speed else {
+        return Ok(());
+    };
+    let mm_sec_per_unit = match speed_type {
+        SpeedType::MMSec => 1.0,
+        SpeedType::InchMin => MM_SEC_PER_INCH_MIN,
+        SpeedType::Time | SpeedType::MilliSeconds => return Ok(()),
+    };
+
+    let speed_mm_sec = *speed * mm_sec_per_unit;
+    if speed_mm_sec <= max_speed {
+        return Ok(());
+    }
+    match limits.speed_limit_action {
+        SpeedLimitAction::Reject => Err(format!(
+            "Speed {:.1} mm/s exceeds the limit of {:.1} mm/s",
+            speed_mm_sec, max_speed
+        )),
+        SpeedLimitAction::Clamp => {
+            *speed = max_speed / mm_sec_per_unit;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fanuc_rmi::dto::{Configuration, FrcLinearMotion, FrcLinearRelative};
+    use fanuc_rmi::TermType;
+
+    fn position(x: f64, y: f64, z: f64) -> Position {
+        Position { x, y, z, w: 180.0, p: 0.0, r: 0.0, ext1: 0.0, ext2: 0.0, ext3: 0.0 }
+    }
+
+    fn configuration() -> Configuration {
+        Configuration {
+            u_tool_number: 1,
+            u_frame_number: 1,
+            front: 1,
+            up: 1,
+            left: 0,
+            flip: 0,
+            turn4: 0,
+            turn5: 0,
+            turn6: 0,
+        }
+    }
+
+    fn linear_move(position: Position, speed: f64) -> SendPacket {
+        SendPacket::Instruction(Instruction::FrcLinearMotion(FrcLinearMotion {
+            sequence_id: 0,
+            configuration: configuration(),
+            position,
+            speed_type: SpeedType::MMSec,
+            speed,
+            term_type: TermType::FINE,
+            term_value: 0,
+            no_blend: false,
+        }))
+    }
+
+    fn speed_of(packet: &SendPacket) -> f64 {
+        match packet {
+            SendPacket::Instruction(Instruction::FrcLinearMotion(i)) => i.speed,
+            other => panic!("expected FrcLinearMotion, got {:?}", other),
+        }
+    }
+
+    fn limits(speed_limit_action: SpeedLimitAction) -> SafetyLimitsDto {
+        SafetyLimitsDto {
+            z_min: Some(-100.0),
+            max_speed: Some(250.0),
+            speed_limit_action,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_target_below_floor_is_rejected() {
+        let mut packet = linear_move(position(400.0, 0.0, -150.0), 100.0);
+        let err = enforce_limits(&limits(SpeedLimitAction::Clamp), &mut packet).unwrap_err();
+        assert!(err.contains("below"), "{}", err);
+
+        let mut packet = linear_move(position(400.0, 0.0, -50.0), 100.0);
+        assert_eq!(enforce_limits(&limits(SpeedLimitAction::Clamp), &mut packet), Ok(()));
+    }
+
+    #[test]
+    fn test_speed_above_cap_is_clamped_or_rejected() {
+        let mut packet = linear_move(position(400.0, 0.0, 0.0), 1000.0);
+        assert_eq!(enforce_limits(&limits(SpeedLimitAction::Clamp), &mut packet), Ok(()));
+        assert_eq!(speed_of(&packet), 250.0);
+
+        let mut packet = linear_move(position(400.0, 0.0, 0.0), 1000.0);
+        let err = enforce_limits(&limits(SpeedLimitAction::Reject), &mut packet).unwrap_err();
+        assert!(err.contains("exceeds"), "{}", err);
+        assert_eq!(speed_of(&packet), 1000.0);
+    }
+
+    #[test]
+    fn test_relative_move_only_checks_speed() {
+        let mut packet = SendPacket::Instruction(Instruction::FrcLinearRelative(FrcLinearRelative {
+            sequence_id: 0,
+            configuration: configuration(),
+            position: position(0.0, 0.0, -500.0),
+            speed_type: SpeedType::InchMin,
+            speed: 1200.0,
+            term_type: TermType::FINE,
+            term_value: 0,
+            no_blend: false,
+        }));
+        assert_eq!(enforce_limits(&limits(SpeedLimitAction::Clamp), &mut packet), Ok(()));
+        match packet {
+            SendPacket::Instruction(Instruction::FrcLinearRelative(i)) => {
+                assert!((i.speed * MM_SEC_PER_INCH_MIN - 250.0).abs() < 1e-9, "{}", i.speed);
+            }
+            other => panic!("expected FrcLinearRelative, got {:?}", other),
+        }
+    }
+}
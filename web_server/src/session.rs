@@ -3,7 +3,7 @@
 //! This module provides server-side state management for robot connections
 //! and client sessions. The server is the source of truth for execution state.
 
-use crate::api_types::ServerResponse;
+use crate::api_types::{IoPoint, ServerResponse};
 use crate::program_executor::ProgramExecutor;
 use futures_util::SinkExt;
 use std::collections::{HashMap, HashSet};
@@ -27,6 +27,88 @@ pub enum ControlError {
     },
     /// Control lock timed out (informational)
     TimedOut { previous_holder: Uuid },
+    /// The client's role may not hold control under the current policy
+    RoleNotPermitted {
+        role: ClientRole,
+        holder: Option<Uuid>,
+    },
+}
+
+/// A client's role, given when it connects. Under
+/// [`AcceptOrder::RolePriority`] a higher role takes control from a lower
+/// one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ClientRole {
+    /// Watches the robot; may be barred from control entirely
+    Viewer,
+    #[default]
+    Operator,
+    Supervisor,
+}
+
+impl std::str::FromStr for ClientRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "viewer" => Ok(Self::Viewer),
+            "operator" => Ok(Self::Operator),
+            "supervisor" => Ok(Self::Supervisor),
+            other => Err(format!("Unknown client role '{}'", other)),
+        }
+    }
+}
+
+/// How control requests are settled while another client holds control.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AcceptOrder {
+    /// The holder keeps control until it releases it or times out
+    #[default]
+    FirstCome,
+    /// A client takes control from a holder with a lower role
+    RolePriority,
+}
+
+impl std::str::FromStr for AcceptOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "first_come" => Ok(Self::FirstCome),
+            "role_priority" => Ok(Self::RolePriority),
+            other => Err(format!("Unknown control accept order '{}'", other)),
+        }
+    }
+}
+
+/// Who may take control, and from whom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ControlPolicy {
+    pub accept_order: AcceptOrder,
+    /// Whether viewers may take control at all
+    pub viewers_may_control: bool,
+}
+
+impl Default for ControlPolicy {
+    /// First come, first served, for every role.
+    fn default() -> Self {
+        Self {
+            accept_order: AcceptOrder::FirstCome,
+            viewers_may_control: true,
+        }
+    }
+}
+
+impl ControlPolicy {
+    /// Whether `role` may hold control at all.
+    pub fn permits(&self, role: ClientRole) -> bool {
+        role != ClientRole::Viewer || self.viewers_may_control
+    }
+
+    /// Whether a `challenger` may take control from a `holder`.
+    pub fn preempts(&self, challenger: ClientRole, holder: ClientRole) -> bool {
+        self.accept_order == AcceptOrder::RolePriority && challenger > holder
+    }
 }
 
 /// Control lock for a robot - only one client can control at a time.
@@ -37,6 +119,10 @@ pub struct RobotControlLock {
     acquired_at: Option<Instant>,
     /// Last activity time (for timeout)
     last_activity: Option<Instant>,
+    /// Last heartbeat from the holder (for the motion watchdog)
+    last_heartbeat: Option<Instant>,
+    /// How long the holder may go without a command before losing control
+    inactivity_timeout: Duration,
 }
 
 impl RobotControlLock {
@@ -44,10 +130,18 @@ impl RobotControlLock {
     pub const INACTIVITY_TIMEOUT: Duration = Duration::from_secs(600);
 
     pub fn new() -> Self {
+        Self::with_inactivity_timeout(Self::INACTIVITY_TIMEOUT)
+    }
+
+    /// Create a lock that times out after `inactivity_timeout` without a
+    /// command from the holder.
+    pub fn with_inactivity_timeout(inactivity_timeout: Duration) -> Self {
         Self {
             holder: None,
             acquired_at: None,
             last_activity: None,
+            last_heartbeat: None,
+            inactivity_timeout,
         }
     }
 
@@ -64,14 +158,21 @@ impl RobotControlLock {
     /// Check if control has timed out due to inactivity.
     pub fn is_timed_out(&self) -> bool {
         if let Some(last) = self.last_activity {
-            last.elapsed() > Self::INACTIVITY_TIMEOUT
+            last.elapsed() > self.inactivity_timeout
         } else {
             false
         }
     }
 
+    /// Check if the holder has sent no heartbeat for longer than `timeout`.
+    /// Acquiring control counts as the first heartbeat.
+    pub fn is_silent(&self, timeout: Duration) -> bool {
+        self.last_heartbeat.is_some_and(|last| last.elapsed() > timeout)
+    }
+
     /// Try to acquire control.
-    /// Returns Ok(()) if control was acquired, or Err with details if not.
+    /// Returns Ok with the holder whose control timed out (if any) when
+    /// control was acquired, or Err with details if not.
     pub fn try_acquire(&mut self, client_id: Uuid) -> Result<Option<Uuid>, ControlError> {
         // If we already hold control, just update activity
         if self.holder == Some(client_id) {
@@ -83,8 +184,7 @@ impl RobotControlLock {
         if let Some(holder) = self.holder {
             // Check for timeout
             if self.is_timed_out() {
-                let previous = self.holder.take();
-                info!("Control lock timed out, releasing from {:?}", previous);
+                info!("Control lock timed out, releasing from {}", holder);
                 // Fall through to acquire below
             } else {
                 return Err(ControlError::AlreadyControlled {
@@ -99,6 +199,7 @@ impl RobotControlLock {
         self.holder = Some(client_id);
         self.acquired_at = Some(Instant::now());
         self.last_activity = Some(Instant::now());
+        self.last_heartbeat = Some(Instant::now());
         info!("Control acquired by {}", client_id);
         Ok(previous)
     }
@@ -113,12 +214,23 @@ impl RobotControlLock {
         }
     }
 
+    /// Record a heartbeat from the holder.
+    pub fn heartbeat(&mut self, client_id: Uuid) -> bool {
+        if self.holder == Some(client_id) {
+            self.last_heartbeat = Some(Instant::now());
+            true
+        } else {
+            false
+        }
+    }
+
     /// Release control voluntarily.
     pub fn release(&mut self, client_id: Uuid) -> bool {
         if self.holder == Some(client_id) {
             self.holder = None;
             self.acquired_at = None;
             self.last_activity = None;
+            self.last_heartbeat = None;
             info!("Control released by {}", client_id);
             true
         } else {
@@ -131,6 +243,7 @@ impl RobotControlLock {
         let holder = self.holder.take();
         self.acquired_at = None;
         self.last_activity = None;
+        self.last_heartbeat = None;
         if let Some(h) = holder {
             info!("Control force-released from {}", h);
         }
@@ -143,6 +256,7 @@ impl RobotControlLock {
             self.holder = Some(to);
             self.acquired_at = Some(Instant::now());
             self.last_activity = Some(Instant::now());
+            self.last_heartbeat = Some(Instant::now());
             info!("Control transferred from {} to {}", from, to);
             true
         } else {
@@ -151,6 +265,30 @@ impl RobotControlLock {
     }
 }
 
+/// A waiting client's request for the control holder to hand over control.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandoffRequest {
+    /// Client asking for control
+    pub from: Uuid,
+    /// Client that held control when the request was made
+    pub holder: Uuid,
+    /// When the request was made
+    pub requested_at: Instant,
+}
+
+/// Error when a control handoff cannot be requested.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandoffError {
+    /// Nobody holds control; request it directly instead
+    NoHolder,
+    /// The requester already holds control
+    AlreadyHolder,
+    /// Another client's request is still waiting for an answer
+    AlreadyPending { holder: Uuid },
+    /// The requester's role may not hold control under the current policy
+    RoleNotPermitted { holder: Uuid },
+}
+
 /// Type alias for WebSocket sender
 pub type WsSender = Arc<Mutex<futures_util::stream::SplitSink<
     tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>,
@@ -162,23 +300,37 @@ pub type WsSender = Arc<Mutex<futures_util::stream::SplitSink<
 pub struct Client {
     pub id: Uuid,
     pub sender: WsSender,
+    pub role: ClientRole,
     /// The robot connection ID this client is subscribed to (if any)
     pub subscribed_robot: Option<i64>,
+    /// I/O points whose changes are pushed to this client
+    pub io_subscriptions: HashSet<IoPoint>,
+    /// Messages held back while one of the client's requests is in flight,
+    /// or `None` when [`send`](Self::send) delivers straight away
+    held: Arc<std::sync::Mutex<Option<Vec<String>>>>,
 }
 
 impl Client {
-    pub fn new(sender: WsSender) -> Self {
+    pub fn new(sender: WsSender, role: ClientRole) -> Self {
         Self {
             id: Uuid::new_v4(),
             sender,
+            role,
             subscribed_robot: None,
+            io_subscriptions: HashSet::new(),
+            held: Default::default(),
         }
     }
 
-    /// Send a response to this client.
+    /// Send a response to this client, or hold it until
+    /// [`ClientManager::release_messages`] if a request is in flight.
     pub async fn send(&self, response: &ServerResponse) -> Result<(), String> {
         let json = serde_json::to_string(response)
             .map_err(|e| format!("Serialization error: {}", e))?;
+        if let Some(held) = self.held.lock().unwrap().as_mut() {
+            held.push(json);
+            return Ok(());
+        }
         let mut sender = self.sender.lock().await;
         sender.send(Message::Text(json)).await
             .map_err(|e| format!("Send error: {}", e))
@@ -191,29 +343,90 @@ impl Client {
 pub struct ClientManager {
     clients: RwLock<HashMap<Uuid, Client>>,
     control_lock: RwLock<RobotControlLock>,
+    /// The one handoff request waiting for the holder's answer
+    handoff: RwLock<Option<HandoffRequest>>,
+    /// How long the holder has to answer a handoff request
+    handoff_timeout: Duration,
+    /// How long the holder may go without a heartbeat during motion
+    /// (`None` disables the watchdog)
+    deadman_timeout: Option<Duration>,
+    control_policy: ControlPolicy,
 }
 
 impl ClientManager {
+    /// How long the holder has to answer a handoff request by default
+    pub const DEFAULT_HANDOFF_TIMEOUT: Duration = Duration::from_secs(30);
+    /// How long the holder may go without a heartbeat during motion by default
+    pub const DEFAULT_DEADMAN_TIMEOUT: Duration = Duration::from_secs(2);
+
     pub fn new() -> Self {
+        Self::with_handoff_timeout(Self::DEFAULT_HANDOFF_TIMEOUT)
+    }
+
+    /// Create a manager whose control holders get `handoff_timeout` to
+    /// answer a handoff request.
+    pub fn with_handoff_timeout(handoff_timeout: Duration) -> Self {
         Self {
             clients: RwLock::new(HashMap::new()),
             control_lock: RwLock::new(RobotControlLock::new()),
+            handoff: RwLock::new(None),
+            handoff_timeout,
+            deadman_timeout: Some(Self::DEFAULT_DEADMAN_TIMEOUT),
+            control_policy: ControlPolicy::default(),
         }
     }
 
-    /// Register a new client and return its ID.
-    pub async fn register(&self, sender: WsSender) -> Uuid {
-        let client = Client::new(sender);
+    /// Settle control requests by `control_policy`.
+    pub fn with_control_policy(mut self, control_policy: ControlPolicy) -> Self {
+        self.control_policy = control_policy;
+        self
+    }
+
+    /// Release control after `inactivity_timeout` without a command from
+    /// the holder.
+    pub fn with_inactivity_timeout(mut self, inactivity_timeout: Duration) -> Self {
+        self.control_lock = RwLock::new(RobotControlLock::with_inactivity_timeout(inactivity_timeout));
+        self
+    }
+
+    /// Use `deadman_timeout` for the motion watchdog; `None` disables it.
+    pub fn with_deadman_timeout(mut self, deadman_timeout: Option<Duration>) -> Self {
+        self.deadman_timeout = deadman_timeout;
+        self
+    }
+
+    /// How long the holder has to answer a handoff request.
+    pub fn handoff_timeout(&self) -> Duration {
+        self.handoff_timeout
+    }
+
+    /// Register a new client with `role` and return its ID.
+    pub async fn register(&self, sender: WsSender, role: ClientRole) -> Uuid {
+        let client = Client::new(sender, role);
         let id = client.id;
         let mut clients = self.clients.write().await;
         clients.insert(id, client);
-        info!("Client {} registered ({} total)", id, clients.len());
+        info!("Client {} registered as {:?} ({} total)", id, role, clients.len());
         id
     }
 
+    /// The role `client_id` registered with; unknown clients are viewers.
+    pub async fn role(&self, client_id: Uuid) -> ClientRole {
+        let clients = self.clients.read().await;
+        clients.get(&client_id).map_or(ClientRole::Viewer, |c| c.role)
+    }
+
     /// Unregister a client and release control if they held it.
     pub async fn unregister(&self, client_id: Uuid) {
-        // First, release control if this client held it
+        // A handoff involving this client can no longer be answered
+        {
+            let mut handoff = self.handoff.write().await;
+            if handoff.is_some_and(|h| h.from == client_id || h.holder == client_id) {
+                *handoff = None;
+            }
+        }
+
+        // Then release control if this client held it
         {
             let mut lock = self.control_lock.write().await;
             if lock.is_holder(client_id) {
@@ -235,6 +448,11 @@ impl ClientManager {
         clients.get(&client_id).cloned()
     }
 
+    /// Number of connected clients.
+    pub async fn client_count(&self) -> usize {
+        self.clients.read().await.len()
+    }
+
     /// Send a response to a specific client.
     pub async fn send_to_client(&self, client_id: Uuid, response: &ServerResponse) {
         if let Some(client) = self.get(client_id).await {
@@ -244,6 +462,36 @@ impl ClientManager {
         }
     }
 
+    /// Hold back everything sent to `client_id` through the manager until
+    /// [`release_messages`](Self::release_messages).
+    ///
+    /// The connection handler holds messages while it handles one of the
+    /// client's requests, so the direct response to a request reaches the
+    /// client before any broadcast the request triggered.
+    pub async fn hold_messages(&self, client_id: Uuid) {
+        if let Some(client) = self.get(client_id).await {
+            client.held.lock().unwrap().get_or_insert_with(Vec::new);
+        }
+    }
+
+    /// Deliver the messages held for `client_id`, in order, and go back to
+    /// sending straight away.
+    pub async fn release_messages(&self, client_id: Uuid) {
+        let Some(client) = self.get(client_id).await else {
+            return;
+        };
+        // Take the sender first: anything sent from here on waits for it,
+        // so it can't overtake the held messages
+        let mut sender = client.sender.lock().await;
+        let held = client.held.lock().unwrap().take().unwrap_or_default();
+        for json in held {
+            if let Err(e) = sender.send(Message::Text(json)).await {
+                warn!("Failed to send to client {}: {}", client_id, e);
+                break;
+            }
+        }
+    }
+
     /// Subscribe a client to a robot connection.
     pub async fn subscribe_to_robot(&self, client_id: Uuid, robot_connection_id: i64) {
         let mut clients = self.clients.write().await;
@@ -272,6 +520,47 @@ impl ClientManager {
         }
     }
 
+    /// Add I/O points to a client's subscriptions. Returns how many points
+    /// the client is now subscribed to.
+    pub async fn subscribe_io(&self, client_id: Uuid, points: &[IoPoint]) -> usize {
+        let mut clients = self.clients.write().await;
+        let Some(client) = clients.get_mut(&client_id) else {
+            return 0;
+        };
+        client.io_subscriptions.extend(points.iter().copied());
+        client.io_subscriptions.len()
+    }
+
+    /// Remove I/O points from a client's subscriptions. Returns how many
+    /// points the client is still subscribed to.
+    pub async fn unsubscribe_io(&self, client_id: Uuid, points: &[IoPoint]) -> usize {
+        let mut clients = self.clients.write().await;
+        let Some(client) = clients.get_mut(&client_id) else {
+            return 0;
+        };
+        for point in points {
+            client.io_subscriptions.remove(point);
+        }
+        client.io_subscriptions.len()
+    }
+
+    /// Send an `IoChanged` to every client subscribed to `point`.
+    pub async fn notify_io_changed(&self, point: IoPoint, value: f64) {
+        let subscribers: Vec<Client> = {
+            let clients = self.clients.read().await;
+            clients.values()
+                .filter(|c| c.io_subscriptions.contains(&point))
+                .cloned()
+                .collect()
+        };
+        let response = ServerResponse::IoChanged { point, value };
+        for client in subscribers {
+            if let Err(e) = client.send(&response).await {
+                warn!("Failed to send to client {}: {}", client.id, e);
+            }
+        }
+    }
+
     /// Broadcast a response to all connected clients.
     pub async fn broadcast_all(&self, response: &ServerResponse) {
         let clients = self.clients.read().await;
@@ -286,10 +575,33 @@ impl ClientManager {
 
     /// Try to acquire control of the robot.
     pub async fn try_acquire_control(&self, client_id: Uuid) -> Result<Option<Uuid>, ControlError> {
+        let role = self.role(client_id).await;
         let mut lock = self.control_lock.write().await;
+        if !self.control_policy.permits(role) {
+            return Err(ControlError::RoleNotPermitted { role, holder: lock.holder() });
+        }
         lock.try_acquire(client_id)
     }
 
+    /// Take control from `holder` if the policy lets `client_id` preempt
+    /// it. Returns whether control moved; a handoff request pending for
+    /// `holder` is dropped with it.
+    pub async fn preempt_control(&self, client_id: Uuid, holder: Uuid) -> bool {
+        let (role, holder_role) = (self.role(client_id).await, self.role(holder).await);
+        if !self.control_policy.permits(role) || !self.control_policy.preempts(role, holder_role) {
+            return false;
+        }
+        let mut handoff = self.handoff.write().await;
+        if !self.control_lock.write().await.transfer(holder, client_id) {
+            return false;
+        }
+        if handoff.is_some_and(|h| h.holder == holder) {
+            *handoff = None;
+        }
+        info!("Client {} ({:?}) preempted control from {} ({:?})", client_id, role, holder, holder_role);
+        true
+    }
+
     /// Release control of the robot.
     pub async fn release_control(&self, client_id: Uuid) -> bool {
         let mut lock = self.control_lock.write().await;
@@ -314,6 +626,78 @@ impl ClientManager {
         lock.touch(client_id)
     }
 
+    /// Record a heartbeat from the control holder.
+    pub async fn heartbeat(&self, client_id: Uuid) -> bool {
+        let mut lock = self.control_lock.write().await;
+        lock.heartbeat(client_id)
+    }
+
+    /// Get the control holder if it has sent no heartbeat within the
+    /// deadman timeout.
+    pub async fn silent_holder(&self) -> Option<Uuid> {
+        let timeout = self.deadman_timeout?;
+        let lock = self.control_lock.read().await;
+        lock.holder().filter(|_| lock.is_silent(timeout))
+    }
+
+    /// Record `from`'s request for the current holder to hand over control.
+    /// A request older than [`Self::handoff_timeout`] no longer blocks a
+    /// new one.
+    pub async fn request_handoff(&self, from: Uuid) -> Result<HandoffRequest, HandoffError> {
+        let role = self.role(from).await;
+        let mut handoff = self.handoff.write().await;
+        let holder = match self.control_lock.read().await.holder() {
+            None => return Err(HandoffError::NoHolder),
+            Some(holder) if holder == from => return Err(HandoffError::AlreadyHolder),
+            Some(holder) => holder,
+        };
+        if !self.control_policy.permits(role) {
+            return Err(HandoffError::RoleNotPermitted { holder });
+        }
+        if let Some(pending) = *handoff {
+            if pending.holder == holder && pending.requested_at.elapsed() < self.handoff_timeout {
+                return Err(HandoffError::AlreadyPending { holder });
+            }
+        }
+        let request = HandoffRequest { from, holder, requested_at: Instant::now() };
+        *handoff = Some(request);
+        info!("Client {} asked {} to hand over control", from, holder);
+        Ok(request)
+    }
+
+    /// Hand control from `holder` to the client whose request is pending.
+    /// Returns the new holder, or `None` if `holder` has no pending request
+    /// or no longer holds control.
+    pub async fn grant_handoff(&self, holder: Uuid) -> Option<Uuid> {
+        let mut handoff = self.handoff.write().await;
+        let request = handoff.filter(|h| h.holder == holder)?;
+        *handoff = None;
+        let mut lock = self.control_lock.write().await;
+        lock.transfer(holder, request.from).then_some(request.from)
+    }
+
+    /// Drop the handoff request pending for `holder`. Returns the client
+    /// that asked, or `None` if there was no request.
+    pub async fn deny_handoff(&self, holder: Uuid) -> Option<Uuid> {
+        let mut handoff = self.handoff.write().await;
+        let request = handoff.filter(|h| h.holder == holder)?;
+        *handoff = None;
+        info!("Client {} denied the handoff to {}", holder, request.from);
+        Some(request.from)
+    }
+
+    /// Drop `request` if it is still pending. Returns whether it was.
+    pub async fn expire_handoff(&self, request: HandoffRequest) -> bool {
+        let mut handoff = self.handoff.write().await;
+        if *handoff == Some(request) {
+            *handoff = None;
+            info!("Handoff request from {} to {} expired", request.from, request.holder);
+            true
+        } else {
+            false
+        }
+    }
+
     /// Check for and release timed-out control.
     /// Returns the previous holder's UUID if control was released due to timeout.
     pub async fn check_control_timeout(&self) -> Option<Uuid> {
@@ -401,60 +785,209 @@ impl RobotSession {
     }
 }
 
+/// Build an `ExecutionStateChanged` response, filling the legacy `state` string.
+pub fn execution_state_changed(
+    execution_state: crate::api_types::ExecutionState,
+    program_id: Option<i64>,
+    current_line: Option<usize>,
+    total_lines: Option<usize>,
+    message: Option<String>,
+) -> ServerResponse {
+    ServerResponse::ExecutionStateChanged {
+        execution_state,
+        state: execution_state.as_str().to_string(),
+        program_id,
+        current_line,
+        total_lines,
+        message,
+        subprogram: None,
+    }
+}
+
+/// Set the `subprogram` of an `ExecutionStateChanged` response.
+fn with_subprogram(mut response: ServerResponse, name: &Option<String>) -> ServerResponse {
+    if let ServerResponse::ExecutionStateChanged { subprogram, .. } = &mut response {
+        subprogram.clone_from(name);
+    }
+    response
+}
+
 /// Convert ExecutionState to a ServerResponse for broadcasting.
 pub fn execution_state_to_response(state: &crate::program_executor::ExecutionState) -> ServerResponse {
+    use crate::api_types::ExecutionState as Event;
     use crate::program_executor::ExecutionState;
 
     match state {
-        ExecutionState::Idle => ServerResponse::ExecutionStateChanged {
-            state: "idle".to_string(),
-            program_id: None,
-            current_line: None,
-            total_lines: None,
-            message: None,
-        },
-        ExecutionState::Loaded { program_id, total_lines } => ServerResponse::ExecutionStateChanged {
-            state: "loaded".to_string(),
-            program_id: Some(*program_id),
-            current_line: Some(0),
-            total_lines: Some(*total_lines),
-            message: None,
-        },
-        ExecutionState::Running { program_id, total_lines, last_completed } => ServerResponse::ExecutionStateChanged {
-            state: "running".to_string(),
-            program_id: Some(*program_id),
-            current_line: Some(*last_completed),
-            total_lines: Some(*total_lines),
-            message: None,
-        },
-        ExecutionState::Paused { program_id, total_lines, last_completed } => ServerResponse::ExecutionStateChanged {
-            state: "paused".to_string(),
-            program_id: Some(*program_id),
-            current_line: Some(*last_completed),
-            total_lines: Some(*total_lines),
-            message: None,
-        },
-        ExecutionState::Stopping => ServerResponse::ExecutionStateChanged {
-            state: "stopping".to_string(),
-            program_id: None,
-            current_line: None,
-            total_lines: None,
-            message: None,
-        },
-        ExecutionState::Completed { program_id, total_lines } => ServerResponse::ExecutionStateChanged {
-            state: "completed".to_string(),
-            program_id: Some(*program_id),
-            current_line: Some(*total_lines),
-            total_lines: Some(*total_lines),
-            message: None,
-        },
-        ExecutionState::Error { message } => ServerResponse::ExecutionStateChanged {
-            state: "error".to_string(),
-            program_id: None,
-            current_line: None,
-            total_lines: None,
-            message: Some(message.clone()),
-        },
+        ExecutionState::Idle => execution_state_changed(Event::Idle, None, None, None, None),
+        ExecutionState::Loaded { program_id, total_lines } => {
+            execution_state_changed(Event::Loaded, Some(*program_id), Some(0), Some(*total_lines), None)
+        }
+        ExecutionState::Running { program_id, total_lines, last_completed, subprogram } => with_subprogram(
+            execution_state_changed(Event::Running, Some(*program_id), Some(*last_completed), Some(*total_lines), None),
+            subprogram,
+        ),
+        ExecutionState::Paused { program_id, total_lines, last_completed, subprogram, breakpoint } => with_subprogram(
+            execution_state_changed(
+                Event::Paused,
+                Some(*program_id),
+                Some(*last_completed),
+                Some(*total_lines),
+                breakpoint.map(|line| format!("Breakpoint before line {}", line)),
+            ),
+            subprogram,
+        ),
+        ExecutionState::Stopping => execution_state_changed(Event::Stopping, None, None, None, None),
+        ExecutionState::Completed { program_id, total_lines } => {
+            execution_state_changed(Event::Completed, Some(*program_id), Some(*total_lines), Some(*total_lines), None)
+        }
+        ExecutionState::Aborted { program_id, total_lines, last_completed } => execution_state_changed(
+            Event::Aborted,
+            Some(*program_id),
+            Some(*last_completed),
+            Some(*total_lines),
+            Some("Program aborted".to_string()),
+        ),
+        ExecutionState::Failed { program_id, total_lines, line, error_id } => execution_state_changed(
+            Event::Error,
+            Some(*program_id),
+            Some(*line),
+            Some(*total_lines),
+            Some(format!("Line {} failed with error {}", line, error_id)),
+        ),
+        ExecutionState::Error { message } => {
+            execution_state_changed(Event::Error, None, None, None, Some(message.clone()))
+        }
     }
 }
 
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+    use futures_util::StreamExt;
+    use tokio::net::TcpListener;
+
+    pub type ClientSocket = tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>;
+
+    /// A client registered with `client_manager` over a real WebSocket;
+    /// returns the client's id and the client end of the socket.
+    pub async fn connect_client(client_manager: &ClientManager) -> (Uuid, ClientSocket) {
+        connect_client_as(client_manager, ClientRole::default()).await
+    }
+
+    /// [`connect_client`] for a client with `role`.
+    pub async fn connect_client_as(client_manager: &ClientManager, role: ClientRole) -> (Uuid, ClientSocket) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = tokio::spawn(async move {
+            let tcp = tokio::net::TcpStream::connect(addr).await.unwrap();
+            tokio_tungstenite::client_async(format!("ws://{}", addr), tcp).await.unwrap().0
+        });
+        let (stream, _) = listener.accept().await.unwrap();
+        let server = tokio_tungstenite::accept_async(stream).await.unwrap();
+        let (sender, _receiver) = server.split();
+        let client_id = client_manager.register(Arc::new(Mutex::new(sender)), role).await;
+        (client_id, client.await.unwrap())
+    }
+
+    /// Everything pushed to `socket` until it goes quiet for `quiet`.
+    pub async fn pushed(socket: &mut ClientSocket, quiet: Duration) -> Vec<ServerResponse> {
+        let mut pushed = Vec::new();
+        while let Ok(Some(Ok(Message::Text(text)))) = tokio::time::timeout(quiet, socket.next()).await {
+            pushed.push(serde_json::from_str(&text).unwrap());
+        }
+        pushed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
+
+    #[test]
+    fn test_acquiring_timed_out_control_reports_the_previous_holder() {
+        let mut lock = RobotControlLock::with_inactivity_timeout(Duration::ZERO);
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        assert_eq!(lock.try_acquire(first).unwrap(), None);
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(lock.try_acquire(second).unwrap(), Some(first));
+        assert!(lock.is_holder(second));
+    }
+
+    /// Clients race to acquire, release and time out control. Each client
+    /// keeps a balance of grants minus frees, where a free is its own
+    /// release, the timeout checker or a timed-out takeover naming it. A
+    /// client with a balance of 1 believes it holds control.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_racing_clients_never_share_or_double_free_control() {
+        const CLIENTS: usize = 16;
+        const ROUNDS: usize = 200;
+
+        let client_manager = Arc::new(ClientManager::new().with_inactivity_timeout(Duration::from_millis(1)));
+        let clients: Vec<Uuid> = (0..CLIENTS).map(|_| Uuid::new_v4()).collect();
+        let balances: Arc<HashMap<Uuid, AtomicI64>> =
+            Arc::new(clients.iter().map(|&id| (id, AtomicI64::new(0))).collect());
+        let frees = Arc::new(AtomicUsize::new(0));
+        let free = {
+            let (balances, frees) = (Arc::clone(&balances), Arc::clone(&frees));
+            move |client_id: Uuid| {
+                balances[&client_id].fetch_sub(1, Ordering::SeqCst);
+                frees.fetch_add(1, Ordering::SeqCst);
+            }
+        };
+
+        let mut tasks = Vec::new();
+        for (n, &client_id) in clients.iter().enumerate() {
+            let (client_manager, balances, free) = (Arc::clone(&client_manager), Arc::clone(&balances), free.clone());
+            tasks.push(tokio::spawn(async move {
+                let mut grants = 0;
+                for round in 0..ROUNDS {
+                    // Only this task records grants, so every free of this
+                    // client's control has followed one of them
+                    let balance = balances[&client_id].load(Ordering::SeqCst);
+                    assert!(balance >= 0, "control of {} freed twice", client_id);
+                    if balance == 0 {
+                        if let Ok(previous) = client_manager.try_acquire_control(client_id).await {
+                            balances[&client_id].fetch_add(1, Ordering::SeqCst);
+                            grants += 1;
+                            if let Some(previous) = previous {
+                                free(previous);
+                            }
+                        }
+                    } else if (round + n) % 3 == 0 {
+                        // Hold on long enough to time out
+                        tokio::time::sleep(Duration::from_millis(2)).await;
+                    } else if client_manager.release_control(client_id).await {
+                        free(client_id);
+                    }
+                    tokio::task::yield_now().await;
+                }
+                grants
+            }));
+        }
+        let checker = {
+            let client_manager = Arc::clone(&client_manager);
+            tokio::spawn(async move {
+                for _ in 0..ROUNDS * 4 {
+                    if let Some(timed_out) = client_manager.check_control_timeout().await {
+                        free(timed_out);
+                    }
+                    tokio::task::yield_now().await;
+                }
+            })
+        };
+
+        let mut grants = 0;
+        for task in tasks {
+            grants += task.await.unwrap();
+        }
+        checker.await.unwrap();
+
+        assert!(clients.iter().all(|id| (0..=1).contains(&balances[id].load(Ordering::SeqCst))));
+        let believers: Vec<Uuid> =
+            clients.iter().copied().filter(|id| balances[id].load(Ordering::SeqCst) == 1).collect();
+        assert!(believers.len() <= 1, "{} clients believe they hold control", believers.len());
+        assert_eq!(believers.first().copied(), client_manager.get_control_holder().await);
+        assert_eq!(grants, frees.load(Ordering::SeqCst) + believers.len());
+    }
+}
@@ -0,0 +1,109 @@
+//! Last known state of the active robot.
+//!
+//! Clients get the robot's position and status from the polled responses the
+//! broadcast tasks forward. When those tasks resubscribe to a new driver, a
+//! client that connected during the switch would see nothing until the new
+//! driver's first answers arrive, so the last known state is replayed to
+//! every client on each (re)subscribe.
+//!
+//! The cache belongs to one driver: state recorded from another driver
+//! replaces it, and only state from the driver being subscribed to is
+//! replayed, so clients never see a previous robot's pose as current.
+
+use crate::api_types::ServerResponse;
+use crate::session::ClientManager;
+use crate::RobotConnection;
+use fanuc_rmi::dto;
+use std::collections::BTreeMap;
+use tokio::sync::{broadcast, RwLock};
+
+/// Polled response kinds whose latest frame is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum StateKind {
+    CartesianPosition,
+    JointAngles,
+    Status,
+}
+
+/// Latest robot state frames and status, replayed by [`replay_state`].
+#[derive(Debug, Default)]
+pub struct StateCache {
+    /// Driver (by `Arc` address) the cached state came from.
+    driver_id: Option<usize>,
+    /// Encoded binary frame of the latest response of each kind.
+    frames: BTreeMap<StateKind, Vec<u8>>,
+    /// Status of the latest `StatusChanged`.
+    status: Option<dto::RobotStatus>,
+}
+
+impl StateCache {
+    /// Drop everything cached, e.g. when the robot disconnects.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Drop the cached state unless it came from `driver_id`.
+    fn follow_driver(&mut self, driver_id: usize) {
+        if self.driver_id != Some(driver_id) {
+            *self = Self {
+                driver_id: Some(driver_id),
+                ..Self::default()
+            };
+        }
+    }
+
+    /// Keep `frame`, the broadcast encoding of `response` from the driver
+    /// `driver_id`, if the response carries robot state. Failed reads are
+    /// ignored.
+    pub fn record_response(&mut self, driver_id: usize, response: &dto::ResponsePacket, frame: &[u8]) {
+        let kind = match response {
+            dto::ResponsePacket::CommandResponse(dto::CommandResponse::FrcReadCartesianPosition(r)) if r.error_id == 0 => {
+                StateKind::CartesianPosition
+            }
+            dto::ResponsePacket::CommandResponse(dto::CommandResponse::FrcReadJointAngles(r)) if r.error_id == 0 => {
+                StateKind::JointAngles
+            }
+            dto::ResponsePacket::CommandResponse(dto::CommandResponse::FrcGetStatus(r)) if r.error_id == 0 => {
+                StateKind::Status
+            }
+            _ => return,
+        };
+        self.follow_driver(driver_id);
+        self.frames.insert(kind, frame.to_vec());
+    }
+
+    /// Keep the status of the driver `driver_id` last broadcast as
+    /// `StatusChanged`.
+    pub fn record_status(&mut self, driver_id: usize, status: dto::RobotStatus) {
+        self.follow_driver(driver_id);
+        self.status = Some(status);
+    }
+}
+
+/// Send the state cached for the driver `driver_id` to every client: the
+/// response frames on `broadcast_tx`, the status as `StatusChanged`, and the
+/// known I/O values of `robot_connection` as `IoChanged` to the clients
+/// subscribed to them. State cached for any other driver is dropped instead.
+pub async fn replay_state(
+    cache: &std::sync::Mutex<StateCache>,
+    driver_id: usize,
+    robot_connection: &RwLock<RobotConnection>,
+    broadcast_tx: &broadcast::Sender<Vec<u8>>,
+    client_manager: &ClientManager,
+) {
+    let (frames, status) = {
+        let mut cache = cache.lock().unwrap();
+        cache.follow_driver(driver_id);
+        (cache.frames.values().cloned().collect::<Vec<_>>(), cache.status.clone())
+    };
+    for frame in frames {
+        let _ = broadcast_tx.send(frame);
+    }
+    if let Some(status) = status {
+        client_manager.broadcast_all(&ServerResponse::StatusChanged { status }).await;
+    }
+    let io_values = robot_connection.read().await.io_cache.lock().unwrap().values();
+    for (point, value) in io_values {
+        client_manager.notify_io_changed(point, value).await;
+    }
+}
@@ -0,0 +1,95 @@
+//! A minimal in-process fake controller shared by the server tests.
+
+use crate::RobotConnection;
+use fanuc_rmi::drivers::{FanucDriver, FanucDriverConfig};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+/// Start a fake controller on an ephemeral port. Returns the connect port.
+///
+/// The connect port answers `FRC_Connect` with the data port. Every JSON
+/// packet received on the data port is passed to `answer`, and the lines it
+/// returns are written back in order.
+pub async fn start_fake_controller<F, I>(mut answer: F) -> u32
+where
+    F: FnMut(serde_json::Value) -> I + Send + 'static,
+    I: IntoIterator,
+    I::Item: Into<String>,
+{
+    let connect_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let data_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let connect_port = connect_listener.local_addr().unwrap().port();
+    let data_port = data_listener.local_addr().unwrap().port();
+
+    tokio::spawn(async move {
+        let (mut socket, _) = connect_listener.accept().await.unwrap();
+        let mut reader = BufReader::new(&mut socket);
+        let mut line = String::new();
+        reader.read_line(&mut line).await.unwrap();
+        let reply = format!(
+            "{{\"Communication\":\"FRC_Connect\",\"ErrorID\":0,\"PortNumber\":{},\"MajorVersion\":1,\"MinorVersion\":0}}\r\n",
+            data_port
+        );
+        socket.write_all(reply.as_bytes()).await.unwrap();
+    });
+
+    tokio::spawn(async move {
+        let (socket, _) = data_listener.accept().await.unwrap();
+        let (read_half, mut write_half) = socket.into_split();
+        let mut lines = BufReader::new(read_half).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let Ok(packet) = serde_json::from_str::<serde_json::Value>(&line) else {
+                continue;
+            };
+            let replies: Vec<String> = answer(packet).into_iter().map(Into::into).collect();
+            for reply in replies {
+                if write_half.write_all(reply.as_bytes()).await.is_err() {
+                    return;
+                }
+            }
+        }
+    });
+
+    connect_port as u32
+}
+
+/// A fake controller that completes the connect handshake and then reads
+/// every packet without answering.
+pub async fn start_silent_controller() -> u32 {
+    start_fake_controller(|_| None::<String>).await
+}
+
+/// A successful response to `packet` if it is an instruction, echoing its
+/// `SequenceID`.
+pub fn instruction_reply(packet: &serde_json::Value) -> Option<String> {
+    let instruction = packet["Instruction"].as_str()?;
+    Some(format!(
+        "{{\"Instruction\":\"{}\",\"ErrorID\":0,\"SequenceID\":{}}}\r\n",
+        instruction, packet["SequenceID"]
+    ))
+}
+
+/// A bare successful response to `packet` if it is a command.
+pub fn command_reply(packet: &serde_json::Value) -> Option<String> {
+    let command = packet["Command"].as_str()?;
+    Some(format!("{{\"Command\":\"{}\",\"ErrorID\":0}}\r\n", command))
+}
+
+/// A driver connected to the fake controller on `port`.
+pub async fn connect_driver(port: u32) -> Arc<FanucDriver> {
+    let config = FanucDriverConfig {
+        addr: "127.0.0.1".to_string(),
+        port,
+        ..Default::default()
+    };
+    Arc::new(FanucDriver::connect(config).await.expect("connect to fake controller"))
+}
+
+/// A connected robot whose driver talks to the fake controller on `port`.
+pub async fn connect_robot(port: u32) -> RobotConnection {
+    let mut conn = RobotConnection::new("127.0.0.1".to_string(), port);
+    conn.driver = Some(connect_driver(port).await);
+    conn.connected = true;
+    conn
+}